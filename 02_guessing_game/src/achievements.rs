@@ -0,0 +1,234 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Difficulty;
+
+/// What achievements are judged on once a game is over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Finished {
+    pub won: bool,
+    pub guesses: u32,
+    /// The `--difficulty` it was played at, if any.
+    pub difficulty: Option<Difficulty>,
+    /// Days in a row the daily challenge has been won.
+    pub streak: u32,
+}
+
+/// Something to achieve. Adding one is a matter of implementing this and
+/// listing it in [`RULES`].
+pub trait Rule: Sync {
+    /// Names the achievement in the saved file, so it mustn't change.
+    fn id(&self) -> &'static str;
+
+    fn name(&self) -> &'static str;
+
+    /// What it takes, for the announcement.
+    fn description(&self) -> &'static str;
+
+    /// Whether `game` earns the achievement.
+    fn unlocked_by(&self, game: &Finished) -> bool;
+}
+
+struct FirstWin;
+
+impl Rule for FirstWin {
+    fn id(&self) -> &'static str {
+        "first-win"
+    }
+
+    fn name(&self) -> &'static str {
+        "Beginner's luck"
+    }
+
+    fn description(&self) -> &'static str {
+        "win a game"
+    }
+
+    fn unlocked_by(&self, game: &Finished) -> bool {
+        game.won
+    }
+}
+
+struct QuickWin;
+
+impl Rule for QuickWin {
+    fn id(&self) -> &'static str {
+        "quick-win"
+    }
+
+    fn name(&self) -> &'static str {
+        "Sharpshooter"
+    }
+
+    fn description(&self) -> &'static str {
+        "win in 5 guesses or fewer"
+    }
+
+    fn unlocked_by(&self, game: &Finished) -> bool {
+        game.won && game.guesses <= 5
+    }
+}
+
+struct HardWin;
+
+impl Rule for HardWin {
+    fn id(&self) -> &'static str {
+        "hard-win"
+    }
+
+    fn name(&self) -> &'static str {
+        "Hard as nails"
+    }
+
+    fn description(&self) -> &'static str {
+        "win a game with --difficulty hard"
+    }
+
+    fn unlocked_by(&self, game: &Finished) -> bool {
+        game.won && game.difficulty == Some(Difficulty::Hard)
+    }
+}
+
+struct WeekStreak;
+
+impl Rule for WeekStreak {
+    fn id(&self) -> &'static str {
+        "week-streak"
+    }
+
+    fn name(&self) -> &'static str {
+        "Creature of habit"
+    }
+
+    fn description(&self) -> &'static str {
+        "win the daily challenge 7 days in a row"
+    }
+
+    fn unlocked_by(&self, game: &Finished) -> bool {
+        game.streak >= 7
+    }
+}
+
+/// Every achievement there is.
+pub static RULES: &[&dyn Rule] = &[&FirstWin, &QuickWin, &HardWin, &WeekStreak];
+
+/// The achievements the player has unlocked, kept between runs.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Achievements {
+    unlocked: BTreeSet<String>,
+}
+
+impl Achievements {
+    /// Reads the achievements at `path`. A missing or damaged file is a
+    /// fresh start.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(serde_json::from_str(&text).unwrap_or_default()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes the achievements to `path`, creating its directory if need
+    /// be. The file is replaced all at once so a crash can't leave it half
+    /// written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, json)?;
+        fs::rename(&temp, path)
+    }
+
+    pub fn is_unlocked(&self, rule: &dyn Rule) -> bool {
+        self.unlocked.contains(rule.id())
+    }
+
+    /// Unlocks whatever `game` earns, returning the achievements that
+    /// weren't unlocked before.
+    pub fn unlock(&mut self, game: &Finished) -> Vec<&'static dyn Rule> {
+        RULES
+            .iter()
+            .copied()
+            .filter(|rule| rule.unlocked_by(game) && self.unlocked.insert(rule.id().to_string()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn won(guesses: u32) -> Finished {
+        Finished {
+            won: true,
+            guesses,
+            difficulty: None,
+            streak: 0,
+        }
+    }
+
+    fn ids(rules: Vec<&dyn Rule>) -> Vec<&'static str> {
+        rules.iter().map(|rule| rule.id()).collect()
+    }
+
+    #[test]
+    fn ids_are_unique() {
+        let ids: BTreeSet<_> = RULES.iter().map(|rule| rule.id()).collect();
+        assert_eq!(RULES.len(), ids.len());
+    }
+
+    #[test]
+    fn unlocks_each_achievement_once() {
+        let mut achievements = Achievements::default();
+        let lost = Finished {
+            won: false,
+            ..won(3)
+        };
+        assert!(achievements.unlock(&lost).is_empty());
+        assert_eq!(vec!["first-win"], ids(achievements.unlock(&won(9))));
+        assert_eq!(vec!["quick-win"], ids(achievements.unlock(&won(5))));
+        assert!(achievements.unlock(&won(1)).is_empty());
+        assert!(achievements.is_unlocked(&FirstWin));
+        assert!(!achievements.is_unlocked(&HardWin));
+    }
+
+    #[test]
+    fn hard_games_and_streaks() {
+        let mut achievements = Achievements::default();
+        let hard = Finished {
+            difficulty: Some(Difficulty::Hard),
+            ..won(10)
+        };
+        assert_eq!(
+            vec!["first-win", "hard-win"],
+            ids(achievements.unlock(&hard))
+        );
+        let streak = Finished {
+            streak: 7,
+            ..won(10)
+        };
+        assert_eq!(vec!["week-streak"], ids(achievements.unlock(&streak)));
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let dir =
+            env::temp_dir().join(format!("guessing-game-achievements-{}", std::process::id()));
+        let path = dir.join("achievements.json");
+        assert_eq!(Achievements::default(), Achievements::load(&path).unwrap());
+
+        let mut achievements = Achievements::default();
+        achievements.unlock(&won(3));
+        achievements.save(&path).unwrap();
+        assert_eq!(achievements, Achievements::load(&path).unwrap());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
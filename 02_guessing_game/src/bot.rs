@@ -0,0 +1,153 @@
+use std::fmt;
+
+use rand::Rng;
+
+use crate::{pick_secret, Config, ConfigError, Game, Lang, Outcome, Session, State};
+
+/// How the computer picks its guesses (`--bot`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Halve the numbers that are left every time.
+    Binary,
+    /// Pick any of the numbers that are left.
+    Random,
+}
+
+impl Strategy {
+    pub fn parse(value: &str) -> Result<Self, ConfigError> {
+        match value {
+            "binary" => Ok(Strategy::Binary),
+            "random" => Ok(Strategy::Random),
+            _ => Err(ConfigError::UnknownStrategy(value.to_string())),
+        }
+    }
+
+    /// The next guess when the secret must be in `low..=high`.
+    pub fn guess<R: Rng>(self, rng: &mut R, low: i64, high: i64) -> i64 {
+        match self {
+            Strategy::Binary => (i128::from(low) + (i128::from(high) - i128::from(low)) / 2) as i64,
+            Strategy::Random => pick_secret(rng, low, high),
+        }
+    }
+
+    /// Why the bot guessed `guess` when the secret must be in `low..=high`.
+    pub fn reason(self, lang: Lang, guess: i64, low: i64, high: i64) -> String {
+        if low == high {
+            return t!(lang, "bot.only", guess = guess);
+        }
+        let key = match self {
+            Strategy::Binary => "bot.binary",
+            Strategy::Random => "bot.random",
+        };
+        t!(lang, key, low = low, high = high, guess = guess)
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Strategy::Binary => write!(f, "binary"),
+            Strategy::Random => write!(f, "random"),
+        }
+    }
+}
+
+/// Plays `game` to the end with `strategy`. `step` is told about each guess
+/// as it's made, along with the bounds the bot chose it from and what it
+/// turned out to be.
+pub fn play<R: Rng>(
+    game: &mut Game,
+    strategy: Strategy,
+    rng: &mut R,
+    mut step: impl FnMut(i64, (i64, i64), Outcome),
+) {
+    while game.state() == State::Playing {
+        let (low, high) = game.history().bounds();
+        // Only possible if the secret is outside the range it was said to
+        // be in; there's nothing sensible left to guess.
+        if low > high {
+            game.give_up();
+            break;
+        }
+        let guess = strategy.guess(rng, low, high);
+        let outcome = game.guess(guess);
+        step(guess, (low, high), outcome);
+    }
+}
+
+/// Has the bot play `games` games with the range and guess limit in
+/// `config`, and gathers the statistics.
+pub fn simulate<R: Rng>(strategy: Strategy, config: &Config, games: u32, rng: &mut R) -> Session {
+    let mut session = Session::default();
+    for _ in 0..games {
+        let secret = pick_secret(rng, config.min, config.max);
+        let mut game = Game::new(secret)
+            .with_range(config.min, config.max)
+            .with_max_attempts(config.max_attempts);
+        play(&mut game, strategy, rng, |_, _, _| {});
+        session.record(&game);
+    }
+    session
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    #[test]
+    fn parses_strategies() {
+        assert_eq!(Ok(Strategy::Binary), Strategy::parse("binary"));
+        assert_eq!(Ok(Strategy::Random), Strategy::parse("random"));
+        assert!(Strategy::parse("psychic").is_err());
+    }
+
+    #[test]
+    fn binary_guesses_the_middle() {
+        let mut rng = StdRng::from_seed(&[1][..]);
+        assert_eq!(50, Strategy::Binary.guess(&mut rng, 1, 100));
+        assert_eq!(7, Strategy::Binary.guess(&mut rng, 7, 7));
+        assert_eq!(-1, Strategy::Binary.guess(&mut rng, i64::MIN, i64::MAX));
+    }
+
+    #[test]
+    fn random_guesses_stay_in_bounds() {
+        let mut rng = StdRng::from_seed(&[1][..]);
+        for _ in 0..100 {
+            assert!((40..=60).contains(&Strategy::Random.guess(&mut rng, 40, 60)));
+        }
+    }
+
+    #[test]
+    fn binary_search_finds_any_secret_in_seven_guesses() {
+        let mut rng = StdRng::from_seed(&[1][..]);
+        for secret in 1..=100 {
+            let mut game = Game::new(secret).with_range(1, 100);
+            let mut steps = Vec::new();
+            play(&mut game, Strategy::Binary, &mut rng, |guess, bounds, _| {
+                steps.push((guess, bounds))
+            });
+            assert_eq!(State::Won, game.state());
+            assert!(game.attempts() <= 7, "{secret} took {}", game.attempts());
+            assert_eq!((50, (1, 100)), steps[0]);
+        }
+    }
+
+    #[test]
+    fn simulations_report_average_guesses() {
+        let config = Config::default();
+        let mut rng = StdRng::from_seed(&[1][..]);
+        let binary = simulate(Strategy::Binary, &config, 200, &mut rng);
+        let random = simulate(Strategy::Random, &config, 200, &mut rng);
+        assert_eq!((200, 200), (binary.games, binary.wins));
+        assert!(binary.average_guesses().unwrap() < random.average_guesses().unwrap());
+
+        let limited = Config {
+            max_attempts: Some(1),
+            ..config
+        };
+        let session = simulate(Strategy::Binary, &limited, 50, &mut rng);
+        assert_eq!(50, session.guesses);
+        assert!(session.wins < 50);
+    }
+}
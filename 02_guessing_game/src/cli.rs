@@ -0,0 +1,334 @@
+//! Playing at the terminal: what each command line does, with the games
+//! reading the player's lines from stdin and printing to stdout.
+
+use std::env;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use rand::{SeedableRng, StdRng};
+
+use crate::achievements::Finished;
+use crate::feedback::Style;
+use crate::records::Record;
+use crate::scores::Score;
+use crate::{bot, daily, hangman, profiles, scores};
+use crate::{Config, Difficulty, Lang, Localize, Session, State, Strategy};
+
+mod challenges;
+mod game;
+mod games;
+mod race;
+mod reports;
+
+/// Does what `config` says, returning how the process should exit: whether
+/// the game was won, or 2 when it couldn't be set up.
+pub fn run(mut config: Config) -> ExitCode {
+    config.lang = config.lang.or_else(Lang::from_env);
+    let lang = config.lang.unwrap_or_default();
+
+    if config.export_scores.is_some() || config.import_scores.is_some() {
+        return exit_code(reports::transfer_scores(&config));
+    }
+    if let Some(path) = &config.replay {
+        return exit_code(game::play_replay(lang, path));
+    }
+    if config.stats {
+        reports::show_stats(lang, config.profile.as_deref());
+        return ExitCode::SUCCESS;
+    }
+    if let Some(action) = &config.profiles {
+        return exit_code(reports::manage_profiles(lang, action));
+    }
+
+    // A seeded generator plays the same games every time, and the daily
+    // challenge is seeded with the date.
+    let today = daily::today();
+    let seed = if config.daily {
+        Some(daily::seed(today))
+    } else {
+        config.seed
+    };
+    let mut rng = match seed {
+        Some(seed) => StdRng::from_seed(&[seed as usize][..]),
+        None => StdRng::new().expect("Failed to seed the random number generator"),
+    };
+
+    if let Some(games) = config.simulate {
+        let strategy = config.bot.unwrap_or(Strategy::Binary);
+        let session = bot::simulate(strategy, &config, games, &mut rng);
+        reports::show_simulation(lang, strategy, &session);
+        return ExitCode::SUCCESS;
+    }
+    if config.host.is_some() || config.join.is_some() {
+        let won = match (config.host, &config.join) {
+            (Some(port), _) => race::play_host(&config, port, &mut rng),
+            (None, Some(addr)) => race::play_guest(lang, addr),
+            (None, None) => unreachable!(),
+        };
+        let won = won.unwrap_or_else(|e| {
+            eprintln!("{}", t!(lang, "race.failed", error = e));
+            false
+        });
+        return exit_code(won);
+    }
+    if config.digits {
+        return exit_code(games::play_digits(&config, &mut rng));
+    }
+    if config.float {
+        return exit_code(games::play_float(&config, &mut rng));
+    }
+    if config.feedback == Style::Proximity {
+        return exit_code(games::play_proximity(&config, &mut rng));
+    }
+    if config.reverse {
+        let strategy = config.bot.unwrap_or(Strategy::Binary);
+        return exit_code(games::play_reverse(&config, strategy, &mut rng));
+    }
+    if let Some(strategy) = config.bot {
+        let game = games::play_bot(&config, strategy, &mut rng);
+        return exit_code(game.state() == State::Won);
+    }
+
+    // Scripted games, from --guesses or piped input, play once and leave the
+    // high-score table alone so their output only depends on the arguments.
+    let scripted = config.guesses.is_some() || !io::stdin().is_terminal();
+    if !scripted && config.profile.is_none() {
+        config.profile = choose_profile(lang);
+    }
+    let profile = config.profile.as_deref();
+    if config.words {
+        let words = match &config.wordlist {
+            Some(path) => match hangman::load_words(path) {
+                Ok(words) => words,
+                Err(e) => {
+                    let path = path.display();
+                    eprintln!("{}", t!(lang, "words.cant-read", path = path, error = e));
+                    return ExitCode::from(2);
+                }
+            },
+            None => hangman::WORDS.iter().map(|word| word.to_string()).collect(),
+        };
+        let paths = SessionPaths {
+            scores: scores::words_path(profile),
+            records: None,
+            achievements: scores::achievements_path(profile),
+        };
+        let done = play_session(lang, &mut Input::Stdin, scripted, paths, |input| {
+            games::play_word(&config, &words, &mut rng, input)
+        });
+        return exit_code(done);
+    }
+
+    let mut input = match &config.guesses {
+        Some(guesses) => Input::Script(guesses.clone().into_iter()),
+        None => Input::Stdin,
+    };
+    if config.daily {
+        return exit_code(challenges::play_daily(&config, today, &mut rng, &mut input));
+    }
+    if let Some(rounds) = config.rounds {
+        let won = challenges::play_tournament(&config, rounds, &mut rng, &mut input);
+        return exit_code(won);
+    }
+    let mut resumed = if config.resume {
+        game::resume_game(lang, profile)
+    } else {
+        None
+    };
+    // Games played by hand can be put aside by quitting.
+    let save_to = if scripted {
+        None
+    } else {
+        scores::save_path(profile)
+    };
+    let paths = SessionPaths {
+        scores: scores::default_path(profile),
+        records: scores::records_path(profile),
+        achievements: scores::achievements_path(profile),
+    };
+    let done = play_session(lang, &mut input, scripted, paths, |input| {
+        let (game, elapsed) =
+            game::play(&config, &mut rng, input, resumed.take(), save_to.as_deref())?;
+        let score = (game.state() == State::Won).then(|| {
+            let (min, max) = game.range();
+            Score::new(min, max, game.attempts(), game.hints(), elapsed).with_name(player(&config))
+        });
+        Some(Played {
+            guesses: game.attempts(),
+            difficulty: config.difficulty,
+            seconds: elapsed.as_secs(),
+            score,
+        })
+    });
+    exit_code(done)
+}
+
+/// Exits with 0 when something worked, or 1 when it didn't.
+fn exit_code(ok: bool) -> ExitCode {
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// How a finished game of any kind went.
+struct Played {
+    guesses: u32,
+    /// The `--difficulty` it was played at, if it has one.
+    difficulty: Option<Difficulty>,
+    seconds: u64,
+    /// What the game scored, if it was won.
+    score: Option<Score>,
+}
+
+/// Where a session keeps what it remembers between runs, each `None` to
+/// keep nothing.
+struct SessionPaths {
+    /// The high-score table.
+    scores: Option<PathBuf>,
+    /// A record of each game, for `stats`.
+    records: Option<PathBuf>,
+    achievements: Option<PathBuf>,
+}
+
+/// Plays games until the player is done, keeping the high scores, records
+/// and achievements at `paths`, and showing statistics at the end. A
+/// `scripted` session plays one game and leaves all of them alone. Returns
+/// whether a scripted game was won, and `true` once any other session ends.
+fn play_session(
+    lang: Lang,
+    input: &mut Input,
+    scripted: bool,
+    paths: SessionPaths,
+    mut play: impl FnMut(&mut Input) -> Option<Played>,
+) -> bool {
+    if scripted {
+        return play(input).is_some_and(|played| played.score.is_some());
+    }
+
+    let mut session = Session::default();
+    // Each game is played inside the session loop until the player is done.
+    while let Some(played) = play(input) {
+        let won = played.score.is_some();
+        session.add(won, played.guesses);
+        if let Some(path) = &paths.records {
+            let record = Record {
+                won,
+                guesses: played.guesses,
+                difficulty: played.difficulty,
+                seconds: played.seconds,
+            };
+            reports::save_record(lang, path, record);
+        }
+        reports::show_high_scores(lang, paths.scores.as_deref(), played.score);
+        reports::show_achievements(
+            lang,
+            paths.achievements.as_deref(),
+            &Finished {
+                won,
+                guesses: played.guesses,
+                difficulty: played.difficulty,
+                streak: 0,
+            },
+        );
+        println!("{}", t!(lang, "session.again"));
+        let again = input.next_line().is_some_and(|answer| {
+            let answer = answer.trim();
+            answer.eq_ignore_ascii_case("y")
+                || answer.eq_ignore_ascii_case(lang.text("session.yes"))
+        });
+        if !again {
+            break;
+        }
+    }
+    reports::show_session(lang, &session);
+    true
+}
+
+/// Where guesses come from.
+enum Input {
+    /// The player, typing at the terminal or piping lines in.
+    Stdin,
+    /// A list given with `--guesses`.
+    Script(std::vec::IntoIter<i64>),
+}
+
+impl Input {
+    /// The next line of input, or `None` once there's nothing left.
+    fn next_line(&mut self) -> Option<String> {
+        match self {
+            Input::Stdin => read_line(),
+            Input::Script(guesses) => guesses.next().map(|guess| guess.to_string()),
+        }
+    }
+}
+
+/// Reads a line from the player, or `None` once there's nothing left to
+/// read.
+fn read_line() -> Option<String> {
+    // Declare a new variable with `let`.
+    // Mark the variable as mutable with `mut`.
+    // Assign a new string instance to the variable.
+    let mut line = String::new();
+
+    let read = io::stdin()
+        // Pass a mutable reference to `line`.
+        // The & indicates that this argument is a reference.
+        .read_line(&mut line)
+        // Handle the Result type returned from `.read_line()`.
+        .expect("Failed to read line");
+    (read > 0).then_some(line)
+}
+
+/// Who's playing: the `--name` given, or else the profile's name or the
+/// name they're logged in with.
+fn player(config: &Config) -> Option<String> {
+    config.name.clone().or(config.profile.clone()).or_else(|| {
+        ["USER", "USERNAME"]
+            .into_iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|name| !name.trim().is_empty())
+    })
+}
+
+/// Asks who's playing, so their high scores, stats and achievements are
+/// kept apart from everyone else's. Returns `None` to play without a
+/// profile.
+fn choose_profile(lang: Lang) -> Option<String> {
+    let existing = scores::profiles_dir()
+        .and_then(|dir| profiles::list(&dir).ok())
+        .unwrap_or_default();
+    if !existing.is_empty() {
+        let names = existing.join(", ");
+        println!("{}", t!(lang, "profiles.existing", profiles = names));
+    }
+    loop {
+        println!("{}", t!(lang, "profiles.ask"));
+        let line = read_line()?;
+        if line.trim().is_empty() {
+            return None;
+        }
+        match profiles::validate(&line) {
+            Ok(name) => {
+                println!("{}", t!(lang, "profiles.playing-as", name = name));
+                return Some(name);
+            }
+            Err(e) => println!("{}", e.localize(lang)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_give_their_guesses_a_line_at_a_time() {
+        let mut input = Input::Script(vec![50, -3].into_iter());
+        assert_eq!(Some("50".to_string()), input.next_line());
+        assert_eq!(Some("-3".to_string()), input.next_line());
+        assert_eq!(None, input.next_line());
+    }
+}
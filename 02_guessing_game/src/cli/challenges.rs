@@ -0,0 +1,154 @@
+//! The daily challenge and `--rounds` tournaments, each a number game or a
+//! few kept score of.
+
+use rand::StdRng;
+
+use super::game::play;
+use super::reports::{save_record, show_achievements};
+use super::Input;
+use crate::achievements::Finished;
+use crate::records::Record;
+use crate::tournament::{self, Tournament};
+use crate::{daily, scores, Config, Lang, Localize, State};
+
+/// Plays today's challenge, unless it's been played already, and keeps
+/// track of the streak. Returns whether it was won.
+pub fn play_daily(config: &Config, today: i64, rng: &mut StdRng, input: &mut Input) -> bool {
+    let lang = config.lang.unwrap_or_default();
+    println!(
+        "{}",
+        t!(lang, "daily.opening", date = daily::format_date(today))
+    );
+    let profile = config.profile.as_deref();
+    let path = scores::stats_path(profile);
+    let mut stats = match &path {
+        Some(path) => daily::Stats::load(path).unwrap_or_else(|e| {
+            let path = path.display();
+            eprintln!("{}", t!(lang, "daily.cant-read", path = path, error = e));
+            daily::Stats::default()
+        }),
+        None => daily::Stats::default(),
+    };
+    if stats.played(today) {
+        println!("{}", t!(lang, "daily.played"));
+        let streak = stats.current_streak(today);
+        println!("{}", t!(lang, "daily.streak", count = streak));
+        return stats.last_win == Some(today);
+    }
+
+    let Some((game, elapsed)) = play(config, rng, input, None, None) else {
+        return false;
+    };
+    let won = game.state() == State::Won;
+    if let Some(path) = scores::records_path(profile) {
+        let record = Record {
+            won,
+            guesses: game.attempts(),
+            difficulty: config.difficulty,
+            seconds: elapsed.as_secs(),
+        };
+        save_record(lang, &path, record);
+    }
+    stats.finish(today, won);
+    if let Some(path) = &path {
+        if let Err(e) = stats.save(path) {
+            let path = path.display();
+            eprintln!("{}", t!(lang, "daily.cant-save", path = path, error = e));
+        }
+    }
+    let best = stats.best_streak;
+    println!(
+        "{}",
+        t!(lang, "daily.best-streak", count = stats.streak, best = best)
+    );
+    show_achievements(
+        lang,
+        scores::achievements_path(profile).as_deref(),
+        &Finished {
+            won,
+            guesses: game.attempts(),
+            difficulty: config.difficulty,
+            streak: stats.streak,
+        },
+    );
+    won
+}
+
+/// Plays a `--rounds` tournament, a game per round at the difficulty the
+/// round calls for, and shows the scoreboard once they're done or the player
+/// quits. Returns whether every round was won.
+pub fn play_tournament(config: &Config, rounds: u32, rng: &mut StdRng, input: &mut Input) -> bool {
+    let lang = config.lang.unwrap_or_default();
+    let mut tournament = Tournament::new(rounds);
+    while let Some(difficulty) = tournament.next() {
+        let (min, max) = difficulty.range();
+        let round = Config {
+            min,
+            max,
+            max_attempts: Some(difficulty.max_attempts()),
+            difficulty: Some(difficulty),
+            ..config.clone()
+        };
+        println!(
+            "{}",
+            t!(
+                lang,
+                "tournament.round",
+                round = tournament.round(),
+                rounds = rounds,
+                difficulty = difficulty.localize(lang),
+                weight = tournament::weight(difficulty)
+            )
+        );
+        let Some((game, elapsed)) = play(&round, rng, input, None, None) else {
+            break;
+        };
+        let result = tournament.finish(&game, elapsed);
+        println!("{}", t!(lang, "tournament.points", points = result.points));
+        println!();
+    }
+    show_scoreboard(lang, &tournament);
+    tournament.won()
+}
+
+/// Prints how each round of `tournament` went and the total.
+fn show_scoreboard(lang: Lang, tournament: &Tournament) {
+    let rows: Vec<(String, String, u32)> = tournament
+        .results()
+        .iter()
+        .map(|result| {
+            let outcome = if result.won {
+                t!(lang, "tournament.won", count = result.attempts)
+            } else {
+                t!(lang, "tournament.lost")
+            };
+            (result.difficulty.localize(lang), outcome, result.points)
+        })
+        .collect();
+    let widest = |column: fn(&(String, String, u32)) -> &String| {
+        rows.iter()
+            .map(|row| column(row).chars().count())
+            .max()
+            .unwrap_or_default()
+    };
+    let (names, outcomes) = (widest(|row| &row.0), widest(|row| &row.1));
+    let places = tournament.rounds().to_string().len();
+    println!("{}", t!(lang, "tournament.scoreboard"));
+    for (i, (name, outcome, points)) in rows.iter().enumerate() {
+        println!(
+            "{}",
+            t!(
+                lang,
+                "tournament.row",
+                round = format!("{:>places$}", i + 1),
+                difficulty = format!("{name:<names$}"),
+                outcome = format!("{outcome:<outcomes$}"),
+                points = format!("{points:>4}")
+            )
+        );
+    }
+    println!(
+        "{}",
+        t!(lang, "tournament.total", points = tournament.total())
+    );
+}
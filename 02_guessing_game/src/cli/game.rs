@@ -0,0 +1,358 @@
+//! The number game, played a line at a time or in the `--tui` frame, and
+//! played back from a `--replay` transcript.
+
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::StdRng;
+
+use super::Input;
+use crate::replay;
+use crate::save::SavedGame;
+use crate::scores;
+use crate::ui::{Screen, View};
+use crate::{hints, opening, pick_secret, Command, Config, Game, Lang, Localize, Outcome};
+use crate::{Retries, State};
+
+/// Plays one game, returning it and how long it took once it's over, or
+/// `None` if the player quit or input ran out first.
+pub fn play(
+    config: &Config,
+    rng: &mut StdRng,
+    input: &mut Input,
+    resumed: Option<(Game, Duration)>,
+    save_to: Option<&Path>,
+) -> Option<(Game, Duration)> {
+    let lang = config.lang.unwrap_or_default();
+    let mut transcript = config.record.as_deref();
+    if config.tui {
+        let (game, earlier) = resumed.unwrap_or_else(|| (new_game(config, rng), Duration::ZERO));
+        for event in replay::start(&game) {
+            record(lang, &mut transcript, event);
+        }
+        return play_tui(config, game, earlier, input, save_to, transcript);
+    }
+    let (mut game, earlier) = match resumed {
+        Some((game, earlier)) => {
+            let (min, max) = game.range();
+            println!("{}", t!(lang, "play.welcome-back", min = min, max = max));
+            if config.cheat {
+                println!("{}", t!(lang, "play.cheat", secret = game.secret()));
+            }
+            show_history(lang, &game);
+            (game, earlier)
+        }
+        None => {
+            let game = new_game(config, rng);
+            print!("{}", opening(config, &game));
+            (game, Duration::ZERO)
+        }
+    };
+    for event in replay::start(&game) {
+        record(lang, &mut transcript, event);
+    }
+    let (min, max) = game.range();
+
+    let start = Instant::now();
+    let mut retries = Retries::default();
+    loop {
+        println!("{}", t!(lang, "play.prompt", min = min, max = max));
+
+        // Match expressions are the result of all blocks being values.
+        // All blocks are values unless a `;` is added.
+        let command = Command::parse_within(&input.next_line()?, min, max);
+        if command.is_ok() {
+            retries.reset();
+        }
+        let guess = match command {
+            Ok(Command::Guess(num)) => num,
+            Ok(Command::Hint) => {
+                show_hint(lang, &mut game);
+                record(lang, &mut transcript, replay::Event::Hint);
+                continue;
+            }
+            Ok(Command::History) => {
+                show_history(lang, &game);
+                continue;
+            }
+            Ok(Command::Range) => {
+                println!("{}", game.history().localize(lang));
+                continue;
+            }
+            Ok(Command::GiveUp) => {
+                game.give_up();
+                println!("{}", t!(lang, "play.number-was", secret = game.secret()));
+                record(lang, &mut transcript, replay::Event::GiveUp);
+                break;
+            }
+            Ok(Command::Quit) => {
+                record(lang, &mut transcript, replay::Event::Quit);
+                if let Some(path) = save_to {
+                    save_game(lang, path, &game, earlier + start.elapsed());
+                }
+                return None;
+            }
+            Err(err) => {
+                println!("{}", err.localize(lang));
+                if retries.fail() {
+                    println!("{}", t!(lang, "input.stuck", min = min, max = max));
+                    println!("{}", t!(lang, "command.help"));
+                }
+                continue;
+            }
+        };
+
+        let outcome = game.guess(guess);
+        show_guess(lang, &game, guess, outcome);
+        record(
+            lang,
+            &mut transcript,
+            replay::Event::Guess { guess, outcome },
+        );
+        if game.state() != State::Playing {
+            break;
+        }
+    }
+    Some((game, earlier + start.elapsed()))
+}
+
+/// Prints what guessing `guess` told the player, and what's left to go on
+/// if `game` isn't over.
+fn show_guess(lang: Lang, game: &Game, guess: i64, outcome: Outcome) {
+    println!("{}", t!(lang, "play.guessed", guess = guess));
+    match outcome {
+        Outcome::TooSmall => println!("{}", t!(lang, "outcome.too-small")),
+        Outcome::TooBig => println!("{}", t!(lang, "outcome.too-big")),
+        Outcome::Win => println!("{}", t!(lang, "play.win")),
+        Outcome::Lose => println!(
+            "{}",
+            t!(lang, "play.out-of-guesses", secret = game.secret())
+        ),
+    }
+    if game.state() == State::Playing {
+        println!("{}", game.history().localize(lang));
+        if let Some(remaining) = game.remaining() {
+            println!("{}", t!(lang, "play.guesses-left", count = remaining));
+        }
+    }
+}
+
+/// Takes the next hint about `game`'s secret and prints it.
+fn show_hint(lang: Lang, game: &mut Game) {
+    let hint = game.hint().localize(lang);
+    println!(
+        "{}",
+        t!(lang, "play.hint", hint = hint, cost = hints::HINT_COST)
+    );
+}
+
+/// Adds `event` to the `--record` transcript at `transcript`, if there is
+/// one, giving up on it for the rest of the game if it can't be written.
+fn record(lang: Lang, transcript: &mut Option<&Path>, event: replay::Event) {
+    if let Some(path) = *transcript {
+        if let Err(e) = replay::append(path, event) {
+            let path = path.display();
+            eprintln!("{}", t!(lang, "replay.cant-record", path = path, error = e));
+            *transcript = None;
+        }
+    }
+}
+
+/// Plays back the games recorded at `path` for `--replay`, a turn at a
+/// time. Returns whether there were any to show.
+pub fn play_replay(lang: Lang, path: &Path) -> bool {
+    let recordings = match replay::load(path) {
+        Ok(recordings) => recordings,
+        Err(e) => {
+            let path = path.display();
+            eprintln!("{}", t!(lang, "replay.cant-read", path = path, error = e));
+            return false;
+        }
+    };
+    if recordings.is_empty() {
+        println!("{}", t!(lang, "replay.empty", path = path.display()));
+        return false;
+    }
+    // Piped output is for reading, not watching.
+    let delay = if io::stdout().is_terminal() {
+        replay::TURN_DELAY
+    } else {
+        Duration::ZERO
+    };
+    for (i, recording) in recordings.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        let time = replay::format_time(recording.at);
+        println!("{}", t!(lang, "replay.opening", time = time));
+        let mut game = recording.game();
+        let (min, max) = game.range();
+        println!("{}", t!(lang, "play.opening", min = min, max = max));
+        println!("{}", t!(lang, "replay.secret", secret = game.secret()));
+        for entry in &recording.turns {
+            thread::sleep(delay);
+            match entry.event {
+                // The game is played again rather than trusting what was
+                // written down.
+                replay::Event::Guess { guess, .. } => {
+                    let outcome = game.guess(guess);
+                    show_guess(lang, &game, guess, outcome);
+                }
+                replay::Event::Hint => show_hint(lang, &mut game),
+                replay::Event::GiveUp => {
+                    game.give_up();
+                    println!("{}", t!(lang, "play.number-was", secret = game.secret()));
+                }
+                replay::Event::Quit => println!("{}", t!(lang, "replay.quit")),
+                replay::Event::Start { .. } => {}
+            }
+        }
+    }
+    true
+}
+
+/// A number game set up as `config` says, with its secret picked by `rng`.
+pub fn new_game(config: &Config, rng: &mut StdRng) -> Game {
+    let secret = pick_secret(rng, config.min, config.max);
+    Game::new(secret)
+        .with_range(config.min, config.max)
+        .with_max_attempts(config.max_attempts)
+}
+
+/// Plays `game` for `--tui`, redrawing it as the player types, and returns
+/// it like [`play`] does.
+fn play_tui(
+    config: &Config,
+    mut game: Game,
+    earlier: Duration,
+    input: &mut Input,
+    save_to: Option<&Path>,
+    mut transcript: Option<&Path>,
+) -> Option<(Game, Duration)> {
+    let lang = config.lang.unwrap_or_default();
+    let mut message = if config.cheat {
+        t!(lang, "play.cheat", secret = game.secret())
+    } else {
+        String::new()
+    };
+    let (min, max) = game.range();
+    let start = Instant::now();
+    let mut retries = Retries::default();
+    let mut screen = Screen::open();
+    while game.state() == State::Playing {
+        let view = View::new(&game, lang, &message);
+        let line = screen.next_line(view, || input.next_line())?;
+        let command = Command::parse_within(&line, min, max);
+        if command.is_ok() {
+            retries.reset();
+        }
+        message = match command {
+            Ok(Command::Guess(guess)) => {
+                let outcome = game.guess(guess);
+                record(
+                    lang,
+                    &mut transcript,
+                    replay::Event::Guess { guess, outcome },
+                );
+                match outcome {
+                    Outcome::TooSmall => t!(lang, "outcome.too-small"),
+                    Outcome::TooBig => t!(lang, "outcome.too-big"),
+                    Outcome::Win => t!(lang, "play.win"),
+                    Outcome::Lose => t!(lang, "play.out-of-guesses", secret = game.secret()),
+                }
+            }
+            Ok(Command::Hint) => {
+                record(lang, &mut transcript, replay::Event::Hint);
+                let hint = game.hint().localize(lang);
+                t!(lang, "play.hint", hint = hint, cost = hints::HINT_COST)
+            }
+            // The frame always shows both.
+            Ok(Command::History | Command::Range) => String::new(),
+            Ok(Command::GiveUp) => {
+                record(lang, &mut transcript, replay::Event::GiveUp);
+                game.give_up();
+                t!(lang, "play.number-was", secret = game.secret())
+            }
+            Ok(Command::Quit) => {
+                // Back to the normal screen, where saving can be reported.
+                drop(screen);
+                record(lang, &mut transcript, replay::Event::Quit);
+                if let Some(path) = save_to {
+                    save_game(lang, path, &game, earlier + start.elapsed());
+                }
+                return None;
+            }
+            Err(err) if retries.fail() => format!(
+                "{} {} {}",
+                err.localize(lang),
+                t!(lang, "input.stuck", min = min, max = max),
+                t!(lang, "command.help")
+            ),
+            Err(err) => err.localize(lang),
+        };
+    }
+    screen.close(View::new(&game, lang, &message));
+    Some((game, earlier + start.elapsed()))
+}
+
+/// Puts `game` aside at `path` to be resumed later.
+fn save_game(lang: Lang, path: &Path, game: &Game, elapsed: Duration) {
+    match SavedGame::new(game, elapsed).save(path) {
+        Ok(()) => println!("{}", t!(lang, "save.saved")),
+        Err(e) => {
+            let path = path.display();
+            eprintln!("{}", t!(lang, "save.cant-save", path = path, error = e));
+        }
+    }
+}
+
+/// Takes the game put aside by quitting, if there's one that can be
+/// resumed. Either way it's gone afterwards, so it can only be resumed once.
+pub fn resume_game(lang: Lang, profile: Option<&str>) -> Option<(Game, Duration)> {
+    let path = scores::save_path(profile)?;
+    let restored = match SavedGame::load(&path) {
+        Ok(None) => {
+            println!("{}", t!(lang, "save.none"));
+            return None;
+        }
+        Ok(Some(saved)) => saved.restore(),
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => None,
+        Err(e) => {
+            let path = path.display();
+            eprintln!("{}", t!(lang, "save.cant-read", path = path, error = e));
+            return None;
+        }
+    };
+    if let Err(e) = fs::remove_file(&path) {
+        let path = path.display();
+        eprintln!("{}", t!(lang, "save.cant-remove", path = path, error = e));
+    }
+    if restored.is_none() {
+        println!("{}", t!(lang, "save.changed"));
+    }
+    restored
+}
+
+/// Prints each guess so far and what it told the player.
+fn show_history(lang: Lang, game: &Game) {
+    if game.history().turns().is_empty() {
+        println!("{}", t!(lang, "history.empty"));
+    }
+    for (turn, &(guess, outcome)) in game.history().turns().iter().enumerate() {
+        let turn = format!("{:>3}", turn + 1);
+        let feedback = outcome.localize(lang);
+        println!(
+            "{}",
+            t!(
+                lang,
+                "history.turn",
+                turn = turn,
+                guess = guess,
+                feedback = feedback
+            )
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! The other kinds of game: guessing digits, decimals, hot and cold or
+//! words, and the computer guessing.
+
+use std::fmt;
+use std::time::Instant;
+
+use rand::StdRng;
+
+use super::game::new_game;
+use super::{player, read_line, Input, Played};
+use crate::digits::{Code, Digits, CODE_LENGTH};
+use crate::feedback::Proximity;
+use crate::hangman::{self, Hangman, Letter};
+use crate::reverse::{Answer, Guesser};
+use crate::scores::Score;
+use crate::{bot, number};
+use crate::{opening, pick_secret, Classic, Command, Config, Feedback, Game, Lang, Localize};
+use crate::{Number, Outcome, Round, State, Strategy};
+
+/// Plays `round` at the terminal until it's over or the player quits. Works
+/// for any kind of game, with the feedback saying how guesses are read and
+/// judged.
+fn play_round<F>(lang: Lang, round: &mut Round<F>)
+where
+    F: Feedback,
+    F::Guess: fmt::Display,
+    F::Reply: Localize,
+{
+    while round.state() == State::Playing {
+        println!("{}", t!(lang, "round.prompt"));
+        let Some(line) = read_line() else {
+            return;
+        };
+        if line.trim().eq_ignore_ascii_case("quit") {
+            round.give_up();
+            return;
+        }
+        let guess = match round.feedback().parse_guess(&line) {
+            Ok(guess) => guess,
+            Err(e) => {
+                println!("{}", e.localize(lang));
+                continue;
+            }
+        };
+        println!("{}", t!(lang, "play.guessed", guess = guess));
+        if let Some(reply) = round.guess(&guess) {
+            println!("{}", reply.localize(lang));
+        }
+        match round.remaining() {
+            Some(0) => println!("{}", t!(lang, "round.out-of-guesses")),
+            Some(remaining) => println!("{}", t!(lang, "play.guesses-left", count = remaining)),
+            None => {}
+        }
+    }
+}
+
+/// Plays a game of guessing a code of digits, returning whether it was won.
+pub fn play_digits(config: &Config, rng: &mut StdRng) -> bool {
+    let lang = config.lang.unwrap_or_default();
+    let code = Code::random(rng);
+    println!("{}", t!(lang, "digits.opening", length = CODE_LENGTH));
+    if config.cheat {
+        println!("{}", t!(lang, "digits.cheat", code = code));
+    }
+    let mut round = Round::new(Digits { secret: code }).with_max_attempts(config.max_attempts);
+    play_round(lang, &mut round);
+    match round.state() {
+        State::Won => println!("{}", t!(lang, "digits.won", count = round.attempts())),
+        _ => println!("{}", t!(lang, "digits.lost", code = code)),
+    }
+    round.state() == State::Won
+}
+
+/// Plays a game of guessing a number with decimal places, returning
+/// whether it was won.
+pub fn play_float(config: &Config, rng: &mut StdRng) -> bool {
+    let lang = config.lang.unwrap_or_default();
+    let precision = config.precision;
+    let (min, max) = (config.min as f64, config.max as f64);
+    let secret = number::round(f64::pick(rng, min, max), precision);
+    let secret_text = format!("{secret:.*}", precision as usize);
+    println!(
+        "{}",
+        t!(
+            lang,
+            "float.opening",
+            count = precision,
+            min = min,
+            max = max
+        )
+    );
+    if config.cheat {
+        println!("{}", t!(lang, "play.cheat", secret = secret_text));
+    }
+    let classic = Classic::new(secret).with_tolerance(number::tolerance(precision));
+    let mut round = Round::new(classic).with_max_attempts(config.max_attempts);
+    play_round(lang, &mut round);
+    match round.state() {
+        State::Won => println!("{}", t!(lang, "float.won", count = round.attempts())),
+        _ => println!("{}", t!(lang, "play.number-was", secret = secret_text)),
+    }
+    round.state() == State::Won
+}
+
+/// Plays a game of hot and cold, told how close each guess is rather than
+/// which way it's off, returning whether it was won.
+pub fn play_proximity(config: &Config, rng: &mut StdRng) -> bool {
+    let lang = config.lang.unwrap_or_default();
+    let secret = pick_secret(rng, config.min, config.max);
+    let (min, max) = (config.min, config.max);
+    println!("{}", t!(lang, "proximity.opening", min = min, max = max));
+    if config.cheat {
+        println!("{}", t!(lang, "play.cheat", secret = secret));
+    }
+    let proximity = Proximity::new(secret, min, max);
+    let mut round = Round::new(proximity).with_max_attempts(config.max_attempts);
+    play_round(lang, &mut round);
+    match round.state() {
+        State::Won => println!("{}", t!(lang, "proximity.won", count = round.attempts())),
+        _ => println!("{}", t!(lang, "play.number-was", secret = secret)),
+    }
+    round.state() == State::Won
+}
+
+/// Has the computer play one game with `strategy`, explaining each guess.
+pub fn play_bot(config: &Config, strategy: Strategy, rng: &mut StdRng) -> Game {
+    let lang = config.lang.unwrap_or_default();
+    let mut game = new_game(config, rng);
+
+    print!("{}", opening(config, &game));
+    bot::play(&mut game, strategy, rng, |guess, (low, high), outcome| {
+        println!("{}", strategy.reason(lang, guess, low, high));
+        let key = match outcome {
+            Outcome::TooSmall => "outcome.too-small",
+            Outcome::TooBig => "outcome.too-big",
+            Outcome::Win => "bot.win",
+            Outcome::Lose => "round.out-of-guesses",
+        };
+        println!("{}", t!(lang, key));
+    });
+    match game.state() {
+        State::Won => {
+            let guesses = game.attempts();
+            println!(
+                "{}",
+                t!(lang, "bot.took", strategy = strategy, guesses = guesses)
+            );
+        }
+        _ => println!("{}", t!(lang, "play.number-was", secret = game.secret())),
+    }
+    game
+}
+
+/// Has the computer guess a number the player thinks of, returning whether
+/// it found it before the player quit or input ran out.
+pub fn play_reverse(config: &Config, strategy: Strategy, rng: &mut StdRng) -> bool {
+    let lang = config.lang.unwrap_or_default();
+    let (min, max) = (config.min, config.max);
+    println!("{}", t!(lang, "reverse.opening", min = min, max = max));
+    let mut guesser = Guesser::new(strategy, config.min, config.max);
+    while !guesser.found() {
+        let guess = guesser.guess(rng);
+        println!("{}", t!(lang, "reverse.ask", guess = guess));
+        loop {
+            let Some(line) = read_line() else {
+                return false;
+            };
+            if line.trim().eq_ignore_ascii_case("quit") {
+                return false;
+            }
+            let answer = match Answer::parse(&line) {
+                Ok(answer) => answer,
+                Err(_) => {
+                    println!("{}", t!(lang, "reverse.help"));
+                    continue;
+                }
+            };
+            match guesser.answer(guess, answer) {
+                Ok(()) => break,
+                Err(contradiction) => {
+                    println!("{}", contradiction.localize(lang));
+                    println!("{}", t!(lang, "reverse.again", guess = guess));
+                }
+            }
+        }
+    }
+    println!("{}", t!(lang, "reverse.got-it", count = guesser.guesses()));
+    true
+}
+
+/// Plays one word game, returning how it went once it's over, or `None` if
+/// the player quit or input ran out first.
+pub fn play_word(
+    config: &Config,
+    words: &[String],
+    rng: &mut StdRng,
+    input: &mut Input,
+) -> Option<Played> {
+    let lang = config.lang.unwrap_or_default();
+    let mut game = Hangman::new(hangman::pick_word(rng, words));
+    let letters = game.word().chars().count();
+    println!("{}", t!(lang, "words.opening", letters = letters));
+    if config.cheat {
+        println!("{}", t!(lang, "words.cheat", word = game.word()));
+    }
+
+    let start = Instant::now();
+    while game.state() == State::Playing {
+        println!("{}", hangman::gallows(game.wrong()));
+        println!("{}", game.masked());
+        let misses: String = game.misses().into_iter().collect();
+        if !misses.is_empty() {
+            println!("{}", t!(lang, "words.missed", misses = misses));
+        }
+        println!("{}", t!(lang, "words.prompt"));
+
+        let line = input.next_line()?;
+        let mut letters = line.trim().chars();
+        let letter = match (letters.next(), letters.next()) {
+            (Some(letter), None) if letter.is_alphabetic() => letter,
+            _ => match Command::parse(&line) {
+                Ok(Command::GiveUp) => {
+                    game.give_up();
+                    break;
+                }
+                Ok(Command::Quit) => return None,
+                _ => {
+                    println!("{}", t!(lang, "words.help"));
+                    continue;
+                }
+            },
+        };
+        let reply = match game.guess(letter) {
+            Letter::Found(found) => t!(lang, "words.found", count = found as u32, letter = letter),
+            Letter::Missing => t!(lang, "words.missing", letter = letter),
+            Letter::Repeated => t!(lang, "words.repeated", letter = letter),
+        };
+        println!("{reply}");
+    }
+
+    let score = match game.state() {
+        State::Won => {
+            println!("{}", game.masked());
+            println!("{}", t!(lang, "play.win"));
+            let score = Score::for_word(game.word(), game.guesses(), game.wrong(), start.elapsed());
+            Some(score.with_name(player(config)))
+        }
+        _ => {
+            println!("{}", hangman::gallows(game.wrong()));
+            println!("{}", t!(lang, "words.was", word = game.word()));
+            None
+        }
+    };
+    Some(Played {
+        guesses: game.guesses(),
+        difficulty: None,
+        seconds: start.elapsed().as_secs(),
+        score,
+    })
+}
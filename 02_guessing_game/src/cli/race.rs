@@ -0,0 +1,169 @@
+//! Racing another player over the network to guess the same secret.
+
+use std::io;
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+use rand::StdRng;
+
+use super::read_line;
+use crate::net::{self, Connection, Incoming, Message, Player};
+use crate::{opening, pick_secret, Command, Config, Game, Lang, Localize, Outcome};
+
+/// Something that happened during a network race.
+enum Event {
+    /// A line the local player typed, or `None` once they stop.
+    Typed(Option<String>),
+    /// A message from the other player, or `None` once they hang up.
+    Received(Option<Message>),
+}
+
+/// Forwards what the local player types and what the other player sends,
+/// as they happen, to one channel.
+fn listen(lang: Lang, mut incoming: Incoming) -> mpsc::Receiver<Event> {
+    let (sender, receiver) = mpsc::channel();
+    let typed = sender.clone();
+    thread::spawn(move || loop {
+        let line = read_line();
+        let done = line.is_none();
+        if typed.send(Event::Typed(line)).is_err() || done {
+            break;
+        }
+    });
+    thread::spawn(move || loop {
+        let message = incoming.receive().unwrap_or_else(|e| {
+            eprintln!("{}", t!(lang, "race.lost-connection", error = e));
+            None
+        });
+        let done = message.is_none();
+        if sender.send(Event::Received(message)).is_err() || done {
+            break;
+        }
+    });
+    receiver
+}
+
+fn unexpected(message: Message) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("unexpected message {message}"),
+    )
+}
+
+/// Hosts a race on `port`: waits for a guest to join, picks the secret and
+/// referees both players' guesses. Returns whether the local player won.
+pub fn play_host(config: &Config, port: u16, rng: &mut StdRng) -> io::Result<bool> {
+    let lang = config.lang.unwrap_or_default();
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    println!("{}", t!(lang, "race.waiting", port = port));
+    let Connection {
+        incoming,
+        mut outgoing,
+    } = net::accept(&listener, config.min, config.max)?;
+
+    let secret = pick_secret(rng, config.min, config.max);
+    let mut host = Game::new(secret).with_range(config.min, config.max);
+    let mut guest = Game::new(secret).with_range(config.min, config.max);
+    print!("{}", opening(config, &host));
+    println!("{}", t!(lang, "race.host-joined"));
+
+    for event in listen(lang, incoming) {
+        let (player, guess) = match event {
+            Event::Typed(line) => match line.as_deref().map(Command::parse) {
+                Some(Ok(Command::Guess(guess))) => (Player::Host, guess),
+                None | Some(Ok(Command::Quit | Command::GiveUp)) => {
+                    outgoing.send(Message::Bye)?;
+                    return Ok(false);
+                }
+                Some(_) => {
+                    println!("{}", t!(lang, "race.help"));
+                    continue;
+                }
+            },
+            Event::Received(Some(Message::Guess(guess))) => (Player::Guest, guess),
+            Event::Received(Some(Message::Bye) | None) => {
+                println!("{}", t!(lang, "race.left"));
+                return Ok(false);
+            }
+            Event::Received(Some(message)) => return Err(unexpected(message)),
+        };
+        let game = match player {
+            Player::Host => &mut host,
+            Player::Guest => &mut guest,
+        };
+        let outcome = game.guess(guess);
+        show_result(lang, Player::Host, player, guess, outcome);
+        outgoing.send(Message::Result(player, guess, outcome))?;
+        if outcome == Outcome::Win {
+            outgoing.send(Message::Won(player, secret))?;
+            show_winner(lang, Player::Host, player, secret);
+            return Ok(player == Player::Host);
+        }
+    }
+    Ok(false)
+}
+
+/// Joins the race hosted at `addr`, sending guesses and showing both
+/// players' results as the host reports them. Returns whether the local
+/// player won.
+pub fn play_guest(lang: Lang, addr: &str) -> io::Result<bool> {
+    println!("{}", t!(lang, "race.joining", addr = addr));
+    let (
+        Connection {
+            incoming,
+            mut outgoing,
+        },
+        min,
+        max,
+    ) = net::join(addr)?;
+    println!("{}", t!(lang, "play.opening", min = min, max = max));
+    println!("{}", t!(lang, "race.guest-joined"));
+
+    for event in listen(lang, incoming) {
+        match event {
+            Event::Typed(line) => match line.as_deref().map(Command::parse) {
+                Some(Ok(Command::Guess(guess))) => outgoing.send(Message::Guess(guess))?,
+                None | Some(Ok(Command::Quit | Command::GiveUp)) => {
+                    outgoing.send(Message::Bye)?;
+                    return Ok(false);
+                }
+                Some(_) => println!("{}", t!(lang, "race.help")),
+            },
+            Event::Received(Some(Message::Result(player, guess, outcome))) => {
+                show_result(lang, Player::Guest, player, guess, outcome)
+            }
+            Event::Received(Some(Message::Won(player, secret))) => {
+                show_winner(lang, Player::Guest, player, secret);
+                return Ok(player == Player::Guest);
+            }
+            Event::Received(Some(Message::Bye) | None) => {
+                println!("{}", t!(lang, "race.left"));
+                return Ok(false);
+            }
+            Event::Received(Some(message)) => return Err(unexpected(message)),
+        }
+    }
+    Ok(false)
+}
+
+/// Prints what `player`'s guess told them, as seen by `me`.
+fn show_result(lang: Lang, me: Player, player: Player, guess: i64, outcome: Outcome) {
+    let key = if player == me {
+        "race.you-guessed"
+    } else {
+        "race.they-guessed"
+    };
+    let feedback = outcome.localize(lang);
+    println!("{}", t!(lang, key, guess = guess, feedback = feedback));
+}
+
+/// Prints who won the race, as seen by `me`.
+fn show_winner(lang: Lang, me: Player, winner: Player, secret: i64) {
+    let key = if winner == me {
+        "race.you-win"
+    } else {
+        "race.they-win"
+    };
+    println!("{}", t!(lang, key, secret = secret));
+}
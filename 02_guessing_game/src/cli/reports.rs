@@ -0,0 +1,355 @@
+//! What's kept between games: high scores, stats, achievements and
+//! profiles, and how a session or simulation went.
+
+use std::io;
+use std::path::Path;
+
+use crate::achievements::{Achievements, Finished};
+use crate::profiles::{self, Action};
+use crate::records::{self, Record, Records};
+use crate::scores::{self, HighScores, Score};
+use crate::{Config, Lang, Localize, Session, Strategy};
+
+/// Prints how the bot did over a simulation.
+pub fn show_simulation(lang: Lang, strategy: Strategy, session: &Session) {
+    let average = format!("{:.2}", session.average_guesses().unwrap_or_default());
+    let summary = t!(
+        lang,
+        "simulation.summary",
+        strategy = strategy,
+        wins = session.wins,
+        games = session.games,
+        average = average,
+    );
+    println!("{summary}");
+    if let Some(best) = session.best {
+        println!("{}", t!(lang, "simulation.best", count = best));
+    }
+}
+
+/// Unlocks the achievements `game` earns, keeping them at `path`, and
+/// announces the new ones.
+pub fn show_achievements(lang: Lang, path: Option<&Path>, game: &Finished) {
+    let Some(path) = path else {
+        return;
+    };
+    let mut achievements = Achievements::load(path).unwrap_or_else(|e| {
+        let path = path.display();
+        eprintln!(
+            "{}",
+            t!(lang, "achievements.cant-read", path = path, error = e)
+        );
+        Achievements::default()
+    });
+    let unlocked = achievements.unlock(game);
+    if unlocked.is_empty() {
+        return;
+    }
+    for rule in unlocked {
+        // The rules name themselves in English; other languages translate
+        // them by id.
+        let name = lang.get(&format!("{}.name", rule.id()));
+        let description = lang.get(&format!("{}.description", rule.id()));
+        println!(
+            "{}",
+            t!(
+                lang,
+                "achievements.unlocked",
+                name = name.unwrap_or(rule.name()),
+                description = description.unwrap_or(rule.description()),
+            )
+        );
+    }
+    if let Err(e) = achievements.save(path) {
+        let path = path.display();
+        eprintln!(
+            "{}",
+            t!(lang, "achievements.cant-save", path = path, error = e)
+        );
+    }
+}
+
+/// Records a winning `score` in the high-score table at `path` and prints
+/// the table. Trouble with the file is reported but doesn't spoil the game.
+pub fn show_high_scores(lang: Lang, path: Option<&Path>, score: Option<Score>) {
+    let Some(path) = path else {
+        return;
+    };
+    let mut high_scores = HighScores::load(path).unwrap_or_else(|e| {
+        let path = path.display();
+        eprintln!("{}", t!(lang, "scores.cant-read", path = path, error = e));
+        HighScores::default()
+    });
+    if let Some(score) = score {
+        println!("{}", t!(lang, "scores.points", points = score.points));
+        if let Some(place) = high_scores.add(score) {
+            println!("{}", t!(lang, "scores.place", place = place));
+            if let Err(e) = high_scores.save(path) {
+                let path = path.display();
+                eprintln!("{}", t!(lang, "scores.cant-save", path = path, error = e));
+            }
+        }
+    }
+    if high_scores.entries().is_empty() {
+        return;
+    }
+    println!("{}", t!(lang, "scores.title"));
+    for (place, entry) in high_scores.entries().iter().enumerate() {
+        let secret = match &entry.word {
+            Some(word) => word.clone(),
+            None => t!(lang, "scores.range", min = entry.min, max = entry.max),
+        };
+        // Entries from before names were kept don't have one.
+        let key = if entry.name.is_some() {
+            "scores.named-entry"
+        } else {
+            "scores.entry"
+        };
+        println!(
+            "{}",
+            t!(
+                lang,
+                key,
+                name = entry.name.as_deref().unwrap_or_default(),
+                place = format!("{:>2}", place + 1),
+                points = format!("{:>4}", entry.points),
+                attempts = entry.attempts,
+                seconds = entry.seconds,
+                secret = secret,
+            )
+        );
+    }
+}
+
+/// Adds `record` to the games kept at `path` for `stats`.
+pub fn save_record(lang: Lang, path: &Path, record: Record) {
+    let mut records = Records::load(path).unwrap_or_else(|e| {
+        let path = path.display();
+        eprintln!("{}", t!(lang, "stats.cant-read", path = path, error = e));
+        Records::default()
+    });
+    records.add(record);
+    if let Err(e) = records.save(path) {
+        let path = path.display();
+        eprintln!("{}", t!(lang, "stats.cant-save", path = path, error = e));
+    }
+}
+
+/// Prints how the games kept for `stats` went: how many guesses each win
+/// took, how often each difficulty is won and how long a game takes.
+pub fn show_stats(lang: Lang, profile: Option<&str>) {
+    let records = match scores::records_path(profile) {
+        Some(path) => Records::load(&path).unwrap_or_else(|e| {
+            let path = path.display();
+            eprintln!("{}", t!(lang, "stats.cant-read", path = path, error = e));
+            Records::default()
+        }),
+        None => Records::default(),
+    };
+    let games = records.games();
+    if games.is_empty() {
+        println!("{}", t!(lang, "stats.none"));
+        return;
+    }
+    let played = games.len() as u32;
+    let won = games.iter().filter(|game| game.won).count() as u32;
+    let rate = records::percent(won, played);
+    println!(
+        "{}",
+        t!(lang, "stats.played", count = played, won = won, rate = rate)
+    );
+
+    let distribution = records.distribution();
+    if let Some(&most) = distribution.iter().max() {
+        println!();
+        println!("{}", t!(lang, "stats.distribution"));
+        let width = distribution.len().to_string().len();
+        for (i, &wins) in distribution.iter().enumerate() {
+            let guesses = format!("{:>width$}", i + 1);
+            let bar = format!("{:<1$}", records::bar(wins, most), records::BAR_WIDTH);
+            println!(
+                "{}",
+                t!(
+                    lang,
+                    "stats.guesses",
+                    guesses = guesses,
+                    bar = bar,
+                    wins = wins
+                )
+            );
+        }
+    }
+
+    println!();
+    println!("{}", t!(lang, "stats.by-difficulty"));
+    let rows: Vec<(String, u32, u32)> = records
+        .by_difficulty()
+        .into_iter()
+        .map(|(difficulty, played, won)| {
+            let name = match difficulty {
+                Some(difficulty) => difficulty.localize(lang),
+                None => t!(lang, "stats.custom"),
+            };
+            (name, played, won)
+        })
+        .collect();
+    let width = rows
+        .iter()
+        .map(|(name, _, _)| name.chars().count())
+        .max()
+        .unwrap_or_default();
+    for (name, played, won) in rows {
+        let difficulty = format!("{name:<width$}");
+        let bar = format!("{:<1$}", records::bar(won, played), records::BAR_WIDTH);
+        let rate = records::percent(won, played);
+        println!(
+            "{}",
+            t!(
+                lang,
+                "stats.rate",
+                difficulty = difficulty,
+                bar = bar,
+                rate = format!("{rate:>3}"),
+                won = won,
+                played = played
+            )
+        );
+    }
+
+    if let Some(seconds) = records.average_seconds() {
+        println!();
+        println!("{}", t!(lang, "stats.average", seconds = seconds));
+    }
+}
+
+/// Lists the profiles or deletes one, as `profiles` says. Returns whether
+/// it worked.
+pub fn manage_profiles(lang: Lang, action: &Action) -> bool {
+    let Some(dir) = scores::profiles_dir() else {
+        eprintln!("{}", t!(lang, "profiles.no-dir"));
+        return false;
+    };
+    match action {
+        Action::List => match profiles::list(&dir) {
+            Ok(names) if names.is_empty() => println!("{}", t!(lang, "profiles.none")),
+            Ok(names) => {
+                println!("{}", t!(lang, "profiles.title"));
+                for name in names {
+                    println!("  {name}");
+                }
+            }
+            Err(e) => {
+                let path = dir.display();
+                eprintln!("{}", t!(lang, "profiles.cant-read", path = path, error = e));
+                return false;
+            }
+        },
+        Action::Delete(name) => match profiles::delete(&dir, name) {
+            Ok(()) => println!("{}", t!(lang, "profiles.deleted", name = name)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                eprintln!("{}", t!(lang, "profiles.not-found", name = name));
+                return false;
+            }
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    t!(lang, "profiles.cant-delete", name = name, error = e)
+                );
+                return false;
+            }
+        },
+    }
+    true
+}
+
+/// Imports and then exports the high scores, as `--import-scores` and
+/// `--export-scores` say, showing the table after an import. Returns
+/// whether it all worked.
+pub fn transfer_scores(config: &Config) -> bool {
+    let lang = config.lang.unwrap_or_default();
+    let profile = config.profile.as_deref();
+    let table = if config.words {
+        scores::words_path(profile)
+    } else {
+        scores::default_path(profile)
+    };
+    let Some(table) = table else {
+        eprintln!("{}", t!(lang, "scores.no-table"));
+        return false;
+    };
+    let mut high_scores = match HighScores::load(&table) {
+        Ok(high_scores) => high_scores,
+        Err(e) => {
+            let path = table.display();
+            eprintln!("{}", t!(lang, "scores.cant-read", path = path, error = e));
+            return false;
+        }
+    };
+    if let Some(path) = &config.import_scores {
+        match high_scores.import(path) {
+            Ok(imported) => {
+                let path = path.display();
+                println!(
+                    "{}",
+                    t!(
+                        lang,
+                        "scores.imported",
+                        count = imported as u32,
+                        path = path
+                    )
+                );
+            }
+            Err(e) => {
+                let path = path.display();
+                eprintln!("{}", t!(lang, "scores.cant-import", path = path, error = e));
+                return false;
+            }
+        }
+        if let Err(e) = high_scores.save(&table) {
+            let path = table.display();
+            eprintln!("{}", t!(lang, "scores.cant-save", path = path, error = e));
+            return false;
+        }
+        show_high_scores(lang, Some(&table), None);
+    }
+    if let Some(path) = &config.export_scores {
+        match high_scores.export(path) {
+            Ok(exported) => {
+                let path = path.display();
+                println!(
+                    "{}",
+                    t!(
+                        lang,
+                        "scores.exported",
+                        count = exported as u32,
+                        path = path
+                    )
+                );
+            }
+            Err(e) => {
+                let path = path.display();
+                eprintln!("{}", t!(lang, "scores.cant-export", path = path, error = e));
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Prints what happened over all the games played.
+pub fn show_session(lang: Lang, session: &Session) {
+    let Some(average) = session.average_guesses() else {
+        return;
+    };
+    let summary = t!(
+        lang,
+        "session.summary",
+        count = session.games,
+        wins = session.wins,
+        average = format!("{average:.1}"),
+    );
+    println!("{summary}");
+    if let Some(best) = session.best {
+        println!("{}", t!(lang, "session.best", best = best));
+    }
+}
@@ -0,0 +1,173 @@
+use std::num::IntErrorKind;
+
+use crate::{Lang, Localize};
+
+/// Something the player typed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Guess(i64),
+    Hint,
+    /// Show the guesses made so far.
+    History,
+    /// Show the range the guesses so far leave.
+    Range,
+    /// Reveal the number and end the game.
+    GiveUp,
+    /// Stop playing altogether.
+    Quit,
+}
+
+/// What to tell the player when they type something else.
+pub const HELP: &str = "Type a number, or one of hint, history, range, giveup or quit.";
+
+/// How many lines in a row can go wrong before the player is offered help.
+pub const HELP_AFTER: u32 = 3;
+
+/// Why a line of input isn't a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputError {
+    /// Nothing but whitespace was typed.
+    Empty,
+    /// What was typed is neither a number nor a command.
+    NotANumber(String),
+    /// A number too far from zero to be any guess.
+    Overflow(String),
+    /// A guess outside the range the secret was picked from.
+    OutOfRange { guess: i64, min: i64, max: i64 },
+}
+
+impl Localize for InputError {
+    /// What went wrong, like "fifty isn't a number or a command."
+    fn localize(&self, lang: Lang) -> String {
+        match self {
+            InputError::Empty => t!(lang, "input.empty"),
+            InputError::NotANumber(input) => t!(lang, "input.not-a-number", input = input),
+            InputError::Overflow(input) => t!(lang, "input.overflow", input = input),
+            InputError::OutOfRange { guess, min, max } => t!(
+                lang,
+                "input.out-of-range",
+                guess = guess,
+                min = min,
+                max = max
+            ),
+        }
+    }
+}
+
+impl Command {
+    /// Parses a line of input, ignoring case and surrounding whitespace.
+    pub fn parse(input: &str) -> Result<Command, InputError> {
+        let input = input.trim();
+        match input.to_ascii_lowercase().as_str() {
+            "" => Err(InputError::Empty),
+            "hint" => Ok(Command::Hint),
+            "history" => Ok(Command::History),
+            "range" => Ok(Command::Range),
+            "giveup" | "give up" => Ok(Command::GiveUp),
+            "quit" | "exit" => Ok(Command::Quit),
+            number => number
+                .parse()
+                .map(Command::Guess)
+                .map_err(|err| match err.kind() {
+                    IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                        InputError::Overflow(input.to_string())
+                    }
+                    _ => InputError::NotANumber(input.to_string()),
+                }),
+        }
+    }
+
+    /// Parses a line of input like [`Command::parse`], also turning away
+    /// guesses outside `min..=max`.
+    pub fn parse_within(input: &str, min: i64, max: i64) -> Result<Command, InputError> {
+        match Command::parse(input)? {
+            Command::Guess(guess) if guess < min || guess > max => {
+                Err(InputError::OutOfRange { guess, min, max })
+            }
+            command => Ok(command),
+        }
+    }
+}
+
+/// Counts the lines in a row that weren't commands, to tell when the player
+/// seems stuck.
+#[derive(Debug, Default)]
+pub struct Retries(u32);
+
+impl Retries {
+    /// Counts another bad line, returning whether it's time to offer help.
+    pub fn fail(&mut self) -> bool {
+        self.0 += 1;
+        self.0.is_multiple_of(HELP_AFTER)
+    }
+
+    /// Starts counting again after a line that worked.
+    pub fn reset(&mut self) {
+        self.0 = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_guesses() {
+        assert_eq!(Ok(Command::Guess(42)), Command::parse("42\n"));
+        assert_eq!(Ok(Command::Guess(-7)), Command::parse("  -7 "));
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(Ok(Command::Hint), Command::parse("hint\n"));
+        assert_eq!(Ok(Command::History), Command::parse("History"));
+        assert_eq!(Ok(Command::Range), Command::parse("RANGE"));
+        assert_eq!(Ok(Command::GiveUp), Command::parse("give up"));
+        assert_eq!(Ok(Command::GiveUp), Command::parse("giveup"));
+        assert_eq!(Ok(Command::Quit), Command::parse("quit"));
+    }
+
+    #[test]
+    fn rejects_anything_else() {
+        assert_eq!(
+            Err(InputError::NotANumber("Fifty".to_string())),
+            Command::parse(" Fifty\n")
+        );
+        assert_eq!(Err(InputError::Empty), Command::parse("  \n"));
+        assert_eq!(
+            Err(InputError::Overflow("-10000000000000000000".to_string())),
+            Command::parse("-10000000000000000000")
+        );
+    }
+
+    #[test]
+    fn rejects_guesses_out_of_range() {
+        assert_eq!(
+            Ok(Command::Guess(100)),
+            Command::parse_within("100", 1, 100)
+        );
+        assert_eq!(Ok(Command::Hint), Command::parse_within("hint", 1, 100));
+        assert_eq!(
+            Err(InputError::OutOfRange {
+                guess: 101,
+                min: 1,
+                max: 100
+            }),
+            Command::parse_within("101", 1, 100)
+        );
+        assert_eq!(Err(InputError::Empty), Command::parse_within("", 1, 100));
+    }
+
+    #[test]
+    fn offers_help_every_few_retries() {
+        let mut retries = Retries::default();
+        assert!(!retries.fail());
+        assert!(!retries.fail());
+        assert!(retries.fail());
+        assert!(!retries.fail());
+        retries.reset();
+        assert!(!retries.fail());
+        assert!(!retries.fail());
+        assert!(retries.fail());
+    }
+}
@@ -0,0 +1,936 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::feedback::Style;
+use crate::profiles::{self, Action, NameError};
+use crate::{number, Lang, Localize, Strategy};
+
+/// A preset range and number of guesses (`--difficulty`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn parse(value: &str) -> Result<Self, ConfigError> {
+        match value {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(ConfigError::UnknownDifficulty(value.to_string())),
+        }
+    }
+
+    /// The smallest and largest number the secret can be.
+    pub fn range(self) -> (i64, i64) {
+        match self {
+            Difficulty::Easy => (1, 50),
+            Difficulty::Normal => (1, 100),
+            Difficulty::Hard => (1, 1000),
+        }
+    }
+
+    /// How many guesses the player gets.
+    pub fn max_attempts(self) -> u32 {
+        match self {
+            Difficulty::Easy => 10,
+            Difficulty::Normal => 7,
+            Difficulty::Hard => 10,
+        }
+    }
+}
+
+impl Localize for Difficulty {
+    fn localize(&self, lang: Lang) -> String {
+        match self {
+            Difficulty::Easy => t!(lang, "difficulty.easy"),
+            Difficulty::Normal => t!(lang, "difficulty.normal"),
+            Difficulty::Hard => t!(lang, "difficulty.hard"),
+        }
+    }
+}
+
+/// What a flag given without its value needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Needs {
+    Number,
+    Guesses,
+    Strategy,
+    Style,
+    Name,
+    Profile,
+    File,
+    Language,
+    Address,
+    Level,
+}
+
+/// Why the command line doesn't set up a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// `flag` came without a value, or with one that isn't what it needs.
+    Needs {
+        flag: &'static str,
+        needs: Needs,
+    },
+    /// One of the `--guesses` isn't a number.
+    BadGuess(String),
+    /// An argument that isn't any of the flags.
+    Unknown(String),
+    /// `profiles` followed by something other than `list` or `delete`.
+    UnknownProfilesCommand(String),
+    UnknownDifficulty(String),
+    UnknownStrategy(String),
+    UnknownStyle(String),
+    UnknownLanguage(String),
+    /// A `--profile` or `profiles delete` name that can't be used.
+    Profile(NameError),
+    /// `--min` isn't less than `--max`.
+    BadRange {
+        min: i64,
+        max: i64,
+    },
+    /// `--precision` is more than [`number::MAX_PRECISION`].
+    TooPrecise,
+    /// `--simulate 0`.
+    NoGames,
+    /// `--rounds 0`.
+    NoRounds,
+    /// Two things were asked for that can't go together.
+    Conflict {
+        flag: &'static str,
+        other: &'static str,
+    },
+    /// `--daily` with a `--seed`.
+    DailySeed,
+    /// `--rounds` with a `--difficulty`, `--min` or `--max`.
+    RoundsDifficulty,
+}
+
+impl Localize for ConfigError {
+    fn localize(&self, lang: Lang) -> String {
+        match self {
+            ConfigError::Needs { flag, needs } => {
+                let key = match needs {
+                    Needs::Number => "args.needs-number",
+                    Needs::Guesses => "args.needs-guesses",
+                    Needs::Strategy => "args.needs-strategy",
+                    Needs::Style => "args.needs-style",
+                    Needs::Name => "args.needs-name",
+                    Needs::Profile => "args.needs-profile",
+                    Needs::File => "args.needs-file",
+                    Needs::Language => "args.needs-language",
+                    Needs::Address => "args.needs-address",
+                    Needs::Level => "args.needs-level",
+                };
+                t!(lang, key, flag = flag)
+            }
+            ConfigError::BadGuess(guess) => t!(lang, "args.bad-guess", guess = guess),
+            ConfigError::Unknown(arg) => t!(lang, "args.unknown", arg = arg),
+            ConfigError::UnknownProfilesCommand(command) => {
+                t!(lang, "args.unknown-profiles-command", command = command)
+            }
+            ConfigError::UnknownDifficulty(value) => {
+                t!(lang, "args.unknown-difficulty", value = value)
+            }
+            ConfigError::UnknownStrategy(value) => t!(lang, "args.unknown-bot", value = value),
+            ConfigError::UnknownStyle(value) => t!(lang, "args.unknown-feedback", value = value),
+            ConfigError::UnknownLanguage(value) => {
+                t!(lang, "args.unknown-language", value = value)
+            }
+            ConfigError::Profile(e) => e.localize(lang),
+            ConfigError::BadRange { min, max } => t!(lang, "args.bad-range", min = min, max = max),
+            ConfigError::TooPrecise => {
+                t!(lang, "args.too-precise", most = number::MAX_PRECISION)
+            }
+            ConfigError::NoGames => t!(lang, "args.no-games"),
+            ConfigError::NoRounds => t!(lang, "args.no-rounds"),
+            ConfigError::Conflict { flag, other } => {
+                t!(lang, "args.conflict", flag = flag, other = other)
+            }
+            ConfigError::DailySeed => t!(lang, "args.daily-seed"),
+            ConfigError::RoundsDifficulty => t!(lang, "args.rounds-difficulty"),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Lang::En))
+    }
+}
+
+impl From<NameError> for ConfigError {
+    fn from(e: NameError) -> Self {
+        ConfigError::Profile(e)
+    }
+}
+
+/// How a game is set up from the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// The smallest number the secret can be (`--min`).
+    pub min: i64,
+    /// The largest number the secret can be (`--max`).
+    pub max: i64,
+    /// How many guesses the player gets, or `None` for as many as it takes.
+    pub max_attempts: Option<u32>,
+    /// The preset the range and guesses came from (`--difficulty`).
+    pub difficulty: Option<Difficulty>,
+    /// Seeds the random number generator so the same secrets come up every
+    /// time (`--seed`).
+    pub seed: Option<u64>,
+    /// Show the secret number when the game starts (`--cheat`, `--debug`).
+    pub cheat: bool,
+    /// Play these guesses instead of asking for them (`--guesses 50,25,37`).
+    pub guesses: Option<Vec<i64>>,
+    /// Let the computer play instead (`--bot binary`).
+    pub bot: Option<Strategy>,
+    /// Have the bot play this many games and report how it did
+    /// (`--simulate 1000`).
+    pub simulate: Option<u32>,
+    /// Have the computer guess a number the player thinks of
+    /// (`--reverse`), using the `--bot` strategy.
+    pub reverse: bool,
+    /// Race another player to the secret, waiting for them on this port
+    /// (`--host 7878`).
+    pub host: Option<u16>,
+    /// Race the player hosting a game at this address
+    /// (`--join 192.168.1.2:7878`).
+    pub join: Option<String>,
+    /// Guess a code of different digits instead of a number, told how many
+    /// are in the right place and how many are misplaced (`--digits`).
+    pub digits: bool,
+    /// Guess a word letter by letter before the gallows are finished
+    /// (`--words`).
+    pub words: bool,
+    /// Pick the words from this file, one per line, instead of the
+    /// built-in list (`--wordlist words.txt`). Implies `--words`.
+    pub wordlist: Option<PathBuf>,
+    /// Guess a number with decimal places between `min` and `max`
+    /// (`--float`).
+    pub float: bool,
+    /// How many decimal places `--float` guesses are judged to
+    /// (`--precision 2`). Implies `--float`.
+    pub precision: u32,
+    /// What each guess is told (`--feedback proximity`).
+    pub feedback: Style,
+    /// Play today's challenge, with the same secret for everyone
+    /// (`--daily`).
+    pub daily: bool,
+    /// Carry on with the game put aside by quitting (`--resume`).
+    pub resume: bool,
+    /// Play a tournament of this many games, each harder than the last,
+    /// adding up their scores (`--rounds 3`).
+    pub rounds: Option<u32>,
+    /// Draw the game as a frame that updates after every guess, instead of
+    /// a line at a time (`--tui`).
+    pub tui: bool,
+    /// Who's playing (`--name ada`), credited with their high scores.
+    pub name: Option<String>,
+    /// The profile whose high scores, stats and achievements to keep
+    /// (`--profile ada`), or `None` for the ones shared by everyone.
+    pub profile: Option<String>,
+    /// Write the high scores to this file and stop (`--export-scores
+    /// scores.json`). With `--words`, the word game's scores.
+    pub export_scores: Option<PathBuf>,
+    /// Merge the high scores exported to this file into the table and stop
+    /// (`--import-scores scores.json`), keeping each player's best.
+    pub import_scores: Option<PathBuf>,
+    /// Add each number game played to the end of this transcript
+    /// (`--record replay.log`).
+    pub record: Option<PathBuf>,
+    /// Play back the games recorded in this transcript, turn by turn, and
+    /// stop (`--replay replay.log`).
+    pub replay: Option<PathBuf>,
+    /// The language to play in (`--lang es`), or `None` to go by the
+    /// locale.
+    pub lang: Option<Lang>,
+    /// Show how the games played so far went and stop (`stats`).
+    pub stats: bool,
+    /// List the profiles, or delete one, and stop (`profiles`,
+    /// `profiles delete ada`).
+    pub profiles: Option<Action>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            min: 1,
+            max: 100,
+            max_attempts: None,
+            difficulty: None,
+            seed: None,
+            cheat: false,
+            guesses: None,
+            bot: None,
+            simulate: None,
+            reverse: false,
+            host: None,
+            join: None,
+            digits: false,
+            words: false,
+            wordlist: None,
+            float: false,
+            precision: number::DEFAULT_PRECISION,
+            feedback: Style::Classic,
+            daily: false,
+            resume: false,
+            rounds: None,
+            tui: false,
+            name: None,
+            profile: None,
+            export_scores: None,
+            import_scores: None,
+            record: None,
+            replay: None,
+            lang: None,
+            stats: false,
+            profiles: None,
+        }
+    }
+}
+
+impl Config {
+    /// Parses the arguments after the program name, like
+    /// `--min 1 --max 1000` or `--difficulty hard`. `--min` and `--max`
+    /// override the range a difficulty sets. A first argument of `stats`
+    /// or `profiles` asks for that instead of a game.
+    pub fn parse(args: impl Iterator<Item = String>) -> Result<Config, ConfigError> {
+        let mut config = Config::default();
+        let mut args = args.peekable();
+        match args
+            .next_if(|arg| arg == "stats" || arg == "profiles")
+            .as_deref()
+        {
+            Some("stats") => config.stats = true,
+            Some("profiles") => {
+                let action = match args.next_if(|arg| !arg.starts_with("--")).as_deref() {
+                    None | Some("list") => Action::List,
+                    Some("delete") => {
+                        let name = next_value(&mut args, "profiles delete", Needs::Profile)?;
+                        Action::Delete(profiles::validate(&name)?)
+                    }
+                    Some(other) => {
+                        return Err(ConfigError::UnknownProfilesCommand(other.to_string()))
+                    }
+                };
+                config.profiles = Some(action);
+            }
+            _ => {}
+        }
+        let (mut min, mut max) = (None, None);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--min" => min = Some(next_number(&mut args, "--min")?),
+                "--max" => max = Some(next_number(&mut args, "--max")?),
+                "--seed" => config.seed = Some(next_number(&mut args, "--seed")?),
+                "--cheat" | "--debug" => config.cheat = true,
+                "--guesses" => {
+                    let list = next_value(&mut args, "--guesses", Needs::Guesses)?;
+                    config.guesses = Some(parse_guesses(&list)?);
+                }
+                "--bot" => {
+                    let value = next_value(&mut args, "--bot", Needs::Strategy)?;
+                    config.bot = Some(Strategy::parse(&value)?);
+                }
+                "--simulate" => config.simulate = Some(next_number(&mut args, "--simulate")?),
+                "--reverse" => config.reverse = true,
+                "--digits" => config.digits = true,
+                "--words" => config.words = true,
+                "--float" => config.float = true,
+                "--tui" => config.tui = true,
+                "--precision" => {
+                    config.precision = next_number(&mut args, "--precision")?;
+                    config.float = true;
+                }
+                "--feedback" => {
+                    let value = next_value(&mut args, "--feedback", Needs::Style)?;
+                    config.feedback = Style::parse(&value)?;
+                }
+                "--daily" => config.daily = true,
+                "--resume" => config.resume = true,
+                "--rounds" => config.rounds = Some(next_number(&mut args, "--rounds")?),
+                "--name" => {
+                    let name = args.next().filter(|name| !name.trim().is_empty());
+                    let name = name.ok_or(ConfigError::Needs {
+                        flag: "--name",
+                        needs: Needs::Name,
+                    })?;
+                    config.name = Some(name.trim().to_string());
+                }
+                "--profile" => {
+                    let name = next_value(&mut args, "--profile", Needs::Name)?;
+                    config.profile = Some(profiles::validate(&name)?);
+                }
+                "--export-scores" => {
+                    let path = next_value(&mut args, "--export-scores", Needs::File)?;
+                    config.export_scores = Some(PathBuf::from(path));
+                }
+                "--import-scores" => {
+                    let path = next_value(&mut args, "--import-scores", Needs::File)?;
+                    config.import_scores = Some(PathBuf::from(path));
+                }
+                "--record" => {
+                    let path = next_value(&mut args, "--record", Needs::File)?;
+                    config.record = Some(PathBuf::from(path));
+                }
+                "--replay" => {
+                    let path = next_value(&mut args, "--replay", Needs::File)?;
+                    config.replay = Some(PathBuf::from(path));
+                }
+                "--wordlist" => {
+                    let path = next_value(&mut args, "--wordlist", Needs::File)?;
+                    config.wordlist = Some(PathBuf::from(path));
+                    config.words = true;
+                }
+                "--lang" => {
+                    let value = next_value(&mut args, "--lang", Needs::Language)?;
+                    config.lang = Some(Lang::parse(&value)?);
+                }
+                "--host" => config.host = Some(next_number(&mut args, "--host")?),
+                "--join" => config.join = Some(next_value(&mut args, "--join", Needs::Address)?),
+                "--difficulty" => {
+                    let value = next_value(&mut args, "--difficulty", Needs::Level)?;
+                    let difficulty = Difficulty::parse(&value)?;
+                    (config.min, config.max) = difficulty.range();
+                    config.max_attempts = Some(difficulty.max_attempts());
+                    config.difficulty = Some(difficulty);
+                }
+                _ => return Err(ConfigError::Unknown(arg)),
+            }
+        }
+        config.min = min.unwrap_or(config.min);
+        config.max = max.unwrap_or(config.max);
+        if config.min >= config.max {
+            return Err(ConfigError::BadRange {
+                min: config.min,
+                max: config.max,
+            });
+        }
+        if config.precision > number::MAX_PRECISION {
+            return Err(ConfigError::TooPrecise);
+        }
+        if config.simulate == Some(0) {
+            return Err(ConfigError::NoGames);
+        }
+        // Only one way of playing at a time, though --bot picks how the
+        // computer plays with --simulate and --reverse.
+        let modes: Vec<&'static str> = [
+            ("--guesses", config.guesses.is_some()),
+            ("--simulate", config.simulate.is_some()),
+            ("--reverse", config.reverse),
+            ("--host", config.host.is_some()),
+            ("--join", config.join.is_some()),
+            ("--digits", config.digits),
+            ("--words", config.words),
+            ("--float", config.float),
+            ("--feedback", config.feedback != Style::Classic),
+            ("--tui", config.tui),
+        ]
+        .into_iter()
+        .filter_map(|(flag, given)| given.then_some(flag))
+        .collect();
+        if let [first, second, ..] = modes[..] {
+            return Err(ConfigError::Conflict {
+                flag: first,
+                other: second,
+            });
+        }
+        if let (
+            Some(_),
+            ["--guesses" | "--host" | "--join" | "--digits" | "--words" | "--float" | "--feedback"
+            | "--tui"],
+        ) = (config.bot, &modes[..])
+        {
+            return Err(ConfigError::Conflict {
+                flag: "--bot",
+                other: modes[0],
+            });
+        }
+        if config.daily && config.seed.is_some() {
+            return Err(ConfigError::DailySeed);
+        }
+        if config.rounds == Some(0) {
+            return Err(ConfigError::NoRounds);
+        }
+        if config.rounds.is_some()
+            && (config.difficulty.is_some() || min.is_some() || max.is_some())
+        {
+            return Err(ConfigError::RoundsDifficulty);
+        }
+        // The daily challenge, resumed games and tournaments are only for
+        // the number game, played by hand, though it can be drawn with
+        // --tui.
+        let special = [
+            ("--daily", config.daily),
+            ("--resume", config.resume),
+            ("--rounds", config.rounds.is_some()),
+        ];
+        let special: Vec<&'static str> = special
+            .into_iter()
+            .filter_map(|(flag, given)| given.then_some(flag))
+            .collect();
+        if let [first, ..] = special[..] {
+            if let Some(mode) = special[1..]
+                .iter()
+                .chain(&modes)
+                .chain(config.bot.is_some().then_some(&"--bot"))
+                .find(|&&mode| mode != "--guesses" && mode != "--tui")
+            {
+                return Err(ConfigError::Conflict {
+                    flag: first,
+                    other: mode,
+                });
+            }
+        }
+        // Exporting and importing don't play a game, though --words picks
+        // which table.
+        let transfers = [
+            ("--export-scores", config.export_scores.is_some()),
+            ("--import-scores", config.import_scores.is_some()),
+        ];
+        if let Some((first, _)) = transfers.into_iter().find(|&(_, given)| given) {
+            if let Some(mode) = special
+                .iter()
+                .chain(&modes)
+                .chain(config.bot.is_some().then_some(&"--bot"))
+                .chain(config.record.is_some().then_some(&"--record"))
+                .find(|&&mode| mode != "--words")
+            {
+                return Err(ConfigError::Conflict {
+                    flag: first,
+                    other: mode,
+                });
+            }
+        }
+        // Only the number game is recorded, however it's played.
+        if config.record.is_some() {
+            if let Some(mode) = modes
+                .iter()
+                .chain(config.bot.is_some().then_some(&"--bot"))
+                .find(|&&mode| mode != "--guesses" && mode != "--tui")
+            {
+                return Err(ConfigError::Conflict {
+                    flag: "--record",
+                    other: mode,
+                });
+            }
+        }
+        // Replaying doesn't play a game either.
+        if config.replay.is_some() {
+            if let Some(mode) = special
+                .iter()
+                .chain(&modes)
+                .chain(config.bot.is_some().then_some(&"--bot"))
+                .chain(config.record.is_some().then_some(&"--record"))
+                .chain(
+                    transfers
+                        .iter()
+                        .filter(|(_, given)| *given)
+                        .map(|(flag, _)| flag),
+                )
+                .next()
+            {
+                return Err(ConfigError::Conflict {
+                    flag: "--replay",
+                    other: mode,
+                });
+            }
+        }
+        // Nor does showing the statistics or the profiles, though the
+        // statistics can be a profile's.
+        let command = match (config.stats, &config.profiles) {
+            (true, _) => Some("stats"),
+            (false, Some(_)) => Some("profiles"),
+            (false, None) => None,
+        };
+        if let Some(command) = command {
+            if let Some(mode) = special
+                .iter()
+                .chain(&modes)
+                .chain(config.bot.is_some().then_some(&"--bot"))
+                .chain(config.record.is_some().then_some(&"--record"))
+                .chain(config.replay.is_some().then_some(&"--replay"))
+                .chain(
+                    transfers
+                        .iter()
+                        .filter(|(_, given)| *given)
+                        .map(|(flag, _)| flag),
+                )
+                .chain((command == "profiles" && config.profile.is_some()).then_some(&"--profile"))
+                .next()
+            {
+                return Err(ConfigError::Conflict {
+                    flag: command,
+                    other: mode,
+                });
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Parses a comma-separated list of guesses.
+fn parse_guesses(list: &str) -> Result<Vec<i64>, ConfigError> {
+    list.split(',')
+        .map(|guess| {
+            guess
+                .trim()
+                .parse()
+                .map_err(|_| ConfigError::BadGuess(guess.to_string()))
+        })
+        .collect()
+}
+
+/// Reads the value following `flag`.
+fn next_value(
+    args: &mut impl Iterator<Item = String>,
+    flag: &'static str,
+    needs: Needs,
+) -> Result<String, ConfigError> {
+    args.next().ok_or(ConfigError::Needs { flag, needs })
+}
+
+/// Reads the number following `flag`.
+fn next_number<T: FromStr>(
+    args: &mut impl Iterator<Item = String>,
+    flag: &'static str,
+) -> Result<T, ConfigError> {
+    args.next()
+        .and_then(|value| value.parse().ok())
+        .ok_or(ConfigError::Needs {
+            flag,
+            needs: Needs::Number,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Config, String> {
+        Config::parse(args.iter().map(|arg| arg.to_string())).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn defaults_to_one_to_a_hundred() {
+        assert_eq!(Ok(Config::default()), parse(&[]));
+        assert_eq!((1, 100), (Config::default().min, Config::default().max));
+    }
+
+    #[test]
+    fn parses_the_range() {
+        let config = parse(&["--min", "-5", "--max", "1000"]).unwrap();
+        assert_eq!((-5, 1000), (config.min, config.max));
+        assert_eq!(None, config.max_attempts);
+    }
+
+    #[test]
+    fn difficulty_sets_range_and_attempts() {
+        let config = parse(&["--difficulty", "hard"]).unwrap();
+        assert_eq!((1, 1000), (config.min, config.max));
+        assert_eq!(Some(10), config.max_attempts);
+        assert_eq!(Some(Difficulty::Hard), config.difficulty);
+
+        let config = parse(&["--max", "20", "--difficulty", "easy"]).unwrap();
+        assert_eq!((1, 20), (config.min, config.max));
+        assert_eq!(Some(10), config.max_attempts);
+
+        assert!(parse(&["--difficulty", "impossible"]).is_err());
+        assert!(parse(&["--difficulty"]).is_err());
+    }
+
+    #[test]
+    fn parses_the_seed() {
+        assert_eq!(None, parse(&[]).unwrap().seed);
+        assert_eq!(Some(42), parse(&["--seed", "42"]).unwrap().seed);
+        assert!(parse(&["--seed", "-1"]).is_err());
+    }
+
+    #[test]
+    fn parses_scripted_guesses() {
+        assert_eq!(None, parse(&[]).unwrap().guesses);
+        assert_eq!(
+            Some(vec![50, 25, -37]),
+            parse(&["--guesses", "50, 25,-37"]).unwrap().guesses
+        );
+        assert!(parse(&["--guesses", "50,,25"]).is_err());
+        assert!(parse(&["--guesses"]).is_err());
+    }
+
+    #[test]
+    fn parses_cheat_flags() {
+        assert!(!parse(&[]).unwrap().cheat);
+        assert!(parse(&["--cheat"]).unwrap().cheat);
+        assert!(parse(&["--debug"]).unwrap().cheat);
+    }
+
+    #[test]
+    fn parses_bot_options() {
+        assert_eq!(None, parse(&[]).unwrap().bot);
+        let config = parse(&["--bot", "random", "--simulate", "1000"]).unwrap();
+        assert_eq!(Some(Strategy::Random), config.bot);
+        assert_eq!(Some(1000), config.simulate);
+        assert!(parse(&["--bot", "psychic"]).is_err());
+        assert!(parse(&["--simulate", "0"]).is_err());
+        assert!(parse(&["--bot", "binary", "--guesses", "50"]).is_err());
+    }
+
+    #[test]
+    fn parses_reverse_mode() {
+        assert!(!parse(&[]).unwrap().reverse);
+        let config = parse(&["--reverse", "--bot", "random"]).unwrap();
+        assert!(config.reverse);
+        assert_eq!(Some(Strategy::Random), config.bot);
+        assert!(parse(&["--reverse", "--guesses", "50"]).is_err());
+        assert!(parse(&["--reverse", "--simulate", "10"]).is_err());
+    }
+
+    #[test]
+    fn parses_network_options() {
+        assert_eq!(Some(7878), parse(&["--host", "7878"]).unwrap().host);
+        assert_eq!(
+            Some("localhost:7878".to_string()),
+            parse(&["--join", "localhost:7878"]).unwrap().join
+        );
+        assert!(parse(&["--host", "70000"]).is_err());
+        assert!(parse(&["--join"]).is_err());
+        assert!(parse(&["--host", "7878", "--join", "localhost:7878"]).is_err());
+        assert!(parse(&["--host", "7878", "--reverse"]).is_err());
+    }
+
+    #[test]
+    fn parses_digit_mode() {
+        assert!(!parse(&[]).unwrap().digits);
+        let config = parse(&["--digits", "--difficulty", "hard"]).unwrap();
+        assert!(config.digits);
+        assert_eq!(Some(10), config.max_attempts);
+        assert!(parse(&["--digits", "--reverse"]).is_err());
+    }
+
+    #[test]
+    fn parses_word_mode() {
+        assert!(!parse(&[]).unwrap().words);
+        assert!(parse(&["--words"]).unwrap().words);
+        let config = parse(&["--wordlist", "words.txt"]).unwrap();
+        assert!(config.words);
+        assert_eq!(Some(PathBuf::from("words.txt")), config.wordlist);
+        assert!(parse(&["--wordlist"]).is_err());
+        assert_eq!(
+            Err("--digits can't be used with --words".to_string()),
+            parse(&["--words", "--digits"])
+        );
+        assert_eq!(
+            Err("--bot can't be used with --words".to_string()),
+            parse(&["--bot", "binary", "--words"])
+        );
+    }
+
+    #[test]
+    fn parses_float_mode() {
+        assert!(!parse(&[]).unwrap().float);
+        let config = parse(&["--float"]).unwrap();
+        assert_eq!(number::DEFAULT_PRECISION, config.precision);
+        let config = parse(&["--precision", "3", "--max", "10"]).unwrap();
+        assert!(config.float);
+        assert_eq!(3, config.precision);
+        assert!(parse(&["--precision", "-1"]).is_err());
+        assert!(parse(&["--precision", "10"]).is_err());
+        assert_eq!(
+            Err("--digits can't be used with --float".to_string()),
+            parse(&["--float", "--digits"])
+        );
+        assert!(parse(&["--float", "--bot", "binary"]).is_err());
+        assert!(parse(&["--float", "--daily"]).is_err());
+    }
+
+    #[test]
+    fn parses_tui() {
+        assert!(!parse(&[]).unwrap().tui);
+        assert!(parse(&["--tui", "--difficulty", "hard"]).unwrap().tui);
+        assert!(parse(&["--tui", "--daily"]).unwrap().tui);
+        assert!(parse(&["--tui", "--resume"]).unwrap().tui);
+        assert_eq!(
+            Err("--guesses can't be used with --tui".to_string()),
+            parse(&["--tui", "--guesses", "50"])
+        );
+        assert!(parse(&["--tui", "--words"]).is_err());
+        assert!(parse(&["--tui", "--bot", "binary"]).is_err());
+    }
+
+    #[test]
+    fn parses_score_transfers() {
+        assert_eq!(
+            Some("ada".to_string()),
+            parse(&["--name", " ada "]).unwrap().name
+        );
+        assert!(parse(&["--name", " "]).is_err());
+        let config = parse(&["--export-scores", "out.json", "--words"]).unwrap();
+        assert_eq!(Some(PathBuf::from("out.json")), config.export_scores);
+        let config = parse(&["--import-scores", "in.json", "--export-scores", "out.json"]).unwrap();
+        assert_eq!(Some(PathBuf::from("in.json")), config.import_scores);
+        assert_eq!(
+            Err("--import-scores can't be used with --daily".to_string()),
+            parse(&["--import-scores", "in.json", "--daily"])
+        );
+        assert!(parse(&["--export-scores", "out.json", "--guesses", "5"]).is_err());
+        assert!(parse(&["--export-scores"]).is_err());
+    }
+
+    #[test]
+    fn parses_recording_and_replays() {
+        let config = parse(&["--record", "replay.log", "--daily", "--tui"]).unwrap();
+        assert_eq!(Some(PathBuf::from("replay.log")), config.record);
+        assert!(parse(&["--record", "replay.log", "--resume"]).is_ok());
+        assert_eq!(
+            Err("--record can't be used with --words".to_string()),
+            parse(&["--record", "replay.log", "--words"])
+        );
+        assert!(parse(&["--record", "replay.log", "--bot", "binary"]).is_err());
+        let config = parse(&["--replay", "replay.log"]).unwrap();
+        assert_eq!(Some(PathBuf::from("replay.log")), config.replay);
+        assert_eq!(
+            Err("--replay can't be used with --record".to_string()),
+            parse(&["--replay", "replay.log", "--record", "replay.log"])
+        );
+        assert_eq!(
+            Err("--replay can't be used with --export-scores".to_string()),
+            parse(&["--replay", "replay.log", "--export-scores", "out.json"])
+        );
+        assert!(parse(&["--replay", "replay.log", "--tui"]).is_err());
+        assert!(parse(&["--replay"]).is_err());
+    }
+
+    #[test]
+    fn parses_rounds() {
+        assert_eq!(Some(3), parse(&["--rounds", "3", "--tui"]).unwrap().rounds);
+        assert!(parse(&["--rounds", "0"]).is_err());
+        assert!(parse(&["--rounds", "three"]).is_err());
+        assert_eq!(
+            Err("--rounds picks the difficulty of each round".to_string()),
+            parse(&["--rounds", "3", "--difficulty", "hard"])
+        );
+        assert!(parse(&["--rounds", "3", "--max", "10"]).is_err());
+        assert_eq!(
+            Err("--resume can't be used with --rounds".to_string()),
+            parse(&["--rounds", "3", "--resume"])
+        );
+        assert_eq!(
+            Err("--rounds can't be used with --digits".to_string()),
+            parse(&["--rounds", "3", "--digits"])
+        );
+    }
+
+    #[test]
+    fn parses_profiles() {
+        assert_eq!(
+            Some("ada".to_string()),
+            parse(&["--profile", " ada "]).unwrap().profile
+        );
+        assert!(parse(&["--profile", "../ada"]).is_err());
+        assert!(parse(&["--profile"]).is_err());
+        assert!(parse(&["stats", "--profile", "ada"]).is_ok());
+        assert_eq!(Some(Action::List), parse(&["profiles"]).unwrap().profiles);
+        assert_eq!(
+            Some(Action::List),
+            parse(&["profiles", "list", "--lang", "es"])
+                .unwrap()
+                .profiles
+        );
+        assert_eq!(
+            Some(Action::Delete("ada".to_string())),
+            parse(&["profiles", "delete", "ada"]).unwrap().profiles
+        );
+        assert!(parse(&["profiles", "delete"]).is_err());
+        assert!(parse(&["profiles", "rename"]).is_err());
+        assert_eq!(
+            Err("profiles can't be used with --profile".to_string()),
+            parse(&["profiles", "--profile", "ada"])
+        );
+        assert!(parse(&["profiles", "--daily"]).is_err());
+    }
+
+    #[test]
+    fn parses_feedback() {
+        assert_eq!(Style::Classic, parse(&[]).unwrap().feedback);
+        let config = parse(&["--feedback", "proximity", "--difficulty", "hard"]).unwrap();
+        assert_eq!(Style::Proximity, config.feedback);
+        assert_eq!(
+            Style::Classic,
+            parse(&["--feedback", "classic", "--tui"]).unwrap().feedback
+        );
+        assert!(parse(&["--feedback", "lukewarm"]).is_err());
+        assert_eq!(
+            Err("--feedback can't be used with --tui".to_string()),
+            parse(&["--feedback", "proximity", "--tui"])
+        );
+        assert!(parse(&["--feedback", "proximity", "--bot", "binary"]).is_err());
+    }
+
+    #[test]
+    fn parses_stats() {
+        assert!(!parse(&[]).unwrap().stats);
+        assert!(parse(&["stats"]).unwrap().stats);
+        assert!(parse(&["stats", "--lang", "es"]).unwrap().stats);
+        assert_eq!(
+            Err("unknown argument stats".to_string()),
+            parse(&["--lang", "es", "stats"])
+        );
+        assert_eq!(
+            Err("stats can't be used with --daily".to_string()),
+            parse(&["stats", "--daily"])
+        );
+        assert!(parse(&["stats", "--replay", "replay.log"]).is_err());
+    }
+
+    #[test]
+    fn parses_daily_challenge() {
+        assert!(!parse(&[]).unwrap().daily);
+        assert!(parse(&["--daily", "--difficulty", "hard"]).unwrap().daily);
+        assert!(parse(&["--daily", "--guesses", "50"]).unwrap().daily);
+        assert!(parse(&["--daily", "--seed", "3"]).is_err());
+        assert_eq!(
+            Err("--daily can't be used with --words".to_string()),
+            parse(&["--daily", "--words"])
+        );
+        assert!(parse(&["--daily", "--bot", "binary"]).is_err());
+    }
+
+    #[test]
+    fn parses_resume() {
+        assert!(!parse(&[]).unwrap().resume);
+        assert!(parse(&["--resume"]).unwrap().resume);
+        assert_eq!(
+            Err("--daily can't be used with --resume".to_string()),
+            parse(&["--resume", "--daily"])
+        );
+        assert!(parse(&["--resume", "--digits"]).is_err());
+    }
+
+    #[test]
+    fn parses_the_language() {
+        assert_eq!(None, parse(&[]).unwrap().lang);
+        assert_eq!(Some(Lang::Es), parse(&["--lang", "es"]).unwrap().lang);
+        assert_eq!(
+            Err("unknown language fr, expected en or es".to_string()),
+            parse(&["--lang", "fr"])
+        );
+        assert!(parse(&["--lang"]).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_ranges() {
+        assert!(parse(&["--min", "10", "--max", "10"]).is_err());
+        assert!(parse(&["--max", "0"]).is_err());
+        assert!(parse(&["--min"]).is_err());
+        assert!(parse(&["--min", "ten"]).is_err());
+        assert!(parse(&["--size", "3"]).is_err());
+    }
+}
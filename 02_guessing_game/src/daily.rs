@@ -0,0 +1,172 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// Today, as days since 1970-01-01 in UTC, so everyone's day changes at
+/// the same moment.
+pub fn today() -> i64 {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    (since_epoch.as_secs() / 86_400) as i64
+}
+
+/// The year, month and day `days` after 1970-01-01.
+pub fn date(days: i64) -> (i64, u32, u32) {
+    // Counts from 0000-03-01 so leap days fall at the end of each year.
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = era * 400 + year_of_era + i64::from(month <= 2);
+    (year, month as u32, day as u32)
+}
+
+/// `days` after 1970-01-01 written like 2024-02-29.
+pub fn format_date(days: i64) -> String {
+    let (year, month, day) = date(days);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// The seed for the challenge on `days` after 1970-01-01, which reads as
+/// the date, like 20240229.
+pub fn seed(days: i64) -> u64 {
+    let (year, month, day) = date(days);
+    year as u64 * 10_000 + u64::from(month) * 100 + u64::from(day)
+}
+
+/// How the player has done at the daily challenge, kept between runs.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    /// The last day a challenge was finished, won or lost.
+    #[serde(default)]
+    pub last_daily: Option<i64>,
+    /// The last day a challenge was won.
+    #[serde(default)]
+    pub last_win: Option<i64>,
+    /// Days in a row the challenge has been won, up to the last win.
+    #[serde(default)]
+    pub streak: u32,
+    #[serde(default)]
+    pub best_streak: u32,
+}
+
+impl Stats {
+    /// Reads the stats at `path`. A missing or damaged file is a fresh
+    /// start.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(serde_json::from_str(&text).unwrap_or_default()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes the stats to `path`, creating its directory if need be. The
+    /// file is replaced all at once so a crash can't leave it half written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, json)?;
+        fs::rename(&temp, path)
+    }
+
+    /// Whether the challenge for `day` has been finished already.
+    pub fn played(&self, day: i64) -> bool {
+        self.last_daily == Some(day)
+    }
+
+    /// The streak as of `day`, which is broken if neither `day` nor the day
+    /// before were won.
+    pub fn current_streak(&self, day: i64) -> u32 {
+        match self.last_win {
+            Some(last) if last == day || last == day - 1 => self.streak,
+            _ => 0,
+        }
+    }
+
+    /// Records finishing the challenge for `day`. Winning the day after the
+    /// last win adds to the streak, and losing ends it.
+    pub fn finish(&mut self, day: i64, won: bool) {
+        if self.played(day) {
+            return;
+        }
+        self.last_daily = Some(day);
+        if won {
+            self.streak = self.current_streak(day) + 1;
+            self.best_streak = self.best_streak.max(self.streak);
+            self.last_win = Some(day);
+        } else {
+            self.streak = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn converts_days_to_dates() {
+        assert_eq!((1970, 1, 1), date(0));
+        assert_eq!((1969, 12, 31), date(-1));
+        assert_eq!((2000, 2, 29), date(11_016));
+        assert_eq!((2024, 3, 1), date(19_783));
+        assert_eq!("2024-02-29", format_date(19_782));
+        assert_eq!(20_240_229, seed(19_782));
+    }
+
+    #[test]
+    fn today_is_after_this_was_written() {
+        assert!(date(today()).0 >= 2024);
+    }
+
+    #[test]
+    fn streaks_need_consecutive_wins() {
+        let mut stats = Stats::default();
+        stats.finish(100, true);
+        stats.finish(101, true);
+        assert!(stats.played(101));
+        assert_eq!(2, stats.current_streak(101));
+        assert_eq!(2, stats.current_streak(102));
+
+        // Playing twice in a day doesn't count.
+        stats.finish(101, true);
+        assert_eq!(2, stats.streak);
+
+        assert_eq!(0, stats.current_streak(103));
+        stats.finish(103, true);
+        assert_eq!(1, stats.streak);
+        stats.finish(104, false);
+        assert_eq!(0, stats.current_streak(104));
+        assert_eq!(2, stats.best_streak);
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let dir = env::temp_dir().join(format!("guessing-game-stats-{}", std::process::id()));
+        let path = dir.join("stats.json");
+        assert_eq!(Stats::default(), Stats::load(&path).unwrap());
+
+        let mut stats = Stats::default();
+        stats.finish(100, true);
+        stats.save(&path).unwrap();
+        assert_eq!(stats, Stats::load(&path).unwrap());
+
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(Stats::default(), Stats::load(&path).unwrap());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
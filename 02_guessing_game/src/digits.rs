@@ -0,0 +1,192 @@
+use std::fmt;
+
+use rand::Rng;
+
+use crate::{Feedback, Lang, Localize};
+
+/// How many digits a code has.
+pub const CODE_LENGTH: usize = 4;
+
+/// A code of different digits, like 0427.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Code([u8; CODE_LENGTH]);
+
+impl Code {
+    /// Parses a code like `0427`, ignoring surrounding whitespace.
+    pub fn parse(input: &str) -> Result<Code, CodeError> {
+        let input = input.trim();
+        let digits: Vec<u8> = input
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as u8))
+            .collect::<Option<_>>()
+            .ok_or_else(|| CodeError::NotDigits(input.to_string()))?;
+        let digits: [u8; CODE_LENGTH] = digits.try_into().map_err(|_| CodeError::WrongLength)?;
+        if (1..CODE_LENGTH).any(|i| digits[..i].contains(&digits[i])) {
+            return Err(CodeError::RepeatedDigit);
+        }
+        Ok(Code(digits))
+    }
+
+    /// Picks a code with `rng`.
+    pub fn random<R: Rng>(rng: &mut R) -> Code {
+        let mut left: Vec<u8> = (0..10).collect();
+        let mut digits = [0; CODE_LENGTH];
+        for digit in &mut digits {
+            *digit = left.remove(rng.gen_range(0, left.len()));
+        }
+        Code(digits)
+    }
+}
+
+/// Why something typed isn't a code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodeError {
+    /// What was typed has something other than digits in it.
+    NotDigits(String),
+    /// It doesn't have [`CODE_LENGTH`] digits.
+    WrongLength,
+    /// The same digit comes up twice.
+    RepeatedDigit,
+}
+
+impl Localize for CodeError {
+    fn localize(&self, lang: Lang) -> String {
+        match self {
+            CodeError::NotDigits(input) => t!(lang, "digits.not-digits", input = input),
+            CodeError::WrongLength => t!(lang, "digits.wrong-length", length = CODE_LENGTH),
+            CodeError::RepeatedDigit => t!(lang, "digits.repeated-digit"),
+        }
+    }
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Lang::En))
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for digit in self.0 {
+            write!(f, "{digit}")?;
+        }
+        Ok(())
+    }
+}
+
+/// What a guess at a code tells the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BullsAndCows {
+    /// Right digits in the right place.
+    pub bulls: u32,
+    /// Right digits in the wrong place.
+    pub cows: u32,
+}
+
+impl Localize for BullsAndCows {
+    fn localize(&self, lang: Lang) -> String {
+        t!(
+            lang,
+            "digits.reply",
+            bulls = t!(lang, "digits.bulls", count = self.bulls),
+            cows = t!(lang, "digits.cows", count = self.cows),
+        )
+    }
+}
+
+impl fmt::Display for BullsAndCows {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Lang::En))
+    }
+}
+
+/// The digit game: the secret is a code, and each guess says how many of
+/// its digits are right and in the right place, and how many are right but
+/// misplaced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Digits {
+    pub secret: Code,
+}
+
+impl Feedback for Digits {
+    type Guess = Code;
+    type Reply = BullsAndCows;
+    type Error = CodeError;
+
+    fn parse_guess(&self, input: &str) -> Result<Code, CodeError> {
+        Code::parse(input)
+    }
+
+    fn judge(&self, guess: &Code) -> BullsAndCows {
+        let (secret, guess) = (self.secret.0, guess.0);
+        let bulls = (0..CODE_LENGTH).filter(|&i| guess[i] == secret[i]).count() as u32;
+        let shared = guess.iter().filter(|digit| secret.contains(digit)).count() as u32;
+        BullsAndCows {
+            bulls,
+            cows: shared - bulls,
+        }
+    }
+
+    fn solved(&self, reply: &BullsAndCows) -> bool {
+        reply.bulls == CODE_LENGTH as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Round, State};
+    use rand::{SeedableRng, StdRng};
+
+    fn code(input: &str) -> Code {
+        Code::parse(input).unwrap()
+    }
+
+    #[test]
+    fn parses_codes() {
+        assert_eq!(Code([0, 4, 2, 7]), code(" 0427\n"));
+        assert_eq!("0427", code("0427").to_string());
+        assert_eq!(Err(CodeError::WrongLength), Code::parse("427"));
+        assert_eq!(Err(CodeError::WrongLength), Code::parse("04275"));
+        assert_eq!(
+            Err(CodeError::NotDigits("04a7".to_string())),
+            Code::parse("04a7")
+        );
+        assert_eq!(Err(CodeError::RepeatedDigit), Code::parse("0447"));
+    }
+
+    #[test]
+    fn random_codes_are_valid() {
+        let mut rng = StdRng::from_seed(&[3][..]);
+        for _ in 0..100 {
+            let random = Code::random(&mut rng);
+            assert_eq!(Ok(random), Code::parse(&random.to_string()));
+        }
+    }
+
+    #[test]
+    fn counts_bulls_and_cows() {
+        let digits = Digits {
+            secret: code("1234"),
+        };
+        let judge = |guess| digits.judge(&code(guess));
+        assert_eq!(BullsAndCows { bulls: 0, cows: 0 }, judge("5678"));
+        assert_eq!(BullsAndCows { bulls: 1, cows: 2 }, judge("1352"));
+        assert_eq!(BullsAndCows { bulls: 0, cows: 4 }, judge("4321"));
+        assert_eq!(BullsAndCows { bulls: 4, cows: 0 }, judge("1234"));
+        assert!(digits.solved(&judge("1234")));
+        assert_eq!("1 bull and 2 cows.", judge("1352").to_string());
+    }
+
+    #[test]
+    fn shares_the_rounds_rules() {
+        let mut round = Round::new(Digits {
+            secret: code("1234"),
+        })
+        .with_max_attempts(Some(3));
+        round.guess(&code("5678"));
+        round.guess(&code("1234"));
+        assert_eq!(State::Won, round.state());
+        assert_eq!(2, round.attempts());
+    }
+}
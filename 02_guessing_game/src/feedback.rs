@@ -0,0 +1,295 @@
+use std::cmp::Ordering;
+
+use crate::number::{NotANumber, Number};
+use crate::{ConfigError, Lang, Localize, State};
+
+/// How a kind of game reads guesses and judges them against its secret, so
+/// every kind can share the same rules for attempts, winning and losing.
+pub trait Feedback {
+    type Guess;
+    /// What a guess tells the player.
+    type Reply;
+    /// Why something the player typed isn't a guess.
+    type Error: Localize;
+
+    /// Reads a guess the player typed.
+    fn parse_guess(&self, input: &str) -> Result<Self::Guess, Self::Error>;
+
+    /// Compares `guess` with the secret.
+    fn judge(&self, guess: &Self::Guess) -> Self::Reply;
+
+    /// Whether `reply` means the guess was right.
+    fn solved(&self, reply: &Self::Reply) -> bool;
+}
+
+/// Which feedback the number game gives (`--feedback proximity`).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Too small or too big.
+    #[default]
+    Classic,
+    /// How close the guess is, from boiling to cold.
+    Proximity,
+}
+
+impl Style {
+    pub fn parse(value: &str) -> Result<Self, ConfigError> {
+        match value {
+            "classic" => Ok(Style::Classic),
+            "proximity" => Ok(Style::Proximity),
+            _ => Err(ConfigError::UnknownStyle(value.to_string())),
+        }
+    }
+}
+
+/// The classic game: the secret is a number of any kind, and each guess is
+/// too small, too big or right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Classic<T = i64> {
+    pub secret: T,
+    /// How far off a guess can be and still be right.
+    tolerance: T,
+}
+
+impl<T: Number> Classic<T> {
+    pub fn new(secret: T) -> Self {
+        Classic {
+            secret,
+            tolerance: T::default(),
+        }
+    }
+
+    /// Counts guesses no more than `tolerance` from the secret as right,
+    /// for numbers that can't be typed exactly.
+    pub fn with_tolerance(mut self, tolerance: T) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+impl<T: Number> Feedback for Classic<T> {
+    type Guess = T;
+    /// How the guess compares with the secret.
+    type Reply = Ordering;
+    type Error = NotANumber;
+
+    fn parse_guess(&self, input: &str) -> Result<T, NotANumber> {
+        T::parse(input)
+    }
+
+    fn judge(&self, guess: &T) -> Ordering {
+        guess.compare(self.secret, self.tolerance)
+    }
+
+    fn solved(&self, reply: &Ordering) -> bool {
+        *reply == Ordering::Equal
+    }
+}
+
+impl Localize for Ordering {
+    fn localize(&self, lang: Lang) -> String {
+        match self {
+            Ordering::Less => t!(lang, "outcome.too-small"),
+            Ordering::Greater => t!(lang, "outcome.too-big"),
+            Ordering::Equal => t!(lang, "play.win"),
+        }
+    }
+}
+
+/// How close a guess came to the secret, without saying which way it was
+/// off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Warmth {
+    /// Within a twentieth of the range.
+    Boiling,
+    /// Within a fifth of the range.
+    Warm,
+    Cold,
+    Right,
+}
+
+/// The hot and cold game: the secret is a number from `min..=max`, and each
+/// guess is told how close it is, measured against the size of the range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Proximity {
+    pub secret: i64,
+    min: i64,
+    max: i64,
+}
+
+impl Proximity {
+    pub fn new(secret: i64, min: i64, max: i64) -> Self {
+        Proximity { secret, min, max }
+    }
+}
+
+impl Feedback for Proximity {
+    type Guess = i64;
+    type Reply = Warmth;
+    type Error = NotANumber;
+
+    fn parse_guess(&self, input: &str) -> Result<i64, NotANumber> {
+        i64::parse(input)
+    }
+
+    fn judge(&self, guess: &i64) -> Warmth {
+        let distance = (i128::from(*guess) - i128::from(self.secret)).abs();
+        let size = i128::from(self.max) - i128::from(self.min) + 1;
+        // Right next to the secret is always boiling, however small the
+        // range.
+        if distance == 0 {
+            Warmth::Right
+        } else if distance == 1 || distance * 20 <= size {
+            Warmth::Boiling
+        } else if distance * 5 <= size {
+            Warmth::Warm
+        } else {
+            Warmth::Cold
+        }
+    }
+
+    fn solved(&self, reply: &Warmth) -> bool {
+        *reply == Warmth::Right
+    }
+}
+
+impl Localize for Warmth {
+    fn localize(&self, lang: Lang) -> String {
+        match self {
+            Warmth::Boiling => t!(lang, "proximity.boiling"),
+            Warmth::Warm => t!(lang, "proximity.warm"),
+            Warmth::Cold => t!(lang, "proximity.cold"),
+            Warmth::Right => t!(lang, "play.win"),
+        }
+    }
+}
+
+/// Guesses at a secret judged by `F`, up to an optional limit.
+#[derive(Debug)]
+pub struct Round<F> {
+    feedback: F,
+    attempts: u32,
+    max_attempts: Option<u32>,
+    state: State,
+}
+
+impl<F: Feedback> Round<F> {
+    pub fn new(feedback: F) -> Self {
+        Round {
+            feedback,
+            attempts: 0,
+            max_attempts: None,
+            state: State::Playing,
+        }
+    }
+
+    /// Ends the round in a loss once `max` guesses have missed.
+    pub fn with_max_attempts(mut self, max: Option<u32>) -> Self {
+        self.max_attempts = max;
+        self
+    }
+
+    pub fn feedback(&self) -> &F {
+        &self.feedback
+    }
+
+    /// How many guesses there are in all, if they're limited.
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    /// How many guesses have been made so far.
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// How many guesses are left, if they're limited.
+    pub fn remaining(&self) -> Option<u32> {
+        self.max_attempts.map(|max| max - self.attempts)
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Ends the round in a loss.
+    pub fn give_up(&mut self) {
+        if self.state == State::Playing {
+            self.state = State::Lost;
+        }
+    }
+
+    /// Judges `guess`, or returns `None` without counting it if the round is
+    /// already over.
+    pub fn guess(&mut self, guess: &F::Guess) -> Option<F::Reply> {
+        if self.state != State::Playing {
+            return None;
+        }
+        self.attempts += 1;
+        let reply = self.feedback.judge(guess);
+        if self.feedback.solved(&reply) {
+            self.state = State::Won;
+        } else if self.remaining() == Some(0) {
+            self.state = State::Lost;
+        }
+        Some(reply)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classic_compares_numbers() {
+        let classic = Classic::new(50);
+        assert_eq!(Ok(42), classic.parse_guess(" 42\n"));
+        assert!(classic.parse_guess("forty-two").is_err());
+        assert_eq!(Ordering::Less, classic.judge(&42));
+        assert!(classic.solved(&classic.judge(&50)));
+    }
+
+    #[test]
+    fn classic_works_for_any_number() {
+        let big = Classic::new(10_000_000_000_i64);
+        assert_eq!(Ok(9_999_999_999), big.parse_guess("9999999999"));
+        assert_eq!(Ordering::Less, big.judge(&9_999_999_999));
+
+        let float = Classic::new(2.5).with_tolerance(0.05);
+        assert!(float.solved(&float.judge(&2.54)));
+        assert_eq!(Ordering::Greater, float.judge(&2.56));
+    }
+
+    #[test]
+    fn proximity_says_how_close() {
+        let proximity = Proximity::new(50, 1, 100);
+        assert_eq!(Warmth::Right, proximity.judge(&50));
+        assert_eq!(Warmth::Boiling, proximity.judge(&45));
+        assert_eq!(Warmth::Warm, proximity.judge(&70));
+        assert_eq!(Warmth::Cold, proximity.judge(&71));
+        assert_eq!(Warmth::Cold, proximity.judge(&i64::MIN));
+        assert!(proximity.solved(&proximity.judge(&50)));
+        // Off by one is boiling even when the range is small.
+        assert_eq!(Warmth::Boiling, Proximity::new(2, 1, 3).judge(&3));
+        assert_eq!(Ok(Style::Proximity), Style::parse("proximity"));
+        assert!(Style::parse("lukewarm").is_err());
+    }
+
+    #[test]
+    fn rounds_count_guesses_until_solved() {
+        let mut round = Round::new(Classic::new(50));
+        assert_eq!(Some(Ordering::Greater), round.guess(&60));
+        assert_eq!(Some(Ordering::Equal), round.guess(&50));
+        assert_eq!(State::Won, round.state());
+        assert_eq!(None, round.guess(&50));
+        assert_eq!(2, round.attempts());
+    }
+
+    #[test]
+    fn rounds_run_out_of_guesses() {
+        let mut round = Round::new(Classic::new(50)).with_max_attempts(Some(1));
+        assert_eq!(Some(Ordering::Less), round.guess(&10));
+        assert_eq!(State::Lost, round.state());
+        assert_eq!(Some(0), round.remaining());
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use rand::Rng;
+
+use crate::State;
+
+/// The words to guess when there's no `--wordlist`.
+pub const WORDS: &[&str] = &[
+    "borrow",
+    "cargo",
+    "closure",
+    "compiler",
+    "crate",
+    "enum",
+    "generic",
+    "iterator",
+    "lifetime",
+    "macro",
+    "module",
+    "option",
+    "ownership",
+    "pattern",
+    "pointer",
+    "reference",
+    "result",
+    "slice",
+    "string",
+    "struct",
+    "thread",
+    "trait",
+    "vector",
+];
+
+/// How many wrong letters it takes to finish the gallows.
+pub const MAX_WRONG: u32 = 6;
+
+/// Reads a word list with one word per line. Blank lines, and lines that
+/// aren't a single word of letters, are skipped.
+pub fn load_words(path: &Path) -> io::Result<Vec<String>> {
+    let words: Vec<String> = fs::read_to_string(path)?
+        .lines()
+        .map(|line| line.trim().to_lowercase())
+        .filter(|word| !word.is_empty() && word.chars().all(char::is_alphabetic))
+        .collect();
+    if words.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "there are no words in it",
+        ));
+    }
+    Ok(words)
+}
+
+/// Picks one of `words` with `rng`.
+pub fn pick_word<'a, R: Rng>(rng: &mut R, words: &'a [String]) -> &'a str {
+    &words[rng.gen_range(0, words.len())]
+}
+
+/// What guessing a letter told the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Letter {
+    /// The letter is in the word this many times.
+    Found(usize),
+    Missing,
+    /// The letter was already guessed, and doesn't count again.
+    Repeated,
+}
+
+/// A game of guessing a word letter by letter, before the gallows are
+/// finished.
+#[derive(Debug)]
+pub struct Hangman {
+    word: String,
+    guessed: BTreeSet<char>,
+    wrong: u32,
+    max_wrong: u32,
+    state: State,
+}
+
+impl Hangman {
+    pub fn new(word: &str) -> Self {
+        Hangman {
+            word: word.to_lowercase(),
+            guessed: BTreeSet::new(),
+            wrong: 0,
+            max_wrong: MAX_WRONG,
+            state: State::Playing,
+        }
+    }
+
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+
+    /// How many different letters have been guessed.
+    pub fn guesses(&self) -> u32 {
+        self.guessed.len() as u32
+    }
+
+    /// How many guessed letters weren't in the word.
+    pub fn wrong(&self) -> u32 {
+        self.wrong
+    }
+
+    /// How many more wrong letters the player can get away with.
+    pub fn remaining(&self) -> u32 {
+        self.max_wrong - self.wrong
+    }
+
+    /// The guessed letters that aren't in the word, in alphabetical order.
+    pub fn misses(&self) -> Vec<char> {
+        self.guessed
+            .iter()
+            .copied()
+            .filter(|&letter| !self.word.contains(letter))
+            .collect()
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// The word with the letters not guessed yet blanked out, like
+    /// `c _ _ g o`.
+    pub fn masked(&self) -> String {
+        self.word
+            .chars()
+            .map(|letter| {
+                if self.guessed.contains(&letter) {
+                    letter.to_string()
+                } else {
+                    "_".to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Ends the game in a loss.
+    pub fn give_up(&mut self) {
+        if self.state == State::Playing {
+            self.state = State::Lost;
+        }
+    }
+
+    /// Guesses that `letter` is in the word. Letters guessed after the game
+    /// is over don't count.
+    pub fn guess(&mut self, letter: char) -> Letter {
+        let letter = letter.to_lowercase().next().unwrap_or(letter);
+        if self.state != State::Playing || !self.guessed.insert(letter) {
+            return Letter::Repeated;
+        }
+        let found = self.word.chars().filter(|&c| c == letter).count();
+        if found == 0 {
+            self.wrong += 1;
+            if self.wrong == self.max_wrong {
+                self.state = State::Lost;
+            }
+            return Letter::Missing;
+        }
+        if self.word.chars().all(|c| self.guessed.contains(&c)) {
+            self.state = State::Won;
+        }
+        Letter::Found(found)
+    }
+}
+
+/// The gallows after `wrong` wrong letters, out of [`MAX_WRONG`].
+pub fn gallows(wrong: u32) -> String {
+    let part = |n: u32, drawn: &'static str| if wrong >= n { drawn } else { " " };
+    format!(
+        "  +---+\n  |   |\n  {}   |\n {}{}{}  |\n {} {}  |\n      |\n=========",
+        part(1, "O"),
+        part(3, "/"),
+        part(2, "|"),
+        part(4, "\\"),
+        part(5, "/"),
+        part(6, "\\"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    #[test]
+    fn finds_letters() {
+        let mut game = Hangman::new("Cargo");
+        assert_eq!("_ _ _ _ _", game.masked());
+        assert_eq!(Letter::Found(1), game.guess('A'));
+        assert_eq!(Letter::Missing, game.guess('z'));
+        assert_eq!(Letter::Repeated, game.guess('a'));
+        assert_eq!("_ a _ _ _", game.masked());
+        assert_eq!((2, 1, 5), (game.guesses(), game.wrong(), game.remaining()));
+        assert_eq!(vec!['z'], game.misses());
+
+        for letter in "cgor".chars() {
+            game.guess(letter);
+        }
+        assert_eq!(State::Won, game.state());
+        assert_eq!("c a r g o", game.masked());
+    }
+
+    #[test]
+    fn counts_repeated_letters() {
+        let mut game = Hangman::new("borrow");
+        assert_eq!(Letter::Found(2), game.guess('o'));
+        assert_eq!(Letter::Found(2), game.guess('r'));
+    }
+
+    #[test]
+    fn loses_once_the_gallows_are_finished() {
+        let mut game = Hangman::new("enum");
+        for letter in "abcdf".chars() {
+            game.guess(letter);
+        }
+        assert_eq!(State::Playing, game.state());
+        game.guess('g');
+        assert_eq!(State::Lost, game.state());
+        assert_eq!(Letter::Repeated, game.guess('e'));
+    }
+
+    #[test]
+    fn draws_the_gallows() {
+        assert!(!gallows(0).contains('O'));
+        assert_eq!(
+            "  +---+\n  |   |\n  O   |\n /|\\  |\n / \\  |\n      |\n=========",
+            gallows(MAX_WRONG)
+        );
+    }
+
+    #[test]
+    fn loads_word_lists() {
+        let path = std::env::temp_dir().join(format!("guessing-game-words-{}", std::process::id()));
+        fs::write(&path, "Apple\n\n banana \ntwo words\nc3po\n").unwrap();
+        assert_eq!(vec!["apple", "banana"], load_words(&path).unwrap());
+        fs::write(&path, "\n42\n").unwrap();
+        assert!(load_words(&path).is_err());
+        fs::remove_file(&path).unwrap();
+
+        let words: Vec<String> = WORDS.iter().map(|word| word.to_string()).collect();
+        let mut rng = StdRng::from_seed(&[1][..]);
+        assert!(WORDS.contains(&pick_word(&mut rng, &words)));
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+
+use crate::{Lang, Localize};
+
+/// How many points each hint takes off the score.
+pub const HINT_COST: u32 = 100;
+
+/// Something true about the secret number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    Even(bool),
+    DivisibleBy(i64, bool),
+    /// The secret is between these, inclusive.
+    Between(i64, i64),
+}
+
+impl Localize for Hint {
+    fn localize(&self, lang: Lang) -> String {
+        match *self {
+            Hint::Even(true) => t!(lang, "hint.even"),
+            Hint::Even(false) => t!(lang, "hint.odd"),
+            Hint::DivisibleBy(n, true) => t!(lang, "hint.divisible", n = n),
+            Hint::DivisibleBy(n, false) => t!(lang, "hint.not-divisible", n = n),
+            Hint::Between(low, high) => t!(lang, "bounds.between", low = low, high = high),
+        }
+    }
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Lang::En))
+    }
+}
+
+/// The `nth` hint (counting from 0) about `secret`, which was picked from
+/// `min..=max`. The first two give its parity and whether it's divisible by
+/// three; after that each narrows the range to a smaller slice holding it.
+pub fn hint(nth: u32, secret: i64, min: i64, max: i64) -> Hint {
+    match nth {
+        0 => Hint::Even(secret % 2 == 0),
+        1 => Hint::DivisibleBy(3, secret % 3 == 0),
+        _ => {
+            // A quarter of the range, then an eighth, and so on.
+            let size = i128::from(max) - i128::from(min) + 1;
+            let width = (size >> nth.min(126)).max(2);
+            // Slices are lined up from `min` rather than centered on the
+            // secret, which would give it away.
+            let low = i128::from(min) + (i128::from(secret) - i128::from(min)) / width * width;
+            let high = (low + width - 1).min(i128::from(max));
+            Hint::Between(low as i64, high as i64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parity_then_divisibility() {
+        assert_eq!(Hint::Even(true), hint(0, 42, 1, 100));
+        assert_eq!(Hint::Even(false), hint(0, -7, -10, 10));
+        assert_eq!(Hint::DivisibleBy(3, true), hint(1, 42, 1, 100));
+        assert_eq!(Hint::DivisibleBy(3, false), hint(1, 43, 1, 100));
+    }
+
+    #[test]
+    fn ranges_narrow_and_hold_the_secret() {
+        assert_eq!(Hint::Between(26, 50), hint(2, 42, 1, 100));
+        assert_eq!(Hint::Between(37, 48), hint(3, 42, 1, 100));
+        for nth in 2..20 {
+            let Hint::Between(low, high) = hint(nth, 100, 1, 100) else {
+                panic!("expected a range");
+            };
+            assert!(low <= 100 && 100 <= high && high <= 100);
+        }
+        assert_eq!(
+            Hint::Between(-(1 << 62), -1),
+            hint(2, -5, i64::MIN, i64::MAX)
+        );
+    }
+
+    #[test]
+    fn displays_hints() {
+        assert_eq!("The number is odd.", Hint::Even(false).to_string());
+        assert_eq!(
+            "The number isn't divisible by 3.",
+            Hint::DivisibleBy(3, false).to_string()
+        );
+        assert_eq!(
+            "The number is between 1 and 25.",
+            Hint::Between(1, 25).to_string()
+        );
+    }
+}
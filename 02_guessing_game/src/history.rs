@@ -0,0 +1,115 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::{Lang, Localize, Outcome};
+
+/// Every guess made in a game and what it told the player, along with the
+/// range the secret must still be in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct History {
+    turns: Vec<(i64, Outcome)>,
+    low: i64,
+    high: i64,
+}
+
+impl History {
+    /// An empty history for a secret picked from `min..=max`.
+    pub fn new(min: i64, max: i64) -> Self {
+        History {
+            turns: Vec::new(),
+            low: min,
+            high: max,
+        }
+    }
+
+    /// Records that `guess` was `outcome`, narrowing the bounds.
+    pub fn record(&mut self, guess: i64, outcome: Outcome) {
+        self.turns.push((guess, outcome));
+        match outcome {
+            Outcome::TooSmall => self.low = self.low.max(guess.saturating_add(1)),
+            Outcome::TooBig => self.high = self.high.min(guess.saturating_sub(1)),
+            Outcome::Win => (self.low, self.high) = (guess, guess),
+            Outcome::Lose => {}
+        }
+    }
+
+    pub fn turns(&self) -> &[(i64, Outcome)] {
+        &self.turns
+    }
+
+    /// The smallest and largest numbers the secret can still be.
+    pub fn bounds(&self) -> (i64, i64) {
+        (self.low, self.high)
+    }
+}
+
+/// Describes what the guesses so far imply about the secret.
+impl Localize for History {
+    fn localize(&self, lang: Lang) -> String {
+        match self.low.cmp(&self.high) {
+            Ordering::Less => t!(lang, "bounds.between", low = self.low, high = self.high),
+            Ordering::Equal => t!(lang, "bounds.exactly", n = self.low),
+            // Only possible if the secret was outside the range it was said
+            // to be in.
+            Ordering::Greater => t!(lang, "bounds.none"),
+        }
+    }
+}
+
+impl fmt::Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Lang::En))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_with_the_whole_range() {
+        let history = History::new(1, 100);
+        assert_eq!((1, 100), history.bounds());
+        assert!(history.turns().is_empty());
+    }
+
+    #[test]
+    fn narrows_with_each_guess() {
+        let mut history = History::new(1, 100);
+        history.record(37, Outcome::TooSmall);
+        assert_eq!((38, 100), history.bounds());
+        history.record(63, Outcome::TooBig);
+        assert_eq!((38, 62), history.bounds());
+        history.record(50, Outcome::Win);
+        assert_eq!((50, 50), history.bounds());
+        assert_eq!(
+            &[
+                (37, Outcome::TooSmall),
+                (63, Outcome::TooBig),
+                (50, Outcome::Win)
+            ],
+            history.turns()
+        );
+    }
+
+    #[test]
+    fn describes_the_bounds() {
+        let mut history = History::new(1, 100);
+        history.record(37, Outcome::TooSmall);
+        history.record(63, Outcome::TooBig);
+        assert_eq!("The number is between 38 and 62.", history.to_string());
+        history.record(39, Outcome::TooBig);
+        assert_eq!("The number must be 38.", history.to_string());
+        history.record(38, Outcome::TooBig);
+        assert_eq!("No number fits those answers.", history.to_string());
+    }
+
+    #[test]
+    fn wasted_guesses_dont_widen_the_bounds() {
+        let mut history = History::new(1, 100);
+        history.record(50, Outcome::TooSmall);
+        history.record(20, Outcome::TooSmall);
+        history.record(200, Outcome::TooBig);
+        assert_eq!((51, 100), history.bounds());
+    }
+}
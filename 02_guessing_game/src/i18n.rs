@@ -0,0 +1,640 @@
+use std::env;
+use std::fmt;
+
+use crate::{command, reverse, ConfigError};
+
+/// A language the game can be played in (`--lang`, or the locale).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    #[default]
+    En,
+    Es,
+}
+
+impl Lang {
+    /// Parses a language like `es`, or a locale like `es_ES.UTF-8`.
+    pub fn parse(value: &str) -> Result<Self, ConfigError> {
+        let code = value
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match code.as_str() {
+            "en" => Ok(Lang::En),
+            "es" => Ok(Lang::Es),
+            _ => Err(ConfigError::UnknownLanguage(value.to_string())),
+        }
+    }
+
+    /// The language of the locale in `LC_ALL`, `LC_MESSAGES` or `LANG`,
+    /// whichever is set first, if the game speaks it.
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Lang::parse(&value).ok())
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::En => EN,
+            Lang::Es => ES,
+        }
+    }
+
+    /// The message for `key`, if this language has one.
+    pub fn get(self, key: &str) -> Option<&'static str> {
+        self.catalog()
+            .iter()
+            .find(|&&(k, _)| k == key)
+            .map(|&(_, text)| text)
+    }
+
+    /// The message for `key`, falling back to English, and then to the key
+    /// itself so a missing message shows up rather than going blank.
+    pub fn text(self, key: &str) -> &str {
+        self.get(key).or_else(|| Lang::En.get(key)).unwrap_or(key)
+    }
+
+    /// The message for `key` with each `{name}` in it filled in from `args`.
+    pub fn format(self, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+        args.iter()
+            .fold(self.text(key).to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), &value.to_string())
+            })
+    }
+
+    /// Like [`format`](Lang::format), but picks `key.one` or `key.other` by
+    /// the count `n`, which fills in `{n}`.
+    pub fn plural(self, key: &str, n: u32, args: &[(&str, &dyn fmt::Display)]) -> String {
+        let form = if n == 1 { "one" } else { "other" };
+        let mut args = args.to_vec();
+        args.push(("n", &n));
+        self.format(&format!("{key}.{form}"), &args)
+    }
+}
+
+/// Something that can be told to the player in any [`Lang`].
+pub trait Localize {
+    fn localize(&self, lang: Lang) -> String;
+}
+
+/// Looks up a message in a [`Lang`], filling in its `{name}`s:
+/// `t!(lang, "play.guessed", guess = 50)`. With `count = n` first, the
+/// message is picked by [`Lang::plural`].
+#[macro_export]
+macro_rules! t {
+    ($lang:expr, $key:expr, count = $n:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $lang.plural($key, $n, &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),*])
+    };
+    ($lang:expr, $key:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $lang.format($key, &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),*])
+    };
+}
+
+static EN: &[(&str, &str)] = &[
+    ("args.problem", "Problem parsing arguments: {error}"),
+    ("args.needs-number", "{flag} needs a number"),
+    ("args.needs-guesses", "{flag} needs a list like 50,25,37"),
+    ("args.needs-strategy", "{flag} needs a strategy"),
+    ("args.needs-style", "{flag} needs a style"),
+    ("args.needs-name", "{flag} needs a name"),
+    ("args.needs-profile", "{flag} needs a profile"),
+    ("args.needs-file", "{flag} needs a file"),
+    ("args.needs-language", "{flag} needs a language"),
+    ("args.needs-address", "{flag} needs an address"),
+    ("args.needs-level", "{flag} needs a level"),
+    ("args.bad-guess", "--guesses: \"{guess}\" isn't a number"),
+    ("args.unknown", "unknown argument {arg}"),
+    ("args.unknown-profiles-command", "unknown profiles command {command}, expected list or delete"),
+    ("args.unknown-difficulty", "unknown difficulty {value}, expected easy, normal or hard"),
+    ("args.unknown-bot", "unknown bot {value}, expected binary or random"),
+    ("args.unknown-feedback", "unknown feedback {value}, expected classic or proximity"),
+    ("args.unknown-language", "unknown language {value}, expected en or es"),
+    ("args.bad-range", "--min ({min}) must be less than --max ({max})"),
+    ("args.too-precise", "--precision can be at most {most}"),
+    ("args.no-games", "--simulate needs at least one game"),
+    ("args.no-rounds", "--rounds needs at least one round"),
+    ("args.conflict", "{flag} can't be used with {other}"),
+    ("args.daily-seed", "--daily picks its own --seed"),
+    ("args.rounds-difficulty", "--rounds picks the difficulty of each round"),
+    ("play.opening", "Guess the number between {min} and {max}!"),
+    ("play.cheat", "The secret number is: {secret}"),
+    ("play.welcome-back", "Welcome back! Guess the number between {min} and {max}."),
+    ("play.prompt", "Please input your guess ({min} to {max}), or hint, history, range, giveup or quit."),
+    ("play.hint", "{hint} (-{cost} points)"),
+    ("play.guessed", "You guessed: {guess}"),
+    ("play.win", "You win!"),
+    ("play.number-was", "The number was {secret}."),
+    ("play.out-of-guesses", "Out of guesses! The number was {secret}."),
+    ("play.guesses-left.one", "{n} guess left."),
+    ("play.guesses-left.other", "{n} guesses left."),
+    ("command.help", command::HELP),
+    ("input.empty", "You didn't type anything."),
+    ("input.not-a-number", "\"{input}\" isn't a number or a command."),
+    ("input.overflow", "{input} is far too big to be the number."),
+    ("input.out-of-range", "{guess} can't be it: the number is between {min} and {max}."),
+    ("input.stuck", "Stuck? Guesses are whole numbers from {min} to {max}."),
+    ("number.not-a-number", "\"{input}\" isn't a number"),
+    ("outcome.too-small", "Too small!"),
+    ("outcome.too-big", "Too big!"),
+    ("feedback.too-small", "too small"),
+    ("feedback.too-big", "too big"),
+    ("feedback.right", "right"),
+    ("history.empty", "No guesses yet."),
+    ("history.turn", "{turn}. {guess} was {feedback}"),
+    ("bounds.between", "The number is between {low} and {high}."),
+    ("bounds.exactly", "The number must be {n}."),
+    ("bounds.none", "No number fits those answers."),
+    ("hint.even", "The number is even."),
+    ("hint.odd", "The number is odd."),
+    ("hint.divisible", "The number is divisible by {n}."),
+    ("hint.not-divisible", "The number isn't divisible by {n}."),
+    ("session.again", "Play again? (y/n)"),
+    ("session.yes", "y"),
+    ("session.summary.one", "You played {n} game and won {wins}, taking {average} guesses on average."),
+    ("session.summary.other", "You played {n} games and won {wins}, taking {average} guesses on average."),
+    ("session.best", "Your best game took {best} guesses."),
+    ("save.saved", "Saved your game. Carry on with --resume."),
+    ("save.cant-save", "Can't save the game to {path}: {error}"),
+    ("save.none", "There's no saved game, so here's a new one."),
+    ("save.cant-read", "Can't read the saved game from {path}: {error}"),
+    ("save.cant-remove", "Can't remove the saved game at {path}: {error}"),
+    ("save.changed", "The saved game has been changed, so here's a new one."),
+    ("scores.cant-read", "Can't read high scores from {path}: {error}"),
+    ("scores.cant-save", "Can't save high scores to {path}: {error}"),
+    ("scores.points", "You scored {points} points."),
+    ("scores.place", "That's number {place} on the high-score table!"),
+    ("scores.title", "High scores:"),
+    ("scores.range", "{min} to {max}"),
+    ("scores.entry", "{place}. {points} points, {attempts} guesses in {seconds}s ({secret})"),
+    ("scores.named-entry", "{place}. {points} points by {name}, {attempts} guesses in {seconds}s ({secret})"),
+    ("scores.no-table", "There's no home directory to keep the high scores in."),
+    ("scores.cant-import", "Can't import scores from {path}: {error}"),
+    ("scores.cant-export", "Can't export scores to {path}: {error}"),
+    ("scores.imported.one", "Imported {n} score from {path}."),
+    ("scores.imported.other", "Imported {n} scores from {path}."),
+    ("scores.exported.one", "Exported {n} score to {path}."),
+    ("scores.exported.other", "Exported {n} scores to {path}."),
+    ("achievements.cant-read", "Can't read achievements from {path}: {error}"),
+    ("achievements.cant-save", "Can't save achievements to {path}: {error}"),
+    ("achievements.unlocked", "Achievement unlocked: {name} ({description})!"),
+    ("daily.opening", "Daily challenge for {date}"),
+    ("daily.cant-read", "Can't read stats from {path}: {error}"),
+    ("profiles.existing", "Profiles: {profiles}"),
+    ("profiles.ask", "Who's playing? Type a profile name, or press Enter to play without one."),
+    ("profiles.playing-as", "Playing as {name}."),
+    ("profiles.title", "Profiles:"),
+    ("profiles.none", "There are no profiles yet. Pick one with --profile or when the game starts."),
+    ("profiles.deleted", "Deleted the profile {name}."),
+    ("profiles.not-found", "There's no profile called {name}."),
+    ("profiles.no-dir", "Can't find a home directory to keep profiles in."),
+    ("profiles.cant-read", "Can't read the profiles in {path}: {error}"),
+    ("profiles.cant-delete", "Can't delete the profile {name}: {error}"),
+    ("profiles.name-empty", "a profile needs a name"),
+    ("profiles.name-too-long", "profile names can be at most {most} characters"),
+    ("profiles.name-bad-character", "\"{name}\" isn't a profile name: use letters, digits, - and _"),
+    ("profiles.name-blocked", "\"{name}\" isn't allowed as a profile name"),
+    ("tournament.round", "Round {round} of {rounds}: {difficulty} (points ×{weight})."),
+    ("tournament.points", "That round scored {points} points."),
+    ("tournament.scoreboard", "Scoreboard:"),
+    ("tournament.row", "{round}. {difficulty} | {outcome} | {points} points"),
+    ("tournament.won.one", "won in {n} guess"),
+    ("tournament.won.other", "won in {n} guesses"),
+    ("tournament.lost", "lost"),
+    ("tournament.total", "Total: {points} points"),
+    ("stats.none", "No games played yet. Play one and come back!"),
+    ("stats.played.one", "{n} game played, {won} won ({rate}%)."),
+    ("stats.played.other", "{n} games played, {won} won ({rate}%)."),
+    ("stats.distribution", "Guesses per win:"),
+    ("stats.guesses", "{guesses} | {bar} {wins}"),
+    ("stats.by-difficulty", "Win rate by difficulty:"),
+    ("stats.rate", "{difficulty} | {bar} {rate}% ({won} of {played})"),
+    ("stats.custom", "custom"),
+    ("stats.average", "Average time: {seconds}s."),
+    ("stats.cant-read", "Can't read the games played from {path}: {error}"),
+    ("stats.cant-save", "Can't save the game to {path}: {error}"),
+    ("difficulty.easy", "easy"),
+    ("difficulty.normal", "normal"),
+    ("difficulty.hard", "hard"),
+    ("replay.cant-record", "Can't record the game to {path}: {error}"),
+    ("replay.cant-read", "Can't read the recorded games from {path}: {error}"),
+    ("replay.empty", "There are no recorded games in {path}."),
+    ("replay.opening", "Replaying the game played at {time}."),
+    ("replay.secret", "The secret number was {secret}."),
+    ("replay.quit", "The player quit here."),
+    ("daily.cant-save", "Can't save stats to {path}: {error}"),
+    ("daily.played", "You've already played today's challenge. Come back tomorrow!"),
+    ("daily.streak.one", "Your streak is {n} day."),
+    ("daily.streak.other", "Your streak is {n} days."),
+    ("daily.best-streak.one", "Your streak is {n} day (best {best})."),
+    ("daily.best-streak.other", "Your streak is {n} days (best {best})."),
+    ("round.prompt", "Please input your guess, or quit."),
+    ("round.out-of-guesses", "Out of guesses!"),
+    ("digits.opening", "Guess the {length}-digit code! Every digit is different."),
+    ("digits.cheat", "The secret code is: {code}"),
+    ("digits.won.one", "You cracked it in {n} guess!"),
+    ("digits.won.other", "You cracked it in {n} guesses!"),
+    ("digits.lost", "The code was {code}."),
+    ("digits.reply", "{bulls} and {cows}."),
+    ("digits.bulls.one", "{n} bull"),
+    ("digits.bulls.other", "{n} bulls"),
+    ("digits.cows.one", "{n} cow"),
+    ("digits.cows.other", "{n} cows"),
+    ("digits.not-digits", "\"{input}\" isn't all digits"),
+    ("digits.wrong-length", "a code has {length} digits"),
+    ("digits.repeated-digit", "every digit in a code is different"),
+    ("float.opening.one", "Guess the number between {min} and {max}, to {n} decimal place!"),
+    ("float.opening.other", "Guess the number between {min} and {max}, to {n} decimal places!"),
+    ("float.won.one", "You got it in {n} guess!"),
+    ("float.won.other", "You got it in {n} guesses!"),
+    ("proximity.opening", "Guess the number between {min} and {max}! I'll only say how close you are."),
+    ("proximity.boiling", "Boiling!"),
+    ("proximity.warm", "Warm."),
+    ("proximity.cold", "Cold."),
+    ("proximity.won.one", "You found it in {n} guess!"),
+    ("proximity.won.other", "You found it in {n} guesses!"),
+    ("ui.attempts", "Guesses: {guesses}"),
+    ("ui.attempts-of", "Guesses: {guesses} of {most}"),
+    ("ui.prompt", "Type a guess, or hint, range, giveup or quit."),
+    ("words.cant-read", "Can't read words from {path}: {error}"),
+    ("words.opening", "Guess the word, one letter at a time! It has {letters} letters."),
+    ("words.cheat", "The secret word is: {word}"),
+    ("words.missed", "Missed: {misses}"),
+    ("words.prompt", "Please input a letter, or giveup or quit."),
+    ("words.help", "Type a single letter, or giveup or quit."),
+    ("words.found.one", "Yes, there's one {letter}."),
+    ("words.found.other", "Yes, there are {n} {letter}s."),
+    ("words.missing", "No {letter}, sorry."),
+    ("words.repeated", "You've already guessed {letter}."),
+    ("words.was", "The word was {word}."),
+    ("bot.win", "I win!"),
+    ("bot.took", "The {strategy} bot took {guesses} guesses."),
+    ("bot.only", "It can only be {guess}, so I guess {guess}."),
+    ("bot.binary", "It's between {low} and {high}, so I guess {guess}, halfway, to rule out half of them."),
+    ("bot.random", "It's between {low} and {high}, so I guess {guess} at random."),
+    ("simulation.summary", "The {strategy} bot won {wins} of {games} games, taking {average} guesses on average."),
+    ("simulation.best.one", "Its best game took {n} guess."),
+    ("simulation.best.other", "Its best game took {n} guesses."),
+    ("reverse.opening", "Think of a number between {min} and {max}, and I'll guess it!"),
+    ("reverse.ask", "Is it {guess}? (h if yours is higher, l if it's lower, c if that's it)"),
+    ("reverse.help", reverse::HELP),
+    ("reverse.again", "Let's try again: is it {guess}?"),
+    ("reverse.got-it.one", "Got it in {n} guess!"),
+    ("reverse.got-it.other", "Got it in {n} guesses!"),
+    ("contradiction.higher-earlier", "That can't be right: you said it's higher than {guess}, but earlier you said it's lower than {earlier}."),
+    ("contradiction.higher-range", "That can't be right: you said it's higher than {guess}, but it's no more than {max}."),
+    ("contradiction.lower-earlier", "That can't be right: you said it's lower than {guess}, but earlier you said it's higher than {earlier}."),
+    ("contradiction.lower-range", "That can't be right: you said it's lower than {guess}, but it's no less than {min}."),
+    ("contradiction.correct", "That can't be right: {guess} doesn't fit your earlier answers."),
+    ("race.failed", "Network game failed: {error}"),
+    ("race.lost-connection", "Lost the connection: {error}"),
+    ("race.help", "In a race, type a number, or quit."),
+    ("race.waiting", "Waiting for another player to join on port {port}..."),
+    ("race.host-joined", "They've joined, so it's a race! Type a number, or quit."),
+    ("race.joining", "Joining the game at {addr}..."),
+    ("race.guest-joined", "You've joined, so it's a race! Type a number, or quit."),
+    ("race.left", "The other player left."),
+    ("race.you-guessed", "You guessed {guess}: {feedback}!"),
+    ("race.they-guessed", "They guessed {guess}: {feedback}!"),
+    ("race.you-win", "You win! The number was {secret}."),
+    ("race.they-win", "They got there first! The number was {secret}."),
+];
+
+static ES: &[(&str, &str)] = &[
+    ("args.problem", "Problema con los argumentos: {error}"),
+    ("args.needs-number", "{flag} necesita un número"),
+    ("args.needs-guesses", "{flag} necesita una lista como 50,25,37"),
+    ("args.needs-strategy", "{flag} necesita una estrategia"),
+    ("args.needs-style", "{flag} necesita un estilo"),
+    ("args.needs-name", "{flag} necesita un nombre"),
+    ("args.needs-profile", "{flag} necesita un perfil"),
+    ("args.needs-file", "{flag} necesita un archivo"),
+    ("args.needs-language", "{flag} necesita un idioma"),
+    ("args.needs-address", "{flag} necesita una dirección"),
+    ("args.needs-level", "{flag} necesita un nivel"),
+    ("args.bad-guess", "--guesses: «{guess}» no es un número"),
+    ("args.unknown", "argumento desconocido {arg}"),
+    ("args.unknown-profiles-command", "orden de profiles desconocida {command}, se esperaba list o delete"),
+    ("args.unknown-difficulty", "dificultad desconocida {value}, se esperaba easy, normal o hard"),
+    ("args.unknown-bot", "bot desconocido {value}, se esperaba binary o random"),
+    ("args.unknown-feedback", "feedback desconocido {value}, se esperaba classic o proximity"),
+    ("args.unknown-language", "idioma desconocido {value}, se esperaba en o es"),
+    ("args.bad-range", "--min ({min}) tiene que ser menor que --max ({max})"),
+    ("args.too-precise", "--precision puede ser como mucho {most}"),
+    ("args.no-games", "--simulate necesita al menos una partida"),
+    ("args.no-rounds", "--rounds necesita al menos una ronda"),
+    ("args.conflict", "{flag} no se puede usar con {other}"),
+    ("args.daily-seed", "--daily elige su propia --seed"),
+    ("args.rounds-difficulty", "--rounds elige la dificultad de cada ronda"),
+    ("play.opening", "¡Adivina el número entre {min} y {max}!"),
+    ("play.cheat", "El número secreto es: {secret}"),
+    ("play.welcome-back", "¡Bienvenido de nuevo! Adivina el número entre {min} y {max}."),
+    ("play.prompt", "Escribe tu intento ({min} a {max}), o hint, history, range, giveup o quit."),
+    ("play.hint", "{hint} (-{cost} puntos)"),
+    ("play.guessed", "Has dicho: {guess}"),
+    ("play.win", "¡Has ganado!"),
+    ("play.number-was", "El número era {secret}."),
+    ("play.out-of-guesses", "¡Sin intentos! El número era {secret}."),
+    ("play.guesses-left.one", "Queda {n} intento."),
+    ("play.guesses-left.other", "Quedan {n} intentos."),
+    ("command.help", "Escribe un número, o hint, history, range, giveup o quit."),
+    ("input.empty", "No has escrito nada."),
+    ("input.not-a-number", "«{input}» no es un número ni una orden."),
+    ("input.overflow", "{input} es demasiado grande para ser el número."),
+    ("input.out-of-range", "{guess} no puede ser: el número está entre {min} y {max}."),
+    ("input.stuck", "¿Atascado? Los intentos son números enteros de {min} a {max}."),
+    ("number.not-a-number", "«{input}» no es un número"),
+    ("outcome.too-small", "¡Demasiado pequeño!"),
+    ("outcome.too-big", "¡Demasiado grande!"),
+    ("feedback.too-small", "demasiado pequeño"),
+    ("feedback.too-big", "demasiado grande"),
+    ("feedback.right", "correcto"),
+    ("history.empty", "Todavía no hay intentos."),
+    ("history.turn", "{turn}. {guess} era {feedback}"),
+    ("bounds.between", "El número está entre {low} y {high}."),
+    ("bounds.exactly", "El número tiene que ser {n}."),
+    ("bounds.none", "Ningún número encaja con esas respuestas."),
+    ("hint.even", "El número es par."),
+    ("hint.odd", "El número es impar."),
+    ("hint.divisible", "El número es divisible entre {n}."),
+    ("hint.not-divisible", "El número no es divisible entre {n}."),
+    ("session.again", "¿Otra partida? (s/n)"),
+    ("session.yes", "s"),
+    ("session.summary.one", "Has jugado {n} partida y has ganado {wins}, con {average} intentos de media."),
+    ("session.summary.other", "Has jugado {n} partidas y has ganado {wins}, con {average} intentos de media."),
+    ("session.best", "Tu mejor partida fue en {best} intentos."),
+    ("save.saved", "Partida guardada. Sigue con --resume."),
+    ("save.cant-save", "No se puede guardar la partida en {path}: {error}"),
+    ("save.none", "No hay ninguna partida guardada, así que empieza una nueva."),
+    ("save.cant-read", "No se puede leer la partida guardada de {path}: {error}"),
+    ("save.cant-remove", "No se puede borrar la partida guardada en {path}: {error}"),
+    ("save.changed", "La partida guardada ha cambiado, así que empieza una nueva."),
+    ("scores.cant-read", "No se pueden leer las puntuaciones de {path}: {error}"),
+    ("scores.cant-save", "No se pueden guardar las puntuaciones en {path}: {error}"),
+    ("scores.points", "Has conseguido {points} puntos."),
+    ("scores.place", "¡Estás en el puesto {place} de las mejores puntuaciones!"),
+    ("scores.title", "Mejores puntuaciones:"),
+    ("scores.range", "{min} a {max}"),
+    ("scores.entry", "{place}. {points} puntos, {attempts} intentos en {seconds}s ({secret})"),
+    ("scores.named-entry", "{place}. {points} puntos de {name}, {attempts} intentos en {seconds}s ({secret})"),
+    ("scores.no-table", "No hay directorio personal donde guardar las puntuaciones."),
+    ("scores.cant-import", "No se pueden importar puntuaciones de {path}: {error}"),
+    ("scores.cant-export", "No se pueden exportar puntuaciones a {path}: {error}"),
+    ("scores.imported.one", "Importada {n} puntuación de {path}."),
+    ("scores.imported.other", "Importadas {n} puntuaciones de {path}."),
+    ("scores.exported.one", "Exportada {n} puntuación a {path}."),
+    ("scores.exported.other", "Exportadas {n} puntuaciones a {path}."),
+    ("achievements.cant-read", "No se pueden leer los logros de {path}: {error}"),
+    ("achievements.cant-save", "No se pueden guardar los logros en {path}: {error}"),
+    ("achievements.unlocked", "¡Logro desbloqueado: {name} ({description})!"),
+    ("first-win.name", "La suerte del principiante"),
+    ("first-win.description", "gana una partida"),
+    ("quick-win.name", "Francotirador"),
+    ("quick-win.description", "gana en 5 intentos o menos"),
+    ("hard-win.name", "Duro de pelar"),
+    ("hard-win.description", "gana una partida con --difficulty hard"),
+    ("week-streak.name", "Animal de costumbres"),
+    ("week-streak.description", "gana el desafío diario 7 días seguidos"),
+    ("daily.opening", "Desafío diario del {date}"),
+    ("daily.cant-read", "No se pueden leer las estadísticas de {path}: {error}"),
+    ("profiles.existing", "Perfiles: {profiles}"),
+    ("profiles.ask", "¿Quién juega? Escribe un nombre de perfil, o pulsa Intro para jugar sin perfil."),
+    ("profiles.playing-as", "Juegas como {name}."),
+    ("profiles.title", "Perfiles:"),
+    ("profiles.none", "Aún no hay perfiles. Elige uno con --profile o al empezar la partida."),
+    ("profiles.deleted", "Perfil {name} borrado."),
+    ("profiles.not-found", "No hay ningún perfil llamado {name}."),
+    ("profiles.no-dir", "No se encuentra una carpeta personal donde guardar los perfiles."),
+    ("profiles.cant-read", "No se pueden leer los perfiles de {path}: {error}"),
+    ("profiles.cant-delete", "No se puede borrar el perfil {name}: {error}"),
+    ("profiles.name-empty", "un perfil necesita un nombre"),
+    ("profiles.name-too-long", "los nombres de perfil pueden tener como mucho {most} caracteres"),
+    ("profiles.name-bad-character", "«{name}» no es un nombre de perfil: usa letras, cifras, - y _"),
+    ("profiles.name-blocked", "«{name}» no está permitido como nombre de perfil"),
+    ("tournament.round", "Ronda {round} de {rounds}: {difficulty} (puntos ×{weight})."),
+    ("tournament.points", "Esa ronda ha sumado {points} puntos."),
+    ("tournament.scoreboard", "Marcador:"),
+    ("tournament.row", "{round}. {difficulty} | {outcome} | {points} puntos"),
+    ("tournament.won.one", "ganada en {n} intento"),
+    ("tournament.won.other", "ganada en {n} intentos"),
+    ("tournament.lost", "perdida"),
+    ("tournament.total", "Total: {points} puntos"),
+    ("stats.none", "Aún no has jugado ninguna partida. ¡Juega una y vuelve!"),
+    ("stats.played.one", "{n} partida jugada, {won} ganadas ({rate} %)."),
+    ("stats.played.other", "{n} partidas jugadas, {won} ganadas ({rate} %)."),
+    ("stats.distribution", "Intentos por victoria:"),
+    ("stats.guesses", "{guesses} | {bar} {wins}"),
+    ("stats.by-difficulty", "Victorias por dificultad:"),
+    ("stats.rate", "{difficulty} | {bar} {rate} % ({won} de {played})"),
+    ("stats.custom", "a medida"),
+    ("stats.average", "Tiempo medio: {seconds} s."),
+    ("stats.cant-read", "No se pueden leer las partidas jugadas de {path}: {error}"),
+    ("stats.cant-save", "No se puede guardar la partida en {path}: {error}"),
+    ("difficulty.easy", "fácil"),
+    ("difficulty.normal", "normal"),
+    ("difficulty.hard", "difícil"),
+    ("replay.cant-record", "No se puede grabar la partida en {path}: {error}"),
+    ("replay.cant-read", "No se pueden leer las partidas grabadas de {path}: {error}"),
+    ("replay.empty", "No hay partidas grabadas en {path}."),
+    ("replay.opening", "Repitiendo la partida jugada el {time}."),
+    ("replay.secret", "El número secreto era {secret}."),
+    ("replay.quit", "Aquí el jugador lo dejó."),
+    ("daily.cant-save", "No se pueden guardar las estadísticas en {path}: {error}"),
+    ("daily.played", "Ya has jugado el desafío de hoy. ¡Vuelve mañana!"),
+    ("daily.streak.one", "Tu racha es de {n} día."),
+    ("daily.streak.other", "Tu racha es de {n} días."),
+    ("daily.best-streak.one", "Tu racha es de {n} día (récord {best})."),
+    ("daily.best-streak.other", "Tu racha es de {n} días (récord {best})."),
+    ("round.prompt", "Escribe tu intento, o quit."),
+    ("round.out-of-guesses", "¡Sin intentos!"),
+    ("digits.opening", "¡Adivina el código de {length} cifras! Todas las cifras son distintas."),
+    ("digits.cheat", "El código secreto es: {code}"),
+    ("digits.won.one", "¡Lo has descifrado en {n} intento!"),
+    ("digits.won.other", "¡Lo has descifrado en {n} intentos!"),
+    ("digits.lost", "El código era {code}."),
+    ("digits.reply", "{bulls} y {cows}."),
+    ("digits.bulls.one", "{n} toro"),
+    ("digits.bulls.other", "{n} toros"),
+    ("digits.cows.one", "{n} vaca"),
+    ("digits.cows.other", "{n} vacas"),
+    ("digits.not-digits", "«{input}» no son todo cifras"),
+    ("digits.wrong-length", "un código tiene {length} cifras"),
+    ("digits.repeated-digit", "todas las cifras de un código son distintas"),
+    ("float.opening.one", "¡Adivina el número entre {min} y {max}, con {n} decimal!"),
+    ("float.opening.other", "¡Adivina el número entre {min} y {max}, con {n} decimales!"),
+    ("float.won.one", "¡Lo has adivinado en {n} intento!"),
+    ("float.won.other", "¡Lo has adivinado en {n} intentos!"),
+    ("proximity.opening", "¡Adivina el número entre {min} y {max}! Solo te diré si te acercas."),
+    ("proximity.boiling", "¡Te quemas!"),
+    ("proximity.warm", "Caliente."),
+    ("proximity.cold", "Frío."),
+    ("proximity.won.one", "¡Lo has encontrado en {n} intento!"),
+    ("proximity.won.other", "¡Lo has encontrado en {n} intentos!"),
+    ("ui.attempts", "Intentos: {guesses}"),
+    ("ui.attempts-of", "Intentos: {guesses} de {most}"),
+    ("ui.prompt", "Escribe un intento, o hint, range, giveup o quit."),
+    ("words.cant-read", "No se pueden leer palabras de {path}: {error}"),
+    ("words.opening", "¡Adivina la palabra, letra a letra! Tiene {letters} letras."),
+    ("words.cheat", "La palabra secreta es: {word}"),
+    ("words.missed", "Fallos: {misses}"),
+    ("words.prompt", "Escribe una letra, o giveup o quit."),
+    ("words.help", "Escribe una sola letra, o giveup o quit."),
+    ("words.found.one", "Sí, hay una {letter}."),
+    ("words.found.other", "Sí, hay {n} letras {letter}."),
+    ("words.missing", "No hay ninguna {letter}, lo siento."),
+    ("words.repeated", "Ya has probado la {letter}."),
+    ("words.was", "La palabra era {word}."),
+    ("bot.win", "¡He ganado!"),
+    ("bot.took", "El bot {strategy} necesitó {guesses} intentos."),
+    ("bot.only", "Solo puede ser {guess}, así que digo {guess}."),
+    ("bot.binary", "Está entre {low} y {high}, así que digo {guess}, en medio, para descartar la mitad."),
+    ("bot.random", "Está entre {low} y {high}, así que digo {guess} al azar."),
+    ("simulation.summary", "El bot {strategy} ganó {wins} de {games} partidas, con {average} intentos de media."),
+    ("simulation.best.one", "Su mejor partida fue en {n} intento."),
+    ("simulation.best.other", "Su mejor partida fue en {n} intentos."),
+    ("reverse.opening", "Piensa un número entre {min} y {max}, ¡y lo adivinaré!"),
+    ("reverse.ask", "¿Es {guess}? (h si el tuyo es mayor, l si es menor, c si es ese)"),
+    ("reverse.help", "Escribe h si tu número es mayor, l si es menor, o c si es ese."),
+    ("reverse.again", "Probemos otra vez: ¿es {guess}?"),
+    ("reverse.got-it.one", "¡Acerté en {n} intento!"),
+    ("reverse.got-it.other", "¡Acerté en {n} intentos!"),
+    ("contradiction.higher-earlier", "Eso no puede ser: dices que es mayor que {guess}, pero antes dijiste que es menor que {earlier}."),
+    ("contradiction.higher-range", "Eso no puede ser: dices que es mayor que {guess}, pero no pasa de {max}."),
+    ("contradiction.lower-earlier", "Eso no puede ser: dices que es menor que {guess}, pero antes dijiste que es mayor que {earlier}."),
+    ("contradiction.lower-range", "Eso no puede ser: dices que es menor que {guess}, pero no baja de {min}."),
+    ("contradiction.correct", "Eso no puede ser: {guess} no encaja con tus respuestas anteriores."),
+    ("race.failed", "La partida en red ha fallado: {error}"),
+    ("race.lost-connection", "Se ha perdido la conexión: {error}"),
+    ("race.help", "En una carrera, escribe un número, o quit."),
+    ("race.waiting", "Esperando a que otro jugador se una en el puerto {port}..."),
+    ("race.host-joined", "¡Se ha unido, así que es una carrera! Escribe un número, o quit."),
+    ("race.joining", "Uniéndose a la partida en {addr}..."),
+    ("race.guest-joined", "¡Te has unido, así que es una carrera! Escribe un número, o quit."),
+    ("race.left", "El otro jugador se ha ido."),
+    ("race.you-guessed", "Has dicho {guess}: ¡{feedback}!"),
+    ("race.they-guessed", "Ha dicho {guess}: ¡{feedback}!"),
+    ("race.you-win", "¡Has ganado! El número era {secret}."),
+    ("race.they-win", "¡Ha llegado antes! El número era {secret}."),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::achievements::RULES;
+
+    /// The `{name}`s in `text`, in order.
+    fn placeholders(text: &str) -> Vec<&str> {
+        text.split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+            .collect()
+    }
+
+    #[test]
+    fn parses_languages_and_locales() {
+        assert_eq!(Ok(Lang::En), Lang::parse("en"));
+        assert_eq!(Ok(Lang::Es), Lang::parse("ES"));
+        assert_eq!(Ok(Lang::Es), Lang::parse("es_ES.UTF-8"));
+        assert_eq!(Ok(Lang::En), Lang::parse("en-GB"));
+        assert!(Lang::parse("C").is_err());
+        assert!(Lang::parse("").is_err());
+    }
+
+    #[test]
+    fn fills_in_messages() {
+        assert_eq!("You guessed: 50", t!(Lang::En, "play.guessed", guess = 50));
+        assert_eq!(
+            "Quedan 3 intentos.",
+            t!(Lang::Es, "play.guesses-left", count = 3)
+        );
+        assert_eq!(
+            "1 guess left.",
+            t!(Lang::En, "play.guesses-left", count = 1)
+        );
+        assert_eq!("no.such.key", Lang::Es.text("no.such.key"));
+    }
+
+    #[test]
+    fn every_message_is_translated() {
+        for &(key, text) in EN {
+            let translated = Lang::Es
+                .get(key)
+                .unwrap_or_else(|| panic!("no es for {key}"));
+            let mut expected = placeholders(text);
+            let mut found = placeholders(translated);
+            expected.sort_unstable();
+            expected.dedup();
+            found.sort_unstable();
+            found.dedup();
+            assert_eq!(expected, found, "{key}");
+        }
+        for rule in RULES {
+            assert!(Lang::Es.get(&format!("{}.name", rule.id())).is_some());
+            assert!(Lang::Es
+                .get(&format!("{}.description", rule.id()))
+                .is_some());
+        }
+    }
+
+    #[test]
+    fn errors_are_translated() {
+        use crate::digits::{Code, CodeError};
+        use crate::number::Number;
+        use crate::{profiles, Config};
+
+        let config = |args: &[&str]| {
+            Config::parse(args.iter().map(|arg| arg.to_string()))
+                .unwrap_err()
+                .localize(Lang::Es)
+        };
+        assert_eq!(
+            "--min (5) tiene que ser menor que --max (1)",
+            config(&["--min", "5", "--max", "1"])
+        );
+        assert_eq!(
+            "--digits no se puede usar con --words",
+            config(&["--words", "--digits"])
+        );
+        assert_eq!("--seed necesita un número", config(&["--seed"]));
+        assert_eq!(
+            "idioma desconocido fr, se esperaba en o es",
+            config(&["--lang", "fr"])
+        );
+        assert_eq!(
+            "«../ada» no es un nombre de perfil: usa letras, cifras, - y _",
+            config(&["--profile", "../ada"])
+        );
+
+        assert_eq!(
+            "«ten» no es un número",
+            i32::parse("ten").unwrap_err().localize(Lang::Es)
+        );
+        assert_eq!(
+            "un código tiene 4 cifras",
+            Code::parse("427").unwrap_err().localize(Lang::Es)
+        );
+        assert_eq!(
+            "todas las cifras de un código son distintas",
+            CodeError::RepeatedDigit.localize(Lang::Es)
+        );
+        assert_eq!(
+            "un perfil necesita un nombre",
+            profiles::validate(" ").unwrap_err().localize(Lang::Es)
+        );
+        assert_eq!(
+            "a profile needs a name",
+            profiles::validate(" ").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn keys_are_unique() {
+        for catalog in [EN, ES] {
+            for (i, (key, _)) in catalog.iter().enumerate() {
+                assert!(!catalog[..i].iter().any(|(k, _)| k == key), "{key}");
+            }
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use std::cmp::Ordering;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+#[macro_use]
+mod i18n;
+
+pub mod achievements;
+pub mod bot;
+pub mod cli;
+mod command;
+mod config;
+pub mod daily;
+pub mod digits;
+pub mod feedback;
+pub mod hangman;
+pub mod hints;
+mod history;
+pub mod net;
+pub mod number;
+pub mod profiles;
+pub mod records;
+pub mod replay;
+pub mod reverse;
+pub mod save;
+pub mod scores;
+mod session;
+pub mod tournament;
+pub mod ui;
+
+pub use bot::Strategy;
+pub use command::{Command, InputError, Retries};
+pub use config::{Config, ConfigError, Difficulty, Needs};
+pub use feedback::{Classic, Feedback, Round};
+pub use hints::Hint;
+pub use history::History;
+pub use i18n::{Lang, Localize};
+pub use number::Number;
+pub use session::Session;
+
+/// Picks a secret number from `min..=max` with `rng`, which can be seeded
+/// for a repeatable game.
+pub fn pick_secret<R: Rng>(rng: &mut R, min: i64, max: i64) -> i64 {
+    i64::pick(rng, min, max)
+}
+
+/// What's shown as `game` starts: the range to guess from, and the secret
+/// itself only with `--cheat`.
+pub fn opening(config: &Config, game: &Game) -> String {
+    let lang = config.lang.unwrap_or_default();
+    let mut text = t!(lang, "play.opening", min = config.min, max = config.max) + "\n";
+    if config.cheat {
+        text += &(t!(lang, "play.cheat", secret = game.secret()) + "\n");
+    }
+    text
+}
+
+/// What a single guess told the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Outcome {
+    TooSmall,
+    TooBig,
+    Win,
+    /// The last guess allowed missed.
+    Lose,
+}
+
+impl Localize for Outcome {
+    /// What the guess was, like "too small".
+    fn localize(&self, lang: Lang) -> String {
+        match self {
+            Outcome::TooSmall => t!(lang, "feedback.too-small"),
+            Outcome::TooBig => t!(lang, "feedback.too-big"),
+            Outcome::Win | Outcome::Lose => t!(lang, "feedback.right"),
+        }
+    }
+}
+
+/// Whether the game is still going.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Playing,
+    Won,
+    Lost,
+}
+
+/// One round of the guessing game, without any input or output, so the
+/// rules can be tested on their own.
+#[derive(Debug)]
+pub struct Game {
+    round: Round<Classic>,
+    /// The range the secret was picked from.
+    min: i64,
+    max: i64,
+    hints: u32,
+    history: History,
+}
+
+impl Game {
+    pub fn new(secret: i64) -> Self {
+        Game {
+            round: Round::new(Classic::new(secret)),
+            min: i64::MIN,
+            max: i64::MAX,
+            hints: 0,
+            history: History::new(i64::MIN, i64::MAX),
+        }
+    }
+
+    /// Tells the game which range the secret was picked from, for hints
+    /// and the bounds its history narrows down.
+    pub fn with_range(mut self, min: i64, max: i64) -> Self {
+        self.min = min;
+        self.max = max;
+        self.history = History::new(min, max);
+        self
+    }
+
+    /// Ends the game in a loss once `max` guesses have missed.
+    pub fn with_max_attempts(mut self, max: Option<u32>) -> Self {
+        self.round = self.round.with_max_attempts(max);
+        self
+    }
+
+    pub fn secret(&self) -> i64 {
+        self.round.feedback().secret
+    }
+
+    /// The range the secret was picked from.
+    pub fn range(&self) -> (i64, i64) {
+        (self.min, self.max)
+    }
+
+    /// How many guesses the player gets, if they're limited.
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.round.max_attempts()
+    }
+
+    /// How many guesses have been made so far.
+    pub fn attempts(&self) -> u32 {
+        self.round.attempts()
+    }
+
+    /// How many guesses are left, if they're limited.
+    pub fn remaining(&self) -> Option<u32> {
+        self.round.remaining()
+    }
+
+    /// How many hints have been given.
+    pub fn hints(&self) -> u32 {
+        self.hints
+    }
+
+    /// The guesses so far, with whether each was too small or too big.
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn state(&self) -> State {
+        self.round.state()
+    }
+
+    /// Ends the game in a loss.
+    pub fn give_up(&mut self) {
+        self.round.give_up();
+    }
+
+    /// Gives the next hint about the secret. Hints don't use up guesses.
+    pub fn hint(&mut self) -> Hint {
+        let hint = hints::hint(self.hints, self.secret(), self.min, self.max);
+        self.hints += 1;
+        hint
+    }
+
+    /// Compares `n` with the secret number. Guesses made after the game is
+    /// over aren't counted.
+    pub fn guess(&mut self, n: i64) -> Outcome {
+        let Some(ordering) = self.round.guess(&n) else {
+            return match self.state() {
+                State::Won => Outcome::Win,
+                _ => Outcome::Lose,
+            };
+        };
+        let outcome = match ordering {
+            Ordering::Less => Outcome::TooSmall,
+            Ordering::Greater => Outcome::TooBig,
+            Ordering::Equal => Outcome::Win,
+        };
+        // The history keeps which way a losing last guess was off.
+        self.history.record(n, outcome);
+        if self.state() == State::Lost {
+            return Outcome::Lose;
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    /// A generator that always produces the same bits.
+    struct Constant(u32);
+
+    impl Rng for Constant {
+        fn next_u32(&mut self) -> u32 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn secrets_come_from_the_generator() {
+        assert_eq!(1, pick_secret(&mut Constant(0), 1, 100));
+        assert_eq!(
+            i64::MAX - 1,
+            pick_secret(&mut Constant(0), i64::MAX - 1, i64::MAX)
+        );
+
+        let secrets = |seed| {
+            let mut rng = StdRng::from_seed(&[seed][..]);
+            (0..20)
+                .map(|_| pick_secret(&mut rng, 1, 10))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(secrets(7), secrets(7));
+        assert!(secrets(7).iter().all(|secret| (1..=10).contains(secret)));
+    }
+
+    #[test]
+    fn opening_keeps_the_secret() {
+        let config = Config {
+            min: 1_000,
+            max: 9_999,
+            ..Config::default()
+        };
+        let game = Game::new(4_321).with_range(config.min, config.max);
+        assert!(!opening(&config, &game).contains("4321"));
+
+        let cheat = Config {
+            cheat: true,
+            ..config
+        };
+        assert!(opening(&cheat, &game).contains("The secret number is: 4321"));
+    }
+
+    #[test]
+    fn too_small_and_too_big() {
+        let mut game = Game::new(50);
+        assert_eq!(Outcome::TooSmall, game.guess(10));
+        assert_eq!(Outcome::TooBig, game.guess(90));
+        assert_eq!(State::Playing, game.state());
+        assert_eq!(2, game.attempts());
+    }
+
+    #[test]
+    fn win() {
+        let mut game = Game::new(50);
+        game.guess(25);
+        assert_eq!(Outcome::Win, game.guess(50));
+        assert_eq!(State::Won, game.state());
+        assert_eq!(2, game.attempts());
+
+        // Once won, the game stays won.
+        assert_eq!(Outcome::Win, game.guess(1));
+        assert_eq!(2, game.attempts());
+    }
+
+    #[test]
+    fn hints_are_counted_but_cost_no_guesses() {
+        let mut game = Game::new(42).with_range(1, 100);
+        assert_eq!(Hint::Even(true), game.hint());
+        assert_eq!(Hint::DivisibleBy(3, true), game.hint());
+        assert_eq!(Hint::Between(26, 50), game.hint());
+        assert_eq!(3, game.hints());
+        assert_eq!(0, game.attempts());
+    }
+
+    #[test]
+    fn lose_when_out_of_attempts() {
+        let mut game = Game::new(50).with_max_attempts(Some(2));
+        assert_eq!(Outcome::TooSmall, game.guess(10));
+        assert_eq!(Some(1), game.remaining());
+        assert_eq!(Outcome::Lose, game.guess(90));
+        assert_eq!(State::Lost, game.state());
+        assert_eq!(Some(0), game.remaining());
+
+        // Even the right answer is too late now.
+        assert_eq!(Outcome::Lose, game.guess(50));
+        assert_eq!(
+            &[(10, Outcome::TooSmall), (90, Outcome::TooBig)],
+            game.history().turns()
+        );
+    }
+
+    #[test]
+    fn giving_up_loses() {
+        let mut game = Game::new(50).with_range(1, 100);
+        game.guess(60);
+        game.give_up();
+        assert_eq!(State::Lost, game.state());
+        assert_eq!((1, 59), game.history().bounds());
+        assert_eq!(Outcome::Lose, game.guess(50));
+    }
+
+    #[test]
+    fn last_attempt_can_still_win() {
+        let mut game = Game::new(50).with_max_attempts(Some(1));
+        assert_eq!(Outcome::Win, game.guess(50));
+        assert_eq!(State::Won, game.state());
+    }
+}
@@ -0,0 +1,282 @@
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::Outcome;
+
+/// Which version of the protocol this is, sent with `HELLO`.
+pub const VERSION: u32 = 1;
+
+/// How long to wait for the other side while connecting, and for any
+/// message to be sent.
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One of the two players in a race.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Player {
+    /// Picks the secret and keeps score.
+    Host,
+    Guest,
+}
+
+impl Player {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "host" => Ok(Player::Host),
+            "guest" => Ok(Player::Guest),
+            _ => Err(format!("unknown player {value}")),
+        }
+    }
+}
+
+impl fmt::Display for Player {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Player::Host => write!(f, "host"),
+            Player::Guest => write!(f, "guest"),
+        }
+    }
+}
+
+/// A line sent between host and guest. The guest opens with `HELLO 1` and
+/// the host answers `START <min> <max>`. From then on the guest sends
+/// `GUESS <n>`, and the host sends both players' results as `RESULT <host|guest>
+/// <n> <small|big|win|lose>`, then `WON <host|guest> <secret>` when somebody
+/// gets it. Either side can send `BYE` to leave.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    Hello(u32),
+    /// The secret has been picked from `min..=max`.
+    Start(i64, i64),
+    Guess(i64),
+    Result(Player, i64, Outcome),
+    /// The player guessed the secret.
+    Won(Player, i64),
+    Bye,
+}
+
+impl Message {
+    /// Parses a line, ignoring surrounding whitespace.
+    pub fn parse(line: &str) -> Result<Message, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let number = |word: &str| {
+            word.parse::<i64>()
+                .map_err(|_| format!("{word:?} isn't a number"))
+        };
+        match words.as_slice() {
+            ["HELLO", version] => version
+                .parse()
+                .map(Message::Hello)
+                .map_err(|_| format!("{version:?} isn't a version")),
+            ["START", min, max] => Ok(Message::Start(number(min)?, number(max)?)),
+            ["GUESS", guess] => Ok(Message::Guess(number(guess)?)),
+            ["RESULT", player, guess, outcome] => {
+                let outcome = match *outcome {
+                    "small" => Outcome::TooSmall,
+                    "big" => Outcome::TooBig,
+                    "win" => Outcome::Win,
+                    "lose" => Outcome::Lose,
+                    _ => return Err(format!("unknown outcome {outcome}")),
+                };
+                Ok(Message::Result(
+                    Player::parse(player)?,
+                    number(guess)?,
+                    outcome,
+                ))
+            }
+            ["WON", player, secret] => Ok(Message::Won(Player::parse(player)?, number(secret)?)),
+            ["BYE"] => Ok(Message::Bye),
+            _ => Err(format!("unexpected message {:?}", line.trim())),
+        }
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Message::Hello(version) => write!(f, "HELLO {version}"),
+            Message::Start(min, max) => write!(f, "START {min} {max}"),
+            Message::Guess(guess) => write!(f, "GUESS {guess}"),
+            Message::Result(player, guess, outcome) => {
+                let outcome = match outcome {
+                    Outcome::TooSmall => "small",
+                    Outcome::TooBig => "big",
+                    Outcome::Win => "win",
+                    Outcome::Lose => "lose",
+                };
+                write!(f, "RESULT {player} {guess} {outcome}")
+            }
+            Message::Won(player, secret) => write!(f, "WON {player} {secret}"),
+            Message::Bye => write!(f, "BYE"),
+        }
+    }
+}
+
+/// The receiving half of a connection.
+#[derive(Debug)]
+pub struct Incoming(BufReader<TcpStream>);
+
+impl Incoming {
+    /// Waits for the next message, or `None` once the other side has hung
+    /// up.
+    pub fn receive(&mut self) -> io::Result<Option<Message>> {
+        let mut line = String::new();
+        if self.0.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Message::parse(&line)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// The sending half of a connection.
+#[derive(Debug)]
+pub struct Outgoing(TcpStream);
+
+impl Outgoing {
+    pub fn send(&mut self, message: Message) -> io::Result<()> {
+        writeln!(self.0, "{message}")
+    }
+}
+
+/// A connection to the other player.
+#[derive(Debug)]
+pub struct Connection {
+    pub incoming: Incoming,
+    pub outgoing: Outgoing,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        Ok(Connection {
+            incoming: Incoming(BufReader::new(stream.try_clone()?)),
+            outgoing: Outgoing(stream),
+        })
+    }
+
+    /// Waits up to `TIMEOUT` for the next message, which must be there.
+    fn expect(&mut self) -> io::Result<Message> {
+        // Both halves share the socket, and so its timeouts.
+        self.outgoing.0.set_read_timeout(Some(TIMEOUT))?;
+        let message = self.incoming.receive()?;
+        // Once connected, players can take as long as they like.
+        self.outgoing.0.set_read_timeout(None)?;
+        message
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "the other player hung up"))
+    }
+}
+
+/// Waits for a guest to connect to `listener` and tells them the secret is
+/// in `min..=max`.
+pub fn accept(listener: &TcpListener, min: i64, max: i64) -> io::Result<Connection> {
+    let (stream, _) = listener.accept()?;
+    let mut connection = Connection::new(stream)?;
+    match connection.expect()? {
+        Message::Hello(VERSION) => {}
+        message => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("expected HELLO {VERSION}, got {message}"),
+            ))
+        }
+    }
+    connection.outgoing.send(Message::Start(min, max))?;
+    Ok(connection)
+}
+
+/// Connects to the host at `addr`, returning the connection and the range
+/// the secret is in.
+pub fn join(addr: impl ToSocketAddrs) -> io::Result<(Connection, i64, i64)> {
+    let mut last_error = None;
+    for addr in addr.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, TIMEOUT) {
+            Ok(stream) => {
+                let mut connection = Connection::new(stream)?;
+                connection.outgoing.send(Message::Hello(VERSION))?;
+                return match connection.expect()? {
+                    Message::Start(min, max) => Ok((connection, min, max)),
+                    message => Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("expected START, got {message}"),
+                    )),
+                };
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error
+        .unwrap_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address to connect to")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn messages_round_trip() {
+        let messages = [
+            Message::Hello(VERSION),
+            Message::Start(-5, 1000),
+            Message::Guess(42),
+            Message::Result(Player::Guest, 42, Outcome::TooSmall),
+            Message::Result(Player::Host, 7, Outcome::Win),
+            Message::Won(Player::Host, 7),
+            Message::Bye,
+        ];
+        for message in messages {
+            assert_eq!(Ok(message), Message::parse(&format!("{message}\n")));
+        }
+        assert_eq!(
+            "RESULT guest 42 big",
+            Message::Result(Player::Guest, 42, Outcome::TooBig).to_string()
+        );
+    }
+
+    #[test]
+    fn rejects_bad_messages() {
+        assert!(Message::parse("GUESS fifty").is_err());
+        assert!(Message::parse("RESULT nobody 1 win").is_err());
+        assert!(Message::parse("RESULT host 1 close").is_err());
+        assert!(Message::parse("HELLO").is_err());
+        assert!(Message::parse("").is_err());
+    }
+
+    #[test]
+    fn host_and_guest_talk() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let guest = thread::spawn(move || {
+            let (mut connection, min, max) = join(addr).unwrap();
+            assert_eq!((1, 100), (min, max));
+            connection.outgoing.send(Message::Guess(50)).unwrap();
+            connection.incoming.receive().unwrap()
+        });
+
+        let mut connection = accept(&listener, 1, 100).unwrap();
+        assert_eq!(
+            Some(Message::Guess(50)),
+            connection.incoming.receive().unwrap()
+        );
+        let result = Message::Result(Player::Guest, 50, Outcome::TooBig);
+        connection.outgoing.send(result).unwrap();
+        assert_eq!(Some(result), guest.join().unwrap());
+        assert_eq!(None, connection.incoming.receive().unwrap());
+    }
+
+    #[test]
+    fn host_turns_away_strangers() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let stranger = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            writeln!(stream, "HELLO 99").unwrap();
+        });
+
+        assert!(accept(&listener, 1, 100).is_err());
+        stranger.join().unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use rand::Rng;
+
+use crate::{Lang, Localize};
+
+/// How many decimal places `--float` guesses are judged to, unless
+/// `--precision` says otherwise.
+pub const DEFAULT_PRECISION: u32 = 1;
+
+/// The most decimal places `--precision` allows, well within what an `f64`
+/// can tell apart.
+pub const MAX_PRECISION: u32 = 9;
+
+/// A kind of number the secret can be. Whatever reads, picks and compares
+/// numbers goes through this, so a game works the same for any of them.
+pub trait Number: Copy + Default + PartialOrd + fmt::Debug + fmt::Display {
+    /// Reads a number the player typed, ignoring surrounding whitespace.
+    fn parse(input: &str) -> Result<Self, NotANumber>;
+
+    /// Picks a number from `min..=max` with `rng`.
+    fn pick<R: Rng>(rng: &mut R, min: Self, max: Self) -> Self;
+
+    /// How `self` compares with `secret`, counting it as right when it's
+    /// no more than `tolerance` away.
+    fn compare(self, secret: Self, tolerance: Self) -> Ordering;
+}
+
+impl Number for i32 {
+    fn parse(input: &str) -> Result<i32, NotANumber> {
+        parse(input)
+    }
+
+    fn pick<R: Rng>(rng: &mut R, min: i32, max: i32) -> i32 {
+        i64::pick(rng, min.into(), max.into()) as i32
+    }
+
+    fn compare(self, secret: i32, _tolerance: i32) -> Ordering {
+        self.cmp(&secret)
+    }
+}
+
+impl Number for i64 {
+    fn parse(input: &str) -> Result<i64, NotANumber> {
+        parse(input)
+    }
+
+    fn pick<R: Rng>(rng: &mut R, min: i64, max: i64) -> i64 {
+        // The upper bound of `gen_range` is exclusive, so a range that
+        // ends at `i64::MAX` is shifted down by one to pick from it.
+        match max.checked_add(1) {
+            Some(end) => rng.gen_range(min, end),
+            None if min == i64::MIN => rng.gen(),
+            None => rng.gen_range(min - 1, max) + 1,
+        }
+    }
+
+    fn compare(self, secret: i64, _tolerance: i64) -> Ordering {
+        self.cmp(&secret)
+    }
+}
+
+impl Number for f64 {
+    fn parse(input: &str) -> Result<f64, NotANumber> {
+        parse(input).and_then(|number: f64| {
+            if number.is_finite() {
+                Ok(number)
+            } else {
+                Err(NotANumber(input.trim().to_string()))
+            }
+        })
+    }
+
+    fn pick<R: Rng>(rng: &mut R, min: f64, max: f64) -> f64 {
+        rng.gen_range(min, max)
+    }
+
+    fn compare(self, secret: f64, tolerance: f64) -> Ordering {
+        if (self - secret).abs() <= tolerance {
+            Ordering::Equal
+        } else if self < secret {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        }
+    }
+}
+
+fn parse<T: std::str::FromStr>(input: &str) -> Result<T, NotANumber> {
+    input
+        .trim()
+        .parse()
+        .map_err(|_| NotANumber(input.trim().to_string()))
+}
+
+/// What was typed, without surrounding whitespace, when it isn't a number
+/// of the kind wanted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotANumber(pub String);
+
+impl Localize for NotANumber {
+    fn localize(&self, lang: Lang) -> String {
+        t!(lang, "number.not-a-number", input = self.0)
+    }
+}
+
+impl fmt::Display for NotANumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Lang::En))
+    }
+}
+
+/// `value` rounded to `precision` decimal places.
+pub fn round(value: f64, precision: u32) -> f64 {
+    let scale = 10f64.powi(precision as i32);
+    (value * scale).round() / scale
+}
+
+/// How far a guess can be from a secret with `precision` decimal places and
+/// still round to it.
+pub fn tolerance(precision: u32) -> f64 {
+    0.5 * 10f64.powi(-(precision as i32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(Ok(-7), i32::parse(" -7\n"));
+        assert_eq!(Ok(9_000_000_000), i64::parse("9000000000"));
+        assert!(i32::parse("9000000000").is_err());
+        assert_eq!(Ok(3.25), f64::parse("3.25\n"));
+        assert!(f64::parse("NaN").is_err());
+        assert!(f64::parse("inf").is_err());
+        assert_eq!(Err(NotANumber("three".to_string())), f64::parse(" three"));
+    }
+
+    #[test]
+    fn floats_are_right_within_the_tolerance() {
+        let secret = round(4.56789, 2);
+        assert_eq!(4.57, secret);
+        assert_eq!(Ordering::Equal, 4.57.compare(secret, tolerance(2)));
+        assert_eq!(Ordering::Equal, 4.574.compare(secret, tolerance(2)));
+        assert_eq!(Ordering::Less, 4.56.compare(secret, tolerance(2)));
+        assert_eq!(Ordering::Greater, 4.58.compare(secret, tolerance(2)));
+        assert_eq!(Ordering::Greater, 4.574.compare(secret, 0.0));
+    }
+
+    #[test]
+    fn picks_from_big_ranges() {
+        let mut rng = StdRng::from_seed(&[3][..]);
+        for _ in 0..100 {
+            let big = i64::pick(&mut rng, 1, 10_000_000_000);
+            assert!((1..=10_000_000_000).contains(&big));
+            let top = i64::pick(&mut rng, i64::MAX - 1, i64::MAX);
+            assert!(top >= i64::MAX - 1);
+            let float = f64::pick(&mut rng, 1.0, 2.0);
+            assert!((1.0..=2.0).contains(&float));
+        }
+        i64::pick(&mut rng, i64::MIN, i64::MAX);
+    }
+}
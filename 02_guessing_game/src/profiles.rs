@@ -0,0 +1,208 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::mem;
+use std::path::Path;
+
+use crate::{Lang, Localize};
+
+/// The longest a profile name can be.
+pub const MAX_NAME_LENGTH: usize = 20;
+
+/// Words a profile name can't contain, however they're capitalised, since
+/// names turn up on the high-score table. Only whole words of the name are
+/// checked, so "Scunthorpe" is fine.
+const BLOCKED: &[&str] = &[
+    "bastard", "bitch", "bollock", "cunt", "fuck", "nigga", "nigger", "piss", "shit", "slut",
+    "twat", "wank", "whore",
+];
+
+/// Endings a blocked word can take and still be blocked, like "wanker".
+const ENDINGS: &[&str] = &["", "s", "es", "er", "ers", "ing", "ed", "y"];
+
+/// What `profiles` was asked to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    List,
+    Delete(String),
+}
+
+/// Why a name can't be used for a profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameError {
+    /// Nothing but whitespace was given.
+    Empty,
+    /// The name is longer than [`MAX_NAME_LENGTH`].
+    TooLong,
+    /// The name has something other than letters, digits, `-` and `_`.
+    BadCharacter(String),
+    /// The name contains one of the blocked words.
+    Blocked(String),
+}
+
+impl Localize for NameError {
+    fn localize(&self, lang: Lang) -> String {
+        match self {
+            NameError::Empty => t!(lang, "profiles.name-empty"),
+            NameError::TooLong => t!(lang, "profiles.name-too-long", most = MAX_NAME_LENGTH),
+            NameError::BadCharacter(name) => t!(lang, "profiles.name-bad-character", name = name),
+            NameError::Blocked(name) => t!(lang, "profiles.name-blocked", name = name),
+        }
+    }
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Lang::En))
+    }
+}
+
+/// Checks `name` can be used for a profile, returning it without the
+/// surrounding whitespace. Names are letters, digits, `-` and `_`, so they
+/// make safe directory names.
+pub fn validate(name: &str) -> Result<String, NameError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(NameError::Empty);
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(NameError::TooLong);
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(NameError::BadCharacter(name.to_string()));
+    }
+    if words(name).iter().any(|word| is_blocked(word)) {
+        return Err(NameError::Blocked(name.to_string()));
+    }
+    Ok(name.to_string())
+}
+
+/// The words in `name`, split at `-`, `_` and wherever lower case turns to
+/// upper case, lower-cased and with digits read as the letters they look
+/// like.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut after_lower = false;
+    for c in name.chars() {
+        let separator = c == '-' || c == '_';
+        if (separator || (after_lower && c.is_uppercase())) && !word.is_empty() {
+            words.push(mem::take(&mut word));
+        }
+        after_lower = c.is_lowercase();
+        if separator {
+            continue;
+        }
+        word.extend(c.to_lowercase().map(|c| match c {
+            '0' => 'o',
+            '1' => 'i',
+            '3' => 'e',
+            '4' => 'a',
+            '5' => 's',
+            '7' => 't',
+            c => c,
+        }));
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn is_blocked(word: &str) -> bool {
+    BLOCKED.iter().any(|blocked| {
+        word.strip_prefix(blocked)
+            .is_some_and(|ending| ENDINGS.contains(&ending))
+    })
+}
+
+/// The profiles kept in `dir`, in alphabetical order. A missing directory
+/// has none.
+pub fn list(dir: &Path) -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        // Anything that isn't a valid name wasn't made by the game.
+        if let Some(name) = entry.file_name().to_str() {
+            if validate(name).as_deref() == Ok(name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort_by_key(|name| name.to_lowercase());
+    Ok(names)
+}
+
+/// Deletes the profile `name` from `dir`, with everything it kept.
+pub fn delete(dir: &Path, name: &str) -> io::Result<()> {
+    fs::remove_dir_all(dir.join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_names() {
+        assert_eq!(Ok("ada".to_string()), validate(" ada\n"));
+        assert_eq!(Ok("Ada_L-2".to_string()), validate("Ada_L-2"));
+        assert_eq!(Ok("José".to_string()), validate("José"));
+        assert_eq!(Err(NameError::Empty), validate(""));
+        assert_eq!(
+            Err(NameError::BadCharacter("../ada".to_string())),
+            validate("../ada")
+        );
+        assert!(validate("ada lovelace").is_err());
+        assert_eq!(
+            Err(NameError::TooLong),
+            validate(&"a".repeat(MAX_NAME_LENGTH + 1))
+        );
+        assert!(validate(&"a".repeat(MAX_NAME_LENGTH)).is_ok());
+    }
+
+    #[test]
+    fn rejects_profanity() {
+        assert_eq!(
+            Err(NameError::Blocked("ShitHead".to_string())),
+            validate("ShitHead")
+        );
+        assert!(validate("xX_fuck_Xx").is_err());
+        assert!(validate("Wanker").is_err());
+        assert!(validate("sh1t-4-brains").is_err());
+        assert!(validate("Scrappy").is_ok());
+        assert!(validate("Scunthorpe").is_ok());
+        assert!(validate("Sniggers").is_ok());
+    }
+
+    #[test]
+    fn lists_and_deletes_profiles() {
+        let dir = std::env::temp_dir().join(format!(
+            "guessing-game-test-profiles-{}",
+            std::process::id()
+        ));
+        assert_eq!(Vec::<String>::new(), list(&dir).unwrap());
+        for name in ["bo", "Ada", "not a profile"] {
+            fs::create_dir_all(dir.join(name)).unwrap();
+        }
+        fs::write(dir.join("stray.json"), "").unwrap();
+        fs::write(dir.join("bo").join("scores.json"), "[]").unwrap();
+        assert_eq!(vec!["Ada", "bo"], list(&dir).unwrap());
+
+        delete(&dir, "bo").unwrap();
+        assert_eq!(vec!["Ada"], list(&dir).unwrap());
+        let err = delete(&dir, "bo").unwrap_err();
+        assert_eq!(io::ErrorKind::NotFound, err.kind());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
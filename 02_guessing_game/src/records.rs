@@ -0,0 +1,194 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Difficulty;
+
+/// How many characters the longest bar in a `stats` report takes.
+pub const BAR_WIDTH: usize = 30;
+
+/// How a finished number game went, kept for `stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    pub won: bool,
+    pub guesses: u32,
+    /// The `--difficulty` it was played at, or `None` for a range of its
+    /// own.
+    #[serde(default)]
+    pub difficulty: Option<Difficulty>,
+    pub seconds: u64,
+}
+
+/// Every number game finished by hand, kept between runs.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Records {
+    games: Vec<Record>,
+}
+
+impl Records {
+    /// Reads the records at `path`. A missing or damaged file is a fresh
+    /// start.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(serde_json::from_str(&text).unwrap_or_default()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes the records to `path`, creating its directory if need be. The
+    /// file is replaced all at once so a crash can't leave it half written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, json)?;
+        fs::rename(&temp, path)
+    }
+
+    pub fn add(&mut self, record: Record) {
+        self.games.push(record);
+    }
+
+    /// The games played, oldest first.
+    pub fn games(&self) -> &[Record] {
+        &self.games
+    }
+
+    /// How many games were won in one guess, two guesses and so on, up to
+    /// the most any win took.
+    pub fn distribution(&self) -> Vec<u32> {
+        let wins = self.games.iter().filter(|game| game.won);
+        let most = wins.clone().map(|game| game.guesses).max().unwrap_or(0);
+        let mut counts = vec![0; most as usize];
+        for game in wins.filter(|game| game.guesses > 0) {
+            counts[game.guesses as usize - 1] += 1;
+        }
+        counts
+    }
+
+    /// How many games were played and won at each difficulty that has been
+    /// played, easiest first, with games at a range of their own last.
+    pub fn by_difficulty(&self) -> Vec<(Option<Difficulty>, u32, u32)> {
+        [
+            Some(Difficulty::Easy),
+            Some(Difficulty::Normal),
+            Some(Difficulty::Hard),
+            None,
+        ]
+        .into_iter()
+        .filter_map(|difficulty| {
+            let games = self
+                .games
+                .iter()
+                .filter(|game| game.difficulty == difficulty);
+            let played = games.clone().count() as u32;
+            let won = games.filter(|game| game.won).count() as u32;
+            (played > 0).then_some((difficulty, played, won))
+        })
+        .collect()
+    }
+
+    /// How long a game took on average, in whole seconds, or `None` before
+    /// any have been played.
+    pub fn average_seconds(&self) -> Option<u64> {
+        let total: u64 = self.games.iter().map(|game| game.seconds).sum();
+        total.checked_div(self.games.len() as u64)
+    }
+}
+
+/// `part` as a percentage of `whole`, to the nearest whole number.
+pub fn percent(part: u32, whole: u32) -> u32 {
+    if whole == 0 {
+        return 0;
+    }
+    ((u64::from(part) * 200 + u64::from(whole)) / (u64::from(whole) * 2)) as u32
+}
+
+/// A bar of `#`s standing for `count` out of `most`, [`BAR_WIDTH`] long
+/// when they're equal. Anything more than none gets at least one.
+pub fn bar(count: u32, most: u32) -> String {
+    if most == 0 {
+        return String::new();
+    }
+    let length = (count as usize * BAR_WIDTH).div_ceil(most as usize);
+    "#".repeat(length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(won: bool, guesses: u32, difficulty: Option<Difficulty>) -> Record {
+        Record {
+            won,
+            guesses,
+            difficulty,
+            seconds: 10 * u64::from(guesses),
+        }
+    }
+
+    #[test]
+    fn counts_guesses_per_win() {
+        let mut records = Records::default();
+        assert!(records.distribution().is_empty());
+        records.add(record(true, 3, None));
+        records.add(record(true, 1, None));
+        records.add(record(true, 3, None));
+        records.add(record(false, 7, None));
+        assert_eq!(vec![1, 0, 2], records.distribution());
+    }
+
+    #[test]
+    fn counts_wins_by_difficulty() {
+        let mut records = Records::default();
+        records.add(record(false, 10, None));
+        records.add(record(true, 5, Some(Difficulty::Hard)));
+        records.add(record(false, 10, Some(Difficulty::Hard)));
+        records.add(record(true, 4, Some(Difficulty::Easy)));
+        assert_eq!(
+            vec![
+                (Some(Difficulty::Easy), 1, 1),
+                (Some(Difficulty::Hard), 2, 1),
+                (None, 1, 0),
+            ],
+            records.by_difficulty()
+        );
+        assert_eq!(Some(72), records.average_seconds());
+        assert_eq!(None, Records::default().average_seconds());
+    }
+
+    #[test]
+    fn scales_bars_and_percentages() {
+        assert_eq!("#".repeat(BAR_WIDTH), bar(4, 4));
+        assert_eq!("#".repeat(BAR_WIDTH / 2), bar(2, 4));
+        assert_eq!("#", bar(1, 1_000));
+        assert_eq!("", bar(0, 4));
+        assert_eq!("", bar(0, 0));
+        assert_eq!(67, percent(2, 3));
+        assert_eq!(100, percent(4, 4));
+        assert_eq!(0, percent(0, 0));
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let path = std::env::temp_dir().join(format!(
+            "guessing-game-test-records-{}.json",
+            std::process::id()
+        ));
+        let mut records = Records::default();
+        records.add(record(true, 6, Some(Difficulty::Normal)));
+        records.add(record(false, 7, None));
+        records.save(&path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.contains("\"difficulty\": \"normal\""), "{text}");
+        assert_eq!(records, Records::load(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+        assert_eq!(Records::default(), Records::load(&path).unwrap());
+    }
+}
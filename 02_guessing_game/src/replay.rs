@@ -0,0 +1,207 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{daily, Game, Outcome};
+
+/// How long `--replay` waits between turns when it's shown at a terminal.
+pub const TURN_DELAY: Duration = Duration::from_millis(800);
+
+/// Something that happened in a recorded game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum Event {
+    /// A game began, with `secret` picked from `min..=max`.
+    Start {
+        min: i64,
+        max: i64,
+        max_attempts: Option<u32>,
+        secret: i64,
+    },
+    /// The player guessed `guess`, and it was `outcome`.
+    Guess {
+        guess: i64,
+        outcome: Outcome,
+    },
+    Hint,
+    GiveUp,
+    /// The player quit part way through.
+    Quit,
+}
+
+/// A line of a transcript.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    /// When it happened, in seconds since 1970-01-01 UTC.
+    pub at: u64,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+/// A game read back from a transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recording {
+    /// When the game began, in seconds since 1970-01-01 UTC.
+    pub at: u64,
+    min: i64,
+    max: i64,
+    max_attempts: Option<u32>,
+    secret: i64,
+    /// What happened after it began, in order.
+    pub turns: Vec<Entry>,
+}
+
+impl Recording {
+    /// The game as it was when it began, ready for its turns to be played
+    /// again.
+    pub fn game(&self) -> Game {
+        Game::new(self.secret)
+            .with_range(self.min, self.max)
+            .with_max_attempts(self.max_attempts)
+    }
+}
+
+/// The events that start a transcript of `game`: how it was set up, and the
+/// guesses and hints already made if it's being resumed.
+pub fn start(game: &Game) -> Vec<Event> {
+    let (min, max) = game.range();
+    let mut events = vec![Event::Start {
+        min,
+        max,
+        max_attempts: game.max_attempts(),
+        secret: game.secret(),
+    }];
+    events.extend(
+        game.history()
+            .turns()
+            .iter()
+            .map(|&(guess, outcome)| Event::Guess { guess, outcome }),
+    );
+    events.extend((0..game.hints()).map(|_| Event::Hint));
+    events
+}
+
+/// Adds `event` to the end of the transcript at `path`, stamped with the
+/// time, creating the file if need be.
+pub fn append(path: &Path, event: Event) -> io::Result<()> {
+    let at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let line = serde_json::to_string(&Entry { at, event })?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")
+}
+
+/// Reads the games recorded at `path`, oldest first. A file that isn't a
+/// transcript is an `InvalidData` error.
+pub fn load(path: &Path) -> io::Result<Vec<Recording>> {
+    parse(&fs::read_to_string(path)?)
+}
+
+/// Reads the games in a transcript, skipping blank lines.
+fn parse(text: &str) -> io::Result<Vec<Recording>> {
+    let mut recordings: Vec<Recording> = Vec::new();
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let entry: Entry = serde_json::from_str(line)?;
+        match entry.event {
+            Event::Start {
+                min,
+                max,
+                max_attempts,
+                secret,
+            } => recordings.push(Recording {
+                at: entry.at,
+                min,
+                max,
+                max_attempts,
+                secret,
+                turns: Vec::new(),
+            }),
+            _ => match recordings.last_mut() {
+                Some(recording) => recording.turns.push(entry),
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "the transcript doesn't start with a game",
+                    ))
+                }
+            },
+        }
+    }
+    Ok(recordings)
+}
+
+/// `at`, in seconds since 1970-01-01 UTC, as a date and time like
+/// "2024-03-01 09:30 UTC".
+pub fn format_time(at: u64) -> String {
+    let days = (at / 86_400) as i64;
+    let minutes = at % 86_400 / 60;
+    format!(
+        "{} {:02}:{:02} UTC",
+        daily::format_date(days),
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_and_reads_back_games() {
+        let path = std::env::temp_dir().join(format!(
+            "guessing-game-test-replay-{}.log",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        let mut game = Game::new(30).with_range(1, 100);
+        game.guess(50);
+        for event in start(&game) {
+            append(&path, event).unwrap();
+        }
+        let outcome = game.guess(30);
+        append(&path, Event::Guess { guess: 30, outcome }).unwrap();
+        append(&path, start(&Game::new(7).with_range(1, 10)).remove(0)).unwrap();
+        append(&path, Event::Quit).unwrap();
+
+        let recordings = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(2, recordings.len());
+        let events: Vec<&Event> = recordings[0].turns.iter().map(|e| &e.event).collect();
+        assert_eq!(
+            vec![
+                &Event::Guess {
+                    guess: 50,
+                    outcome: Outcome::TooBig
+                },
+                &Event::Guess {
+                    guess: 30,
+                    outcome: Outcome::Win
+                },
+            ],
+            events
+        );
+        assert_eq!(30, recordings[0].game().secret());
+        assert_eq!((1, 10), recordings[1].game().range());
+        assert_eq!(Event::Quit, recordings[1].turns[0].event);
+    }
+
+    #[test]
+    fn transcripts_start_with_a_game() {
+        assert!(parse("\n").unwrap().is_empty());
+        let err = parse(r#"{"at": 0, "event": "hint"}"#).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        assert!(parse("not json").is_err());
+    }
+
+    #[test]
+    fn formats_times() {
+        assert_eq!("1970-01-01 00:00 UTC", format_time(59));
+        assert_eq!("2024-03-01 09:30 UTC", format_time(1_709_285_400));
+    }
+}
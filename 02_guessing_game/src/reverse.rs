@@ -0,0 +1,215 @@
+use std::fmt;
+
+use rand::Rng;
+
+use crate::{History, Lang, Localize, Outcome, Strategy};
+
+/// What the player says about one of the computer's guesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    /// Their number is higher than the guess.
+    Higher,
+    /// Their number is lower than the guess.
+    Lower,
+    Correct,
+}
+
+/// What to tell the player when they type something else.
+pub const HELP: &str = "Type h if your number is higher, l if it's lower, or c if that's it.";
+
+impl Answer {
+    /// Parses a line of input, ignoring case and surrounding whitespace.
+    pub fn parse(input: &str) -> Result<Answer, &'static str> {
+        let input = input.trim().to_ascii_lowercase();
+        match input.as_str() {
+            "h" | "higher" => Ok(Answer::Higher),
+            "l" | "lower" => Ok(Answer::Lower),
+            "c" | "correct" => Ok(Answer::Correct),
+            _ => Err(HELP),
+        }
+    }
+}
+
+/// An answer that can't be true given the ones before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contradiction {
+    pub guess: i64,
+    pub answer: Answer,
+    /// The earlier guess the answer conflicts with, or `None` if it
+    /// conflicts with the range the number was picked from.
+    pub earlier: Option<i64>,
+    pub min: i64,
+    pub max: i64,
+}
+
+impl Localize for Contradiction {
+    fn localize(&self, lang: Lang) -> String {
+        let key = match (self.answer, self.earlier) {
+            (Answer::Higher, Some(_)) => "contradiction.higher-earlier",
+            (Answer::Higher, None) => "contradiction.higher-range",
+            (Answer::Lower, Some(_)) => "contradiction.lower-earlier",
+            (Answer::Lower, None) => "contradiction.lower-range",
+            (Answer::Correct, _) => "contradiction.correct",
+        };
+        t!(
+            lang,
+            key,
+            guess = self.guess,
+            earlier = self.earlier.unwrap_or_default(),
+            min = self.min,
+            max = self.max,
+        )
+    }
+}
+
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.localize(Lang::En))
+    }
+}
+
+/// The computer's side of a game where the player picks the number.
+#[derive(Debug)]
+pub struct Guesser {
+    strategy: Strategy,
+    min: i64,
+    max: i64,
+    /// The player's answers, as if the computer were playing a normal game.
+    history: History,
+    found: bool,
+}
+
+impl Guesser {
+    /// A guesser for a number the player picked from `min..=max`.
+    pub fn new(strategy: Strategy, min: i64, max: i64) -> Self {
+        Guesser {
+            strategy,
+            min,
+            max,
+            history: History::new(min, max),
+            found: false,
+        }
+    }
+
+    /// The next number to ask about.
+    pub fn guess<R: Rng>(&self, rng: &mut R) -> i64 {
+        let (low, high) = self.history.bounds();
+        self.strategy.guess(rng, low, high)
+    }
+
+    /// How many guesses the player has answered.
+    pub fn guesses(&self) -> u32 {
+        self.history.turns().len() as u32
+    }
+
+    /// Whether the player has said a guess was right.
+    pub fn found(&self) -> bool {
+        self.found
+    }
+
+    /// Takes the player's `answer` about `guess`. An answer that contradicts
+    /// the earlier ones is left out so the player can answer again.
+    pub fn answer(&mut self, guess: i64, answer: Answer) -> Result<(), Contradiction> {
+        let outcome = match answer {
+            Answer::Higher => Outcome::TooSmall,
+            Answer::Lower => Outcome::TooBig,
+            Answer::Correct => Outcome::Win,
+        };
+        let (low, high) = self.history.bounds();
+        let fits = match answer {
+            Answer::Higher => guess < high,
+            Answer::Lower => guess > low,
+            Answer::Correct => (low..=high).contains(&guess),
+        };
+        if !fits {
+            return Err(Contradiction {
+                guess,
+                answer,
+                earlier: self.earlier(answer),
+                min: self.min,
+                max: self.max,
+            });
+        }
+        self.history.record(guess, outcome);
+        self.found = answer == Answer::Correct;
+        Ok(())
+    }
+
+    /// The earlier guess whose answer set the bound that `answer` runs
+    /// into, if it wasn't the range itself.
+    fn earlier(&self, answer: Answer) -> Option<i64> {
+        let turns = self.history.turns().iter();
+        match answer {
+            Answer::Higher => turns
+                .filter(|(_, outcome)| *outcome == Outcome::TooBig)
+                .map(|&(guess, _)| guess)
+                .min(),
+            Answer::Lower => turns
+                .filter(|(_, outcome)| *outcome == Outcome::TooSmall)
+                .map(|&(guess, _)| guess)
+                .max(),
+            Answer::Correct => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    #[test]
+    fn parses_answers() {
+        assert_eq!(Ok(Answer::Higher), Answer::parse("h\n"));
+        assert_eq!(Ok(Answer::Lower), Answer::parse(" L "));
+        assert_eq!(Ok(Answer::Correct), Answer::parse("correct"));
+        assert_eq!(Err(HELP), Answer::parse("50"));
+    }
+
+    #[test]
+    fn narrows_down_the_players_number() {
+        let mut rng = StdRng::from_seed(&[1][..]);
+        let mut guesser = Guesser::new(Strategy::Binary, 1, 100);
+        assert_eq!(50, guesser.guess(&mut rng));
+        assert_eq!(Ok(()), guesser.answer(50, Answer::Higher));
+        assert_eq!(75, guesser.guess(&mut rng));
+        assert_eq!(Ok(()), guesser.answer(75, Answer::Lower));
+        assert_eq!(62, guesser.guess(&mut rng));
+        assert!(!guesser.found());
+        assert_eq!(Ok(()), guesser.answer(62, Answer::Correct));
+        assert!(guesser.found());
+        assert_eq!(3, guesser.guesses());
+    }
+
+    #[test]
+    fn calls_out_contradictions() {
+        let mut guesser = Guesser::new(Strategy::Binary, 1, 100);
+        guesser.answer(50, Answer::Higher).unwrap();
+        guesser.answer(52, Answer::Lower).unwrap();
+
+        let contradiction = guesser.answer(51, Answer::Higher).unwrap_err();
+        assert_eq!(Some(52), contradiction.earlier);
+        assert_eq!(
+            "That can't be right: you said it's higher than 51, but earlier you said it's lower than 52.",
+            contradiction.to_string()
+        );
+        let contradiction = guesser.answer(51, Answer::Lower).unwrap_err();
+        assert_eq!(Some(50), contradiction.earlier);
+
+        // Contradictions aren't recorded, so the right answer still works.
+        assert_eq!(2, guesser.guesses());
+        assert_eq!(Ok(()), guesser.answer(51, Answer::Correct));
+    }
+
+    #[test]
+    fn calls_out_answers_outside_the_range() {
+        let mut guesser = Guesser::new(Strategy::Binary, 1, 100);
+        let contradiction = guesser.answer(100, Answer::Higher).unwrap_err();
+        assert_eq!(None, contradiction.earlier);
+        assert_eq!(
+            "That can't be right: you said it's higher than 100, but it's no more than 100.",
+            contradiction.to_string()
+        );
+        assert!(guesser.answer(1, Answer::Lower).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Game, State};
+
+/// Mixed into the scrambled secret and checksum, so they can't be worked
+/// out from the rest of the file alone.
+const KEY: u64 = 0x5eed_f00d_cafe_d00d;
+
+/// A game put aside part way through, to be resumed with `--resume`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedGame {
+    min: i64,
+    max: i64,
+    max_attempts: Option<u32>,
+    guesses: Vec<i64>,
+    hints: u32,
+    seconds: u64,
+    /// The secret, scrambled so it can't be read straight off the file.
+    secret: u64,
+    /// Catches changes made to the file by hand.
+    checksum: u64,
+}
+
+impl SavedGame {
+    /// Saves `game`, which has been played for `elapsed` so far.
+    pub fn new(game: &Game, elapsed: Duration) -> Self {
+        let (min, max) = game.range();
+        let mut saved = SavedGame {
+            min,
+            max,
+            max_attempts: game.max_attempts(),
+            guesses: game
+                .history()
+                .turns()
+                .iter()
+                .map(|&(guess, _)| guess)
+                .collect(),
+            hints: game.hints(),
+            seconds: elapsed.as_secs(),
+            secret: 0,
+            checksum: 0,
+        };
+        saved.secret = game.secret() as u64 ^ saved.key();
+        saved.checksum = saved.checksum();
+        saved
+    }
+
+    /// Rebuilds the game and how long it had been played, or `None` if the
+    /// file was changed since it was saved.
+    pub fn restore(&self) -> Option<(Game, Duration)> {
+        if self.checksum != self.checksum() || self.min >= self.max {
+            return None;
+        }
+        let secret = (self.secret ^ self.key()) as i64;
+        if !(self.min..=self.max).contains(&secret) {
+            return None;
+        }
+        let mut game = Game::new(secret)
+            .with_range(self.min, self.max)
+            .with_max_attempts(self.max_attempts);
+        for &guess in &self.guesses {
+            game.guess(guess);
+        }
+        for _ in 0..self.hints {
+            game.hint();
+        }
+        // Only games still being played are saved.
+        if game.state() != State::Playing {
+            return None;
+        }
+        Some((game, Duration::from_secs(self.seconds)))
+    }
+
+    /// Reads the game saved at `path`, or `None` if there isn't one. A file
+    /// that isn't a saved game is an `InvalidData` error.
+    pub fn load(path: &Path) -> io::Result<Option<Self>> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(Some(serde_json::from_str(&text)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes the game to `path`, creating its directory if need be.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, json)?;
+        fs::rename(&temp, path)
+    }
+
+    /// What the secret is scrambled with, which differs between games.
+    fn key(&self) -> u64 {
+        fnv1a(&format!("{KEY}|{}|{}|{}", self.min, self.max, self.seconds))
+    }
+
+    fn checksum(&self) -> u64 {
+        fnv1a(&format!(
+            "{KEY}|{}|{}|{:?}|{:?}|{}|{}|{}",
+            self.min,
+            self.max,
+            self.max_attempts,
+            self.guesses,
+            self.hints,
+            self.seconds,
+            self.secret
+        ))
+    }
+}
+
+/// The 64-bit FNV-1a hash of `text`.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Outcome;
+    use std::env;
+
+    fn game() -> Game {
+        let mut game = Game::new(42).with_range(1, 100).with_max_attempts(Some(7));
+        game.guess(50);
+        game.guess(25);
+        game.hint();
+        game
+    }
+
+    #[test]
+    fn restores_the_game() {
+        let saved = SavedGame::new(&game(), Duration::from_secs(30));
+        let (mut restored, elapsed) = saved.restore().unwrap();
+        assert_eq!(Duration::from_secs(30), elapsed);
+        assert_eq!(42, restored.secret());
+        assert_eq!((1, 100), restored.range());
+        assert_eq!(Some(5), restored.remaining());
+        assert_eq!(1, restored.hints());
+        assert_eq!((26, 49), restored.history().bounds());
+        assert_eq!(Outcome::Win, restored.guess(42));
+    }
+
+    #[test]
+    fn hides_the_secret() {
+        let saved = SavedGame::new(&game(), Duration::from_secs(30));
+        let json = serde_json::to_string(&saved).unwrap();
+        assert!(!json.contains("42"), "{json}");
+    }
+
+    #[test]
+    fn refuses_changed_files() {
+        let saved = SavedGame::new(&game(), Duration::from_secs(30));
+        let mut changed = saved.clone();
+        changed.guesses.pop();
+        assert_eq!(None, changed.restore().map(|(game, _)| game.secret()));
+
+        let mut changed = saved.clone();
+        changed.secret ^= 1;
+        assert!(changed.restore().is_none());
+
+        let mut changed = saved;
+        changed.max = 1_000;
+        assert!(changed.restore().is_none());
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let dir = env::temp_dir().join(format!("guessing-game-save-{}", std::process::id()));
+        let path = dir.join("saved.json");
+        assert_eq!(None, SavedGame::load(&path).unwrap());
+
+        let saved = SavedGame::new(&game(), Duration::from_secs(30));
+        saved.save(&path).unwrap();
+        assert_eq!(Some(saved), SavedGame::load(&path).unwrap());
+
+        fs::write(&path, "{\"min\": 1}").unwrap();
+        assert_eq!(
+            io::ErrorKind::InvalidData,
+            SavedGame::load(&path).unwrap_err().kind()
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
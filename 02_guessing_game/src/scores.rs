@@ -0,0 +1,479 @@
+use std::cmp::Reverse;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::hangman::MAX_WRONG;
+use crate::hints::HINT_COST;
+
+/// How many scores the table keeps.
+pub const MAX_ENTRIES: usize = 10;
+
+/// The version of the file `--export-scores` writes. Files without one are
+/// plain tables, like the one high scores are kept in.
+pub const EXPORT_VERSION: u32 = 1;
+
+/// One won game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Score {
+    pub points: u32,
+    pub attempts: u32,
+    /// Hints taken, which tables from before hints existed don't have.
+    #[serde(default)]
+    pub hints: u32,
+    pub seconds: u64,
+    /// The range the secret was picked from, or zeros for a word game.
+    pub min: i64,
+    pub max: i64,
+    /// The word guessed, in a word game.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word: Option<String>,
+    /// Who set it (`--name`), to tell players apart in a merged table.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl Score {
+    /// Scores a game won in `attempts` guesses and `hints` hints after
+    /// `elapsed`. Finding the number as fast as halving the range each time
+    /// would is worth 1000 points, fewer for each extra guess, less
+    /// [`HINT_COST`] for every hint and a point for every second.
+    pub fn new(min: i64, max: i64, attempts: u32, hints: u32, elapsed: Duration) -> Self {
+        let size = u64::try_from(i128::from(max) - i128::from(min) + 1).unwrap_or(u64::MAX);
+        // Guesses needed to narrow the range down by halves.
+        let ideal = (u64::BITS - (size - 1).leading_zeros()).max(1);
+        let seconds = elapsed.as_secs();
+        let points = (1000 * ideal / attempts.max(1))
+            .min(1000)
+            .saturating_sub(hints.saturating_mul(HINT_COST))
+            .saturating_sub(u32::try_from(seconds).unwrap_or(u32::MAX));
+        Score {
+            points,
+            attempts,
+            hints,
+            seconds,
+            min,
+            max,
+            word: None,
+            name: None,
+        }
+    }
+
+    /// Credits the score to `name`.
+    pub fn with_name(mut self, name: Option<String>) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Scores a word game won with `wrong` wrong letters among `attempts`
+    /// after `elapsed`. A clean game is worth 1000 points, less a share
+    /// for each wrong letter and a point for every second.
+    pub fn for_word(word: &str, attempts: u32, wrong: u32, elapsed: Duration) -> Self {
+        let seconds = elapsed.as_secs();
+        let points = 1000u32
+            .saturating_sub(1000 * wrong / MAX_WRONG)
+            .saturating_sub(u32::try_from(seconds).unwrap_or(u32::MAX));
+        Score {
+            points,
+            attempts,
+            hints: 0,
+            seconds,
+            min: 0,
+            max: 0,
+            word: Some(word.to_string()),
+            name: None,
+        }
+    }
+}
+
+/// The file `--export-scores` writes, for another player to import.
+#[derive(Debug, Serialize, Deserialize)]
+struct Export {
+    version: u32,
+    scores: Vec<Score>,
+}
+
+/// The best scores, highest first.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct HighScores {
+    entries: Vec<Score>,
+}
+
+impl HighScores {
+    /// Reads the table at `path`. A missing file is an empty table, and a
+    /// damaged one keeps whatever entries can still be read.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn parse(text: &str) -> Self {
+        let values = match serde_json::from_str(text) {
+            Ok(Value::Array(values)) => values,
+            _ => Vec::new(),
+        };
+        let mut scores = HighScores {
+            entries: values
+                .into_iter()
+                .filter_map(|value| serde_json::from_value(value).ok())
+                .collect(),
+        };
+        scores.tidy();
+        scores
+    }
+
+    /// Writes the table to `path`, creating its directory if need be. The
+    /// file is replaced all at once so a crash can't leave half a table.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(&self.entries)?;
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, json)?;
+        fs::rename(&temp, path)
+    }
+
+    /// Adds `score`, returning its 1-based place if it made the table.
+    pub fn add(&mut self, score: Score) -> Option<usize> {
+        let place = self
+            .entries
+            .iter()
+            .position(|entry| score.points > entry.points)
+            .unwrap_or(self.entries.len());
+        if place >= MAX_ENTRIES {
+            return None;
+        }
+        self.entries.insert(place, score);
+        self.entries.truncate(MAX_ENTRIES);
+        Some(place + 1)
+    }
+
+    /// Writes the table to `path` for someone else to import, returning
+    /// how many scores it holds.
+    pub fn export(&self, path: &Path) -> io::Result<usize> {
+        let export = Export {
+            version: EXPORT_VERSION,
+            scores: self.entries.clone(),
+        };
+        fs::write(path, serde_json::to_string_pretty(&export)?)?;
+        Ok(self.entries.len())
+    }
+
+    /// Reads the scores exported to `path`, or a plain table, and merges
+    /// them in with [`merge`](HighScores::merge). A file from a newer
+    /// version of the game is an `InvalidData` error rather than being
+    /// half understood.
+    pub fn import(&mut self, path: &Path) -> io::Result<usize> {
+        let text = fs::read_to_string(path)?;
+        let scores = match serde_json::from_str(&text)? {
+            Value::Array(values) => values
+                .into_iter()
+                .filter_map(|value| serde_json::from_value(value).ok())
+                .collect(),
+            value => {
+                // Checked before the rest, which a newer version may have
+                // changed.
+                let version = value.get("version").and_then(Value::as_u64);
+                if let Some(version) = version.filter(|&v| v > u64::from(EXPORT_VERSION)) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "it was exported by a newer version of the game (version {version})"
+                        ),
+                    ));
+                }
+                serde_json::from_value::<Export>(value)?.scores
+            }
+        };
+        Ok(self.merge(scores))
+    }
+
+    /// Merges `scores` into the table, keeping each named player's best
+    /// score only, so a class can pool everyone's results. Unnamed scores
+    /// are kept unless they're already in the table. Returns how many of
+    /// `scores` made it in.
+    pub fn merge(&mut self, scores: Vec<Score>) -> usize {
+        let mut merged: Vec<(Score, bool)> = Vec::new();
+        let ours = self.entries.drain(..).map(|score| (score, false));
+        for (score, imported) in ours.chain(scores.into_iter().map(|score| (score, true))) {
+            let same = merged.iter().position(|(entry, _)| match &score.name {
+                Some(_) => entry.name == score.name,
+                None => *entry == score,
+            });
+            match same {
+                // Ties go to the score already there.
+                Some(i) if score.points > merged[i].0.points => merged[i] = (score, imported),
+                Some(_) => {}
+                None => merged.push((score, imported)),
+            }
+        }
+        // Stable, so equal scores stay in the order they were set.
+        merged.sort_by_key(|(entry, _)| Reverse(entry.points));
+        merged.truncate(MAX_ENTRIES);
+        let imported = merged.iter().filter(|&&(_, imported)| imported).count();
+        self.entries = merged.into_iter().map(|(score, _)| score).collect();
+        imported
+    }
+
+    pub fn entries(&self) -> &[Score] {
+        &self.entries
+    }
+
+    fn tidy(&mut self) {
+        // Stable, so equal scores stay in the order they were set.
+        self.entries.sort_by_key(|entry| Reverse(entry.points));
+        self.entries.truncate(MAX_ENTRIES);
+    }
+}
+
+/// Where the table lives: `guessing_game/scores.json` in the user's data
+/// directory, or in the `profile`'s directory if there is one, or `None` if
+/// there's no home directory to find it from.
+pub fn default_path(profile: Option<&str>) -> Option<PathBuf> {
+    data_path(profile, "scores.json")
+}
+
+/// Where the word game's table lives, next to the number game's.
+pub fn words_path(profile: Option<&str>) -> Option<PathBuf> {
+    data_path(profile, "words.json")
+}
+
+/// Where the daily challenge's stats live, next to the high scores.
+pub fn stats_path(profile: Option<&str>) -> Option<PathBuf> {
+    data_path(profile, "stats.json")
+}
+
+/// Where a game put aside with `quit` is saved.
+pub fn save_path(profile: Option<&str>) -> Option<PathBuf> {
+    data_path(profile, "saved.json")
+}
+
+/// Where every number game finished is kept for `stats`, next to the high
+/// scores.
+pub fn records_path(profile: Option<&str>) -> Option<PathBuf> {
+    data_path(profile, "games.json")
+}
+
+/// Where the unlocked achievements are kept, next to the high scores.
+pub fn achievements_path(profile: Option<&str>) -> Option<PathBuf> {
+    data_path(profile, "achievements.json")
+}
+
+/// Where each profile keeps its own files, in a directory named after it.
+pub fn profiles_dir() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("profiles"))
+}
+
+/// `file` in the `profile`'s directory, or else in the `guessing_game`
+/// directory itself.
+fn data_path(profile: Option<&str>, file: &str) -> Option<PathBuf> {
+    match profile {
+        Some(profile) => profiles_dir().map(|dir| dir.join(profile).join(file)),
+        None => data_dir().map(|dir| dir.join(file)),
+    }
+}
+
+/// The `guessing_game` directory of the user's data directory, or wherever
+/// `GUESSING_GAME_DATA` says instead, as the tests do on every platform.
+fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("GUESSING_GAME_DATA").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    let data = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    }?;
+    Some(data.join("guessing_game"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(points: u32) -> Score {
+        Score {
+            points,
+            attempts: 5,
+            hints: 0,
+            seconds: 10,
+            min: 1,
+            max: 100,
+            word: None,
+            name: None,
+        }
+    }
+
+    fn named(name: &str, points: u32) -> Score {
+        score(points).with_name(Some(name.to_string()))
+    }
+
+    fn names(scores: &HighScores) -> Vec<(Option<&str>, u32)> {
+        scores
+            .entries()
+            .iter()
+            .map(|s| (s.name.as_deref(), s.points))
+            .collect()
+    }
+
+    #[test]
+    fn scores_fewer_guesses_higher() {
+        // 1 to 100 takes 7 halvings.
+        assert_eq!(1000, Score::new(1, 100, 7, 0, Duration::ZERO).points);
+        assert_eq!(1000, Score::new(1, 100, 1, 0, Duration::ZERO).points);
+        assert_eq!(500, Score::new(1, 100, 14, 0, Duration::ZERO).points);
+        assert_eq!(
+            490,
+            Score::new(1, 100, 14, 0, Duration::from_secs(10)).points
+        );
+        assert_eq!(
+            0,
+            Score::new(1, 100, 7, 0, Duration::from_secs(5000)).points
+        );
+    }
+
+    #[test]
+    fn hints_cost_points() {
+        assert_eq!(800, Score::new(1, 100, 7, 2, Duration::ZERO).points);
+        assert_eq!(0, Score::new(1, 100, 7, 20, Duration::ZERO).points);
+    }
+
+    #[test]
+    fn scores_word_games_by_wrong_letters() {
+        let score = Score::for_word("cargo", 6, 0, Duration::ZERO);
+        assert_eq!(1000, score.points);
+        assert_eq!(Some("cargo".to_string()), score.word);
+        assert_eq!(500, Score::for_word("cargo", 8, 3, Duration::ZERO).points);
+        assert_eq!(
+            490,
+            Score::for_word("cargo", 8, 3, Duration::from_secs(10)).points
+        );
+    }
+
+    #[test]
+    fn keeps_the_top_entries_in_order() {
+        let mut scores = HighScores::default();
+        assert_eq!(Some(1), scores.add(score(100)));
+        assert_eq!(Some(1), scores.add(score(300)));
+        assert_eq!(Some(2), scores.add(score(200)));
+        for _ in 0..7 {
+            scores.add(score(50));
+        }
+        assert_eq!(None, scores.add(score(10)));
+        assert_eq!(Some(4), scores.add(score(60)));
+
+        let points: Vec<_> = scores.entries().iter().map(|s| s.points).collect();
+        assert_eq!(vec![300, 200, 100, 60, 50, 50, 50, 50, 50, 50], points);
+    }
+
+    #[test]
+    fn tolerates_damaged_files() {
+        assert_eq!(HighScores::default(), HighScores::parse("not json"));
+        assert_eq!(HighScores::default(), HighScores::parse(r#"{"points": 3}"#));
+
+        let text = r#"[
+            {"points": 10, "attempts": 3, "seconds": 4, "min": 1, "max": 100},
+            {"points": "lots"},
+            42,
+            {"points": 20, "attempts": 2, "hints": 1, "seconds": 1, "min": 1, "max": 50}
+        ]"#;
+        let points: Vec<_> = HighScores::parse(text)
+            .entries()
+            .iter()
+            .map(|s| s.points)
+            .collect();
+        assert_eq!(vec![20, 10], points);
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let dir = env::temp_dir().join(format!("guessing-game-scores-{}", std::process::id()));
+        let path = dir.join("scores.json");
+        assert_eq!(HighScores::default(), HighScores::load(&path).unwrap());
+
+        let mut scores = HighScores::default();
+        scores.add(score(100));
+        scores.add(score(200));
+        scores.save(&path).unwrap();
+        assert_eq!(scores, HighScores::load(&path).unwrap());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn merging_keeps_each_players_best() {
+        let mut scores = HighScores::default();
+        scores.add(named("ada", 300));
+        scores.add(named("bo", 200));
+        scores.add(score(100));
+
+        let imported = scores.merge(vec![
+            named("ada", 250),
+            named("bo", 400),
+            named("cy", 150),
+            score(100),
+            score(90),
+        ]);
+        assert_eq!(3, imported);
+        assert_eq!(
+            vec![
+                (Some("bo"), 400),
+                (Some("ada"), 300),
+                (Some("cy"), 150),
+                (None, 100),
+                (None, 90),
+            ],
+            names(&scores)
+        );
+        // Merging the same scores again changes nothing.
+        let again = scores.entries().to_vec();
+        assert_eq!(0, scores.merge(again));
+        assert_eq!(5, scores.entries().len());
+    }
+
+    #[test]
+    fn exports_and_imports() {
+        let dir = env::temp_dir().join(format!("guessing-game-export-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("export.json");
+
+        let mut theirs = HighScores::default();
+        theirs.add(named("ada", 300));
+        theirs.add(named("bo", 200));
+        assert_eq!(2, theirs.export(&path).unwrap());
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("\"version\": 1"));
+
+        let mut ours = HighScores::default();
+        ours.add(named("bo", 250));
+        assert_eq!(1, ours.import(&path).unwrap());
+        assert_eq!(vec![(Some("ada"), 300), (Some("bo"), 250)], names(&ours));
+
+        // Plain tables can be imported too.
+        theirs.save(&path).unwrap();
+        let mut ours = HighScores::default();
+        assert_eq!(2, ours.import(&path).unwrap());
+
+        fs::write(&path, r#"{"version": 2, "scores": {"ada": 300}}"#).unwrap();
+        let error = ours.import(&path).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+        assert!(error.to_string().contains("newer version"));
+        fs::write(&path, "not json").unwrap();
+        assert!(ours.import(&path).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{Game, State};
+
+/// Statistics over every game played since the program started.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub games: u32,
+    pub wins: u32,
+    /// Guesses made across all games.
+    pub guesses: u32,
+    /// The fewest guesses any won game took.
+    pub best: Option<u32>,
+}
+
+impl Session {
+    /// Adds a finished game to the statistics.
+    pub fn record(&mut self, game: &Game) {
+        self.add(game.state() == State::Won, game.attempts());
+    }
+
+    /// Adds a finished game of any kind that took `guesses` guesses.
+    pub fn add(&mut self, won: bool, guesses: u32) {
+        self.games += 1;
+        self.guesses += guesses;
+        if won {
+            self.wins += 1;
+            self.best = Some(self.best.map_or(guesses, |best| best.min(guesses)));
+        }
+    }
+
+    /// The average number of guesses per game, if any were played.
+    pub fn average_guesses(&self) -> Option<f64> {
+        (self.games > 0).then(|| f64::from(self.guesses) / f64::from(self.games))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn played(secret: i64, guesses: &[i64], max_attempts: Option<u32>) -> Game {
+        let mut game = Game::new(secret).with_max_attempts(max_attempts);
+        for &guess in guesses {
+            game.guess(guess);
+        }
+        game
+    }
+
+    #[test]
+    fn empty_session() {
+        let session = Session::default();
+        assert_eq!(None, session.average_guesses());
+        assert_eq!(None, session.best);
+    }
+
+    #[test]
+    fn records_wins_and_losses() {
+        let mut session = Session::default();
+        session.record(&played(5, &[1, 9, 5], None));
+        session.record(&played(5, &[5], None));
+        session.record(&played(5, &[1, 2], Some(2)));
+
+        assert_eq!(3, session.games);
+        assert_eq!(2, session.wins);
+        assert_eq!(Some(1), session.best);
+        assert_eq!(Some(2.0), session.average_guesses());
+    }
+}
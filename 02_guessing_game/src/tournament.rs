@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use crate::scores::Score;
+use crate::{Difficulty, Game, State};
+
+/// How a round of a tournament went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundResult {
+    pub difficulty: Difficulty,
+    pub won: bool,
+    pub attempts: u32,
+    /// What the round added to the total, after its [`weight`].
+    pub points: u32,
+}
+
+/// A tournament of games played one after another (`--rounds 3`), each a
+/// little harder than the last, scored by adding up the rounds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tournament {
+    rounds: u32,
+    results: Vec<RoundResult>,
+}
+
+impl Tournament {
+    pub fn new(rounds: u32) -> Self {
+        Tournament {
+            rounds,
+            results: Vec::new(),
+        }
+    }
+
+    /// How many rounds there are in all.
+    pub fn rounds(&self) -> u32 {
+        self.rounds
+    }
+
+    /// The round being played, counting from 1.
+    pub fn round(&self) -> u32 {
+        self.results.len() as u32 + 1
+    }
+
+    /// The difficulty of the next round, or `None` once they've all been
+    /// played.
+    pub fn next(&self) -> Option<Difficulty> {
+        (self.round() <= self.rounds).then(|| difficulty(self.round(), self.rounds))
+    }
+
+    /// Scores `game`, the round just played, which took `elapsed`.
+    pub fn finish(&mut self, game: &Game, elapsed: Duration) -> RoundResult {
+        let difficulty = difficulty(self.round(), self.rounds);
+        let won = game.state() == State::Won;
+        let points = if won {
+            let (min, max) = game.range();
+            Score::new(min, max, game.attempts(), game.hints(), elapsed).points * weight(difficulty)
+        } else {
+            0
+        };
+        let result = RoundResult {
+            difficulty,
+            won,
+            attempts: game.attempts(),
+            points,
+        };
+        self.results.push(result);
+        result
+    }
+
+    /// The rounds played so far, in order.
+    pub fn results(&self) -> &[RoundResult] {
+        &self.results
+    }
+
+    pub fn total(&self) -> u32 {
+        self.results.iter().map(|result| result.points).sum()
+    }
+
+    /// Whether every round has been played and won.
+    pub fn won(&self) -> bool {
+        self.results.len() as u32 == self.rounds && self.results.iter().all(|result| result.won)
+    }
+}
+
+/// The difficulty of `round` (counting from 1) out of `rounds`: the first
+/// rounds are easy and the last hard, with the rest split evenly between.
+pub fn difficulty(round: u32, rounds: u32) -> Difficulty {
+    match (round - 1) * 3 / rounds.max(1) {
+        0 => Difficulty::Easy,
+        1 => Difficulty::Normal,
+        _ => Difficulty::Hard,
+    }
+}
+
+/// What a round's points are multiplied by, so harder rounds count for
+/// more.
+pub fn weight(difficulty: Difficulty) -> u32 {
+    match difficulty {
+        Difficulty::Easy => 1,
+        Difficulty::Normal => 2,
+        Difficulty::Hard => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_get_harder() {
+        let levels = |rounds| -> Vec<Difficulty> {
+            (1..=rounds)
+                .map(|round| difficulty(round, rounds))
+                .collect()
+        };
+        use Difficulty::*;
+        assert_eq!(vec![Easy], levels(1));
+        assert_eq!(vec![Easy, Normal], levels(2));
+        assert_eq!(vec![Easy, Normal, Hard], levels(3));
+        assert_eq!(vec![Easy, Easy, Normal, Normal, Hard], levels(5));
+    }
+
+    #[test]
+    fn adds_up_the_rounds() {
+        let mut tournament = Tournament::new(2);
+        assert_eq!(Some(Difficulty::Easy), tournament.next());
+        let mut game = Game::new(25).with_range(1, 50);
+        game.guess(25);
+        let first = tournament.finish(&game, Duration::ZERO);
+        assert_eq!(1000, first.points);
+        assert_eq!(Some(Difficulty::Normal), tournament.next());
+        assert_eq!(2, tournament.round());
+
+        let mut game = Game::new(500)
+            .with_range(1, 1000)
+            .with_max_attempts(Some(1));
+        game.guess(1);
+        let second = tournament.finish(&game, Duration::ZERO);
+        assert!(!second.won);
+        assert_eq!(0, second.points);
+        assert_eq!(None, tournament.next());
+        assert_eq!(1000, tournament.total());
+        assert!(!tournament.won());
+    }
+
+    #[test]
+    fn harder_rounds_are_worth_more() {
+        let mut tournament = Tournament::new(3);
+        for (secret, max) in [(25, 50), (50, 100), (500, 1000)] {
+            let mut game = Game::new(secret).with_range(1, max);
+            game.guess(secret);
+            tournament.finish(&game, Duration::ZERO);
+        }
+        let points: Vec<u32> = tournament.results().iter().map(|r| r.points).collect();
+        assert_eq!(vec![1000, 2000, 3000], points);
+        assert_eq!(6000, tournament.total());
+        assert!(tournament.won());
+    }
+}
@@ -0,0 +1,397 @@
+use std::io::{self, IsTerminal};
+
+use ratatui::buffer::Buffer;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Margin, Position, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::symbols;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, BorderType, LineGauge, List, Paragraph, Widget};
+use ratatui::DefaultTerminal;
+
+use crate::{Game, Lang, Localize, State};
+
+/// How many characters fit between the frame's borders when it's printed
+/// rather than drawn on a terminal.
+pub const WIDTH: usize = 44;
+
+/// How many of the latest guesses the history panel shows when it's
+/// printed. On a terminal it shows as many as fit.
+pub const HISTORY_ROWS: usize = 8;
+
+/// What `--tui` shows of `game`: the range as a bar that shrinks to the
+/// numbers the secret can still be, the guesses so far and how many are
+/// left, with a message saying what just happened and, while the game goes
+/// on, a box to type the next guess in.
+#[derive(Clone, Copy)]
+pub struct View<'a> {
+    game: &'a Game,
+    lang: Lang,
+    message: &'a str,
+    typed: &'a str,
+}
+
+impl<'a> View<'a> {
+    pub fn new(game: &'a Game, lang: Lang, message: &'a str) -> Self {
+        View {
+            game,
+            lang,
+            message,
+            typed: "",
+        }
+    }
+
+    /// The same view with `typed` in the input box.
+    fn typing(self, typed: &'a str) -> Self {
+        View { typed, ..self }
+    }
+
+    fn playing(&self) -> bool {
+        self.game.state() == State::Playing
+    }
+
+    /// How many rows the view needs to show everything when `width`
+    /// characters fit between its borders.
+    fn height(&self, width: usize) -> u16 {
+        let bounds = wrap(&self.game.history().localize(self.lang), width).len();
+        // An empty history still has a row, saying so.
+        let turns = self.game.history().turns().len().clamp(1, HISTORY_ROWS);
+        let message = match self.message {
+            "" => 0,
+            message => wrap(message, width).len(),
+        };
+        let input = match self.playing() {
+            true => wrap(&t!(self.lang, "ui.prompt"), width).len() + 3,
+            false => 0,
+        };
+        (2 + 1 + bounds + 1 + turns + 2 + message + input) as u16
+    }
+
+    /// Renders the view [`WIDTH`] characters wide as plain text, for when
+    /// there's no terminal to draw on.
+    pub fn text(&self) -> String {
+        let area = Rect::new(0, 0, WIDTH as u16 + 4, self.height(WIDTH));
+        let mut buffer = Buffer::empty(area);
+        self.render(area, &mut buffer);
+        let mut text = String::new();
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                text += buffer[(x, y)].symbol();
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Where the cursor goes in the input box when the view fills `area`.
+    fn cursor(&self, area: Rect) -> Position {
+        let typed = self.typed.chars().count() as u16;
+        Position::new(
+            (area.left() + 3 + typed).min(area.right().saturating_sub(4)),
+            area.bottom().saturating_sub(3),
+        )
+    }
+}
+
+impl Widget for &View<'_> {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        let lang = self.lang;
+        let (min, max) = self.game.range();
+        let (low, high) = self.game.history().bounds();
+        let opening = t!(lang, "play.opening", min = min, max = max);
+        let frame = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(format!(" {opening} "));
+        let inner = frame.inner(area).inner(Margin::new(1, 0));
+        frame.render(area, buffer);
+        let width = inner.width as usize;
+
+        let bounds = wrap(&self.game.history().localize(lang), width);
+        let message = match self.message {
+            "" => Vec::new(),
+            message => wrap(message, width),
+        };
+        let prompt = match self.playing() {
+            true => wrap(&t!(lang, "ui.prompt"), width),
+            false => Vec::new(),
+        };
+        let input_rows = if self.playing() { 3 } else { 0 };
+        let [range, bounds_area, attempts, history, message_area, prompt_area, input] =
+            Layout::vertical([
+                Constraint::Length(1),
+                Constraint::Length(bounds.len() as u16),
+                Constraint::Length(1),
+                Constraint::Min(3),
+                Constraint::Length(message.len() as u16),
+                Constraint::Length(prompt.len() as u16),
+                Constraint::Length(input_rows),
+            ])
+            .areas(inner);
+
+        let labels = min.to_string().len() + max.to_string().len() + 2;
+        let bar = bar(min, max, low, high, width.saturating_sub(labels).max(1));
+        Line::from(vec![
+            format!("{min} ").into(),
+            bar.fg(Color::Cyan),
+            format!(" {max}").into(),
+        ])
+        .render(range, buffer);
+        Paragraph::new(lines(bounds)).render(bounds_area, buffer);
+
+        let guesses = self.game.attempts();
+        match self.game.max_attempts() {
+            Some(most) => LineGauge::default()
+                .label(t!(lang, "ui.attempts-of", guesses = guesses, most = most))
+                .ratio((f64::from(guesses) / f64::from(most.max(1))).min(1.0))
+                .filled_symbol(symbols::line::THICK_HORIZONTAL)
+                .filled_style(Style::new().fg(Color::Yellow))
+                .render(attempts, buffer),
+            None => Line::from(t!(lang, "ui.attempts", guesses = guesses)).render(attempts, buffer),
+        }
+
+        // The latest guesses, as many as fit.
+        let turns = self.game.history().turns();
+        let fit = history.height.saturating_sub(2) as usize;
+        let shown = turns.len().saturating_sub(fit);
+        let rows = turns
+            .iter()
+            .enumerate()
+            .skip(shown)
+            .map(|(turn, &(guess, outcome))| {
+                let turn = format!("{:>3}", turn + 1);
+                let feedback = outcome.localize(lang);
+                t!(
+                    lang,
+                    "history.turn",
+                    turn = turn,
+                    guess = guess,
+                    feedback = feedback
+                )
+            });
+        let history_block = Block::bordered().border_type(BorderType::Rounded);
+        if turns.is_empty() {
+            Paragraph::new(t!(lang, "history.empty"))
+                .block(history_block)
+                .render(history, buffer);
+        } else {
+            List::new(rows).block(history_block).render(history, buffer);
+        }
+
+        Paragraph::new(lines(message))
+            .bold()
+            .render(message_area, buffer);
+
+        if self.playing() {
+            Paragraph::new(lines(prompt)).render(prompt_area, buffer);
+            Paragraph::new(self.typed)
+                .block(Block::bordered().border_type(BorderType::Rounded))
+                .render(input, buffer);
+        }
+    }
+}
+
+fn lines(rows: Vec<String>) -> Vec<Line<'static>> {
+    rows.into_iter().map(Line::from).collect()
+}
+
+/// Where `--tui` shows the game: the whole terminal, redrawn as the player
+/// types, or views printed one after another when input or output isn't a
+/// terminal.
+pub enum Screen {
+    Live(DefaultTerminal),
+    Printed,
+}
+
+impl Screen {
+    pub fn open() -> Screen {
+        if io::stdin().is_terminal() && io::stdout().is_terminal() {
+            Screen::Live(ratatui::init())
+        } else {
+            Screen::Printed
+        }
+    }
+
+    /// Shows `view` and returns the next line the player types, or `None`
+    /// once input runs out or they press Ctrl-C. Printed screens get their
+    /// lines from `read_line`.
+    pub fn next_line(
+        &mut self,
+        view: View,
+        read_line: impl FnOnce() -> Option<String>,
+    ) -> Option<String> {
+        let terminal = match self {
+            Screen::Live(terminal) => terminal,
+            Screen::Printed => {
+                print!("{}", view.text());
+                return read_line();
+            }
+        };
+        let mut typed = String::new();
+        loop {
+            let view = view.typing(&typed);
+            terminal
+                .draw(|frame| {
+                    frame.render_widget(&view, frame.area());
+                    frame.set_cursor_position(view.cursor(frame.area()));
+                })
+                .ok()?;
+            let Event::Key(key) = event::read().ok()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Enter => return Some(typed),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return None,
+                KeyCode::Char(c) => typed.push(c),
+                KeyCode::Backspace => {
+                    typed.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Hands the terminal back and prints `view`, so how the game ended
+    /// stays on screen.
+    pub fn close(self, view: View) {
+        drop(self);
+        print!("{}", view.text());
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        if let Screen::Live(_) = self {
+            ratatui::restore();
+        }
+    }
+}
+
+/// A bar `width` characters wide standing for `min..=max`, filled in where
+/// the numbers are still in `low..=high`.
+pub fn bar(min: i64, max: i64, low: i64, high: i64, width: usize) -> String {
+    let size = i128::from(max) - i128::from(min) + 1;
+    let width = width as i128;
+    (0..width)
+        .map(|cell| {
+            // Each cell stands for an equal slice of the range, and small
+            // ranges give some numbers more than one cell.
+            let first = i128::from(min) + size * cell / width;
+            let last = (i128::from(min) + size * (cell + 1) / width - 1).max(first);
+            if first <= i128::from(high) && last >= i128::from(low) {
+                '█'
+            } else {
+                '░'
+            }
+        })
+        .collect()
+}
+
+/// Breaks `text` into rows at most `width` characters long, between words
+/// where it can.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut rows = Vec::new();
+    let mut row = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        // Words too long for a row of their own are split anywhere.
+        while word.len() > width {
+            if !row.is_empty() {
+                rows.push(std::mem::take(&mut row));
+            }
+            rows.push(word.drain(..width).collect());
+        }
+        let word: String = word.into_iter().collect();
+        if !row.is_empty() && row.chars().count() + 1 + word.chars().count() > width {
+            rows.push(std::mem::take(&mut row));
+        }
+        if !row.is_empty() {
+            row.push(' ');
+        }
+        row += &word;
+    }
+    if !row.is_empty() || rows.is_empty() {
+        rows.push(row);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bars_shrink_to_the_bounds() {
+        assert_eq!("██████████", bar(1, 100, 1, 100, 10));
+        assert_eq!("░░██░░░░░░", bar(1, 100, 26, 37, 10));
+        assert_eq!("░░░░░░░░░█", bar(1, 100, 100, 100, 10));
+        assert_eq!("░░░░░░░░░░", bar(1, 100, 60, 40, 10));
+        // Fewer numbers than cells.
+        assert_eq!("░░██░░", bar(1, 3, 2, 2, 6));
+        assert_eq!(
+            "█░░░",
+            bar(i64::MIN, i64::MAX, i64::MIN, i64::MIN / 2 - 1, 4)
+        );
+    }
+
+    #[test]
+    fn wraps_long_messages() {
+        let text = "word ".repeat(20);
+        let rows = wrap(&text, WIDTH);
+        assert!(rows.len() > 1);
+        assert!(rows.iter().all(|row| row.chars().count() <= WIDTH));
+        assert_eq!(text.trim(), rows.join(" "));
+        assert_eq!(
+            vec!["x".repeat(WIDTH), "xx".to_string()],
+            wrap(&"x".repeat(WIDTH + 2), WIDTH)
+        );
+        assert_eq!(vec![String::new()], wrap("", WIDTH));
+    }
+
+    #[test]
+    fn views_show_the_game() {
+        let mut game = Game::new(30).with_range(1, 100).with_max_attempts(Some(7));
+        game.guess(50);
+        game.guess(25);
+        let text = View::new(&game, Lang::En, "Too small!").text();
+        assert!(
+            text.starts_with("╭ Guess the number between 1 and 100! ─"),
+            "{text}"
+        );
+        assert!(text.contains("The number is between 26 and 49."), "{text}");
+        assert!(text.contains("Guesses: 2 of 7"), "{text}");
+        assert!(text.contains("  1. 50 was too big"), "{text}");
+        assert!(text.contains("  2. 25 was too small"), "{text}");
+        assert!(text.contains("Too small!"), "{text}");
+        assert!(text.contains("Type a guess"), "{text}");
+        assert!(
+            text.lines().all(|line| line.chars().count() == WIDTH + 4),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn views_keep_the_latest_guesses() {
+        let mut game = Game::new(1_000).with_range(1, 1_000);
+        for guess in 1..=20 {
+            game.guess(guess);
+        }
+        let text = View::new(&game, Lang::En, "").text();
+        assert!(!text.contains(" 12. "), "{text}");
+        assert!(text.contains(" 13. 13 was too small"), "{text}");
+        assert!(text.contains(" 20. 20 was too small"), "{text}");
+    }
+
+    #[test]
+    fn finished_games_have_no_input_box() {
+        let mut game = Game::new(5).with_range(1, 10);
+        game.guess(5);
+        let text = View::new(&game, Lang::En, "You win!").text();
+        assert!(!text.contains("Type a guess"), "{text}");
+        assert!(text.contains("You win!"), "{text}");
+        assert!(text.trim_end().ends_with('╯'), "{text}");
+    }
+}
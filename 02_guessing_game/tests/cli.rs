@@ -0,0 +1,571 @@
+use std::io::Write;
+use std::ops::{Deref, DerefMut};
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A game command with a data directory of its own, which is removed again
+/// when the command is dropped.
+struct Game {
+    command: Command,
+    data: PathBuf,
+}
+
+impl Deref for Game {
+    type Target = Command;
+
+    fn deref(&self) -> &Command {
+        &self.command
+    }
+}
+
+impl DerefMut for Game {
+    fn deref_mut(&mut self) -> &mut Command {
+        &mut self.command
+    }
+}
+
+impl Drop for Game {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.data);
+    }
+}
+
+/// The game, in English whatever the locale of the machine running the
+/// tests, keeping its files out of the user's own data directory. Tests that
+/// look at those files point `GUESSING_GAME_DATA` somewhere of their own.
+fn guessing_game() -> Game {
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    let mut command = Command::new(env!("CARGO_BIN_EXE_guessing_game"));
+    for var in ["LC_ALL", "LC_MESSAGES", "LANG"] {
+        command.env_remove(var);
+    }
+    let run = RUNS.fetch_add(1, Ordering::Relaxed);
+    let data = std::env::temp_dir().join(format!("guessing-game-cli-{}-{run}", std::process::id()));
+    command.env("GUESSING_GAME_DATA", &data);
+    Game { command, data }
+}
+
+fn run(args: &[&str]) -> Output {
+    guessing_game()
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .expect("failed to run guessing_game")
+}
+
+fn run_with_input(args: &[&str], input: &str) -> Output {
+    // Held until the game has finished, so its data goes after it does.
+    let mut game = guessing_game();
+    let mut child = game
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run guessing_game");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn scripted_win() {
+    let output = run(&["--min", "1", "--max", "2", "--guesses", "0,3,1,2"]);
+    let stdout = stdout(&output);
+
+    assert!(stdout.starts_with("Guess the number between 1 and 2!\n"));
+    assert!(stdout.contains("0 can't be it: the number is between 1 and 2.\n"));
+    assert!(stdout.contains("3 can't be it: the number is between 1 and 2.\n"));
+    assert!(!stdout.contains("You guessed: 0\n"));
+    assert!(stdout.ends_with("You win!\n"));
+    assert_eq!(Some(0), output.status.code());
+}
+
+#[test]
+fn ranges_beyond_i32() {
+    let (min, max) = ("5000000000", "5000000001");
+    let guesses = "4999999999,5000000000,5000000001";
+    let output = run(&["--min", min, "--max", max, "--guesses", guesses]);
+    let stdout = stdout(&output);
+
+    assert!(stdout.starts_with("Guess the number between 5000000000 and 5000000001!\n"));
+    assert!(stdout.contains("4999999999 can't be it: the number is between"));
+    assert!(stdout.ends_with("You win!\n"));
+    assert_eq!(Some(0), output.status.code());
+}
+
+#[test]
+fn scripted_loss_reveals_the_number() {
+    // The secret for this seed is 46.
+    let args = ["--difficulty", "easy", "--seed", "1", "--guesses"];
+    let output = run(&[&args[..], &["1,1,1,1,1,1,1,1,1,1"]].concat());
+
+    assert!(stdout(&output).contains("Out of guesses! The number was "));
+    assert_eq!(Some(1), output.status.code());
+}
+
+#[test]
+fn seeded_games_repeat() {
+    let args = ["--seed", "7", "--cheat", "--guesses", "50"];
+    assert_eq!(stdout(&run(&args)), stdout(&run(&args)));
+}
+
+#[test]
+fn running_out_of_guesses_ends_the_game() {
+    let output = run(&["--guesses", "0"]);
+    assert!(!stdout(&output).contains("You win!"));
+    assert_eq!(Some(1), output.status.code());
+}
+
+#[test]
+fn plays_in_spanish() {
+    let args = ["--min", "1", "--max", "2", "--guesses", "0,2,1"];
+    let output = run(&[&["--lang", "es"], &args[..]].concat());
+    let stdout = stdout(&output);
+
+    assert!(stdout.starts_with("¡Adivina el número entre 1 y 2!\n"));
+    assert!(stdout.contains("0 no puede ser: el número está entre 1 y 2.\n"));
+    assert!(stdout.ends_with("¡Has ganado!\n"));
+
+    let output = guessing_game()
+        .args(args)
+        .env("LANG", "es_ES.UTF-8")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("¡Adivina el número"));
+
+    let output = run(&["--lang", "fr"]);
+    assert_eq!(Some(2), output.status.code());
+
+    let output = run(&["--lang", "es", "--min", "5", "--max", "1"]);
+    assert_eq!(
+        "Problema con los argumentos: --min (5) tiene que ser menor que --max (1)\n",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output = run_with_input(&["--lang", "es", "--digits"], "42\nquit\n");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("un código tiene 4 cifras\n"));
+}
+
+#[test]
+fn bad_arguments() {
+    let output = run(&["--min", "5", "--max", "1"]);
+    assert_eq!(Some(2), output.status.code());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--min (5) must be less than --max (1)")
+    );
+}
+
+#[test]
+fn bot_explains_its_guesses() {
+    let output = run(&["--bot", "binary", "--seed", "3", "--max", "100"]);
+    let stdout = stdout(&output);
+
+    assert!(stdout.contains("It's between 1 and 100, so I guess 50, halfway"));
+    assert!(stdout.contains("I win!\nThe binary bot took "));
+    assert_eq!(Some(0), output.status.code());
+}
+
+#[test]
+fn simulation_reports_average_guesses() {
+    let output = run(&["--simulate", "100", "--seed", "3"]);
+    let stdout = stdout(&output);
+
+    assert!(stdout.starts_with("The binary bot won 100 of 100 games, taking "));
+    assert!(stdout.contains(" guesses on average.\n"));
+    assert_eq!(Some(0), output.status.code());
+}
+
+#[test]
+fn reverse_mode_guesses_the_players_number() {
+    let output = run_with_input(&["--reverse"], "h\nl\nh\nc\n");
+    let stdout = stdout(&output);
+
+    assert!(stdout.starts_with("Think of a number between 1 and 100, and I'll guess it!\n"));
+    assert!(stdout.contains("Is it 50?"));
+    assert!(stdout.contains("Is it 75?"));
+    assert!(stdout.contains("Is it 62?"));
+    assert!(stdout.ends_with(
+        "Is it 68? (h if yours is higher, l if it's lower, c if that's it)\nGot it in 4 guesses!\n"
+    ));
+    assert_eq!(Some(0), output.status.code());
+}
+
+#[test]
+fn reverse_mode_calls_out_contradictions() {
+    let output = run_with_input(&["--reverse", "--max", "4"], "h\nl\n");
+    let stdout = stdout(&output);
+
+    assert!(stdout.contains(
+        "That can't be right: you said it's lower than 3, but earlier you said it's higher than 2.\n"
+    ));
+    assert_eq!(Some(1), output.status.code());
+}
+
+#[test]
+fn digit_mode_counts_bulls_and_cows() {
+    let args = ["--digits", "--seed", "4", "--cheat"];
+    let first = stdout(&run_with_input(&args, ""));
+    let code = first
+        .lines()
+        .find_map(|line| line.strip_prefix("The secret code is: "))
+        .unwrap()
+        .to_string();
+    let wrong: String = code.chars().rev().collect();
+
+    let output = run_with_input(&args, &format!("12\n{wrong}\n{code}\n"));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("a code has 4 digits\n"));
+    assert!(stdout.contains(&format!("You guessed: {wrong}\n")));
+    assert!(stdout.contains(&format!("You guessed: {code}\n4 bulls and 0 cows.\n")));
+    assert!(stdout.ends_with("You cracked it in 2 guesses!\n"));
+    assert_eq!(Some(0), output.status.code());
+}
+
+#[test]
+fn float_mode_judges_to_the_precision() {
+    let args = ["--precision", "2", "--max", "10", "--seed", "2", "--cheat"];
+    let first = stdout(&run_with_input(&args, ""));
+    assert!(first.starts_with("Guess the number between 1 and 10, to 2 decimal places!\n"));
+    let secret: f64 = first
+        .lines()
+        .find_map(|line| line.strip_prefix("The secret number is: "))
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    let close = format!("{:.3}", secret + 0.004);
+    let output = run_with_input(&args, &format!("0\nten\n{close}\n"));
+    let stdout = stdout(&output);
+    assert!(stdout.contains("You guessed: 0\nToo small!\n"));
+    assert!(stdout.contains("\"ten\" isn't a number\n"));
+    assert!(stdout.ends_with("You win!\nYou got it in 2 guesses!\n"));
+    assert_eq!(Some(0), output.status.code());
+}
+
+#[test]
+fn tui_redraws_after_each_guess() {
+    let input = "five\n\n7\n1\n2\n";
+    let output = run_with_input(&["--tui", "--max", "2", "--cheat"], input);
+    let stdout = stdout(&output);
+
+    // Without a terminal, each view is printed in turn.
+    assert!(stdout.starts_with("╭ Guess the number between 1 and 2! ─"));
+    assert!(stdout.contains("│ 1 ████"));
+    assert!(stdout.contains("│ The secret number is: "));
+    assert!(stdout.contains("│ \"five\" isn't a number or a command."));
+    assert!(stdout.contains("│ You didn't type anything."));
+    assert!(stdout.contains("│ 7 can't be it: the number is between 1 and   │\n│ 2. Stuck?"));
+    assert!(stdout.contains("│ You win!"));
+    assert!(!stdout.contains('\x1b'));
+    assert!(stdout.ends_with("╯\n"));
+    assert_eq!(Some(0), output.status.code());
+}
+
+#[test]
+fn word_mode_guesses_letters() {
+    let dir = std::env::temp_dir().join(format!("guessing-game-cli-words-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wordlist = dir.join("words.txt");
+    std::fs::write(&wordlist, "cargo\n").unwrap();
+
+    let args = ["--wordlist", wordlist.to_str().unwrap()];
+    let output = run_with_input(&args, "a\nz\na\nhello\nc\nr\ng\no\n");
+    let stdout = stdout(&output);
+    assert!(stdout.starts_with("Guess the word, one letter at a time! It has 5 letters.\n"));
+    assert!(stdout.contains("_ a _ _ _\nMissed: z\n"));
+    assert!(stdout.contains("You've already guessed a.\n"));
+    assert!(stdout.contains("Type a single letter, or giveup or quit.\n"));
+    assert!(stdout.ends_with("c a r g o\nYou win!\n"));
+    assert_eq!(Some(0), output.status.code());
+
+    let output = run_with_input(&args, "z\ny\nx\nw\nv\nu\n");
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with("=========\nThe word was cargo.\n"));
+    assert_eq!(Some(1), output.status.code());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn daily_challenge_is_played_once_a_day() {
+    let data = std::env::temp_dir().join(format!("guessing-game-cli-daily-{}", std::process::id()));
+    let daily = |guesses: &str| {
+        guessing_game()
+            .args(["--daily", "--cheat", "--guesses", guesses])
+            .env("GUESSING_GAME_DATA", &data)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    };
+
+    let first = stdout(&daily("0"));
+    let secret = first
+        .lines()
+        .find_map(|line| line.strip_prefix("The secret number is: "))
+        .unwrap()
+        .to_string();
+    // Nothing's been finished yet, so the same secret comes up again.
+    let output = daily(&format!("0,{secret}"));
+    let stdout = stdout(&output);
+    assert!(stdout.starts_with("Daily challenge for 20"));
+    assert!(stdout.contains(&format!("The secret number is: {secret}\n")));
+    assert!(stdout.contains("You win!\nYour streak is 1 day (best 1).\n"));
+    assert!(stdout.contains("Achievement unlocked: Beginner's luck (win a game)!\n"));
+    assert_eq!(Some(0), output.status.code());
+
+    let output = daily("1");
+    assert!(String::from_utf8_lossy(&output.stdout)
+        .contains("You've already played today's challenge."));
+    assert_eq!(Some(0), output.status.code());
+    std::fs::remove_dir_all(data).unwrap();
+}
+
+#[test]
+fn resuming_without_a_saved_game_starts_a_new_one() {
+    let data =
+        std::env::temp_dir().join(format!("guessing-game-cli-resume-{}", std::process::id()));
+    let output = guessing_game()
+        .args(["--resume", "--max", "2", "--guesses", "1,2"])
+        .env("GUESSING_GAME_DATA", &data)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+
+    let stdout = stdout(&output);
+    assert!(stdout.starts_with(
+        "There's no saved game, so here's a new one.\nGuess the number between 1 and 2!\n"
+    ));
+    assert!(stdout.ends_with("You win!\n"));
+    assert!(!data.exists());
+}
+
+#[test]
+fn scores_can_be_imported_and_exported() {
+    let data =
+        std::env::temp_dir().join(format!("guessing-game-cli-export-{}", std::process::id()));
+    std::fs::create_dir_all(&data).unwrap();
+    let class = data.join("class.json");
+    std::fs::write(
+        &class,
+        r#"{"version": 1, "scores": [
+            {"points": 900, "attempts": 7, "seconds": 5, "min": 1, "max": 100, "name": "ada"},
+            {"points": 700, "attempts": 9, "seconds": 5, "min": 1, "max": 100, "name": "ada"},
+            {"points": 800, "attempts": 8, "seconds": 5, "min": 1, "max": 100, "name": "bo"}
+        ]}"#,
+    )
+    .unwrap();
+    let transfer = |args: &[&str]| {
+        guessing_game()
+            .args(args)
+            .env("GUESSING_GAME_DATA", &data)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    };
+
+    let output = transfer(&["--import-scores", class.to_str().unwrap()]);
+    let stdout = stdout(&output);
+    assert!(stdout.starts_with("Imported 2 scores from "));
+    assert!(stdout.contains(" 1.  900 points by ada, 7 guesses in 5s (1 to 100)\n"));
+    assert!(stdout.contains(" 2.  800 points by bo, "));
+    assert!(!stdout.contains(" 3. "));
+    assert_eq!(Some(0), output.status.code());
+
+    let out = data.join("out.json");
+    let output = transfer(&["--export-scores", out.to_str().unwrap()]);
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("Exported 2 scores to "));
+    assert!(std::fs::read_to_string(&out)
+        .unwrap()
+        .contains("\"version\": 1"));
+
+    std::fs::write(&class, r#"{"version": 9, "scores": []}"#).unwrap();
+    let output = transfer(&["--import-scores", class.to_str().unwrap()]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("newer version"));
+    assert_eq!(Some(1), output.status.code());
+    std::fs::remove_dir_all(data).unwrap();
+}
+
+#[test]
+fn bad_input_is_explained() {
+    let input = "fifty\n\n99999999999999999999\n0\n1\nhint\n\n2\n";
+    let output = run_with_input(&["--min", "1", "--max", "2"], input);
+    let stdout = stdout(&output);
+
+    assert!(stdout.contains("\"fifty\" isn't a number or a command.\n"));
+    assert!(stdout.contains("You didn't type anything.\n"));
+    assert!(stdout.contains("99999999999999999999 is far too big to be the number.\nStuck? "));
+    assert!(stdout.contains("0 can't be it: the number is between 1 and 2.\nPlease"));
+    assert_eq!(1, stdout.matches("Stuck? ").count());
+    assert_eq!(Some(0), output.status.code());
+}
+
+#[test]
+fn recorded_games_replay() {
+    let dir = std::env::temp_dir().join(format!("guessing-game-cli-replay-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let log = dir.join("replay.log");
+    let log = log.to_str().unwrap();
+    let _ = std::fs::remove_file(log);
+
+    let args = ["--min", "1", "--max", "2", "--record", log, "--guesses"];
+    run(&[&args[..], &["1,2"]].concat());
+    run(&[&args[..], &["2,1"]].concat());
+    let transcript = std::fs::read_to_string(log).unwrap();
+    assert_eq!(2, transcript.matches("\"event\":\"start\"").count());
+
+    let output = run(&["--replay", log]);
+    let stdout = stdout(&output);
+    assert!(stdout.starts_with("Replaying the game played at "));
+    assert_eq!(2, stdout.matches("Replaying the game").count());
+    assert!(stdout.contains("UTC.\nGuess the number between 1 and 2!\nThe secret number was "));
+    assert!(stdout.ends_with("You win!\n"));
+    assert_eq!(Some(0), output.status.code());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    let output = run(&["--replay", log]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Can't read the recorded games"));
+    assert_eq!(Some(1), output.status.code());
+}
+
+#[test]
+fn stats_report_the_games_played() {
+    let data = std::env::temp_dir().join(format!("guessing-game-cli-stats-{}", std::process::id()));
+    let stats = || {
+        guessing_game()
+            .arg("stats")
+            .env("GUESSING_GAME_DATA", &data)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    };
+    let output = stats();
+    assert_eq!(
+        "No games played yet. Play one and come back!\n",
+        stdout(&output)
+    );
+
+    std::fs::create_dir_all(&data).unwrap();
+    std::fs::write(
+        data.join("games.json"),
+        r#"[
+            {"won": true, "guesses": 2, "difficulty": "easy", "seconds": 20},
+            {"won": true, "guesses": 4, "difficulty": null, "seconds": 30},
+            {"won": false, "guesses": 7, "difficulty": "normal", "seconds": 70}
+        ]"#,
+    )
+    .unwrap();
+    let output = stats();
+    let stdout = stdout(&output);
+    let bar = |n| format!("{:<30}", "#".repeat(n));
+    assert!(
+        stdout.starts_with("3 games played, 2 won (67%).\n"),
+        "{stdout}"
+    );
+    assert!(stdout.contains(&format!("1 | {} 0\n2 | {} 1\n", bar(0), bar(30))));
+    assert!(stdout.contains(&format!("easy   | {} 100% (1 of 1)\n", bar(30))));
+    assert!(stdout.contains(&format!("normal | {}   0% (0 of 1)\n", bar(0))));
+    assert!(stdout.contains(&format!("custom | {} 100% (1 of 1)\n", bar(30))));
+    assert!(stdout.ends_with("Average time: 40s.\n"));
+    assert_eq!(Some(0), output.status.code());
+    std::fs::remove_dir_all(data).unwrap();
+}
+
+#[test]
+fn tournaments_add_up_the_rounds() {
+    // The secrets for this seed are 34, then 83.
+    let output = run(&["--rounds", "2", "--seed", "2", "--guesses", "34,50,83"]);
+    let stdout = stdout(&output);
+
+    assert!(
+        stdout.starts_with("Round 1 of 2: easy (points ×1).\nGuess the number between 1 and 50!\n")
+    );
+    assert!(
+        stdout.contains("Round 2 of 2: normal (points ×2).\nGuess the number between 1 and 100!\n")
+    );
+    assert!(stdout.contains("Scoreboard:\n1. easy   | won in 1 guess   | "));
+    assert!(stdout.contains("\n2. normal | won in 2 guesses | "));
+    assert!(stdout.contains("\nTotal: "));
+    assert_eq!(Some(0), output.status.code());
+
+    let output = run(&["--rounds", "2", "--seed", "2", "--guesses", "34"]);
+    let quit = String::from_utf8_lossy(&output.stdout);
+    assert!(quit.contains("Scoreboard:\n1. easy | won in 1 guess | "));
+    assert!(!quit.contains("\n2. "));
+    assert_eq!(Some(1), output.status.code());
+}
+
+#[test]
+fn proximity_feedback_says_how_close() {
+    // The secret for this seed is 46.
+    let args = [
+        "--feedback",
+        "proximity",
+        "--difficulty",
+        "easy",
+        "--seed",
+        "1",
+    ];
+    let output = run_with_input(&args, "1\n40\n45\n46\n");
+    let stdout = stdout(&output);
+
+    assert!(stdout.starts_with("Guess the number between 1 and 50! I'll only say how close"));
+    assert!(stdout.contains("You guessed: 1\nCold.\n"));
+    assert!(stdout.contains("You guessed: 40\nWarm.\n"));
+    assert!(stdout.contains("You guessed: 45\nBoiling!\n"));
+    assert!(!stdout.contains("Too small!"));
+    assert!(stdout.ends_with("You found it in 4 guesses!\n"));
+    assert_eq!(Some(0), output.status.code());
+}
+
+#[test]
+fn profiles_keep_their_own_scores() {
+    let data =
+        std::env::temp_dir().join(format!("guessing-game-cli-profiles-{}", std::process::id()));
+    std::fs::create_dir_all(&data).unwrap();
+    let game = |args: &[&str]| {
+        guessing_game()
+            .args(args)
+            .env("GUESSING_GAME_DATA", &data)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    };
+    let output = game(&["profiles"]);
+    assert!(stdout(&output).starts_with("There are no profiles yet."));
+
+    let class = data.join("class.json");
+    std::fs::write(
+        &class,
+        r#"[{"points": 900, "attempts": 7, "seconds": 5, "min": 1, "max": 100}]"#,
+    )
+    .unwrap();
+    let import = ["--import-scores", class.to_str().unwrap(), "--profile"];
+    assert_eq!(
+        Some(0),
+        game(&[&import[..], &["ada"]].concat()).status.code()
+    );
+    assert!(data.join("profiles/ada/scores.json").exists());
+    assert!(!data.join("scores.json").exists());
+
+    let output = game(&["profiles"]);
+    assert_eq!("Profiles:\n  ada\n", stdout(&output));
+
+    let output = game(&["profiles", "delete", "ada"]);
+    assert_eq!("Deleted the profile ada.\n", stdout(&output));
+    assert!(!data.join("profiles/ada").exists());
+    let output = game(&["profiles", "delete", "ada"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("There's no profile called ada."));
+    assert_eq!(Some(1), output.status.code());
+
+    let output = game(&["--profile", "ShitHead"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("isn't allowed as a profile name"));
+    assert_eq!(Some(2), output.status.code());
+    std::fs::remove_dir_all(data).unwrap();
+}
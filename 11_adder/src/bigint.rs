@@ -0,0 +1,212 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::Add;
+use std::str::FromStr;
+
+/// Each limb holds nine decimal digits, so two of them and a carry still fit
+/// in a `u32`.
+const BASE: u32 = 1_000_000_000;
+const DIGITS_PER_LIMB: usize = 9;
+
+/// A whole number that can be as big as memory allows.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Limbs in base [`BASE`], least significant first, with no zero limbs
+    /// at the end, so zero has none at all.
+    limbs: Vec<u32>,
+}
+
+/// Why a string couldn't be read as a [`BigUint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseBigUintError {
+    Empty,
+    InvalidDigit(char),
+}
+
+impl fmt::Display for ParseBigUintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBigUintError::Empty => write!(f, "cannot parse a number from an empty string"),
+            ParseBigUintError::InvalidDigit(c) => write!(f, "{c:?} is not a decimal digit"),
+        }
+    }
+}
+
+impl Error for ParseBigUintError {}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        BigUint::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn trim(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(mut n: u64) -> Self {
+        let mut limbs = Vec::new();
+        while n > 0 {
+            limbs.push((n % u64::from(BASE)) as u32);
+            n /= u64::from(BASE);
+        }
+        BigUint { limbs }
+    }
+}
+
+/// Reads a string of decimal digits, with no sign or separators. Leading
+/// zeros are allowed.
+impl FromStr for BigUint {
+    type Err = ParseBigUintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseBigUintError::Empty);
+        }
+        if let Some(c) = s.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(ParseBigUintError::InvalidDigit(c));
+        }
+        // Every character is an ASCII digit, so slicing by bytes is safe.
+        let mut limbs = Vec::with_capacity(s.len().div_ceil(DIGITS_PER_LIMB));
+        let mut end = s.len();
+        while end > 0 {
+            let start = end.saturating_sub(DIGITS_PER_LIMB);
+            limbs.push(s[start..end].parse().expect("checked to be digits"));
+            end = start;
+        }
+        let mut n = BigUint { limbs };
+        n.trim();
+        Ok(n)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut limbs = self.limbs.iter().rev();
+        match limbs.next() {
+            None => write!(f, "0"),
+            Some(first) => {
+                write!(f, "{first}")?;
+                for limb in limbs {
+                    write!(f, "{limb:09}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let (longer, shorter) = if self.limbs.len() >= other.limbs.len() {
+            (&self.limbs, &other.limbs)
+        } else {
+            (&other.limbs, &self.limbs)
+        };
+        let mut limbs = Vec::with_capacity(longer.len() + 1);
+        let mut carry = 0;
+        for (i, &limb) in longer.iter().enumerate() {
+            let sum = limb + shorter.get(i).copied().unwrap_or(0) + carry;
+            limbs.push(sum % BASE);
+            carry = sum / BASE;
+        }
+        if carry > 0 {
+            limbs.push(carry);
+        }
+        BigUint { limbs }
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: BigUint) -> BigUint {
+        &self + &other
+    }
+}
+
+/// Adds two numbers written out in decimal, however long they are.
+pub fn add_big(left: &str, right: &str) -> Result<String, ParseBigUintError> {
+    let sum = &left.parse::<BigUint>()? + &right.parse::<BigUint>()?;
+    Ok(sum.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_small_numbers() {
+        assert_eq!("4", add_big("2", "2").unwrap());
+        assert_eq!("0", add_big("0", "0").unwrap());
+        assert_eq!("100", add_big("0", "100").unwrap());
+    }
+
+    #[test]
+    fn carries_all_the_way() {
+        let nines = "9".repeat(50);
+        let expected = format!("1{}", "0".repeat(50));
+        assert_eq!(expected, add_big(&nines, "1").unwrap());
+        assert_eq!(expected, add_big("1", &nines).unwrap());
+    }
+
+    #[test]
+    fn carries_across_limbs() {
+        assert_eq!("1000000000", add_big("999999999", "1").unwrap());
+        assert_eq!(
+            "1000000000000000000",
+            add_big("999999999999999999", "1").unwrap()
+        );
+        // The lower limb is all zeros and has to be printed padded.
+        assert_eq!("2000000001", add_big("1000000000", "1000000001").unwrap());
+    }
+
+    #[test]
+    fn matches_known_large_sums() {
+        // 2^64 + 2^64 = 2^65
+        assert_eq!(
+            "36893488147419103232",
+            add_big("18446744073709551616", "18446744073709551616").unwrap()
+        );
+        // The 100th Fibonacci number is the sum of the 98th and 99th.
+        assert_eq!(
+            "354224848179261915075",
+            add_big("135301852344706746049", "218922995834555169026").unwrap()
+        );
+        let sum = BigUint::from(u64::MAX) + BigUint::from(1);
+        assert_eq!("18446744073709551616", sum.to_string());
+    }
+
+    #[test]
+    fn leading_zeros_are_ignored() {
+        assert_eq!("7", add_big("0000000000000000000007", "0").unwrap());
+        assert!("000".parse::<BigUint>().unwrap().is_zero());
+        assert_eq!(BigUint::from(42), "0042".parse().unwrap());
+    }
+
+    #[test]
+    fn rejects_anything_but_digits() {
+        assert_eq!(Err(ParseBigUintError::Empty), add_big("", "1"));
+        assert_eq!(
+            Err(ParseBigUintError::InvalidDigit('-')),
+            add_big("1", "-1")
+        );
+        assert_eq!(
+            Err(ParseBigUintError::InvalidDigit(' ')),
+            "12 34".parse::<BigUint>()
+        );
+        assert_eq!(
+            "'x' is not a decimal digit",
+            ParseBigUintError::InvalidDigit('x').to_string()
+        );
+    }
+}
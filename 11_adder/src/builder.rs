@@ -0,0 +1,150 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::Rectangle;
+
+/// Builds a [`Rectangle`], checking it makes sense first. Start one with
+/// [`Rectangle::builder`].
+#[derive(Debug, Clone, Default)]
+pub struct RectangleBuilder {
+    width: Option<u32>,
+    height: Option<u32>,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+}
+
+/// Why [`RectangleBuilder::build`] refused to build a rectangle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    MissingWidth,
+    MissingHeight,
+    ZeroWidth,
+    ZeroHeight,
+    TooWide { width: u32, max: u32 },
+    TooTall { height: u32, max: u32 },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::MissingWidth => write!(f, "the rectangle needs a width"),
+            BuildError::MissingHeight => write!(f, "the rectangle needs a height"),
+            BuildError::ZeroWidth => write!(f, "the rectangle's width can't be 0"),
+            BuildError::ZeroHeight => write!(f, "the rectangle's height can't be 0"),
+            BuildError::TooWide { width, max } => {
+                write!(f, "a width of {width} is more than the most allowed, {max}")
+            }
+            BuildError::TooTall { height, max } => {
+                write!(
+                    f,
+                    "a height of {height} is more than the most allowed, {max}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+impl RectangleBuilder {
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Refuses to build anything wider than `max_width`.
+    pub fn max_width(mut self, max_width: u32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Refuses to build anything taller than `max_height`.
+    pub fn max_height(mut self, max_height: u32) -> Self {
+        self.max_height = Some(max_height);
+        self
+    }
+
+    /// The rectangle, as long as it has both sides, neither of them is 0 and
+    /// neither goes over its maximum. The width is checked before the
+    /// height.
+    pub fn build(&self) -> Result<Rectangle, BuildError> {
+        let width = self.width.ok_or(BuildError::MissingWidth)?;
+        let height = self.height.ok_or(BuildError::MissingHeight)?;
+        if width == 0 {
+            return Err(BuildError::ZeroWidth);
+        }
+        if height == 0 {
+            return Err(BuildError::ZeroHeight);
+        }
+        if let Some(max) = self.max_width.filter(|&max| width > max) {
+            return Err(BuildError::TooWide { width, max });
+        }
+        if let Some(max) = self.max_height.filter(|&max| height > max) {
+            return Err(BuildError::TooTall { height, max });
+        }
+        Ok(Rectangle::new(width, height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_a_rectangle() {
+        let rect = Rectangle::builder().width(7).height(8).build();
+        assert_eq!(Ok(Rectangle::new(7, 8)), rect);
+        let square = Rectangle::builder()
+            .width(10)
+            .height(10)
+            .max_width(10)
+            .max_height(10)
+            .build();
+        assert_eq!(Ok(Rectangle::new(10, 10)), square);
+    }
+
+    #[test]
+    fn needs_both_sides() {
+        assert_eq!(
+            Err(BuildError::MissingWidth),
+            Rectangle::builder().height(1).build()
+        );
+        assert_eq!(
+            Err(BuildError::MissingHeight),
+            Rectangle::builder().width(1).build()
+        );
+        assert_eq!(Err(BuildError::MissingWidth), Rectangle::builder().build());
+    }
+
+    #[test]
+    fn refuses_degenerate_rectangles() {
+        assert_eq!(
+            Err(BuildError::ZeroWidth),
+            Rectangle::builder().width(0).height(0).build()
+        );
+        assert_eq!(
+            Err(BuildError::ZeroHeight),
+            Rectangle::builder().width(3).height(0).build()
+        );
+    }
+
+    #[test]
+    fn enforces_maximum_sizes() {
+        let limited = Rectangle::builder().max_width(10).max_height(5);
+        assert_eq!(
+            Err(BuildError::TooWide { width: 11, max: 10 }),
+            limited.clone().width(11).height(1).build()
+        );
+        let err = limited.width(10).height(6).build().unwrap_err();
+        assert_eq!(BuildError::TooTall { height: 6, max: 5 }, err);
+        assert_eq!(
+            "a height of 6 is more than the most allowed, 5",
+            err.to_string()
+        );
+    }
+}
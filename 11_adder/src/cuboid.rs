@@ -0,0 +1,130 @@
+use std::fmt;
+
+use crate::{fits_turned, Rectangle};
+
+/// A box, the three-dimensional cousin of [`Rectangle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cuboid {
+    width: u32,
+    height: u32,
+    depth: u32,
+}
+
+/// Shows a cuboid as its width by its height by its depth, like `2x3x4`.
+impl fmt::Display for Cuboid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}x{}", self.width, self.height, self.depth)
+    }
+}
+
+impl Cuboid {
+    pub fn new(width: u32, height: u32, depth: u32) -> Self {
+        Cuboid {
+            width,
+            height,
+            depth,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// The face it stands on, its width by its depth.
+    pub fn base(&self) -> Rectangle {
+        Rectangle::new(self.width, self.depth)
+    }
+
+    /// Worked out in u128, since three u32 sides multiplied together can be
+    /// more than a u64 holds.
+    pub fn volume(&self) -> u128 {
+        u128::from(self.width) * u128::from(self.height) * u128::from(self.depth)
+    }
+
+    pub fn surface_area(&self) -> u128 {
+        let (w, h, d) = (
+            u128::from(self.width),
+            u128::from(self.height),
+            u128::from(self.depth),
+        );
+        2 * (w * h + h * d + d * w)
+    }
+
+    /// Whether `other` fits inside with room to spare every way, as with
+    /// [`Rectangle::can_hold`]. It may be turned to any of its six
+    /// orientations along the axes, but not tilted.
+    pub fn can_hold(&self, other: &Cuboid) -> bool {
+        fits_turned(
+            [self.width, self.height, self.depth],
+            [other.width, other.height, other.depth],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn volume_and_surface_area() {
+        let cuboid = Cuboid::new(2, 3, 4);
+        assert_eq!(24, cuboid.volume());
+        assert_eq!(52, cuboid.surface_area());
+        assert_eq!(Rectangle::new(2, 4), cuboid.base());
+        assert_eq!("2x3x4", cuboid.to_string());
+        assert_eq!(0, Cuboid::new(2, 0, 4).volume());
+    }
+
+    #[test]
+    fn big_cuboids_do_not_overflow() {
+        assert_eq!(8_000_000_000, Cuboid::new(2000, 2000, 2000).volume());
+        assert_eq!(24_000_000, Cuboid::new(2000, 2000, 2000).surface_area());
+        let side = u128::from(u32::MAX);
+        let biggest = Cuboid::new(u32::MAX, u32::MAX, u32::MAX);
+        assert_eq!(side * side * side, biggest.volume());
+        assert_eq!(6 * side * side, biggest.surface_area());
+    }
+
+    #[test]
+    fn larger_can_hold_smaller() {
+        let larger = Cuboid::new(5, 6, 7);
+        let smaller = Cuboid::new(1, 2, 3);
+        assert!(larger.can_hold(&smaller));
+        assert!(!smaller.can_hold(&larger));
+        assert!(!larger.can_hold(&larger));
+    }
+
+    #[test]
+    fn can_hold_every_orientation() {
+        let container = Cuboid::new(10, 5, 3);
+        for (w, h, d) in [
+            (9, 4, 2),
+            (9, 2, 4),
+            (4, 9, 2),
+            (4, 2, 9),
+            (2, 9, 4),
+            (2, 4, 9),
+        ] {
+            assert!(container.can_hold(&Cuboid::new(w, h, d)), "{w}x{h}x{d}");
+        }
+    }
+
+    #[test]
+    fn needs_room_every_way() {
+        let container = Cuboid::new(10, 5, 3);
+        // Each of these is too big one way however it's turned.
+        assert!(!container.can_hold(&Cuboid::new(4, 4, 4)));
+        assert!(!container.can_hold(&Cuboid::new(11, 1, 1)));
+        assert!(!container.can_hold(&Cuboid::new(2, 6, 6)));
+        assert!(!container.can_hold(&Cuboid::new(3, 3, 3)));
+    }
+}
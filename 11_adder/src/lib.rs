@@ -1,3 +1,23 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+
+pub mod bigint;
+pub mod builder;
+pub mod cuboid;
+pub mod pack;
+pub mod quadtree;
+pub mod rect;
+pub mod shape;
+
+pub use bigint::{add_big, BigUint};
+pub use builder::{BuildError, RectangleBuilder};
+pub use cuboid::Cuboid;
+pub use pack::{pack, PackError, Placement};
+pub use quadtree::QuadTree;
+pub use rect::Rect;
+pub use shape::{AnyShape, Circle, Point, Shape, Triangle};
+
 pub fn add_two(to: usize) -> usize {
     add(to, 2)
 }
@@ -6,15 +26,211 @@ pub fn add(left: usize, right: usize) -> usize {
     left + right
 }
 
+/// Adds up every number in `numbers`, or `None` if the total would overflow.
+/// Nothing at all adds up to 0.
+pub fn add_all(numbers: impl IntoIterator<Item = usize>) -> Option<usize> {
+    numbers
+        .into_iter()
+        .try_fold(0usize, |total, n| total.checked_add(n))
+}
+
+/// Adds the numbers in `left` and `right` pair by pair.
+pub fn add_pairs(left: &[usize], right: &[usize]) -> Result<Vec<usize>, LenMismatch> {
+    if left.len() != right.len() {
+        return Err(LenMismatch {
+            left: left.len(),
+            right: right.len(),
+        });
+    }
+    Ok(left.iter().zip(right).map(|(&l, &r)| add(l, r)).collect())
+}
+
+/// [`add_pairs`] was given slices of different lengths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LenMismatch {
+    pub left: usize,
+    pub right: usize,
+}
+
+impl fmt::Display for LenMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot pair up {} numbers with {}",
+            self.left, self.right
+        )
+    }
+}
+
+impl Error for LenMismatch {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rectangle {
     height: u32,
     width: u32,
 }
 
+/// Shows a rectangle as its width by its height, like `7x8`.
+impl fmt::Display for Rectangle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+/// Rectangles are ordered by area, smallest first. Ones with the same area
+/// go by width and then by height, so a 2x6 comes before a 3x4, a 0x5 before
+/// a 0x7, and only equal rectangles tie.
+impl Ord for Rectangle {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.area()
+            .cmp(&other.area())
+            .then(self.width.cmp(&other.width))
+            .then(self.height.cmp(&other.height))
+    }
+}
+
+impl PartialOrd for Rectangle {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Rectangle {
+    pub fn new(width: u32, height: u32) -> Self {
+        Rectangle { height, width }
+    }
+
+    /// A [`RectangleBuilder`], for making a rectangle that's checked to have
+    /// no zero sides and to be no bigger than allowed.
+    pub fn builder() -> RectangleBuilder {
+        RectangleBuilder::default()
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Worked out in u64, which any two u32 sides fit in.
+    pub fn area(&self) -> u64 {
+        u64::from(self.width) * u64::from(self.height)
+    }
+
+    pub fn perimeter(&self) -> u64 {
+        2 * (u64::from(self.width) + u64::from(self.height))
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.width == self.height
+    }
+
+    /// The rectangle with both sides multiplied by `factor`. A side that
+    /// would go past `u32::MAX` stops there; use
+    /// [`checked_scale`](Rectangle::checked_scale) to find out.
+    pub fn scale(&self, factor: u32) -> Rectangle {
+        Rectangle::new(
+            self.width.saturating_mul(factor),
+            self.height.saturating_mul(factor),
+        )
+    }
+
+    /// Like [`scale`](Rectangle::scale), but `None` if a side would go past
+    /// `u32::MAX`.
+    pub fn checked_scale(&self, factor: u32) -> Option<Rectangle> {
+        Some(Rectangle::new(
+            self.width.checked_mul(factor)?,
+            self.height.checked_mul(factor)?,
+        ))
+    }
+
+    /// The rectangle made `dx` wider and `dy` taller. A side that would go
+    /// past `u32::MAX` stops there; use
+    /// [`checked_grow`](Rectangle::checked_grow) to find out.
+    pub fn grow(&self, dx: u32, dy: u32) -> Rectangle {
+        Rectangle::new(
+            self.width.saturating_add(dx),
+            self.height.saturating_add(dy),
+        )
+    }
+
+    /// Like [`grow`](Rectangle::grow), but `None` if a side would go past
+    /// `u32::MAX`.
+    pub fn checked_grow(&self, dx: u32, dy: u32) -> Option<Rectangle> {
+        Some(Rectangle::new(
+            self.width.checked_add(dx)?,
+            self.height.checked_add(dy)?,
+        ))
+    }
+
+    /// The rectangle made `dx` narrower and `dy` shorter, or `None` if that
+    /// would take more off a side than it has. Shrinking a side to 0 is
+    /// allowed.
+    pub fn shrink_checked(&self, dx: u32, dy: u32) -> Option<Rectangle> {
+        Some(Rectangle::new(
+            self.width.checked_sub(dx)?,
+            self.height.checked_sub(dy)?,
+        ))
+    }
+
+    /// Cuts the rectangle across, `at` up from the bottom, returning the
+    /// part below the cut and the part above it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is more than the height.
+    pub fn split_horizontal(&self, at: u32) -> (Rectangle, Rectangle) {
+        assert!(
+            at <= self.height,
+            "cannot split {self} at height {at}: it's only {} tall",
+            self.height
+        );
+        (
+            Rectangle::new(self.width, at),
+            Rectangle::new(self.width, self.height - at),
+        )
+    }
+
+    /// Cuts the rectangle from top to bottom, `at` in from the left,
+    /// returning the part left of the cut and the part right of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is more than the width.
+    pub fn split_vertical(&self, at: u32) -> (Rectangle, Rectangle) {
+        assert!(
+            at <= self.width,
+            "cannot split {self} at width {at}: it's only {} wide",
+            self.width
+        );
+        (
+            Rectangle::new(at, self.height),
+            Rectangle::new(self.width - at, self.height),
+        )
+    }
+
     pub fn can_hold(&self, other: &Rectangle) -> bool {
         self.height > other.height && self.width > other.width
     }
+
+    /// Like [`can_hold`](Rectangle::can_hold), but `other` may also be
+    /// turned on its side. Only the two axis-aligned orientations count: a
+    /// long thin rectangle that would only fit corner to corner doesn't.
+    pub fn can_hold_rotated(&self, other: &Rectangle) -> bool {
+        fits_turned([self.width, self.height], [other.width, other.height])
+    }
+}
+
+/// Whether a box with sides `inner` fits inside one with sides `outer`, with
+/// room to spare each way, if it can be turned to lie along any of the axes.
+/// Lining both up smallest side first is always the best way to try.
+pub(crate) fn fits_turned<const N: usize>(mut outer: [u32; N], mut inner: [u32; N]) -> bool {
+    outer.sort_unstable();
+    inner.sort_unstable();
+    outer.iter().zip(&inner).all(|(outer, inner)| outer > inner)
 }
 
 #[cfg(test)]
@@ -27,6 +243,33 @@ mod tests {
         assert_eq!(result, 4);
     }
 
+    #[test]
+    fn adds_everything() {
+        assert_eq!(Some(10), add_all([1, 2, 3, 4]));
+        assert_eq!(Some(6), add_all(1..=3));
+        assert_eq!(Some(0), add_all(Vec::new()));
+    }
+
+    #[test]
+    fn add_all_catches_overflow() {
+        assert_eq!(Some(usize::MAX), add_all([usize::MAX - 1, 1]));
+        assert_eq!(None, add_all([usize::MAX, 1]));
+        assert_eq!(None, add_all([usize::MAX, 1, 0]));
+    }
+
+    #[test]
+    fn adds_pairs() {
+        assert_eq!(Ok(vec![5, 7, 9]), add_pairs(&[1, 2, 3], &[4, 5, 6]));
+        assert_eq!(Ok(vec![]), add_pairs(&[], &[]));
+    }
+
+    #[test]
+    fn add_pairs_needs_matching_lengths() {
+        let err = add_pairs(&[1, 2, 3], &[4]).unwrap_err();
+        assert_eq!(LenMismatch { left: 3, right: 1 }, err);
+        assert_eq!("cannot pair up 3 numbers with 1", err.to_string());
+    }
+
     #[test]
     fn larger_can_hold_smaller() {
         let larger = Rectangle {
@@ -55,6 +298,219 @@ mod tests {
         assert!(!smaller.can_hold(&larger));
     }
 
+    #[test]
+    fn rotated_fits_the_other_way_round() {
+        let tall = Rectangle::new(5, 10);
+        let wide = Rectangle::new(9, 4);
+        assert!(!tall.can_hold(&wide));
+        assert!(tall.can_hold_rotated(&wide));
+        assert!(!wide.can_hold_rotated(&tall));
+    }
+
+    #[test]
+    fn rotated_still_needs_room_on_both_sides() {
+        let rect = Rectangle::new(5, 10);
+        assert!(!rect.can_hold_rotated(&Rectangle::new(10, 4)));
+        assert!(!rect.can_hold_rotated(&Rectangle::new(5, 10)));
+        assert!(!rect.can_hold_rotated(&Rectangle::new(11, 1)));
+    }
+
+    #[test]
+    fn rotated_ignores_diagonal_fits() {
+        // An 11×1 strip would fit corner to corner in a 10×10 square, whose
+        // diagonal is about 14, but not lying along either side.
+        let square = Rectangle::new(10, 10);
+        assert!(!square.can_hold_rotated(&Rectangle::new(11, 1)));
+        assert!(!square.can_hold_rotated(&Rectangle::new(1, 11)));
+        assert!(square.can_hold_rotated(&Rectangle::new(9, 1)));
+    }
+
+    #[test]
+    fn new_sets_width_and_height() {
+        let rect = Rectangle::new(3, 4);
+        assert_eq!(3, rect.width());
+        assert_eq!(4, rect.height());
+    }
+
+    #[test]
+    fn area_and_perimeter() {
+        let rect = Rectangle::new(3, 4);
+        assert_eq!(12, rect.area());
+        assert_eq!(14, rect.perimeter());
+        assert_eq!(0, Rectangle::new(0, 4).area());
+    }
+
+    #[test]
+    fn big_rectangles_do_not_overflow() {
+        let rect = Rectangle::new(70_000, 70_000);
+        assert_eq!(4_900_000_000, rect.area());
+        assert_eq!(280_000, rect.perimeter());
+        let biggest = Rectangle::new(u32::MAX, u32::MAX);
+        assert_eq!(u64::from(u32::MAX) * u64::from(u32::MAX), biggest.area());
+        assert_eq!(4 * u64::from(u32::MAX), biggest.perimeter());
+    }
+
+    #[test]
+    fn squares_have_equal_sides() {
+        assert!(Rectangle::new(5, 5).is_square());
+        assert!(!Rectangle::new(5, 6).is_square());
+    }
+
+    #[test]
+    fn scales_both_sides() {
+        let rect = Rectangle::new(3, 4);
+        assert_eq!(Rectangle::new(6, 8), rect.scale(2));
+        assert_eq!(rect, rect.scale(1));
+        assert_eq!(Rectangle::new(0, 0), rect.scale(0));
+        assert_eq!(rect.area() * 9, rect.scale(3).area());
+    }
+
+    #[test]
+    fn grows_and_shrinks() {
+        let rect = Rectangle::new(3, 4);
+        assert_eq!(Rectangle::new(5, 4), rect.grow(2, 0));
+        assert_eq!(rect, rect.grow(0, 0));
+        assert_eq!(Some(Rectangle::new(1, 3)), rect.shrink_checked(2, 1));
+        assert_eq!(Some(rect), rect.grow(7, 9).shrink_checked(7, 9));
+    }
+
+    #[test]
+    fn scaling_stops_at_the_largest_side() {
+        let rect = Rectangle::new(3, u32::MAX / 2);
+        assert_eq!(Rectangle::new(6, u32::MAX - 1), rect.scale(2));
+        assert_eq!(Rectangle::new(9, u32::MAX), rect.scale(3));
+        assert_eq!(Some(Rectangle::new(6, u32::MAX - 1)), rect.checked_scale(2));
+        assert_eq!(None, rect.checked_scale(3));
+        let biggest = Rectangle::new(u32::MAX, u32::MAX);
+        assert_eq!(biggest, biggest.scale(u32::MAX));
+        assert_eq!(Some(biggest), biggest.checked_scale(1));
+        assert_eq!(Some(Rectangle::new(0, 0)), biggest.checked_scale(0));
+    }
+
+    #[test]
+    fn growing_stops_at_the_largest_side() {
+        let rect = Rectangle::new(u32::MAX - 1, 4);
+        assert_eq!(Rectangle::new(u32::MAX, 5), rect.grow(1, 1));
+        assert_eq!(Rectangle::new(u32::MAX, u32::MAX), rect.grow(2, u32::MAX));
+        assert_eq!(Some(Rectangle::new(u32::MAX, 5)), rect.checked_grow(1, 1));
+        assert_eq!(None, rect.checked_grow(2, 0));
+        assert_eq!(None, rect.checked_grow(0, u32::MAX));
+        assert_eq!(Some(rect), rect.checked_grow(0, 0));
+    }
+
+    #[test]
+    fn shrinking_stops_at_zero() {
+        let rect = Rectangle::new(3, 4);
+        assert_eq!(Some(Rectangle::new(0, 0)), rect.shrink_checked(3, 4));
+        assert_eq!(None, rect.shrink_checked(4, 0));
+        assert_eq!(None, rect.shrink_checked(0, 5));
+        assert_eq!(None, rect.shrink_checked(u32::MAX, u32::MAX));
+    }
+
+    #[test]
+    fn splits_into_two() {
+        let rect = Rectangle::new(6, 4);
+        assert_eq!(
+            (Rectangle::new(6, 1), Rectangle::new(6, 3)),
+            rect.split_horizontal(1)
+        );
+        assert_eq!(
+            (Rectangle::new(2, 4), Rectangle::new(4, 4)),
+            rect.split_vertical(2)
+        );
+        let (left, right) = rect.split_vertical(5);
+        assert_eq!(rect.area(), left.area() + right.area());
+    }
+
+    #[test]
+    fn splits_at_the_edges() {
+        let rect = Rectangle::new(6, 4);
+        assert_eq!((Rectangle::new(6, 0), rect), rect.split_horizontal(0));
+        assert_eq!((rect, Rectangle::new(0, 4)), rect.split_vertical(6));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot split 6x4 at height 5: it's only 4 tall")]
+    fn cannot_split_past_the_top() {
+        Rectangle::new(6, 4).split_horizontal(5);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot split 6x4 at width 7: it's only 6 wide")]
+    fn cannot_split_past_the_side() {
+        Rectangle::new(6, 4).split_vertical(7);
+    }
+
+    #[test]
+    fn displays_as_width_by_height() {
+        assert_eq!("7x8", Rectangle::new(7, 8).to_string());
+        assert_eq!("0x3", format!("{}", Rectangle::new(0, 3)));
+    }
+
+    #[test]
+    fn sorts_by_area_then_width() {
+        let mut rects = [
+            Rectangle::new(3, 4),
+            Rectangle::new(1, 1),
+            Rectangle::new(2, 6),
+            Rectangle::new(5, 5),
+            Rectangle::new(4, 3),
+        ];
+        rects.sort();
+        let shown: Vec<String> = rects.iter().map(Rectangle::to_string).collect();
+        assert_eq!(vec!["1x1", "2x6", "3x4", "4x3", "5x5"], shown);
+        assert!(Rectangle::new(3, 4) < Rectangle::new(4, 3));
+        assert_eq!(
+            Ordering::Equal,
+            Rectangle::new(3, 4).cmp(&Rectangle::new(3, 4))
+        );
+    }
+
+    #[test]
+    fn only_equal_rectangles_tie() {
+        let rects = [
+            Rectangle::new(0, 5),
+            Rectangle::new(0, 7),
+            Rectangle::new(5, 0),
+            Rectangle::new(0, 0),
+            Rectangle::new(1, 1),
+        ];
+        for a in &rects {
+            for b in &rects {
+                assert_eq!(a == b, a.cmp(b) == Ordering::Equal, "{a} vs {b}");
+            }
+        }
+        assert!(Rectangle::new(0, 5) < Rectangle::new(0, 7));
+    }
+
+    #[test]
+    fn works_in_sets_and_maps() {
+        use std::collections::{BTreeSet, HashMap, HashSet};
+
+        let rects = [
+            Rectangle::new(3, 4),
+            Rectangle::new(4, 3),
+            Rectangle::new(3, 4),
+        ];
+        assert_eq!(2, rects.iter().collect::<HashSet<_>>().len());
+        assert_eq!(2, rects.iter().collect::<BTreeSet<_>>().len());
+
+        let mut counts = HashMap::new();
+        for rect in rects {
+            *counts.entry(rect).or_insert(0) += 1;
+        }
+        assert_eq!(Some(&2), counts.get(&Rectangle::new(3, 4)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn rectangles_round_trip_through_json() {
+        let rect = Rectangle::new(7, 8);
+        let json = serde_json::to_string(&rect).unwrap();
+        assert_eq!(r#"{"height":8,"width":7}"#, json);
+        assert_eq!(rect, serde_json::from_str(&json).unwrap());
+    }
+
     #[test]
     fn result_works() -> Result<(), String> {
         if 2 + 2 == 4 {
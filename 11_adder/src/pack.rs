@@ -0,0 +1,211 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{Rect, Rectangle};
+
+/// Where [`pack`] put one of the items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    /// The item's place in the slice given to [`pack`].
+    pub index: usize,
+    /// Where it went, inside a container with its bottom-left corner at the
+    /// origin.
+    pub rect: Rect,
+}
+
+/// Why [`pack`] couldn't fit everything in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackError {
+    /// The item at `index` is wider or taller than the container itself.
+    TooBig { index: usize },
+    /// The container filled up before the item at `index` could go in.
+    OutOfRoom { index: usize },
+    /// The item at `index` was given a place that reaches past `i32::MAX`,
+    /// which a [`Rect`] can't go.
+    OutOfRange { index: usize },
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackError::TooBig { index } => {
+                write!(f, "item {index} is bigger than the container")
+            }
+            PackError::OutOfRoom { index } => {
+                write!(f, "ran out of room before item {index} could be packed")
+            }
+            PackError::OutOfRange { index } => {
+                write!(f, "item {index}'s placement doesn't fit in i32 coordinates")
+            }
+        }
+    }
+}
+
+impl Error for PackError {}
+
+/// A row of items along the bottom of the space left in the container.
+struct Shelf {
+    y: u32,
+    height: u32,
+    used: u32,
+}
+
+/// Packs `items` into `container` without turning any of them, returning
+/// where each one goes in the same order as `items`.
+///
+/// Items are placed tallest first onto shelves stacked up from the bottom.
+/// Each goes on the first shelf with room for it, or starts a new one. This
+/// is quick and usually does well, but it isn't perfect: it can give up on
+/// a set of items that would fit if arranged some other way.
+pub fn pack(container: &Rectangle, items: &[Rectangle]) -> Result<Vec<Placement>, PackError> {
+    if let Some(index) = items
+        .iter()
+        .position(|item| item.width() > container.width() || item.height() > container.height())
+    {
+        return Err(PackError::TooBig { index });
+    }
+
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|&a, &b| {
+        (items[b].height(), items[b].width()).cmp(&(items[a].height(), items[a].width()))
+    });
+
+    let mut shelves: Vec<Shelf> = Vec::new();
+    let mut placements = Vec::with_capacity(items.len());
+    for index in order {
+        let item = &items[index];
+        let shelf = shelves.iter_mut().find(|shelf| {
+            item.height() <= shelf.height && item.width() <= container.width() - shelf.used
+        });
+        let shelf = match shelf {
+            Some(shelf) => shelf,
+            None => {
+                let y = shelves.last().map_or(0, |shelf| shelf.y + shelf.height);
+                if item.height() > container.height() - y {
+                    return Err(PackError::OutOfRoom { index });
+                }
+                shelves.push(Shelf {
+                    y,
+                    height: item.height(),
+                    used: 0,
+                });
+                shelves.last_mut().expect("just pushed a shelf")
+            }
+        };
+        let rect = i32::try_from(shelf.used)
+            .ok()
+            .zip(i32::try_from(shelf.y).ok())
+            .and_then(|(x, y)| Rect::checked_at(x, y, item))
+            .ok_or(PackError::OutOfRange { index })?;
+        placements.push(Placement { index, rect });
+        shelf.used += item.width();
+    }
+    placements.sort_by_key(|placement| placement.index);
+    Ok(placements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks every item is placed once, at its own size, inside the
+    /// container and clear of the others.
+    fn assert_packed(container: &Rectangle, items: &[Rectangle], placements: &[Placement]) {
+        let bounds = Rect::at(0, 0, container);
+        assert_eq!(items.len(), placements.len());
+        for (i, placement) in placements.iter().enumerate() {
+            assert_eq!(i, placement.index);
+            assert_eq!(items[i], placement.rect.size());
+            assert!(bounds.contains(&placement.rect), "{placement:?}");
+            for other in &placements[i + 1..] {
+                assert!(
+                    !placement.rect.intersects(&other.rect),
+                    "{placement:?} overlaps {other:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fills_a_container_exactly() {
+        let container = Rectangle::new(4, 4);
+        let items = [Rectangle::new(2, 2); 4];
+        let placements = pack(&container, &items).unwrap();
+        assert_packed(&container, &items, &placements);
+    }
+
+    #[test]
+    fn puts_the_tallest_on_the_bottom_shelf() {
+        let container = Rectangle::new(10, 10);
+        let items = [
+            Rectangle::new(3, 2),
+            Rectangle::new(4, 5),
+            Rectangle::new(6, 3),
+            Rectangle::new(5, 1),
+        ];
+        let placements = pack(&container, &items).unwrap();
+        assert_packed(&container, &items, &placements);
+        let at: Vec<(i32, i32)> = placements
+            .iter()
+            .map(|placement| (placement.rect.x(), placement.rect.y()))
+            .collect();
+        assert_eq!(vec![(0, 5), (0, 0), (4, 0), (3, 5)], at);
+    }
+
+    #[test]
+    fn nothing_packs_into_anything() {
+        assert_eq!(Ok(vec![]), pack(&Rectangle::new(0, 0), &[]));
+    }
+
+    #[test]
+    fn reports_items_too_big_for_the_container() {
+        let container = Rectangle::new(5, 5);
+        let items = [
+            Rectangle::new(2, 2),
+            Rectangle::new(2, 2),
+            Rectangle::new(3, 3),
+            Rectangle::new(6, 1),
+        ];
+        assert_eq!(
+            Err(PackError::TooBig { index: 3 }),
+            pack(&container, &items)
+        );
+        // It would fit turned round, but pack doesn't turn things.
+        let items = [Rectangle::new(2, 6)];
+        assert_eq!(
+            Err(PackError::TooBig { index: 0 }),
+            pack(&container, &items)
+        );
+    }
+
+    #[test]
+    fn reports_running_out_of_room() {
+        let container = Rectangle::new(4, 4);
+        let items = [Rectangle::new(2, 2); 5];
+        let err = pack(&container, &items).unwrap_err();
+        assert_eq!(PackError::OutOfRoom { index: 4 }, err);
+        assert_eq!(
+            "ran out of room before item 4 could be packed",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn reports_placements_past_i32() {
+        let container = Rectangle::new(u32::MAX, 1);
+        let items = [Rectangle::new(u32::MAX, 1)];
+        let err = pack(&container, &items).unwrap_err();
+        assert_eq!(PackError::OutOfRange { index: 0 }, err);
+        assert_eq!(
+            "item 0's placement doesn't fit in i32 coordinates",
+            err.to_string()
+        );
+
+        let container = Rectangle::new(1, u32::MAX);
+        let items = [Rectangle::new(1, 1 << 31), Rectangle::new(1, 1)];
+        assert_eq!(
+            Err(PackError::OutOfRange { index: 0 }),
+            pack(&container, &items)
+        );
+    }
+}
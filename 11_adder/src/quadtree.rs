@@ -0,0 +1,288 @@
+use crate::Rect;
+
+/// How many rects a node holds before it splits into four.
+const MAX_ITEMS: usize = 8;
+/// How many times the space can be split, so piles of rects in one spot
+/// can't make the tree endlessly deep.
+const MAX_DEPTH: u32 = 8;
+
+/// A spatial index over positioned rects, for finding the ones overlapping
+/// a region without checking every one.
+///
+/// The tree covers `bounds`, split into quarters wherever it gets crowded.
+/// Each rect lives in the smallest quarter that holds all of it, so one
+/// straddling a split stays higher up. Rects outside `bounds` can still be
+/// added; they're kept at the top and checked on every query.
+#[derive(Debug, Clone)]
+pub struct QuadTree {
+    root: Node,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    bounds: Rect,
+    depth: u32,
+    items: Vec<Rect>,
+    children: Option<Box<[Node; 4]>>,
+}
+
+impl QuadTree {
+    pub fn new(bounds: Rect) -> Self {
+        QuadTree {
+            root: Node::new(bounds, 0),
+            len: 0,
+        }
+    }
+
+    pub fn bounds(&self) -> Rect {
+        self.root.bounds
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds `rect`. The same rect can be added more than once.
+    pub fn insert(&mut self, rect: Rect) {
+        self.root.insert(rect);
+        self.len += 1;
+    }
+
+    /// Takes out one copy of `rect`, returning whether it was there.
+    pub fn remove(&mut self, rect: &Rect) -> bool {
+        let removed = self.root.remove(rect);
+        if removed {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Every rect overlapping `region`, in no particular order. As with
+    /// [`Rect::intersects`], rects only touching its edge don't count.
+    pub fn query(&self, region: &Rect) -> Vec<&Rect> {
+        let mut found = Vec::new();
+        self.root.query(region, &mut found);
+        found
+    }
+}
+
+impl Node {
+    fn new(bounds: Rect, depth: u32) -> Self {
+        Node {
+            bounds,
+            depth,
+            items: Vec::new(),
+            children: None,
+        }
+    }
+
+    /// Which child holds all of `rect`, if any.
+    fn child_for(&self, rect: &Rect) -> Option<usize> {
+        let children = self.children.as_ref()?;
+        children
+            .iter()
+            .position(|child| child.bounds.contains(rect))
+    }
+
+    fn insert(&mut self, rect: Rect) {
+        if let Some(i) = self.child_for(&rect) {
+            self.children.as_mut().expect("child_for found one")[i].insert(rect);
+            return;
+        }
+        self.items.push(rect);
+        if self.children.is_none()
+            && self.items.len() > MAX_ITEMS
+            && self.depth < MAX_DEPTH
+            && self.bounds.width() >= 2
+            && self.bounds.height() >= 2
+        {
+            self.split();
+        }
+    }
+
+    /// Divides the node into quarters and moves down every rect that fits
+    /// in one.
+    fn split(&mut self) {
+        let (x, y) = (self.bounds.x(), self.bounds.y());
+        let (width, height) = (self.bounds.width(), self.bounds.height());
+        let (left, bottom) = (width / 2, height / 2);
+        let (mid_x, mid_y) = (x + left as i32, y + bottom as i32);
+        let depth = self.depth + 1;
+        self.children = Some(Box::new([
+            Node::new(Rect::new(x, y, left, bottom), depth),
+            Node::new(Rect::new(mid_x, y, width - left, bottom), depth),
+            Node::new(Rect::new(x, mid_y, left, height - bottom), depth),
+            Node::new(
+                Rect::new(mid_x, mid_y, width - left, height - bottom),
+                depth,
+            ),
+        ]));
+        for rect in std::mem::take(&mut self.items) {
+            self.insert(rect);
+        }
+    }
+
+    fn remove(&mut self, rect: &Rect) -> bool {
+        // A rect goes to the same place every time, so if a child could hold
+        // it, that's the only place it can be.
+        if let Some(i) = self.child_for(rect) {
+            return self.children.as_mut().expect("child_for found one")[i].remove(rect);
+        }
+        match self.items.iter().position(|item| item == rect) {
+            Some(i) => {
+                self.items.swap_remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn query<'a>(&'a self, region: &Rect, found: &mut Vec<&'a Rect>) {
+        found.extend(self.items.iter().filter(|item| item.intersects(region)));
+        for child in self.children.iter().flat_map(|children| children.iter()) {
+            if child.bounds.intersects(region) {
+                child.query(region, found);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small random number generator, so the tests see the same "random"
+    /// rects every run.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn below(&mut self, n: u32) -> u32 {
+            self.0 = self
+                .0
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            ((self.0 >> 33) % u64::from(n)) as u32
+        }
+
+        /// A rect somewhere around a 1000×1000 space, now and then hanging
+        /// off its edge or lying outside it altogether.
+        fn rect(&mut self) -> Rect {
+            Rect::new(
+                self.below(1100) as i32 - 50,
+                self.below(1100) as i32 - 50,
+                self.below(60),
+                self.below(60),
+            )
+        }
+    }
+
+    fn key(rect: &Rect) -> (i32, i32, u32, u32) {
+        (rect.x(), rect.y(), rect.width(), rect.height())
+    }
+
+    fn brute_force<'a>(rects: &'a [Rect], region: &Rect) -> Vec<&'a Rect> {
+        sorted(rects.iter().filter(|r| r.intersects(region)).collect())
+    }
+
+    fn sorted(mut found: Vec<&Rect>) -> Vec<&Rect> {
+        found.sort_by_key(|r| key(r));
+        found
+    }
+
+    #[test]
+    fn finds_what_overlaps() {
+        let mut tree = QuadTree::new(Rect::new(0, 0, 100, 100));
+        let a = Rect::new(10, 10, 5, 5);
+        let b = Rect::new(45, 45, 10, 10);
+        let c = Rect::new(80, 80, 5, 5);
+        for rect in [a, b, c] {
+            tree.insert(rect);
+        }
+        assert_eq!(3, tree.len());
+        assert_eq!(vec![&a], tree.query(&Rect::new(0, 0, 20, 20)));
+        assert_eq!(vec![&b], tree.query(&Rect::new(50, 50, 1, 1)));
+        // Touching c's corner isn't overlapping it.
+        assert!(tree.query(&Rect::new(85, 85, 5, 5)).is_empty());
+        assert!(tree.query(&Rect::new(20, 20, 0, 50)).is_empty());
+    }
+
+    #[test]
+    fn keeps_rects_outside_its_bounds() {
+        let mut tree = QuadTree::new(Rect::new(0, 0, 100, 100));
+        let outside = Rect::new(-50, 200, 10, 10);
+        let straddling = Rect::new(95, 95, 10, 10);
+        tree.insert(outside);
+        tree.insert(straddling);
+        assert_eq!(vec![&outside], tree.query(&Rect::new(-45, 205, 1, 1)));
+        assert_eq!(vec![&straddling], tree.query(&Rect::new(100, 100, 1, 1)));
+        assert!(tree.remove(&outside));
+        assert_eq!(1, tree.query(&Rect::new(-100, 100, 200, 200)).len());
+    }
+
+    #[test]
+    fn removes_one_copy_at_a_time() {
+        let mut tree = QuadTree::new(Rect::new(0, 0, 100, 100));
+        let rect = Rect::new(1, 1, 2, 2);
+        tree.insert(rect);
+        tree.insert(rect);
+        assert!(tree.remove(&rect));
+        assert_eq!(1, tree.len());
+        assert_eq!(vec![&rect], tree.query(&rect));
+        assert!(tree.remove(&rect));
+        assert!(!tree.remove(&rect));
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn splits_when_crowded() {
+        let mut tree = QuadTree::new(Rect::new(0, 0, 100, 100));
+        for i in 0..20 {
+            tree.insert(Rect::new(i * 5, i * 5, 1, 1));
+        }
+        assert!(tree.root.children.is_some());
+        assert!(tree.root.items.len() <= MAX_ITEMS);
+        assert_eq!(20, tree.query(&tree.bounds()).len());
+    }
+
+    #[test]
+    fn piles_in_one_spot_stop_splitting() {
+        let mut tree = QuadTree::new(Rect::new(0, 0, 1 << 20, 1 << 20));
+        let rect = Rect::new(3, 3, 1, 1);
+        for _ in 0..100 {
+            tree.insert(rect);
+        }
+        assert_eq!(100, tree.query(&rect).len());
+    }
+
+    #[test]
+    fn matches_a_brute_force_scan() {
+        let mut lcg = Lcg(42);
+        let mut rects: Vec<Rect> = (0..3000).map(|_| lcg.rect()).collect();
+        let mut tree = QuadTree::new(Rect::new(0, 0, 1000, 1000));
+        for &rect in &rects {
+            tree.insert(rect);
+        }
+        let regions: Vec<Rect> = (0..200)
+            .map(|_| {
+                let rect = lcg.rect();
+                Rect::new(rect.x(), rect.y(), rect.width() * 3, rect.height() * 3)
+            })
+            .collect();
+        for region in &regions {
+            assert_eq!(brute_force(&rects, region), sorted(tree.query(region)));
+        }
+
+        for rect in rects.split_off(1500) {
+            assert!(tree.remove(&rect));
+        }
+        assert_eq!(1500, tree.len());
+        for region in &regions {
+            assert_eq!(brute_force(&rects, region), sorted(tree.query(region)));
+        }
+    }
+}
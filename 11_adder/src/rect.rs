@@ -0,0 +1,412 @@
+use crate::Rectangle;
+
+/// A rectangle placed on a grid, with its bottom-left corner at `(x, y)`.
+///
+/// It covers `x..x + width` across and `y..y + height` up, so it includes
+/// its left and bottom edges but not its right and top ones. Two rects that
+/// only touch along an edge or at a corner don't overlap, and a rect with no
+/// width or height overlaps nothing.
+///
+/// Its right and top edges always fit in an `i32`, so no rect reaches past
+/// `i32::MAX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Fields")
+)]
+pub struct Rect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+/// A rect as it's written in JSON, checked by [`Rect::checked_new`] before
+/// it's let in.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct Fields {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<Fields> for Rect {
+    type Error = String;
+
+    fn try_from(fields: Fields) -> Result<Self, Self::Error> {
+        let Fields {
+            x,
+            y,
+            width,
+            height,
+        } = fields;
+        Rect::checked_new(x, y, width, height).ok_or_else(|| too_far(x, y, width, height))
+    }
+}
+
+fn too_far(x: i32, y: i32, width: u32, height: u32) -> String {
+    format!("a {width}x{height} rect at ({x}, {y}) reaches past i32::MAX")
+}
+
+/// Where a rect starting at `start` and `length` long stops, or `None` if
+/// that's past `i32::MAX`.
+fn end(start: i32, length: u32) -> Option<i32> {
+    i32::try_from(i64::from(start) + i64::from(length)).ok()
+}
+
+/// How far it is from `start` to `end`, which is never more than a `u32`
+/// can hold since both are `i32`s.
+fn span(start: i32, end: i32) -> u32 {
+    (i64::from(end) - i64::from(start)) as u32
+}
+
+impl Rect {
+    /// # Panics
+    ///
+    /// Panics if the rect would reach past `i32::MAX` either way.
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Rect::checked_new(x, y, width, height)
+            .unwrap_or_else(|| panic!("{}", too_far(x, y, width, height)))
+    }
+
+    /// Like [`Rect::new`], but `None` if the rect would reach past
+    /// `i32::MAX` either way.
+    pub fn checked_new(x: i32, y: i32, width: u32, height: u32) -> Option<Self> {
+        end(x, width)?;
+        end(y, height)?;
+        Some(Rect {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    /// `size` with its bottom-left corner at `(x, y)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rect would reach past `i32::MAX` either way.
+    pub fn at(x: i32, y: i32, size: &Rectangle) -> Self {
+        Rect::new(x, y, size.width(), size.height())
+    }
+
+    /// Like [`Rect::at`], but `None` if the rect would reach past
+    /// `i32::MAX` either way.
+    pub fn checked_at(x: i32, y: i32, size: &Rectangle) -> Option<Self> {
+        Rect::checked_new(x, y, size.width(), size.height())
+    }
+
+    pub fn x(&self) -> i32 {
+        self.x
+    }
+
+    pub fn y(&self) -> i32 {
+        self.y
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Where it stops across, just past its last column.
+    pub fn right(&self) -> i32 {
+        end(self.x, self.width).expect("checked when the rect was made")
+    }
+
+    /// Where it stops going up, just past its last row.
+    pub fn top(&self) -> i32 {
+        end(self.y, self.height).expect("checked when the rect was made")
+    }
+
+    /// How big it is, leaving out where it is.
+    pub fn size(&self) -> Rectangle {
+        Rectangle::new(self.width, self.height)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Whether the two share any area. Touching edges don't count.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The area the two share, or `None` if they don't overlap.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let top = self.top().min(other.top());
+        (x < right && y < top).then(|| Rect::new(x, y, span(x, right), span(y, top)))
+    }
+
+    /// The smallest rect covering both, including any gap between them.
+    pub fn union_bounding_box(&self, other: &Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = self.right().max(other.right());
+        let top = self.top().max(other.top());
+        Rect::new(x, y, span(x, right), span(y, top))
+    }
+
+    /// Whether `other` lies entirely inside this rect. Their edges may line
+    /// up, so a rect contains itself.
+    pub fn contains(&self, other: &Rect) -> bool {
+        self.x <= other.x
+            && self.y <= other.y
+            && other.right() <= self.right()
+            && other.top() <= self.top()
+    }
+
+    /// The grid cells it covers, as the `(x, y)` of each one's bottom-left
+    /// corner, a row at a time from the bottom, left to right.
+    pub fn points(&self) -> impl Iterator<Item = (i32, i32)> {
+        let (x, right) = (self.x, self.right());
+        (self.y..self.top()).flat_map(move |y| (x..right).map(move |x| (x, y)))
+    }
+
+    /// The cells around its edge, each once, going anticlockwise from the
+    /// bottom-left one: along the bottom, up the right side, back along the
+    /// top and down the left side.
+    pub fn border_points(&self) -> impl Iterator<Item = (i32, i32)> {
+        let (x, y, right, top) = (self.x, self.y, self.right(), self.top());
+        // An empty rect has no cells at all, and one a single cell wide or
+        // tall has no separate far side. Leaving those out first also keeps
+        // the sums below from going past the ends of an i32.
+        let (bottom, up, back, down) = if self.is_empty() {
+            (0..0, 0..0, 0..0, 0..0)
+        } else {
+            (
+                x..right,
+                y + 1..top,
+                if self.height > 1 { x..right - 1 } else { 0..0 },
+                if self.width > 1 { y + 1..top - 1 } else { 0..0 },
+            )
+        };
+        bottom
+            .map(move |x| (x, y))
+            .chain(up.map(move |y| (right - 1, y)))
+            .chain(back.rev().map(move |x| (x, top - 1)))
+            .chain(down.rev().map(move |y| (x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlapping_rects_intersect() {
+        let a = Rect::new(0, 0, 4, 4);
+        let b = Rect::new(2, 1, 4, 2);
+        assert!(a.intersects(&b));
+        assert_eq!(Some(Rect::new(2, 1, 2, 2)), a.intersection(&b));
+        assert_eq!(a.intersection(&b), b.intersection(&a));
+    }
+
+    #[test]
+    fn touching_rects_do_not_intersect() {
+        let a = Rect::new(0, 0, 4, 4);
+        // Sharing the right edge, the top edge and just the top-right corner.
+        for b in [
+            Rect::new(4, 0, 2, 4),
+            Rect::new(0, 4, 4, 2),
+            Rect::new(4, 4, 1, 1),
+        ] {
+            assert!(!a.intersects(&b), "{b:?}");
+            assert_eq!(None, a.intersection(&b));
+        }
+        assert!(!a.intersects(&Rect::new(-10, -10, 2, 2)));
+    }
+
+    #[test]
+    fn empty_rects_intersect_nothing() {
+        let a = Rect::new(0, 0, 4, 4);
+        let line = Rect::new(1, 0, 0, 4);
+        assert!(line.is_empty());
+        assert!(!a.intersects(&line));
+        assert!(!line.intersects(&line));
+    }
+
+    #[test]
+    fn union_covers_both() {
+        let a = Rect::new(0, 0, 2, 2);
+        let b = Rect::new(5, -3, 1, 1);
+        let union = a.union_bounding_box(&b);
+        assert_eq!(Rect::new(0, -3, 6, 5), union);
+        assert!(union.contains(&a));
+        assert!(union.contains(&b));
+        assert_eq!(a, a.union_bounding_box(&a));
+    }
+
+    #[test]
+    fn contains_allows_shared_edges() {
+        let outer = Rect::new(0, 0, 10, 10);
+        assert!(outer.contains(&outer));
+        assert!(outer.contains(&Rect::new(0, 5, 10, 5)));
+        assert!(outer.contains(&Rect::new(3, 3, 2, 2)));
+        assert!(!outer.contains(&Rect::new(8, 8, 3, 2)));
+        assert!(!outer.contains(&Rect::new(-1, 0, 2, 2)));
+        assert!(!Rect::new(3, 3, 2, 2).contains(&outer));
+    }
+
+    #[test]
+    fn reaches_up_to_the_ends_of_i32() {
+        let corner = Rect::new(i32::MAX - 2, i32::MAX - 1, 2, 1);
+        assert_eq!((i32::MAX, i32::MAX), (corner.right(), corner.top()));
+        assert!(Rect::new(i32::MAX, i32::MAX, 0, 0).is_empty());
+
+        let everything = Rect::new(i32::MIN, i32::MIN, u32::MAX, u32::MAX);
+        assert_eq!(i32::MAX, everything.right());
+        assert!(everything.contains(&corner));
+        assert!(everything.intersects(&corner));
+        assert_eq!(Some(corner), everything.intersection(&corner));
+    }
+
+    #[test]
+    fn refuses_to_reach_past_i32() {
+        assert_eq!(None, Rect::checked_new(i32::MAX, 0, 1, 1));
+        assert_eq!(None, Rect::checked_new(0, i32::MAX - 1, 0, 2));
+        assert_eq!(None, Rect::checked_new(0, 0, u32::MAX, 1));
+        assert_eq!(None, Rect::checked_new(i32::MIN + 1, 0, u32::MAX, 1));
+        assert!(Rect::checked_new(i32::MIN, 0, u32::MAX, 1).is_some());
+
+        let size = Rectangle::new(u32::MAX, 1);
+        assert_eq!(None, Rect::checked_at(0, 0, &size));
+        assert_eq!(
+            Some(Rect::new(i32::MIN, 0, u32::MAX, 1)),
+            Rect::checked_at(i32::MIN, 0, &size)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "a 1x1 rect at (2147483647, 0) reaches past i32::MAX")]
+    fn new_panics_past_i32() {
+        Rect::new(i32::MAX, 0, 1, 1);
+    }
+
+    #[test]
+    fn union_spans_all_of_i32() {
+        let low = Rect::new(i32::MIN, i32::MIN, 1, 1);
+        let high = Rect::new(i32::MAX - 1, i32::MAX - 1, 1, 1);
+        let union = low.union_bounding_box(&high);
+        assert_eq!(Rect::new(i32::MIN, i32::MIN, u32::MAX, u32::MAX), union);
+        assert_eq!(None, low.intersection(&high));
+    }
+
+    #[test]
+    fn points_at_the_ends_of_i32() {
+        let corner = Rect::new(i32::MAX - 2, i32::MAX - 2, 2, 2);
+        let (a, b) = (i32::MAX - 2, i32::MAX - 1);
+        assert_eq!(
+            vec![(a, a), (b, a), (a, b), (b, b)],
+            corner.points().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(a, a), (b, a), (b, b), (a, b)],
+            corner.border_points().collect::<Vec<_>>()
+        );
+        let low = Rect::new(i32::MIN, i32::MIN, 1, 2);
+        assert_eq!(
+            vec![(i32::MIN, i32::MIN), (i32::MIN, i32::MIN + 1)],
+            low.border_points().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            0,
+            Rect::new(i32::MAX, i32::MAX, 0, 0).border_points().count()
+        );
+        assert_eq!(
+            0,
+            Rect::new(i32::MIN, i32::MAX, 0, 0).border_points().count()
+        );
+    }
+
+    #[test]
+    fn points_cover_every_cell() {
+        let rect = Rect::new(-1, 2, 3, 2);
+        let points: Vec<(i32, i32)> = rect.points().collect();
+        assert_eq!(
+            vec![(-1, 2), (0, 2), (1, 2), (-1, 3), (0, 3), (1, 3)],
+            points
+        );
+        assert_eq!(0, Rect::new(0, 0, 0, 5).points().count());
+        assert_eq!(0, Rect::new(0, 0, 5, 0).points().count());
+    }
+
+    #[test]
+    fn points_are_lazy() {
+        let huge = Rect::new(0, 0, 1 << 20, 1 << 20);
+        let first: Vec<(i32, i32)> = huge.points().take(3).collect();
+        assert_eq!(vec![(0, 0), (1, 0), (2, 0)], first);
+        assert_eq!(Some((0, 1)), huge.points().nth(1 << 20));
+    }
+
+    #[test]
+    fn border_goes_round_once() {
+        let rect = Rect::new(0, 0, 3, 3);
+        let border: Vec<(i32, i32)> = rect.border_points().collect();
+        assert_eq!(
+            vec![
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (2, 1),
+                (2, 2),
+                (1, 2),
+                (0, 2),
+                (0, 1)
+            ],
+            border
+        );
+    }
+
+    #[test]
+    fn border_is_the_points_on_the_edge() {
+        for (width, height) in [(0, 0), (0, 3), (1, 1), (1, 4), (4, 1), (2, 2), (5, 3)] {
+            let rect = Rect::new(3, -2, width, height);
+            let mut border: Vec<(i32, i32)> = rect.border_points().collect();
+            border.sort();
+            let mut edge: Vec<(i32, i32)> = rect
+                .points()
+                .filter(|&(x, y)| {
+                    x == rect.x() || x == rect.right() - 1 || y == rect.y() || y == rect.top() - 1
+                })
+                .collect();
+            edge.sort();
+            assert_eq!(edge, border, "{width}x{height}");
+        }
+        assert_eq!(
+            2 * 5 + 2 * 3 - 4,
+            Rect::new(0, 0, 5, 3).border_points().count()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn round_trips_through_json() {
+        let rect = Rect::new(-2, 3, 5, 4);
+        let json = serde_json::to_string(&rect).unwrap();
+        assert_eq!(r#"{"x":-2,"y":3,"width":5,"height":4}"#, json);
+        assert_eq!(rect, serde_json::from_str(&json).unwrap());
+        let err = serde_json::from_str::<Rect>(r#"{"x":2147483647,"y":0,"width":1,"height":1}"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("reaches past i32::MAX"), "{err}");
+    }
+
+    #[test]
+    fn placed_from_a_size() {
+        let rect = Rect::at(-2, 3, &Rectangle::new(5, 4));
+        assert_eq!((3, 7), (rect.right(), rect.top()));
+        assert!(rect.size().can_hold(&Rectangle::new(4, 3)));
+    }
+}
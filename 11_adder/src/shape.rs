@@ -0,0 +1,356 @@
+use crate::Rectangle;
+
+/// A point on the plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Point { x, y }
+    }
+
+    pub fn distance(&self, other: Point) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+/// The smallest axis-aligned box around a shape, from its `min` corner to
+/// its `max` one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl BoundingBox {
+    pub fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+}
+
+/// Something flat with an inside and an outside. A point on the edge counts
+/// as inside.
+pub trait Shape {
+    fn area(&self) -> f64;
+    fn perimeter(&self) -> f64;
+    fn bounding_box(&self) -> BoundingBox;
+    fn contains_point(&self, point: Point) -> bool;
+}
+
+/// A rectangle has no position of its own, so as a shape it sits with its
+/// bottom-left corner at the origin.
+impl Shape for Rectangle {
+    fn area(&self) -> f64 {
+        f64::from(self.width()) * f64::from(self.height())
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * (f64::from(self.width()) + f64::from(self.height()))
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox {
+            min: Point::new(0.0, 0.0),
+            max: Point::new(f64::from(self.width()), f64::from(self.height())),
+        }
+    }
+
+    fn contains_point(&self, point: Point) -> bool {
+        let bounds = self.bounding_box();
+        (bounds.min.x..=bounds.max.x).contains(&point.x)
+            && (bounds.min.y..=bounds.max.y).contains(&point.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Circle {
+    center: Point,
+    radius: f64,
+}
+
+impl Circle {
+    pub fn new(center: Point, radius: f64) -> Self {
+        Circle { center, radius }
+    }
+
+    pub fn center(&self) -> Point {
+        self.center
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.radius * self.radius
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * std::f64::consts::PI * self.radius
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox {
+            min: Point::new(self.center.x - self.radius, self.center.y - self.radius),
+            max: Point::new(self.center.x + self.radius, self.center.y + self.radius),
+        }
+    }
+
+    fn contains_point(&self, point: Point) -> bool {
+        self.center.distance(point) <= self.radius
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Triangle {
+    vertices: [Point; 3],
+}
+
+impl Triangle {
+    pub fn new(a: Point, b: Point, c: Point) -> Self {
+        Triangle {
+            vertices: [a, b, c],
+        }
+    }
+
+    pub fn vertices(&self) -> [Point; 3] {
+        self.vertices
+    }
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `c`: positive when they
+/// go anticlockwise, negative when clockwise and zero when they're in a line.
+fn cross(a: Point, b: Point, c: Point) -> f64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+impl Shape for Triangle {
+    fn area(&self) -> f64 {
+        let [a, b, c] = self.vertices;
+        cross(a, b, c).abs() / 2.0
+    }
+
+    fn perimeter(&self) -> f64 {
+        let [a, b, c] = self.vertices;
+        a.distance(b) + b.distance(c) + c.distance(a)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        let [a, b, c] = self.vertices;
+        BoundingBox {
+            min: Point::new(a.x.min(b.x).min(c.x), a.y.min(b.y).min(c.y)),
+            max: Point::new(a.x.max(b.x).max(c.x), a.y.max(b.y).max(c.y)),
+        }
+    }
+
+    fn contains_point(&self, point: Point) -> bool {
+        // Inside (or on an edge) means not on the wrong side of any of them,
+        // whichever way round the vertices go.
+        let [a, b, c] = self.vertices;
+        let sides = [cross(a, b, point), cross(b, c, point), cross(c, a, point)];
+        sides.iter().all(|&side| side >= 0.0) || sides.iter().all(|&side| side <= 0.0)
+    }
+}
+
+/// Any one of the shapes, for when they need keeping together without
+/// boxing them, as in a scene loaded from JSON. With the `serde` feature each
+/// is tagged with its kind, like `{"shape": "circle", "center": ..}`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "shape", rename_all = "lowercase")
+)]
+pub enum AnyShape {
+    Rectangle(Rectangle),
+    Circle(Circle),
+    Triangle(Triangle),
+}
+
+impl AnyShape {
+    fn as_shape(&self) -> &dyn Shape {
+        match self {
+            AnyShape::Rectangle(rect) => rect,
+            AnyShape::Circle(circle) => circle,
+            AnyShape::Triangle(triangle) => triangle,
+        }
+    }
+}
+
+impl Shape for AnyShape {
+    fn area(&self) -> f64 {
+        Shape::area(self.as_shape())
+    }
+
+    fn perimeter(&self) -> f64 {
+        Shape::perimeter(self.as_shape())
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.as_shape().bounding_box()
+    }
+
+    fn contains_point(&self, point: Point) -> bool {
+        self.as_shape().contains_point(point)
+    }
+}
+
+impl From<Rectangle> for AnyShape {
+    fn from(rect: Rectangle) -> Self {
+        AnyShape::Rectangle(rect)
+    }
+}
+
+impl From<Circle> for AnyShape {
+    fn from(circle: Circle) -> Self {
+        AnyShape::Circle(circle)
+    }
+}
+
+impl From<Triangle> for AnyShape {
+    fn from(triangle: Triangle) -> Self {
+        AnyShape::Triangle(triangle)
+    }
+}
+
+/// The area of all of `shapes` added together, counting any overlap twice.
+pub fn total_area(shapes: &[Box<dyn Shape>]) -> f64 {
+    shapes.iter().map(|shape| shape.area()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn rectangle_as_a_shape() {
+        let rect = Rectangle::new(3, 4);
+        assert_eq!(12.0, Shape::area(&rect));
+        assert_eq!(14.0, Shape::perimeter(&rect));
+        assert_eq!(Point::new(3.0, 4.0), rect.bounding_box().max);
+        assert!(rect.contains_point(Point::new(1.5, 2.0)));
+        assert!(rect.contains_point(Point::new(3.0, 0.0)));
+        assert!(!rect.contains_point(Point::new(3.1, 2.0)));
+        assert!(!rect.contains_point(Point::new(1.0, -0.1)));
+    }
+
+    #[test]
+    fn circles() {
+        let circle = Circle::new(Point::new(1.0, 1.0), 2.0);
+        assert_eq!(PI * 4.0, circle.area());
+        assert_eq!(PI * 4.0, circle.perimeter());
+        let bounds = circle.bounding_box();
+        assert_eq!(Point::new(-1.0, -1.0), bounds.min);
+        assert_eq!((4.0, 4.0), (bounds.width(), bounds.height()));
+        assert!(circle.contains_point(Point::new(3.0, 1.0)));
+        // Inside the bounding box but outside the circle.
+        assert!(!circle.contains_point(Point::new(2.5, 2.5)));
+    }
+
+    #[test]
+    fn triangles() {
+        let triangle = Triangle::new(
+            Point::new(0.0, 0.0),
+            Point::new(4.0, 0.0),
+            Point::new(0.0, 3.0),
+        );
+        assert_eq!(6.0, triangle.area());
+        assert_eq!(12.0, triangle.perimeter());
+        assert_eq!(
+            BoundingBox {
+                min: Point::new(0.0, 0.0),
+                max: Point::new(4.0, 3.0),
+            },
+            triangle.bounding_box()
+        );
+        assert!(triangle.contains_point(Point::new(1.0, 1.0)));
+        assert!(triangle.contains_point(Point::new(2.0, 0.0)));
+        assert!(!triangle.contains_point(Point::new(3.0, 2.0)));
+    }
+
+    #[test]
+    fn triangles_either_way_round() {
+        let [a, b, c] = [
+            Point::new(0.0, 0.0),
+            Point::new(4.0, 0.0),
+            Point::new(0.0, 3.0),
+        ];
+        let clockwise = Triangle::new(a, c, b);
+        assert_eq!(6.0, clockwise.area());
+        assert!(clockwise.contains_point(Point::new(1.0, 1.0)));
+        assert!(!clockwise.contains_point(Point::new(-1.0, 1.0)));
+    }
+
+    #[test]
+    fn any_shape_acts_as_the_one_it_holds() {
+        let circle = Circle::new(Point::new(0.0, 0.0), 1.0);
+        let any = AnyShape::from(circle);
+        assert_eq!(circle.area(), any.area());
+        assert_eq!(circle.bounding_box(), any.bounding_box());
+        assert!(!any.contains_point(Point::new(1.0, 1.0)));
+        assert_eq!(12.0, AnyShape::from(Rectangle::new(3, 4)).area());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn scenes_round_trip_through_json() {
+        let scene = vec![
+            AnyShape::from(Rectangle::new(2, 5)),
+            AnyShape::from(Circle::new(Point::new(1.0, -1.0), 0.5)),
+            AnyShape::from(Triangle::new(
+                Point::new(0.0, 0.0),
+                Point::new(2.0, 0.0),
+                Point::new(0.0, 2.0),
+            )),
+        ];
+        let json = serde_json::to_string(&scene).unwrap();
+        assert!(
+            json.contains(r#"{"shape":"circle","center":{"x":1.0,"y":-1.0},"radius":0.5}"#),
+            "{json}"
+        );
+        assert_eq!(scene, serde_json::from_str::<Vec<AnyShape>>(&json).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn loads_a_scene_written_by_hand() {
+        let json = r#"[
+            {"shape": "rectangle", "width": 3, "height": 4},
+            {"shape": "triangle", "vertices": [
+                {"x": 0, "y": 0}, {"x": 4, "y": 0}, {"x": 0, "y": 3}
+            ]}
+        ]"#;
+        let scene: Vec<AnyShape> = serde_json::from_str(json).unwrap();
+        assert_eq!(18.0, scene.iter().map(Shape::area).sum::<f64>());
+        assert!(serde_json::from_str::<AnyShape>(r#"{"shape": "hexagon"}"#).is_err());
+    }
+
+    #[test]
+    fn adds_up_areas() {
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(Rectangle::new(2, 5)),
+            Box::new(Circle::new(Point::new(0.0, 0.0), 1.0)),
+            Box::new(Triangle::new(
+                Point::new(0.0, 0.0),
+                Point::new(2.0, 0.0),
+                Point::new(0.0, 2.0),
+            )),
+        ];
+        assert_eq!(12.0 + PI, total_area(&shapes));
+        assert_eq!(0.0, total_area(&[]));
+    }
+}
@@ -1,5 +1,3 @@
-use adder;
-
 #[test]
 fn it_adds_two() {
     assert_eq!(4, adder::add_two(2));
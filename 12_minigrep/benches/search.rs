@@ -0,0 +1,47 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use minigrep::{bench, count_matches, search_reader};
+
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+/// Every strategy over text already in memory.
+fn in_memory(c: &mut Criterion) {
+    let mut group = c.benchmark_group("in_memory");
+    for lines in SIZES {
+        let corpus = bench::corpus(lines, 42);
+        group.throughput(Throughput::Bytes(corpus.len() as u64));
+        for (name, matcher) in bench::strategies() {
+            group.bench_with_input(BenchmarkId::new(name, lines), &corpus, |b, corpus| {
+                b.iter(|| count_matches(&matcher, black_box(corpus)))
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Every strategy through the line-at-a-time reader that files go through.
+fn streaming(c: &mut Criterion) {
+    let mut group = c.benchmark_group("streaming");
+    for lines in SIZES {
+        let corpus = bench::corpus(lines, 42);
+        group.throughput(Throughput::Bytes(corpus.len() as u64));
+        for (name, matcher) in bench::strategies() {
+            group.bench_with_input(BenchmarkId::new(name, lines), &corpus, |b, corpus| {
+                b.iter(|| {
+                    let mut count = 0;
+                    search_reader(&matcher, black_box(corpus.as_bytes()), |_| {
+                        count += 1;
+                        true
+                    })
+                    .unwrap();
+                    count
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, in_memory, streaming);
+criterion_main!(benches);
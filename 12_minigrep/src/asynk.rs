@@ -0,0 +1,166 @@
+//! An async front end, for embedding minigrep in async programs like a
+//! code-search web service. Files are searched on the blocking pool by the
+//! same per-file search as the synchronous one, so every option that
+//! decides what is searched and found works the same here.
+
+use std::panic;
+use std::path::Path;
+use std::sync::Arc;
+
+use tokio::sync::Semaphore;
+use tokio::task::{self, JoinSet};
+
+use crate::limit::Budget;
+use crate::{search_logged, Config, FileMatches, Result, Search};
+
+/// Searches `path`, a file or a directory to walk, as `config` describes,
+/// searching up to `config.jobs` files at once. Results come back in the
+/// order a synchronous search would print them.
+///
+/// Files left out by `--max-files` or `--max-filesize`, or not reached
+/// before `--max-total` or `--timeout` stopped the search, aren't in the
+/// results. Every line is kept with `--passthru`, and `-o` is up to
+/// whoever shows the results, as with [`search_file`](crate::search_file).
+pub async fn search_path(config: &Config, path: impl AsRef<Path>) -> Result<Vec<FileMatches>> {
+    let config = Arc::new(Config {
+        filenames: vec![path.as_ref().to_string_lossy().into_owned()],
+        files_from: None,
+        ..config.clone()
+    });
+    let prepared = Arc::clone(&config);
+    let (matcher, inputs, deadline) = task::spawn_blocking(move || {
+        let (search, inputs) = Search::new(&prepared)?;
+        Ok::<_, crate::MinigrepError>((search.matcher, inputs, search.deadline))
+    })
+    .await
+    .unwrap_or_else(|e| panic::resume_unwind(e.into_panic()))?;
+
+    let matcher = Arc::new(matcher);
+    let budget = Arc::new(Budget::new(config.max_total).deadline(deadline));
+    let permits = Arc::new(Semaphore::new(config.jobs));
+    let mut results = Vec::with_capacity(inputs.len());
+    let mut tasks = JoinSet::new();
+    for (i, input) in inputs.into_iter().enumerate() {
+        let filename = match input {
+            Ok(filename) => filename,
+            Err(e) => {
+                results.push((i, FileMatches::failed(e)));
+                continue;
+            }
+        };
+        let (config, matcher, budget, permits) = (
+            Arc::clone(&config),
+            Arc::clone(&matcher),
+            Arc::clone(&budget),
+            Arc::clone(&permits),
+        );
+        tasks.spawn(async move {
+            // The semaphore is never closed, so this can't fail.
+            let _permit = permits.acquire_owned().await;
+            if budget.is_cancelled() {
+                return None;
+            }
+            let file = task::spawn_blocking(move || {
+                search_logged(&config, &*matcher, &filename, &budget, None)
+            })
+            .await
+            .unwrap_or_else(|e| panic::resume_unwind(e.into_panic()));
+            Some((i, file))
+        });
+    }
+    while let Some(joined) = tasks.join_next().await {
+        let searched = joined.unwrap_or_else(|e| panic::resume_unwind(e.into_panic()));
+        results.extend(searched);
+    }
+    results.sort_by_key(|&(i, _)| i);
+    Ok(results.into_iter().map(|(_, file)| file).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::tree;
+    use crate::{search_file, MinigrepError, OutputMode};
+    use std::fs;
+
+    fn fixture(name: &str) -> std::path::PathBuf {
+        tree(
+            name,
+            &[
+                (
+                    "poem.txt",
+                    "I'm nobody! Who are you?\nAre you nobody, too?\n",
+                ),
+                ("src/a.rs", "// nobody\n"),
+                ("src/b.rs", "fn main() {}\n"),
+                ("src/c.rs", "// nobody here\n// nor here, nobody\n"),
+            ],
+        )
+    }
+
+    #[tokio::test]
+    async fn agrees_with_the_sync_search() {
+        let root = fixture("async-agrees");
+        let poem = root.join("poem.txt").display().to_string();
+        let config = Config::builder()
+            .query("nobody")
+            .path(&poem)
+            .build()
+            .unwrap();
+
+        let found = search_path(&config, &poem).await.unwrap();
+        let sync = search_file(&config).unwrap();
+        assert_eq!(1, found.len());
+        assert_eq!(sync[0].matches, found[0].matches);
+        assert_eq!(sync[0].bytes, found[0].bytes);
+
+        let found = search_path(&config, root.join("src")).await.unwrap();
+        let paths: Vec<_> = found.iter().map(|file| file.path.as_str()).collect();
+        let mut sorted = paths.clone();
+        sorted.sort();
+        assert_eq!(sorted, paths);
+        assert_eq!(3, paths.len());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn counts_and_reports_errors() {
+        let root = fixture("async-counts");
+        let config = Config::builder()
+            .query("nobody")
+            .output(OutputMode::Count)
+            .build()
+            .unwrap();
+
+        let found = search_path(&config, root.join("poem.txt")).await.unwrap();
+        assert_eq!(2, found[0].count);
+        let missing = search_path(&config, root.join("missing.txt"))
+            .await
+            .unwrap();
+        assert!(matches!(missing[0].error, Some(MinigrepError::Io { .. })));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn honors_the_limits() {
+        let root = fixture("async-limits");
+        let src = root.join("src");
+        let search = |config: Config| {
+            let src = src.clone();
+            async move { search_path(&config, src).await.unwrap() }
+        };
+        let matched = |found: &[FileMatches]| found.iter().map(|file| file.count).sum::<usize>();
+
+        let config = Config::builder().query("nobody").threads(1);
+        assert_eq!(3, matched(&search(config.clone().build().unwrap()).await));
+        let limited = search(config.clone().max_total(1).build().unwrap()).await;
+        assert_eq!(1, matched(&limited));
+        let limited = search(config.clone().max_count(1).build().unwrap()).await;
+        assert_eq!(2, matched(&limited));
+        let limited = search(config.clone().max_files(2).build().unwrap()).await;
+        assert_eq!(2, limited.len());
+        let limited = search(config.max_filesize(20).build().unwrap()).await;
+        assert_eq!(2, limited.len());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,95 @@
+//! Helpers for the benchmarks in `benches/`: generated text to search, and
+//! the matcher strategies to compare on it.
+
+use crate::matcher::{CaseInsensitive, Literal, Matcher, Regex};
+
+/// The word the strategies look for. It turns up in roughly one generated
+/// line in fifty.
+pub const NEEDLE: &str = "productive";
+
+const WORDS: &[&str] = &[
+    "safe", "fast", "rust", "duct", "tape", "three", "pick", "nobody", "frog", "bog", "dreary",
+    "public", "admiring", "june", "tell", "name", "livelong", "day", "who", "are", "you",
+];
+
+/// Generates `lines` lines of pseudo-random words. The same `seed` always
+/// gives the same text, so runs can be compared.
+pub fn corpus(lines: usize, seed: u64) -> String {
+    let mut rng = SplitMix(seed);
+    let mut text = String::new();
+    for _ in 0..lines {
+        let words = 4 + rng.below(12);
+        for i in 0..words {
+            if i > 0 {
+                text.push(' ');
+            }
+            // One word in ~500 makes roughly one line in 50 match.
+            if rng.below(500) == 0 {
+                text.push_str(NEEDLE);
+            } else {
+                text.push_str(WORDS[rng.below(WORDS.len())]);
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// The ways of finding [`NEEDLE`] worth comparing, by name.
+pub fn strategies() -> Vec<(&'static str, Box<dyn Matcher>)> {
+    let others = ["three", "nobody", NEEDLE].map(String::from);
+    vec![
+        ("literal", Box::new(Literal::new(NEEDLE))),
+        ("case-insensitive", Box::new(CaseInsensitive::new(NEEDLE))),
+        (
+            "regex",
+            Box::new(Regex::new(r"prod\w+ive", false).expect("valid pattern")),
+        ),
+        (
+            "multi-pattern",
+            Box::new(Regex::any(&others, false, false).expect("valid patterns")),
+        ),
+    ]
+}
+
+/// A small, fast generator; the corpus needs to look random, not be
+/// unpredictable.
+struct SplitMix(u64);
+
+impl SplitMix {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::count_matches;
+
+    #[test]
+    fn corpus_is_repeatable() {
+        assert_eq!(corpus(100, 7), corpus(100, 7));
+        assert_ne!(corpus(100, 7), corpus(100, 8));
+        assert_eq!(100, corpus(100, 7).lines().count());
+    }
+
+    #[test]
+    fn strategies_agree_on_the_needle() {
+        let text = corpus(2_000, 42);
+        let expected = count_matches(&Literal::new(NEEDLE), &text);
+        assert!(expected > 0);
+        for (name, matcher) in strategies() {
+            assert!(count_matches(&matcher, &text) >= expected, "{name}");
+        }
+    }
+}
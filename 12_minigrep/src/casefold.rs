@@ -0,0 +1,97 @@
+/// Folds `c` the way Unicode simple case folding does (the `C` and `S`
+/// entries of CaseFolding.txt), so two strings match case-insensitively when
+/// their folds are equal. Unlike `to_lowercase`, every character folds to
+/// exactly one character.
+pub fn fold(c: char) -> char {
+    match c {
+        // Already lowercase, but with a more common lowercase form.
+        'µ' => 'μ',
+        'ſ' => 's',
+        '\u{345}' | 'ι' => 'ι',
+        'ς' => 'σ',
+        'ϐ' => 'β',
+        'ϑ' => 'θ',
+        'ϕ' => 'φ',
+        'ϖ' => 'π',
+        'ϰ' => 'κ',
+        'ϱ' => 'ρ',
+        'ϵ' => 'ε',
+        'ẛ' => 'ṡ',
+        // Cherokee folds to its uppercase letters, which came first.
+        '\u{13A0}'..='\u{13F5}' => c,
+        '\u{13F8}'..='\u{13FD}' => char::from_u32(c as u32 - 8).unwrap_or(c),
+        '\u{AB70}'..='\u{ABBF}' => char::from_u32(c as u32 - 0xAB70 + 0x13A0).unwrap_or(c),
+        _ => {
+            // Characters whose lowercase is longer than one character, like
+            // 'İ', have no simple folding and stay as they are.
+            let mut lower = c.to_lowercase();
+            match (lower.next(), lower.next()) {
+                (Some(lower), None) => lower,
+                _ => c,
+            }
+        }
+    }
+}
+
+/// A case-folded copy of some text that remembers where each of its
+/// characters came from.
+pub struct Folded {
+    pub text: String,
+    /// For each byte offset of `text` that starts a character (and its end),
+    /// the matching byte offset in the original.
+    origins: Vec<usize>,
+}
+
+impl Folded {
+    pub fn new(original: &str) -> Self {
+        let mut text = String::with_capacity(original.len());
+        let mut origins = Vec::with_capacity(original.len() + 1);
+        for (index, c) in original.char_indices() {
+            let folded = fold(c);
+            origins.resize(text.len(), index);
+            origins.push(index);
+            text.push(folded);
+        }
+        origins.resize(text.len() + 1, original.len());
+        Folded { text, origins }
+    }
+
+    /// Maps a byte offset at a character boundary of the folded text back
+    /// onto the original.
+    pub fn origin(&self, offset: usize) -> usize {
+        self.origins[offset]
+    }
+}
+
+/// Folds a whole string.
+pub fn fold_str(text: &str) -> String {
+    text.chars().map(fold).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_to_one_character() {
+        assert_eq!('a', fold('A'));
+        assert_eq!('σ', fold('Σ'));
+        assert_eq!('σ', fold('ς'));
+        assert_eq!('s', fold('ſ'));
+        assert_eq!('k', fold('\u{212A}'));
+        assert_eq!('ß', fold('ẞ'));
+        assert_eq!('ß', fold('ß'));
+        assert_eq!('İ', fold('İ'));
+        assert_eq!('Ꭰ', fold('ꭰ'));
+    }
+
+    #[test]
+    fn maps_offsets_back() {
+        // The Kelvin sign is three bytes, its fold is one.
+        let folded = Folded::new("\u{212A}elvin ſun");
+        assert_eq!("kelvin sun", folded.text);
+        assert_eq!(3, folded.origin(1));
+        assert_eq!(9, folded.origin(7));
+        assert_eq!(13, folded.origin(folded.text.len()));
+    }
+}
@@ -0,0 +1,286 @@
+#[cfg(feature = "cli")]
+use std::io::{self, IsTerminal};
+use std::ops::Range;
+
+const RESET: &str = "\x1b[0m";
+
+/// When to wrap matches in ANSI color codes, as chosen by `--color=WHEN`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    Always,
+    Never,
+    #[default]
+    Auto,
+}
+
+impl ColorChoice {
+    pub fn parse(value: &str) -> Result<Self, &'static str> {
+        match value {
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            "auto" => Ok(ColorChoice::Auto),
+            _ => Err("--color must be one of always, never or auto"),
+        }
+    }
+
+    /// Resolves `Auto` by checking whether stdout is a terminal. Library
+    /// callers resolve it themselves and hand the printer the answer.
+    #[cfg(feature = "cli")]
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => io::stdout().is_terminal(),
+        }
+    }
+}
+
+/// One of the eight basic terminal colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    fn parse(value: &str) -> Option<Self> {
+        Some(match value {
+            "black" => Color::Black,
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "yellow" => Color::Yellow,
+            "blue" => Color::Blue,
+            "magenta" => Color::Magenta,
+            "cyan" => Color::Cyan,
+            "white" => Color::White,
+            _ => return None,
+        })
+    }
+
+    /// The color's offset from the base foreground (30) or background (40)
+    /// SGR code.
+    fn code(self) -> u8 {
+        self as u8
+    }
+}
+
+/// How one kind of output is painted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub underline: bool,
+}
+
+impl Style {
+    /// The SGR sequence that starts this style, if it does anything.
+    fn start(&self) -> Option<String> {
+        let mut codes = Vec::new();
+        if self.bold {
+            codes.push(String::from("1"));
+        }
+        if self.underline {
+            codes.push(String::from("4"));
+        }
+        if let Some(fg) = self.fg {
+            codes.push((30 + fg.code()).to_string());
+        }
+        if let Some(bg) = self.bg {
+            codes.push((40 + bg.code()).to_string());
+        }
+        (!codes.is_empty()).then(|| format!("\x1b[{}m", codes.join(";")))
+    }
+
+    pub fn paint(&self, text: &str) -> String {
+        match self.start() {
+            Some(start) => format!("{start}{text}{RESET}"),
+            None => text.to_string(),
+        }
+    }
+}
+
+/// The parts of the output that can be colored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    Path,
+    Line,
+    Column,
+    Match,
+}
+
+/// One `--colors` spec, `part:attribute:value` or `part:none`, e.g.
+/// `match:fg:red`, `path:style:bold` or `line:bg:blue`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorSpec {
+    part: Part,
+    change: Change,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Change {
+    None,
+    Fg(Color),
+    Bg(Color),
+    Bold(bool),
+    Underline(bool),
+}
+
+impl ColorSpec {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        const USAGE: &str = "--colors takes part:attribute:value, e.g. match:fg:red, \
+                             where part is path, line, column or match";
+        let mut fields = spec.split(':');
+        let part = match fields.next() {
+            Some("path") => Part::Path,
+            Some("line") => Part::Line,
+            Some("column") => Part::Column,
+            Some("match") => Part::Match,
+            _ => return Err(USAGE.into()),
+        };
+        let color = |color| {
+            Color::parse(color).ok_or_else(|| {
+                format!(
+                    "--colors: {color} isn't a color; use black, red, green, yellow, blue, \
+                     magenta, cyan or white"
+                )
+            })
+        };
+        let change = match (fields.next(), fields.next(), fields.next()) {
+            (Some("none"), None, _) => Change::None,
+            (Some("fg"), Some(fg), None) => Change::Fg(color(fg)?),
+            (Some("bg"), Some(bg), None) => Change::Bg(color(bg)?),
+            (Some("style"), Some(style), None) => match style {
+                "bold" => Change::Bold(true),
+                "nobold" => Change::Bold(false),
+                "underline" => Change::Underline(true),
+                "nounderline" => Change::Underline(false),
+                _ => return Err(USAGE.into()),
+            },
+            _ => return Err(USAGE.into()),
+        };
+        Ok(ColorSpec { part, change })
+    }
+}
+
+/// The styles used for each part of the output when color is enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub path: Style,
+    pub line: Style,
+    pub column: Style,
+    pub matched: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            path: Style::default(),
+            line: Style::default(),
+            column: Style::default(),
+            matched: Style {
+                fg: Some(Color::Red),
+                bold: true,
+                ..Style::default()
+            },
+        }
+    }
+}
+
+impl Theme {
+    /// Applies `spec` on top of the current styles.
+    pub fn apply(&mut self, spec: &ColorSpec) {
+        let style = match spec.part {
+            Part::Path => &mut self.path,
+            Part::Line => &mut self.line,
+            Part::Column => &mut self.column,
+            Part::Match => &mut self.matched,
+        };
+        match spec.change {
+            Change::None => *style = Style::default(),
+            Change::Fg(color) => style.fg = Some(color),
+            Change::Bg(color) => style.bg = Some(color),
+            Change::Bold(bold) => style.bold = bold,
+            Change::Underline(underline) => style.underline = underline,
+        }
+    }
+
+    /// Paints every byte range in `spans` with the match style.
+    pub fn highlight(&self, line: &str, spans: &[Range<usize>]) -> String {
+        if spans.is_empty() {
+            return line.to_string();
+        }
+        let mut out = String::with_capacity(line.len() + spans.len() * 16);
+        let mut last = 0;
+        for span in spans {
+            out.push_str(&line[last..span.start]);
+            out.push_str(&self.matched.paint(&line[span.clone()]));
+            last = span.end;
+        }
+        out.push_str(&line[last..]);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_color_choice() {
+        assert_eq!(Ok(ColorChoice::Always), ColorChoice::parse("always"));
+        assert_eq!(Ok(ColorChoice::Never), ColorChoice::parse("never"));
+        assert!(ColorChoice::parse("sometimes").is_err());
+    }
+
+    #[test]
+    fn highlights_each_span() {
+        assert_eq!(
+            "a \x1b[1;31mb\x1b[0m c \x1b[1;31mb\x1b[0m",
+            Theme::default().highlight("a b c b", &[2..3, 6..7])
+        );
+    }
+
+    #[test]
+    fn applies_color_specs() {
+        let mut theme = Theme::default();
+        for spec in [
+            "match:none",
+            "match:fg:green",
+            "match:style:underline",
+            "path:bg:blue",
+        ] {
+            theme.apply(&ColorSpec::parse(spec).unwrap());
+        }
+
+        assert_eq!("\x1b[4;32mb\x1b[0m", theme.matched.paint("b"));
+        assert_eq!("\x1b[44mpoem.txt\x1b[0m", theme.path.paint("poem.txt"));
+        assert_eq!("7", theme.line.paint("7"));
+    }
+
+    #[test]
+    fn rejects_bad_color_specs() {
+        for spec in [
+            "match",
+            "match:fg",
+            "match:fg:pink",
+            "title:fg:red",
+            "match:style:bold:x",
+        ] {
+            assert!(ColorSpec::parse(spec).is_err(), "{spec}");
+        }
+        assert_eq!(
+            Err(String::from(
+                "--colors: pink isn't a color; use black, red, green, yellow, blue, magenta, \
+                 cyan or white"
+            )),
+            ColorSpec::parse("match:fg:pink")
+        );
+    }
+}
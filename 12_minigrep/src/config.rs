@@ -0,0 +1,1605 @@
+#[cfg(feature = "cli")]
+use std::env;
+use std::fs;
+use std::time::Duration;
+
+#[cfg(feature = "cli")]
+use crate::filter::PathGlobs;
+use crate::input::{self, MmapChoice, ReadOptions};
+use crate::log::{self, Level};
+use crate::matcher::{CaseInsensitive, Invert, Literal, Matcher, Regex};
+#[cfg(feature = "cli")]
+use crate::preprocess::Preprocessor;
+use crate::printer::Format;
+#[cfg(feature = "cli")]
+use crate::user_config;
+use crate::{
+    pool, ColorChoice, ColorSpec, MinigrepError, SortBy, TextEncoding, Theme, WalkOptions,
+};
+
+/// What `run` prints for each file searched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputMode {
+    /// Every matching line.
+    #[default]
+    Lines,
+    /// Only the number of matching lines (`-c`).
+    Count,
+    /// Only the names of files with at least one match (`-l`).
+    FilesWithMatches,
+    /// Files ranked by their count, drawn as a bar chart once the search is
+    /// done (`--histogram`).
+    Histogram,
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// A line matches if any of these does.
+    pub patterns: Vec<String>,
+    pub filenames: Vec<String>,
+    /// Also search the files listed, one per line, in this file, or in
+    /// standard input if it's `-` (`--files-from`).
+    pub files_from: Option<String>,
+    /// Print the file name with each match even when searching one file
+    /// (`-H`).
+    pub with_filename: bool,
+    pub ignore_case: bool,
+    pub regex: bool,
+    pub invert_match: bool,
+    pub line_number: bool,
+    /// Print the byte offset of each line, or of each match with `-o`
+    /// (`-b`).
+    pub byte_offset: bool,
+    pub column: bool,
+    /// Print every line, highlighting the matching ones (`--passthru`).
+    pub passthru: bool,
+    /// Print each file's name once above its lines rather than on every
+    /// line (`--heading`, `--no-heading`). Unset means only on a terminal.
+    pub heading: Option<bool>,
+    /// Print only the matched parts of lines, one per line (`-o`).
+    pub only_matching: bool,
+    /// End file names with NUL rather than `:` or a newline, for `xargs -0`
+    /// (`-0`).
+    pub null: bool,
+    pub color: ColorChoice,
+    /// The colors to use when color is on (`--colors`).
+    pub theme: Theme,
+    pub output: OutputMode,
+    pub walk: WalkOptions,
+    pub jobs: usize,
+    pub read: ReadOptions,
+    /// Stop searching a file after this many matching lines (`-m`).
+    pub max_count: Option<usize>,
+    /// Cut printed lines longer than this many characters down to the part
+    /// around the first match (`--max-columns`).
+    pub max_columns: Option<usize>,
+    /// Stop the whole run after this many matching lines (`--max-total`).
+    pub max_total: Option<usize>,
+    /// Skip files bigger than this many bytes (`--max-filesize`).
+    pub max_filesize: Option<u64>,
+    /// Search at most this many files, skipping the rest (`--max-files`).
+    pub max_files: Option<usize>,
+    /// Only search files git reports as changed or untracked (`--changed`).
+    #[cfg(feature = "cli")]
+    pub changed: bool,
+    /// Give up after this long, printing what was found so far
+    /// (`--timeout`).
+    pub timeout: Option<Duration>,
+    /// Print lines with matches replaced by this text (`--replace`).
+    pub replace: Option<String>,
+    /// End replaced lines that ended in `\r\n` with it again, rather than
+    /// `\n` (`--crlf`).
+    pub crlf: bool,
+    /// Rewrite files in place instead of printing replaced lines (`--write`).
+    pub write: bool,
+    /// Keep the original as `<file>.bak` when rewriting (`--backup`).
+    pub backup: bool,
+    /// Print nothing; only the exit status reports whether anything matched
+    /// (`-q`).
+    pub quiet: bool,
+    /// Print a summary of the search at the end (`--stats`).
+    pub stats: bool,
+    /// What separates the file name, line number, offset and column from
+    /// each other and the line, instead of `:` (`--field-separator`).
+    pub field_separator: Option<String>,
+    /// Print paths with this between directories instead of the platform's
+    /// own separator (`--path-separator`).
+    pub path_separator: Option<char>,
+    /// Count every match rather than every matching line, with `-c` or
+    /// `--histogram` (`--count-matches`, which implies `-c`).
+    pub count_matches: bool,
+    /// Print results in this order rather than as files finish (`--sort`).
+    pub sort: Option<SortBy>,
+    /// Which printer renders the results (`--format`, `--json`, `--output`).
+    pub format: Format,
+    /// Explain the search on stderr in this much detail (`--verbose`,
+    /// `-vv`).
+    pub verbose: Option<Level>,
+    /// Also dump the parsed configuration on stderr before searching, which
+    /// implies `-vv` (`--debug`).
+    pub debug: bool,
+    /// Keep running and print matches in lines appended to the files
+    /// (`--watch`).
+    pub watch: bool,
+}
+
+/// Every long option, without its `--`, and whether it takes a value. The
+/// config file's keys are checked against these.
+#[cfg(feature = "cli")]
+pub(crate) const LONG_OPTIONS: &[(&str, bool)] = &[
+    ("ignore-case", false),
+    ("regexp", true),
+    ("file", true),
+    ("regex", false),
+    ("invert-match", false),
+    ("line-number", false),
+    ("column", false),
+    ("byte-offset", false),
+    ("only-matching", false),
+    ("passthru", false),
+    ("heading", false),
+    ("no-heading", false),
+    ("null", false),
+    ("count", false),
+    ("files-with-matches", false),
+    ("histogram", false),
+    ("count-matches", false),
+    ("field-separator", true),
+    ("path-separator", true),
+    ("hidden", false),
+    ("no-ignore", false),
+    ("quiet", false),
+    ("stats", false),
+    ("verbose", false),
+    ("debug", false),
+    ("watch", false),
+    ("pre", true),
+    ("pre-glob", true),
+    ("pre-timeout", true),
+    ("files-from", true),
+    ("with-filename", false),
+    ("format", true),
+    ("output", true),
+    ("json", false),
+    ("sort", true),
+    ("max-depth", true),
+    ("follow", false),
+    ("no-follow", false),
+    ("type", true),
+    ("type-not", true),
+    ("include", true),
+    ("exclude", true),
+    ("type-add", true),
+    ("mmap", false),
+    ("no-mmap", false),
+    ("search-zip", false),
+    ("encoding", true),
+    ("replace", true),
+    ("write", false),
+    ("backup", false),
+    ("crlf", false),
+    ("threads", true),
+    ("max-columns", true),
+    ("max-count", true),
+    ("max-total", true),
+    ("max-filesize", true),
+    ("max-files", true),
+    ("changed", false),
+    ("timeout", true),
+    ("colors", true),
+    ("color", true),
+];
+
+/// An iterator that remembers how many items it has handed out, so `parse`
+/// can tell the config file's flags from the command line's.
+struct Counted<I> {
+    inner: I,
+    taken: usize,
+}
+
+impl<I: Iterator> Iterator for Counted<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let item = self.inner.next()?;
+        self.taken += 1;
+        Some(item)
+    }
+}
+
+impl Config {
+    /// Parses command line arguments, the first of which is the program name.
+    /// Defaults are read from the user's config file unless `--no-config`
+    /// is given.
+    #[cfg(feature = "cli")]
+    pub fn new(args: impl Iterator<Item = String>) -> Result<Self, MinigrepError> {
+        let args: Vec<String> = args.collect();
+        let no_config = args
+            .iter()
+            .take_while(|arg| *arg != "--")
+            .any(|arg| arg == "--no-config");
+        let defaults = if no_config {
+            Vec::new()
+        } else {
+            user_config::load()?
+        };
+        // The `-i` flag always wins over the environment variable.
+        Self::parse_with_defaults(
+            defaults,
+            args.into_iter(),
+            env::var("CASE_INSENSITIVE").is_ok(),
+        )
+    }
+
+    /// Starts building a `Config` in code rather than from arguments.
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::new()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn parse(
+        args: impl Iterator<Item = String>,
+        ignore_case_default: bool,
+    ) -> Result<Self, MinigrepError> {
+        Self::parse_with_defaults(Vec::new(), args, ignore_case_default)
+    }
+
+    /// Parses `defaults` from the config file as if they came just before
+    /// the command line's own flags, so a flag given on both uses the
+    /// command line's value. `-c`, `-l`, `--histogram` or `--count-matches`
+    /// on the command line replace whichever of them the config file chose,
+    /// rather than clashing with it.
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    pub(crate) fn parse_with_defaults(
+        defaults: Vec<String>,
+        mut args: impl Iterator<Item = String>,
+        ignore_case_default: bool,
+    ) -> Result<Self, MinigrepError> {
+        args.next();
+        let from_config = defaults.len();
+        let mut args = Counted {
+            inner: defaults.into_iter().chain(args),
+            taken: 0,
+        };
+
+        let mut builder = Config::builder().case_insensitive(ignore_case_default);
+        let mut output = OutputMode::default();
+        // Whether `output` and `--count-matches` were set by the config file.
+        let mut output_from_config = false;
+        let mut pattern_flag = false;
+        let mut positional = Vec::new();
+        while let Some(arg) = args.next() {
+            let in_config = args.taken <= from_config;
+            if !in_config
+                && output_from_config
+                && matches!(
+                    arg.as_str(),
+                    "-c" | "--count"
+                        | "-l"
+                        | "--files-with-matches"
+                        | "--histogram"
+                        | "--count-matches"
+                )
+            {
+                output = OutputMode::default();
+                builder = builder.output(output).count_matches(false);
+                output_from_config = false;
+            }
+            builder = match arg.as_str() {
+                "-i" | "--ignore-case" => builder.case_insensitive(true),
+                "-e" | "--regexp" => {
+                    pattern_flag = true;
+                    builder.query(next_value(&mut args, "-e needs a pattern")?)
+                }
+                "-f" | "--file" => {
+                    let path = next_value(&mut args, "-f needs a pattern file")?;
+                    let contents =
+                        fs::read_to_string(&path).map_err(|e| MinigrepError::io(&path, e))?;
+                    pattern_flag = true;
+                    builder.queries(contents.lines().map(String::from))
+                }
+                "--regex" => builder.regex(true),
+                "-v" | "--invert-match" => builder.invert_match(true),
+                "-n" | "--line-number" => builder.line_number(true),
+                "--column" => builder.column(true),
+                "-b" | "--byte-offset" => builder.byte_offset(true),
+                "-o" | "--only-matching" => builder.only_matching(true),
+                "--passthru" => builder.passthru(true),
+                "--heading" => builder.heading(true),
+                "--no-heading" => builder.heading(false),
+                "-0" | "--null" => builder.null(true),
+                "-H" | "--with-filename" => builder.with_filename(true),
+                "-c" | "--count" => {
+                    output = set_output(output, OutputMode::Count)?;
+                    output_from_config |= in_config;
+                    builder.output(output)
+                }
+                "-l" | "--files-with-matches" => {
+                    output = set_output(output, OutputMode::FilesWithMatches)?;
+                    output_from_config |= in_config;
+                    builder.output(output)
+                }
+                "--histogram" => {
+                    output = set_output(output, OutputMode::Histogram)?;
+                    output_from_config |= in_config;
+                    builder.output(output)
+                }
+                "--count-matches" => {
+                    output_from_config |= in_config;
+                    builder.count_matches(true)
+                }
+                "--field-separator" => builder.field_separator(next_value(
+                    &mut args,
+                    "--field-separator needs a separator",
+                )?),
+                "--path-separator" => {
+                    let separator = next_value(&mut args, "--path-separator needs a character")?;
+                    let mut chars = separator.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => builder.path_separator(c),
+                        _ => return Err("--path-separator must be a single character".into()),
+                    }
+                }
+                "--hidden" => builder.hidden(true),
+                "--no-ignore" => builder.no_ignore(true),
+                "-q" | "--quiet" => builder.quiet(true),
+                // Handled by `new`, before the config file is read.
+                "--no-config" => builder,
+                "--stats" => builder.stats(true),
+                "--verbose" => builder.verbose(Level::Info),
+                "-vv" => builder.verbose(Level::Debug),
+                "--debug" => builder.debug(true),
+                "--watch" => builder.watch(true),
+                #[cfg(feature = "cli")]
+                "--pre" => builder.pre(next_value(&mut args, "--pre needs a command")?),
+                #[cfg(feature = "cli")]
+                "--pre-glob" => builder.pre_glob(next_value(&mut args, "--pre-glob needs a glob")?),
+                #[cfg(feature = "cli")]
+                "--pre-timeout" => builder.pre_timeout(Duration::from_secs(next_number(
+                    &mut args,
+                    "--pre-timeout needs a positive number of seconds",
+                )?
+                    as u64)),
+                "--files-from" => builder.files_from(next_value(
+                    &mut args,
+                    "--files-from needs a file, or - for standard input",
+                )?),
+                "--format" | "--output" => builder.format(Format::parse(&next_value(
+                    &mut args,
+                    "--format needs text, json, ndjson or csv",
+                )?)?),
+                "--json" => builder.format(Format::Json),
+                "--sort" => builder.sort(SortBy::parse(&next_value(
+                    &mut args,
+                    "--sort needs path, modified or size",
+                )?)?),
+                "--max-depth" => builder.max_depth(next_number(
+                    &mut args,
+                    "--max-depth needs a positive number",
+                )?),
+                "-L" | "--follow" => builder.follow(true),
+                "-t" | "--type" => builder.file_type(next_value(&mut args, "--type needs a type")?),
+                "-T" | "--type-not" => {
+                    builder.file_type_not(next_value(&mut args, "--type-not needs a type")?)
+                }
+                "--include" => builder.include(next_value(&mut args, "--include needs a glob")?),
+                "--exclude" => builder.exclude(next_value(&mut args, "--exclude needs a glob")?),
+                "--type-add" => {
+                    builder.file_type_add(next_value(&mut args, "--type-add needs name:glob")?)
+                }
+                "--no-follow" => builder.follow(false),
+                "--mmap" => builder.mmap(MmapChoice::Always),
+                "--no-mmap" => builder.mmap(MmapChoice::Never),
+                "-z" | "--search-zip" => builder.search_zip(true),
+                "--encoding" => {
+                    let value = next_value(&mut args, "--encoding needs an encoding name")?;
+                    builder.encoding(TextEncoding::parse(&value)?)
+                }
+                "--replace" => {
+                    builder.replace(next_value(&mut args, "--replace needs replacement text")?)
+                }
+                "--write" => builder.write(true),
+                "--backup" => builder.backup(true),
+                "--crlf" => builder.crlf(true),
+                "-j" | "--threads" => builder.threads(next_number(
+                    &mut args,
+                    "-j needs a positive number of threads",
+                )?),
+                "--max-columns" => builder.max_columns(next_number(
+                    &mut args,
+                    "--max-columns needs a positive number",
+                )?),
+                // Unlike the other limits, -m 0 is allowed and prints
+                // nothing, as in grep.
+                "-m" | "--max-count" => {
+                    let max = next_value(&mut args, "-m needs a number")?;
+                    builder.max_count(max.parse().map_err(|_| "-m needs a number")?)
+                }
+                "--max-total" => builder.max_total(next_number(
+                    &mut args,
+                    "--max-total needs a positive number",
+                )?),
+                "--max-filesize" => {
+                    let size = next_value(&mut args, "--max-filesize needs a size like 10M")?;
+                    builder.max_filesize(parse_size(&size)?)
+                }
+                "--max-files" => builder.max_files(next_number(
+                    &mut args,
+                    "--max-files needs a positive number",
+                )?),
+                #[cfg(feature = "cli")]
+                "--changed" => builder.changed(true),
+                "--timeout" => {
+                    let timeout = next_value(&mut args, "--timeout needs a duration like 5s")?;
+                    builder.timeout(parse_duration(&timeout)?)
+                }
+                "--colors" => {
+                    let spec = next_value(&mut args, "--colors needs a spec like match:fg:red")?;
+                    builder.colors(ColorSpec::parse(&spec).map_err(MinigrepError::BadArgs)?)
+                }
+                "--color" => {
+                    let when = next_value(&mut args, "--color needs always, never or auto")?;
+                    builder.color(ColorChoice::parse(&when)?)
+                }
+                _ if arg.starts_with("--color=") => {
+                    builder.color(ColorChoice::parse(&arg["--color=".len()..])?)
+                }
+                // Everything after `--` is a pattern or a file, even if it
+                // looks like a flag.
+                "--" => {
+                    positional.extend(args.by_ref());
+                    builder
+                }
+                // A lone `-` is standard input.
+                _ if arg.starts_with('-') && arg != "-" => {
+                    return Err(MinigrepError::BadArgs(format!("unknown option {arg}")));
+                }
+                _ => {
+                    positional.push(arg);
+                    builder
+                }
+            };
+        }
+
+        let mut positional = positional.into_iter();
+        // With -e or -f, every positional argument is a file.
+        if !pattern_flag {
+            builder = builder.queries(positional.next());
+        }
+        builder.paths(positional).build()
+    }
+
+    /// Builds the matcher described by the search flags.
+    pub fn matcher(&self) -> Result<Box<dyn Matcher>, regex::Error> {
+        let (matcher, kind): (Box<dyn Matcher>, _) = match self.patterns.as_slice() {
+            [query] if self.regex => (Box::new(Regex::new(query, self.ignore_case)?), "regex"),
+            [query] if self.ignore_case => (
+                Box::new(CaseInsensitive::new(query)),
+                "case-insensitive literal",
+            ),
+            [query] => (Box::new(Literal::new(query)), "literal"),
+            patterns => (
+                Box::new(Regex::any(patterns, self.regex, self.ignore_case)?),
+                "combined regex",
+            ),
+        };
+        log::info(|| {
+            let inverted = if self.invert_match { ", inverted" } else { "" };
+            format!("using the {kind} matcher{inverted}")
+        });
+        if self.invert_match {
+            Ok(Box::new(Invert::new(matcher)))
+        } else {
+            Ok(matcher)
+        }
+    }
+}
+
+/// Builds a [`Config`] for programs that embed minigrep, e.g.
+///
+/// ```
+/// let config = minigrep::Config::builder()
+///     .query("duct")
+///     .path("poem.txt")
+///     .case_insensitive(true)
+///     .build()
+///     .unwrap();
+/// assert!(config.ignore_case);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    config: Config,
+    /// Kept aside until `build`, as `--pre-glob` and `--pre-timeout` may come
+    /// before `--pre`.
+    #[cfg(feature = "cli")]
+    pre_globs: PathGlobs,
+    #[cfg(feature = "cli")]
+    pre_timeout: Option<Duration>,
+}
+
+impl ConfigBuilder {
+    fn new() -> Self {
+        ConfigBuilder {
+            config: Config {
+                patterns: Vec::new(),
+                filenames: Vec::new(),
+                files_from: None,
+                with_filename: false,
+                ignore_case: false,
+                regex: false,
+                invert_match: false,
+                line_number: false,
+                byte_offset: false,
+                column: false,
+                passthru: false,
+                heading: None,
+                only_matching: false,
+                null: false,
+                color: ColorChoice::default(),
+                theme: Theme::default(),
+                output: OutputMode::default(),
+                walk: WalkOptions::default(),
+                jobs: pool::default_jobs(),
+                read: ReadOptions::default(),
+                max_count: None,
+                max_columns: None,
+                max_total: None,
+                max_filesize: None,
+                max_files: None,
+                #[cfg(feature = "cli")]
+                changed: false,
+                timeout: None,
+                replace: None,
+                crlf: false,
+                write: false,
+                backup: false,
+                quiet: false,
+                stats: false,
+                count_matches: false,
+                field_separator: None,
+                path_separator: None,
+                sort: None,
+                format: Format::Text,
+                verbose: None,
+                debug: false,
+                watch: false,
+            },
+            #[cfg(feature = "cli")]
+            pre_globs: PathGlobs::default(),
+            #[cfg(feature = "cli")]
+            pre_timeout: None,
+        }
+    }
+
+    /// Adds a pattern to search for. Lines matching any pattern are reported.
+    pub fn query(mut self, query: impl Into<String>) -> Self {
+        self.config.patterns.push(query.into());
+        self
+    }
+
+    pub fn queries(mut self, queries: impl IntoIterator<Item = String>) -> Self {
+        self.config.patterns.extend(queries);
+        self
+    }
+
+    /// Adds a file, directory or glob to search. Without any, standard input
+    /// is searched.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.config.filenames.push(path.into());
+        self
+    }
+
+    pub fn paths(mut self, paths: impl IntoIterator<Item = String>) -> Self {
+        self.config.filenames.extend(paths);
+        self
+    }
+
+    pub fn with_filename(mut self, yes: bool) -> Self {
+        self.config.with_filename = yes;
+        self
+    }
+
+    pub fn case_insensitive(mut self, yes: bool) -> Self {
+        self.config.ignore_case = yes;
+        self
+    }
+
+    pub fn regex(mut self, yes: bool) -> Self {
+        self.config.regex = yes;
+        self
+    }
+
+    pub fn invert_match(mut self, yes: bool) -> Self {
+        self.config.invert_match = yes;
+        self
+    }
+
+    pub fn line_number(mut self, yes: bool) -> Self {
+        self.config.line_number = yes;
+        self
+    }
+
+    pub fn column(mut self, yes: bool) -> Self {
+        self.config.column = yes;
+        self
+    }
+
+    pub fn byte_offset(mut self, yes: bool) -> Self {
+        self.config.byte_offset = yes;
+        self
+    }
+
+    pub fn heading(mut self, yes: bool) -> Self {
+        self.config.heading = Some(yes);
+        self
+    }
+
+    pub fn passthru(mut self, yes: bool) -> Self {
+        self.config.passthru = yes;
+        self
+    }
+
+    pub fn only_matching(mut self, yes: bool) -> Self {
+        self.config.only_matching = yes;
+        self
+    }
+
+    pub fn null(mut self, yes: bool) -> Self {
+        self.config.null = yes;
+        self
+    }
+
+    pub fn color(mut self, color: ColorChoice) -> Self {
+        self.config.color = color;
+        self
+    }
+
+    /// Changes one part of the color theme. Later specs win.
+    pub fn colors(mut self, spec: ColorSpec) -> Self {
+        self.config.theme.apply(&spec);
+        self
+    }
+
+    pub fn output(mut self, output: OutputMode) -> Self {
+        self.config.output = output;
+        self
+    }
+
+    pub fn hidden(mut self, yes: bool) -> Self {
+        self.config.walk.hidden = yes;
+        self
+    }
+
+    pub fn no_ignore(mut self, yes: bool) -> Self {
+        self.config.walk.no_ignore = yes;
+        self
+    }
+
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.config.walk.max_depth = Some(depth);
+        self
+    }
+
+    pub fn follow(mut self, yes: bool) -> Self {
+        self.config.walk.follow = yes;
+        self
+    }
+
+    /// Only search files of the type `name` in directories, e.g. `rust`.
+    pub fn file_type(mut self, name: impl Into<String>) -> Self {
+        self.config.walk.types.select(name);
+        self
+    }
+
+    /// Skip files of the type `name` in directories.
+    pub fn file_type_not(mut self, name: impl Into<String>) -> Self {
+        self.config.walk.types.negate(name);
+        self
+    }
+
+    /// Only search files in directories whose paths match `glob`.
+    pub fn include(mut self, glob: impl Into<String>) -> Self {
+        self.config.walk.globs.include(glob);
+        self
+    }
+
+    /// Skip paths in directories that match `glob`.
+    pub fn exclude(mut self, glob: impl Into<String>) -> Self {
+        self.config.walk.globs.exclude(glob);
+        self
+    }
+
+    /// Defines or extends a file type, as `name:glob`.
+    pub fn file_type_add(mut self, definition: impl Into<String>) -> Self {
+        self.config.walk.types.add(definition);
+        self
+    }
+
+    pub fn threads(mut self, jobs: usize) -> Self {
+        self.config.jobs = jobs;
+        self
+    }
+
+    pub fn mmap(mut self, mmap: MmapChoice) -> Self {
+        self.config.read.mmap = mmap;
+        self
+    }
+
+    pub fn search_zip(mut self, yes: bool) -> Self {
+        self.config.read.search_zip = yes;
+        self
+    }
+
+    pub fn encoding(mut self, encoding: TextEncoding) -> Self {
+        self.config.read.encoding = Some(encoding);
+        self
+    }
+
+    /// Searches the output of `command`, run with each file's path, instead
+    /// of the file.
+    #[cfg(feature = "cli")]
+    pub fn pre(mut self, command: impl Into<String>) -> Self {
+        self.config.read.pre = Some(Preprocessor::new(command));
+        self
+    }
+
+    /// Only runs the `--pre` command on files matching `glob`.
+    #[cfg(feature = "cli")]
+    pub fn pre_glob(mut self, glob: impl Into<String>) -> Self {
+        self.pre_globs.include(glob);
+        self
+    }
+
+    #[cfg(feature = "cli")]
+    pub fn pre_timeout(mut self, timeout: Duration) -> Self {
+        self.pre_timeout = Some(timeout);
+        self
+    }
+
+    pub fn max_columns(mut self, max: usize) -> Self {
+        self.config.max_columns = Some(max);
+        self
+    }
+
+    pub fn max_count(mut self, max: usize) -> Self {
+        self.config.max_count = Some(max);
+        self
+    }
+
+    pub fn max_total(mut self, max: usize) -> Self {
+        self.config.max_total = Some(max);
+        self
+    }
+
+    pub fn max_filesize(mut self, bytes: u64) -> Self {
+        self.config.max_filesize = Some(bytes);
+        self
+    }
+
+    pub fn max_files(mut self, max: usize) -> Self {
+        self.config.max_files = Some(max);
+        self
+    }
+
+    #[cfg(feature = "cli")]
+    pub fn changed(mut self, yes: bool) -> Self {
+        self.config.changed = yes;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = Some(timeout);
+        self
+    }
+
+    pub fn replace(mut self, replacement: impl Into<String>) -> Self {
+        self.config.replace = Some(replacement.into());
+        self
+    }
+
+    pub fn crlf(mut self, yes: bool) -> Self {
+        self.config.crlf = yes;
+        self
+    }
+
+    pub fn write(mut self, yes: bool) -> Self {
+        self.config.write = yes;
+        self
+    }
+
+    pub fn backup(mut self, yes: bool) -> Self {
+        self.config.backup = yes;
+        self
+    }
+
+    pub fn quiet(mut self, yes: bool) -> Self {
+        self.config.quiet = yes;
+        self
+    }
+
+    pub fn field_separator(mut self, separator: impl Into<String>) -> Self {
+        self.config.field_separator = Some(separator.into());
+        self
+    }
+
+    pub fn path_separator(mut self, separator: char) -> Self {
+        self.config.path_separator = Some(separator);
+        self
+    }
+
+    pub fn count_matches(mut self, yes: bool) -> Self {
+        self.config.count_matches = yes;
+        self
+    }
+
+    pub fn stats(mut self, yes: bool) -> Self {
+        self.config.stats = yes;
+        self
+    }
+
+    /// Searches the files listed in `list`, or in standard input if it's
+    /// `-`, as well as any paths.
+    pub fn files_from(mut self, list: impl Into<String>) -> Self {
+        self.config.files_from = Some(list.into());
+        self
+    }
+
+    pub fn verbose(mut self, level: Level) -> Self {
+        self.config.verbose = Some(level);
+        self
+    }
+
+    pub fn debug(mut self, yes: bool) -> Self {
+        self.config.debug = yes;
+        if yes {
+            self.config.verbose = Some(Level::Debug);
+        }
+        self
+    }
+
+    pub fn format(mut self, format: Format) -> Self {
+        self.config.format = format;
+        self
+    }
+
+    pub fn sort(mut self, by: SortBy) -> Self {
+        self.config.sort = Some(by);
+        self
+    }
+
+    pub fn watch(mut self, yes: bool) -> Self {
+        self.config.watch = yes;
+        self
+    }
+
+    /// Checks that the options make sense together.
+    pub fn build(self) -> Result<Config, MinigrepError> {
+        let mut config = self.config;
+        if config.patterns.is_empty() {
+            return Err("Didn't get a query string".into());
+        }
+        if config.jobs == 0 {
+            return Err("Need at least one thread".into());
+        }
+        if config.write && config.replace.is_none() {
+            return Err("--write needs --replace".into());
+        }
+        if config.backup && !config.write {
+            return Err("--backup needs --write".into());
+        }
+        if config.crlf && config.replace.is_none() {
+            return Err("--crlf needs --replace".into());
+        }
+        if config.write && config.read.transforms() {
+            return Err("--write can't be combined with -z, --encoding or --pre".into());
+        }
+        #[cfg(feature = "cli")]
+        {
+            let mut pre_globs = self.pre_globs;
+            pre_globs.compile()?;
+            match &mut config.read.pre {
+                Some(pre) => {
+                    pre.globs = pre_globs;
+                    pre.timeout = self.pre_timeout.unwrap_or(pre.timeout);
+                }
+                None if self.pre_timeout.is_some() || !pre_globs.is_empty() => {
+                    return Err("--pre-glob and --pre-timeout need --pre".into());
+                }
+                None => {}
+            }
+        }
+        if config.count_matches {
+            match config.output {
+                OutputMode::Lines => config.output = OutputMode::Count,
+                OutputMode::FilesWithMatches => {
+                    return Err("--count-matches can't be combined with -l".into());
+                }
+                OutputMode::Count | OutputMode::Histogram => {}
+            }
+        }
+        if config.watch && (config.output == OutputMode::Histogram || config.write) {
+            return Err("--watch can't be combined with --histogram or --write".into());
+        }
+        if config.watch && config.timeout.is_some() {
+            return Err("--watch runs until interrupted, so it can't have a --timeout".into());
+        }
+        if config.format != Format::Text && (config.output != OutputMode::Lines || config.write) {
+            return Err(
+                "--format json, ndjson and csv can't be combined with -c, -l or --write".into(),
+            );
+        }
+        config.walk.types.compile()?;
+        config.walk.globs.compile()?;
+        if config.filenames.is_empty() && config.files_from.is_none() {
+            config.filenames.push(String::from(input::STDIN));
+        }
+        Ok(config)
+    }
+}
+
+fn next_value(
+    args: &mut impl Iterator<Item = String>,
+    missing: &'static str,
+) -> Result<String, &'static str> {
+    args.next().ok_or(missing)
+}
+
+fn next_number(
+    args: &mut impl Iterator<Item = String>,
+    invalid: &'static str,
+) -> Result<usize, &'static str> {
+    match args.next().map(|n| n.parse()) {
+        Some(Ok(n)) if n > 0 => Ok(n),
+        _ => Err(invalid),
+    }
+}
+
+/// Parses a size in bytes with an optional `K`, `M` or `G` suffix, each
+/// 1024 times the last.
+fn parse_size(size: &str) -> Result<u64, &'static str> {
+    const INVALID: &str = "--max-filesize needs a size like 10M";
+    let (digits, shift) = match size.char_indices().last() {
+        Some((i, 'K' | 'k')) => (&size[..i], 10),
+        Some((i, 'M' | 'm')) => (&size[..i], 20),
+        Some((i, 'G' | 'g')) => (&size[..i], 30),
+        _ => (size, 0),
+    };
+    let n: u64 = digits.parse().map_err(|_| INVALID)?;
+    n.checked_mul(1 << shift).ok_or(INVALID)
+}
+
+/// Parses a duration in seconds, with an optional `ms`, `s` or `m` suffix.
+fn parse_duration(duration: &str) -> Result<Duration, &'static str> {
+    const INVALID: &str = "--timeout needs a duration like 5s";
+    let (digits, unit) = if let Some(digits) = duration.strip_suffix("ms") {
+        (digits, Duration::from_millis(1))
+    } else if let Some(digits) = duration.strip_suffix('s') {
+        (digits, Duration::from_secs(1))
+    } else if let Some(digits) = duration.strip_suffix('m') {
+        (digits, Duration::from_secs(60))
+    } else {
+        (duration, Duration::from_secs(1))
+    };
+    let n: u32 = digits.parse().map_err(|_| INVALID)?;
+    if n == 0 {
+        return Err("--timeout must be longer than zero");
+    }
+    unit.checked_mul(n).ok_or(INVALID)
+}
+
+fn set_output(current: OutputMode, mode: OutputMode) -> Result<OutputMode, &'static str> {
+    if current != OutputMode::Lines && current != mode {
+        return Err("-c, -l and --histogram can't be used together");
+    }
+    Ok(mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+    use crate::{search_with, Match};
+    use std::path::Path;
+
+    fn lines<'a>(matches: Vec<Match<'a>>) -> Vec<&'a str> {
+        matches.into_iter().map(|m| m.line).collect()
+    }
+
+    fn args(args: &[&str]) -> std::vec::IntoIter<String> {
+        let mut all = vec![String::from("minigrep")];
+        all.extend(args.iter().map(|arg| arg.to_string()));
+        all.into_iter()
+    }
+
+    #[test]
+    fn case_sensitive_by_default() {
+        let config = Config::parse(args(&["duct", "poem.txt"]), false).unwrap();
+        assert!(!config.ignore_case);
+    }
+
+    #[test]
+    fn env_var_enables_ignore_case() {
+        let config = Config::parse(args(&["duct", "poem.txt"]), true).unwrap();
+        assert!(config.ignore_case);
+    }
+
+    #[test]
+    fn flag_enables_ignore_case() {
+        let config = Config::parse(args(&["-i", "duct", "poem.txt"]), false).unwrap();
+        assert!(config.ignore_case);
+        assert_eq!(config.patterns, vec!["duct"]);
+        assert_eq!(config.filenames, vec!["poem.txt"]);
+    }
+
+    #[test]
+    fn line_number_flags() {
+        let config = Config::parse(args(&["-n", "--column", "duct", "poem.txt"]), false).unwrap();
+        assert!(config.line_number);
+        assert!(config.column);
+    }
+
+    #[test]
+    fn color_flag() {
+        let config = Config::parse(args(&["--color=never", "duct", "poem.txt"]), false).unwrap();
+        assert_eq!(ColorChoice::Never, config.color);
+        assert!(Config::parse(args(&["--color=rainbow", "duct", "poem.txt"]), false).is_err());
+        let config =
+            Config::parse(args(&["--color", "always", "duct", "poem.txt"]), false).unwrap();
+        assert_eq!(ColorChoice::Always, config.color);
+        assert!(Config::parse(args(&["duct", "poem.txt", "--color"]), false).is_err());
+
+        let config = Config::parse(
+            args(&[
+                "--colors",
+                "match:fg:green",
+                "--colors",
+                "match:fg:blue",
+                "duct",
+                "poem.txt",
+            ]),
+            false,
+        )
+        .unwrap();
+        assert_eq!(Some(Color::Blue), config.theme.matched.fg);
+        assert!(Config::parse(args(&["--colors", "match:fg", "duct", "poem.txt"]), false).is_err());
+    }
+
+    #[test]
+    fn accepts_multiple_files() {
+        let config = Config::parse(args(&["duct", "a.txt", "-n", "b.txt"]), false).unwrap();
+        assert_eq!(config.filenames, vec!["a.txt", "b.txt"]);
+        assert!(config.line_number);
+    }
+
+    #[test]
+    fn rejects_unknown_options() {
+        let err = Config::parse(args(&["--colr=never", "hello", "a.txt"]), false).unwrap_err();
+        assert_eq!("unknown option --colr=never", err.to_string());
+        assert!(Config::parse(args(&["-x", "hello", "a.txt"]), false).is_err());
+        assert!(Config::parse(args(&["hello", "a.txt", "--nope"]), false).is_err());
+    }
+
+    #[test]
+    fn dash_is_standard_input() {
+        let config = Config::parse(args(&["hello", "a.txt", "-"]), false).unwrap();
+        assert_eq!(config.filenames, vec!["a.txt", "-"]);
+    }
+
+    #[test]
+    fn double_dash_ends_the_flags() {
+        let config = Config::parse(args(&["-n", "--", "--colr", "-x", "-"]), false).unwrap();
+        assert_eq!(config.patterns, vec!["--colr"]);
+        assert_eq!(config.filenames, vec!["-x", "-"]);
+        assert!(config.line_number);
+        let config = Config::parse(args(&["-e", "a", "--", "-i"]), false).unwrap();
+        assert_eq!(config.filenames, vec!["-i"]);
+        assert!(!config.ignore_case);
+    }
+
+    #[test]
+    fn defaults_to_stdin() {
+        let config = Config::parse(args(&["duct"]), false).unwrap();
+        assert_eq!(config.filenames, vec!["-"]);
+    }
+
+    #[test]
+    fn invert_match() {
+        let config = Config::parse(args(&["-v", "-i", "rUsT", "poem.txt"]), false).unwrap();
+        let contents = "\
+Rust:
+safe, fast, productive.
+Trust me.";
+
+        assert_eq!(
+            vec!["safe, fast, productive."],
+            lines(search_with(&config.matcher().unwrap(), contents))
+        );
+    }
+
+    #[test]
+    fn count_and_files_with_matches_modes() {
+        let config = Config::parse(args(&["-c", "duct", "poem.txt"]), false).unwrap();
+        assert_eq!(OutputMode::Count, config.output);
+        let config = Config::parse(args(&["-l", "duct", "poem.txt"]), false).unwrap();
+        assert_eq!(OutputMode::FilesWithMatches, config.output);
+        assert!(Config::parse(args(&["-c", "-l", "duct", "poem.txt"]), false).is_err());
+    }
+
+    #[test]
+    fn count_matches_and_histogram_flags() {
+        let config = Config::parse(args(&["--count-matches", "o", "poem.txt"]), false).unwrap();
+        assert_eq!(OutputMode::Count, config.output);
+        assert!(config.count_matches);
+        let config = Config::parse(
+            args(&["--histogram", "--count-matches", "o", "poem.txt"]),
+            false,
+        )
+        .unwrap();
+        assert_eq!(OutputMode::Histogram, config.output);
+        assert!(config.count_matches);
+        assert!(Config::parse(args(&["--histogram", "-c", "o", "poem.txt"]), false).is_err());
+        assert!(Config::parse(args(&["--count-matches", "-l", "o", "poem.txt"]), false).is_err());
+    }
+
+    #[test]
+    fn walk_flags() {
+        let config = Config::parse(args(&["--hidden", "--no-ignore", "duct", "."]), false).unwrap();
+        assert!(config.walk.hidden);
+        assert!(config.walk.no_ignore);
+        assert!(!config.walk.follow);
+
+        let config = Config::parse(args(&["--max-depth", "2", "-L", "duct", "."]), false).unwrap();
+        assert_eq!(Some(2), config.walk.max_depth);
+        assert!(config.walk.follow);
+        let config = Config::parse(args(&["--follow", "--no-follow", "duct", "."]), false).unwrap();
+        assert!(!config.walk.follow);
+        assert!(Config::parse(args(&["--max-depth", "0", "duct", "."]), false).is_err());
+    }
+
+    #[test]
+    fn type_flags() {
+        let config = Config::parse(
+            args(&[
+                "-t",
+                "poems",
+                "--type-add",
+                "poems:*.poem",
+                "-T",
+                "md",
+                "duct",
+                ".",
+            ]),
+            false,
+        )
+        .unwrap();
+        assert!(config.walk.types.allows(Path::new("frog.poem")));
+        assert!(!config.walk.types.allows(Path::new("poem.txt")));
+        assert!(Config::parse(args(&["--type", "nope", "duct", "."]), false).is_err());
+    }
+
+    #[test]
+    fn byte_offset_and_only_matching_flags() {
+        let config = Config::parse(args(&["-b", "-o", "duct", "poem.txt"]), false).unwrap();
+        assert!(config.byte_offset);
+        assert!(config.only_matching);
+    }
+
+    #[test]
+    fn heading_flags() {
+        let config = Config::parse(args(&["duct", "poem.txt"]), false).unwrap();
+        assert_eq!(None, config.heading);
+        let config = Config::parse(args(&["--heading", "duct", "poem.txt"]), false).unwrap();
+        assert_eq!(Some(true), config.heading);
+        let config = Config::parse(
+            args(&["--heading", "--no-heading", "duct", "poem.txt"]),
+            false,
+        )
+        .unwrap();
+        assert_eq!(Some(false), config.heading);
+    }
+
+    #[test]
+    fn passthru_flag() {
+        let config = Config::parse(args(&["--passthru", "duct", "poem.txt"]), false).unwrap();
+        assert!(config.passthru);
+    }
+
+    #[test]
+    fn null_flag() {
+        let config = Config::parse(args(&["-0", "-l", "duct", "poem.txt"]), false).unwrap();
+        assert!(config.null);
+    }
+
+    #[test]
+    fn command_line_overrides_config_file() {
+        let config = Config::parse(
+            args(&[
+                "--color=always",
+                "-j",
+                "4",
+                "--exclude",
+                "target",
+                "--color=never",
+                "-j",
+                "2",
+                "--exclude",
+                "build",
+                "duct",
+                ".",
+            ]),
+            false,
+        )
+        .unwrap();
+        assert_eq!(ColorChoice::Never, config.color);
+        assert_eq!(2, config.jobs);
+        assert!(config.walk.globs.excludes(Path::new("target")));
+        assert!(config.walk.globs.excludes(Path::new("build")));
+    }
+
+    fn parse_with_config(defaults: &[&str], cli: &[&str]) -> Result<Config, MinigrepError> {
+        let defaults = defaults.iter().map(|arg| arg.to_string()).collect();
+        Config::parse_with_defaults(defaults, args(cli), false)
+    }
+
+    #[test]
+    fn command_line_output_mode_replaces_the_config_files() {
+        let config = parse_with_config(&["--count"], &["-l", "foo", "f"]).unwrap();
+        assert_eq!(OutputMode::FilesWithMatches, config.output);
+        let config = parse_with_config(&["--count-matches"], &["-l", "foo", "f"]).unwrap();
+        assert_eq!(OutputMode::FilesWithMatches, config.output);
+        assert!(!config.count_matches);
+        let config = parse_with_config(&["--histogram"], &["--count-matches", "foo", "f"]).unwrap();
+        assert_eq!(OutputMode::Count, config.output);
+        assert!(config.count_matches);
+
+        let config = parse_with_config(&["--count"], &["foo", "f"]).unwrap();
+        assert_eq!(OutputMode::Count, config.output);
+        // Clashes within the command line itself are still errors.
+        assert!(parse_with_config(&["--count"], &["-l", "-c", "foo", "f"]).is_err());
+        assert!(parse_with_config(&["--count", "--histogram"], &["foo", "f"]).is_err());
+    }
+
+    #[test]
+    fn command_line_values_replace_the_config_files() {
+        let config = parse_with_config(
+            &["--color=never", "--threads", "4", "--max-count", "9"],
+            &["--color=always", "-j", "2", "foo", "f"],
+        )
+        .unwrap();
+        assert_eq!(ColorChoice::Always, config.color);
+        assert_eq!(2, config.jobs);
+        assert_eq!(Some(9), config.max_count);
+        assert_eq!(config.patterns, vec!["foo"]);
+        assert_eq!(config.filenames, vec!["f"]);
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn long_options_are_all_known() {
+        for &(name, takes_value) in LONG_OPTIONS {
+            let flag = format!("--{name}");
+            let mut cli = vec![flag.as_str()];
+            if takes_value {
+                cli.push("1");
+            }
+            cli.extend(["foo", "f"]);
+            if let Err(e) = Config::parse(args(&cli), false) {
+                assert!(!e.to_string().starts_with("unknown option"), "{name}: {e}");
+            }
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn pre_flags() {
+        let config = Config::parse(
+            args(&[
+                "--pre-glob",
+                "*.pdf",
+                "--pre",
+                "pdftotext",
+                "--pre-timeout",
+                "5",
+                "duct",
+                ".",
+            ]),
+            false,
+        )
+        .unwrap();
+        let pre = config.read.pre.unwrap();
+        assert_eq!("pdftotext", pre.command);
+        assert_eq!(Duration::from_secs(5), pre.timeout);
+        assert!(pre.applies(Path::new("manual.pdf")));
+        assert!(!pre.applies(Path::new("poem.txt")));
+        assert!(Config::parse(args(&["--pre-glob", "*.pdf", "duct", "."]), false).is_err());
+    }
+
+    #[test]
+    fn files_from_flag() {
+        let config = Config::parse(args(&["--files-from", "-", "duct"]), false).unwrap();
+        assert_eq!(Some(String::from("-")), config.files_from);
+        assert!(config.filenames.is_empty());
+    }
+
+    #[test]
+    fn sort_flag() {
+        let config = Config::parse(args(&["--sort", "size", "duct", "."]), false).unwrap();
+        assert_eq!(Some(SortBy::Size), config.sort);
+        assert!(Config::parse(args(&["--sort", "name", "duct", "."]), false).is_err());
+    }
+
+    #[test]
+    fn watch_flag() {
+        let config = Config::parse(args(&["--watch", "duct", "poem.txt"]), false).unwrap();
+        assert!(config.watch);
+        assert!(Config::parse(args(&["--watch", "-c", "duct", "poem.txt"]), false).is_ok());
+        let json = ["--watch", "--format", "json", "duct", "poem.txt"];
+        assert!(Config::parse(args(&json), false).is_ok());
+        let histogram = ["--watch", "--histogram", "duct", "poem.txt"];
+        assert!(Config::parse(args(&histogram), false).is_err());
+    }
+
+    #[test]
+    fn quiet_and_stats_flags() {
+        let config = Config::parse(args(&["-q", "--stats", "duct", "poem.txt"]), false).unwrap();
+        assert!(config.quiet);
+        assert!(config.stats);
+    }
+
+    #[test]
+    fn include_and_exclude_flags() {
+        let config = Config::parse(
+            args(&["--include", "*.toml", "--exclude", "target/*", "duct", "."]),
+            false,
+        )
+        .unwrap();
+        assert!(config.walk.globs.includes(Path::new("Cargo.toml")));
+        assert!(config.walk.globs.excludes(Path::new("target/debug")));
+        assert!(Config::parse(args(&["--include", "[", "duct", "."]), false).is_err());
+    }
+
+    #[test]
+    fn threads_flag() {
+        let config = Config::parse(args(&["-j", "3", "duct", "."]), false).unwrap();
+        assert_eq!(3, config.jobs);
+        assert!(Config::parse(args(&["-j", "many", "duct", "."]), false).is_err());
+        assert!(Config::parse(args(&["-j", "0", "duct", "."]), false).is_err());
+    }
+
+    #[test]
+    fn mmap_flags() {
+        let config = Config::parse(args(&["duct", "."]), false).unwrap();
+        assert_eq!(MmapChoice::Auto, config.read.mmap);
+        let config = Config::parse(args(&["--mmap", "duct", "."]), false).unwrap();
+        assert_eq!(MmapChoice::Always, config.read.mmap);
+        let config = Config::parse(args(&["--no-mmap", "-z", "duct", "."]), false).unwrap();
+        assert_eq!(MmapChoice::Never, config.read.mmap);
+        assert!(config.read.search_zip);
+    }
+
+    #[test]
+    fn encoding_flag() {
+        let config = Config::parse(args(&["--encoding", "utf-16le", "duct", "."]), false).unwrap();
+        assert_eq!(Some(TextEncoding::Utf16Le), config.read.encoding);
+        assert!(Config::parse(args(&["--encoding", "klingon", "duct", "."]), false).is_err());
+        assert!(Config::parse(args(&["duct", ".", "--encoding"]), false).is_err());
+    }
+
+    #[test]
+    fn regex_flag() {
+        let config = Config::parse(args(&["--regex", "-i", r"^p\w+", "."]), false).unwrap();
+        let contents = "\
+Rust:
+safe, fast, productive.
+Pick three.";
+
+        assert_eq!(
+            vec!["Pick three."],
+            lines(search_with(&config.matcher().unwrap(), contents))
+        );
+        let config = Config::parse(args(&["--regex", "(", "."]), false).unwrap();
+        assert!(config.matcher().is_err());
+    }
+
+    #[test]
+    fn replace_flags() {
+        let config = Config::parse(
+            args(&["--replace", "x", "--write", "--backup", "a", "."]),
+            false,
+        )
+        .unwrap();
+        assert_eq!(Some(String::from("x")), config.replace);
+        assert!(config.write);
+        assert!(config.backup);
+        assert!(Config::parse(args(&["--write", "a", "."]), false).is_err());
+        assert!(Config::parse(args(&["--replace", "x", "--backup", "a", "."]), false).is_err());
+        assert!(
+            Config::parse(args(&["--replace", "x", "--write", "-z", "a", "."]), false).is_err()
+        );
+        let encoded = [
+            "--replace",
+            "x",
+            "--write",
+            "--encoding",
+            "latin1",
+            "a",
+            ".",
+        ];
+        assert!(Config::parse(args(&encoded), false).is_err());
+    }
+
+    #[test]
+    fn multiple_patterns() {
+        let config = Config::parse(args(&["-e", "fast", "-e", "three", "a.txt"]), false).unwrap();
+        assert_eq!(config.patterns, vec!["fast", "three"]);
+        assert_eq!(config.filenames, vec!["a.txt"]);
+
+        let contents = "\
+Rust:
+safe, fast, productive.
+Pick three.";
+        assert_eq!(
+            vec!["safe, fast, productive.", "Pick three."],
+            lines(search_with(&config.matcher().unwrap(), contents))
+        );
+    }
+
+    #[test]
+    fn pattern_file() {
+        let path = std::env::temp_dir().join(format!("minigrep-patterns-{}", std::process::id()));
+        fs::write(&path, "Rust\nthree\n").unwrap();
+        let config =
+            Config::parse(args(&["-f", path.to_str().unwrap(), "-e", "fast"]), false).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.patterns, vec!["Rust", "three", "fast"]);
+        assert_eq!(config.filenames, vec!["-"]);
+        assert!(Config::parse(args(&["-f", "/no/such/patterns"]), false).is_err());
+    }
+
+    #[test]
+    fn max_count_flags() {
+        let config =
+            Config::parse(args(&["-m", "2", "--max-total", "5", "a", "."]), false).unwrap();
+        assert_eq!(Some(2), config.max_count);
+        assert_eq!(Some(5), config.max_total);
+        let config = Config::parse(args(&["-m", "0", "a", "."]), false).unwrap();
+        assert_eq!(Some(0), config.max_count);
+        assert!(Config::parse(args(&["-m", "-1", "a", "."]), false).is_err());
+    }
+
+    #[test]
+    fn builder_matches_parsed_args() {
+        let built = Config::builder()
+            .query("duct")
+            .path("src")
+            .case_insensitive(true)
+            .line_number(true)
+            .threads(2)
+            .build()
+            .unwrap();
+        let parsed = Config::parse(args(&["-i", "-n", "-j", "2", "duct", "src"]), false).unwrap();
+
+        assert_eq!(format!("{parsed:?}"), format!("{built:?}"));
+    }
+
+    #[test]
+    fn builder_validates() {
+        assert!(Config::builder().path("src").build().is_err());
+        assert!(Config::builder().query("a").threads(0).build().is_err());
+        assert!(Config::builder().query("a").write(true).build().is_err());
+
+        let config = Config::builder().query("a").build().unwrap();
+        assert_eq!(config.filenames, vec!["-"]);
+    }
+
+    #[test]
+    fn size_limits() {
+        let config = Config::parse(
+            args(&["--max-filesize", "10M", "--max-files", "3", "a", "."]),
+            false,
+        )
+        .unwrap();
+        assert_eq!(Some(10 * 1024 * 1024), config.max_filesize);
+        assert_eq!(Some(3), config.max_files);
+    }
+
+    /// Without the `cli` feature the engine never runs other programs, so the
+    /// flags that would are unknown.
+    #[cfg(not(feature = "cli"))]
+    #[test]
+    fn engine_alone_runs_no_commands() {
+        for flag in ["--pre", "--pre-glob", "--pre-timeout", "--changed"] {
+            let e = Config::parse(args(&[flag, "x", "duct", "."]), false).unwrap_err();
+            assert_eq!(format!("unknown option {flag}"), e.to_string());
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn changed_flag() {
+        let config = Config::parse(args(&["--changed", "a", "."]), false).unwrap();
+        assert!(config.changed);
+
+        assert_eq!(Ok(512), parse_size("512"));
+        assert_eq!(Ok(2048), parse_size("2k"));
+        assert_eq!(Ok(1 << 30), parse_size("1G"));
+        assert!(parse_size("ten").is_err());
+        assert!(parse_size("M").is_err());
+        assert!(parse_size("99999999999G").is_err());
+    }
+
+    #[test]
+    fn timeout_flag() {
+        let config = Config::parse(args(&["--timeout", "5s", "a", "."]), false).unwrap();
+        assert_eq!(Some(Duration::from_secs(5)), config.timeout);
+        assert!(Config::parse(args(&["--timeout", "5s", "--watch", "a", "."]), false).is_err());
+
+        assert_eq!(Ok(Duration::from_millis(250)), parse_duration("250ms"));
+        assert_eq!(Ok(Duration::from_secs(120)), parse_duration("2m"));
+        assert_eq!(Ok(Duration::from_secs(3)), parse_duration("3"));
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("-1s").is_err());
+        assert_eq!(
+            Err("--timeout must be longer than zero"),
+            parse_duration("0")
+        );
+        assert!(parse_duration("0ms").is_err());
+        assert!(Config::parse(args(&["--timeout", "0s", "a", "."]), false).is_err());
+    }
+
+    #[test]
+    fn format_flags() {
+        let config = Config::parse(args(&["duct", "poem.txt"]), false).unwrap();
+        assert_eq!(Format::Text, config.format);
+        let config = Config::parse(args(&["--format", "csv", "duct", "poem.txt"]), false).unwrap();
+        assert_eq!(Format::Csv, config.format);
+        let config = Config::parse(args(&["--json", "duct", "poem.txt"]), false).unwrap();
+        assert_eq!(Format::Json, config.format);
+        let config =
+            Config::parse(args(&["--output", "ndjson", "duct", "poem.txt"]), false).unwrap();
+        assert_eq!(Format::Ndjson, config.format);
+        assert!(Config::parse(args(&["--format", "xml", "duct", "poem.txt"]), false).is_err());
+        assert!(Config::parse(args(&["--json", "-c", "duct", "poem.txt"]), false).is_err());
+    }
+
+    #[test]
+    fn verbose_flags() {
+        let config = Config::parse(args(&["duct", "poem.txt"]), false).unwrap();
+        assert_eq!(None, config.verbose);
+        let config = Config::parse(args(&["--verbose", "duct", "poem.txt"]), false).unwrap();
+        assert_eq!(Some(Level::Info), config.verbose);
+        let config = Config::parse(args(&["-vv", "duct", "poem.txt"]), false).unwrap();
+        assert_eq!(Some(Level::Debug), config.verbose);
+        assert!(!config.invert_match);
+        let config = Config::parse(args(&["--debug", "duct", "poem.txt"]), false).unwrap();
+        assert!(config.debug);
+        assert_eq!(Some(Level::Debug), config.verbose);
+    }
+
+    #[test]
+    fn crlf_needs_replace() {
+        let config = Config::parse(args(&["--replace", "x", "--crlf", "a", "."]), false).unwrap();
+        assert!(config.crlf);
+        assert!(Config::parse(args(&["--crlf", "a", "."]), false).is_err());
+    }
+
+    #[test]
+    fn separator_flags() {
+        let config = Config::parse(
+            args(&[
+                "--field-separator",
+                "\t",
+                "--path-separator",
+                "\\",
+                "a",
+                ".",
+            ]),
+            false,
+        )
+        .unwrap();
+        assert_eq!(Some("\t"), config.field_separator.as_deref());
+        assert_eq!(Some('\\'), config.path_separator);
+        assert!(Config::parse(args(&["--path-separator", "::", "a", "."]), false).is_err());
+    }
+}
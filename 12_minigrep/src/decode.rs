@@ -0,0 +1,155 @@
+use std::io::Read;
+use std::path::Path;
+
+use encoding_rs_io::DecodeReaderBytesBuilder;
+use flate2::read::MultiGzDecoder;
+
+/// A compression format that `-z` can see through.
+///
+/// Supporting another format means adding a variant, its extensions, and how
+/// to wrap a reader in its decoder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+}
+
+impl Compression {
+    const ALL: [Compression; 1] = [Compression::Gzip];
+
+    fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Compression::Gzip => &["gz", "tgz"],
+        }
+    }
+
+    /// Picks the format to decode `path` with from its extension.
+    pub fn detect(path: &Path) -> Option<Compression> {
+        let extension = path.extension()?.to_str()?;
+        Compression::ALL
+            .into_iter()
+            .find(|format| format.extensions().contains(&extension))
+    }
+
+    /// Wraps `reader` so that reading from it yields decompressed bytes.
+    pub fn decoder<'a>(self, reader: impl Read + 'a) -> Box<dyn Read + 'a> {
+        match self {
+            Compression::Gzip => Box::new(MultiGzDecoder::new(reader)),
+        }
+    }
+}
+
+/// The text encoding to convert input from before matching (`--encoding`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// Sniff a UTF-8 or UTF-16 byte order mark, assuming UTF-8 without one.
+    Auto,
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl TextEncoding {
+    pub fn parse(value: &str) -> Result<Self, &'static str> {
+        match value.to_ascii_lowercase().as_str() {
+            "auto" => Ok(TextEncoding::Auto),
+            "utf-8" | "utf8" => Ok(TextEncoding::Utf8),
+            "utf-16le" | "utf16le" => Ok(TextEncoding::Utf16Le),
+            "utf-16be" | "utf16be" => Ok(TextEncoding::Utf16Be),
+            "latin1" | "latin-1" | "iso-8859-1" => Ok(TextEncoding::Latin1),
+            _ => Err("--encoding must be one of auto, utf-8, utf-16le, utf-16be or latin1"),
+        }
+    }
+
+    fn encoding(self) -> Option<&'static encoding_rs::Encoding> {
+        match self {
+            TextEncoding::Auto => None,
+            TextEncoding::Utf8 => Some(encoding_rs::UTF_8),
+            TextEncoding::Utf16Le => Some(encoding_rs::UTF_16LE),
+            TextEncoding::Utf16Be => Some(encoding_rs::UTF_16BE),
+            // Latin-1 is decoded as its superset, Windows-1252, per WHATWG.
+            TextEncoding::Latin1 => Some(encoding_rs::WINDOWS_1252),
+        }
+    }
+
+    /// Wraps `reader` so that reading from it yields UTF-8. A byte order mark
+    /// always wins over the chosen encoding, and is never passed through.
+    /// Malformed input becomes U+FFFD rather than an error.
+    pub fn decoder<'a>(self, reader: impl Read + 'a) -> Box<dyn Read + 'a> {
+        Box::new(
+            DecodeReaderBytesBuilder::new()
+                .encoding(self.encoding())
+                .bom_sniffing(true)
+                .build(reader),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    #[test]
+    fn detects_by_extension() {
+        assert_eq!(
+            Some(Compression::Gzip),
+            Compression::detect(Path::new("app.log.gz"))
+        );
+        assert_eq!(None, Compression::detect(Path::new("app.log")));
+    }
+
+    #[test]
+    fn decodes_gzip() {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"Rust:\nPick three.\n").unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut contents = String::new();
+        Compression::Gzip
+            .decoder(compressed.as_slice())
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!("Rust:\nPick three.\n", contents);
+    }
+
+    fn decode(encoding: TextEncoding, bytes: &[u8]) -> String {
+        let mut contents = String::new();
+        encoding
+            .decoder(bytes)
+            .read_to_string(&mut contents)
+            .unwrap();
+        contents
+    }
+
+    #[test]
+    fn decodes_utf16le() {
+        let bytes: Vec<u8> = "Trust me."
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        assert_eq!("Trust me.", decode(TextEncoding::Utf16Le, &bytes));
+    }
+
+    #[test]
+    fn decodes_latin1() {
+        assert_eq!("café", decode(TextEncoding::Latin1, b"caf\xe9"));
+    }
+
+    #[test]
+    fn auto_sniffs_byte_order_marks() {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend("Rust".encode_utf16().flat_map(u16::to_le_bytes));
+        assert_eq!("Rust", decode(TextEncoding::Auto, &bytes));
+        assert_eq!("Rust", decode(TextEncoding::Auto, b"\xEF\xBB\xBFRust"));
+        assert_eq!("Rust", decode(TextEncoding::Auto, b"Rust"));
+    }
+
+    #[test]
+    fn parses_encoding_names() {
+        assert_eq!(Ok(TextEncoding::Utf16Le), TextEncoding::parse("UTF-16LE"));
+        assert_eq!(Ok(TextEncoding::Latin1), TextEncoding::parse("latin1"));
+        assert!(TextEncoding::parse("ebcdic").is_err());
+    }
+}
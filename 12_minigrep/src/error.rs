@@ -0,0 +1,99 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Everything that can go wrong while setting up or running a search.
+#[derive(Debug)]
+pub enum MinigrepError {
+    /// The command line didn't make sense.
+    BadArgs(String),
+    /// A file or directory couldn't be read or written.
+    Io { path: PathBuf, source: io::Error },
+    /// A pattern isn't a valid regular expression.
+    Regex(regex::Error),
+    /// A file wasn't valid text in the expected encoding.
+    Encoding { path: PathBuf },
+}
+
+impl MinigrepError {
+    /// Wraps an I/O error that happened on `path`. Invalid text surfaces from
+    /// the reader as an I/O error too, and is told apart here.
+    pub fn io(path: impl AsRef<Path>, source: io::Error) -> Self {
+        let path = path.as_ref().to_path_buf();
+        if source.kind() == io::ErrorKind::InvalidData {
+            MinigrepError::Encoding { path }
+        } else {
+            MinigrepError::Io { path, source }
+        }
+    }
+}
+
+impl MinigrepError {
+    /// The file the error happened on, if it was about a particular file.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            MinigrepError::Io { path, .. } | MinigrepError::Encoding { path } => Some(path),
+            MinigrepError::BadArgs(_) | MinigrepError::Regex(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for MinigrepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MinigrepError::BadArgs(message) => write!(f, "{message}"),
+            MinigrepError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            MinigrepError::Regex(e) => write!(f, "invalid pattern: {e}"),
+            MinigrepError::Encoding { path } => {
+                write!(f, "{}: not valid UTF-8, try --encoding", path.display())
+            }
+        }
+    }
+}
+
+impl Error for MinigrepError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MinigrepError::Io { source, .. } => Some(source),
+            MinigrepError::Regex(e) => Some(e),
+            MinigrepError::BadArgs(_) | MinigrepError::Encoding { .. } => None,
+        }
+    }
+}
+
+impl From<&str> for MinigrepError {
+    fn from(message: &str) -> Self {
+        MinigrepError::BadArgs(message.to_string())
+    }
+}
+
+impl From<regex::Error> for MinigrepError {
+    fn from(e: regex::Error) -> Self {
+        MinigrepError::Regex(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_data_is_an_encoding_error() {
+        let e = MinigrepError::io("log.txt", io::Error::from(io::ErrorKind::InvalidData));
+        assert!(matches!(e, MinigrepError::Encoding { .. }));
+
+        let e = MinigrepError::io("log.txt", io::Error::from(io::ErrorKind::NotFound));
+        assert!(matches!(e, MinigrepError::Io { .. }));
+        assert!(e.source().is_some());
+    }
+
+    #[test]
+    fn displays_the_path() {
+        let e = MinigrepError::Encoding {
+            path: PathBuf::from("log.txt"),
+        };
+        assert_eq!("log.txt: not valid UTF-8, try --encoding", e.to_string());
+        assert_eq!("bad", MinigrepError::from("bad").to_string());
+    }
+}
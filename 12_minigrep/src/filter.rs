@@ -0,0 +1,216 @@
+use std::path::Path;
+
+use glob::{MatchOptions, Pattern};
+
+use crate::error::MinigrepError;
+use crate::ignore::{self, IgnoreRules};
+use crate::log;
+use crate::walk::WalkOptions;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Glob filters on the paths a recursive search visits (`--include`,
+/// `--exclude`). Globs containing a `/` match the path relative to the
+/// searched directory; the rest match the file name.
+#[derive(Debug, Clone, Default)]
+pub struct PathGlobs {
+    included: Vec<String>,
+    excluded: Vec<String>,
+    /// Filled in by `compile`.
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl PathGlobs {
+    pub(crate) fn include(&mut self, glob: impl Into<String>) {
+        self.included.push(glob.into());
+    }
+
+    pub(crate) fn exclude(&mut self, glob: impl Into<String>) {
+        self.excluded.push(glob.into());
+    }
+
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.included.is_empty() && self.excluded.is_empty()
+    }
+
+    pub(crate) fn compile(&mut self) -> Result<(), MinigrepError> {
+        self.include = compile(&self.included)?;
+        self.exclude = compile(&self.excluded)?;
+        Ok(())
+    }
+
+    /// Whether `relative` is excluded. Excluding a directory prunes
+    /// everything under it.
+    pub fn excludes(&self, relative: &Path) -> bool {
+        self.exclude.iter().any(|glob| matches(glob, relative))
+    }
+
+    /// Whether the file at `relative` passes the include globs, if any.
+    pub fn includes(&self, relative: &Path) -> bool {
+        self.include.is_empty() || self.include.iter().any(|glob| matches(glob, relative))
+    }
+}
+
+fn compile(globs: &[String]) -> Result<Vec<Pattern>, MinigrepError> {
+    globs
+        .iter()
+        .map(|glob| {
+            Pattern::new(glob)
+                .map_err(|e| MinigrepError::BadArgs(format!("{glob}: invalid glob: {e}")))
+        })
+        .collect()
+}
+
+fn matches(glob: &Pattern, relative: &Path) -> bool {
+    if glob.as_str().contains('/') {
+        glob.matches_path_with(relative, MATCH_OPTIONS)
+    } else {
+        relative
+            .file_name()
+            .is_some_and(|name| glob.matches_with(&name.to_string_lossy(), MATCH_OPTIONS))
+    }
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+/// Decides which entries a walk visits, by checking in turn: hidden names,
+/// ignore files, `--exclude`, and for files `--type` and `--include`.
+pub(crate) struct FilterChain<'a> {
+    options: &'a WalkOptions,
+    root: &'a Path,
+    /// The ignore files of the directories being visited, outermost first.
+    ignores: Vec<IgnoreRules>,
+}
+
+impl<'a> FilterChain<'a> {
+    /// Starts a chain for a walk of `root`, whose canonical form is
+    /// `real_root`.
+    pub fn new(options: &'a WalkOptions, root: &'a Path, real_root: &Path) -> Self {
+        let ignores = if options.no_ignore {
+            Vec::new()
+        } else {
+            parent_rules(real_root)
+        };
+        FilterChain {
+            options,
+            root,
+            ignores,
+        }
+    }
+
+    /// Picks up the ignore files of the directory about to be visited.
+    /// Returns whether there were any, to be passed to `leave`.
+    pub fn enter(&mut self, real_dir: &Path) -> bool {
+        if self.options.no_ignore {
+            return false;
+        }
+        let rules = IgnoreRules::load(real_dir);
+        let pushed = rules.is_some();
+        self.ignores.extend(rules);
+        pushed
+    }
+
+    pub fn leave(&mut self, pushed: bool) {
+        if pushed {
+            self.ignores.pop();
+        }
+    }
+
+    /// Whether the walk should visit `path`, whose canonical form is `real`.
+    pub fn allows(&self, path: &Path, real: &Path, is_dir: bool) -> bool {
+        match self.rejects(path, real, is_dir) {
+            Some(reason) => {
+                log::info(|| format!("skipping {}: {reason}", path.display()));
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Why `path` shouldn't be visited, if it shouldn't.
+    fn rejects(&self, path: &Path, real: &Path, is_dir: bool) -> Option<&'static str> {
+        if !self.options.hidden && is_hidden(path) {
+            return Some("hidden");
+        }
+        if ignore::is_ignored(&self.ignores, real, is_dir) {
+            return Some("ignored");
+        }
+        let relative = path.strip_prefix(self.root).unwrap_or(path);
+        if self.options.globs.excludes(relative) {
+            return Some("excluded by --exclude");
+        }
+        if is_dir {
+            None
+        } else if !self.options.types.allows(path) {
+            Some("filtered by --type")
+        } else if !self.options.globs.includes(relative) {
+            Some("not matched by --include")
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether a file from a `--files-from` list should be searched. Lists are
+/// usually already filtered, e.g. by `git ls-files`, so only the type and
+/// path globs apply.
+pub fn allows_listed(options: &WalkOptions, path: &Path) -> bool {
+    !options.globs.excludes(path) && options.types.allows(path) && options.globs.includes(path)
+}
+
+/// Loads the ignore files above `root`, up to the root of the enclosing git
+/// repository. Outside a repository only the searched tree's own files apply.
+fn parent_rules(root: &Path) -> Vec<IgnoreRules> {
+    let mut rules = Vec::new();
+    if root.join(".git").exists() {
+        return rules;
+    }
+    for dir in root.ancestors().skip(1) {
+        rules.extend(IgnoreRules::load(dir));
+        if dir.join(".git").exists() {
+            rules.reverse();
+            return rules;
+        }
+    }
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn include_and_exclude_globs() {
+        let mut globs = PathGlobs::default();
+        globs.include("*.toml");
+        globs.exclude("target/*");
+        globs.compile().unwrap();
+
+        assert!(globs.includes(Path::new("sub/Cargo.toml")));
+        assert!(!globs.includes(Path::new("src/lib.rs")));
+        assert!(globs.excludes(Path::new("target/debug")));
+        assert!(!globs.excludes(Path::new("sub/target/debug")));
+    }
+
+    #[test]
+    fn filters_listed_files() {
+        let mut options = WalkOptions::default();
+        options.types.select("rust");
+        options.types.compile().unwrap();
+        options.globs.exclude("target/*");
+        options.globs.compile().unwrap();
+
+        assert!(allows_listed(&options, Path::new("src/lib.rs")));
+        assert!(!allows_listed(&options, Path::new("README.md")));
+        assert!(!allows_listed(&options, Path::new("target/build.rs")));
+    }
+}
@@ -0,0 +1,166 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+
+/// The ignore files read from each directory, in increasing precedence.
+pub const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// One line of an ignore file.
+struct Rule {
+    pattern: Pattern,
+    negated: bool,
+    dir_only: bool,
+    /// Rules containing a `/` match against the path relative to the ignore
+    /// file; the rest match the file name at any depth.
+    anchored: bool,
+}
+
+impl Rule {
+    fn parse(line: &str) -> Option<Rule> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+        let pattern = Pattern::new(line).ok()?;
+        Some(Rule {
+            pattern,
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    fn matches(&self, relative: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        if self.anchored {
+            self.pattern.matches_path_with(relative, MATCH_OPTIONS)
+        } else {
+            relative.file_name().is_some_and(|name| {
+                self.pattern
+                    .matches_with(&name.to_string_lossy(), MATCH_OPTIONS)
+            })
+        }
+    }
+}
+
+/// The rules from the ignore files of a single directory.
+pub struct IgnoreRules {
+    base: PathBuf,
+    rules: Vec<Rule>,
+}
+
+impl IgnoreRules {
+    /// Reads the ignore files in `dir`, if it has any.
+    pub fn load(dir: &Path) -> Option<IgnoreRules> {
+        let mut rules = Vec::new();
+        for name in IGNORE_FILES {
+            if let Ok(contents) = fs::read_to_string(dir.join(name)) {
+                rules.extend(contents.lines().filter_map(Rule::parse));
+            }
+        }
+        (!rules.is_empty()).then(|| IgnoreRules {
+            base: dir.to_path_buf(),
+            rules,
+        })
+    }
+
+    #[cfg(test)]
+    fn from_str(base: &Path, contents: &str) -> IgnoreRules {
+        IgnoreRules {
+            base: base.to_path_buf(),
+            rules: contents.lines().filter_map(Rule::parse).collect(),
+        }
+    }
+
+    /// `Some(true)` if `path` is ignored, `Some(false)` if it is explicitly
+    /// whitelisted with `!`, and `None` if no rule applies.
+    fn decide(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.base).ok()?;
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(relative, is_dir))
+            .map(|rule| !rule.negated)
+    }
+}
+
+/// Whether `path` is ignored by a stack of rules ordered from the outermost
+/// directory to the innermost. Deeper ignore files take precedence.
+pub fn is_ignored(stack: &[IgnoreRules], path: &Path, is_dir: bool) -> bool {
+    stack
+        .iter()
+        .rev()
+        .find_map(|rules| rules.decide(path, is_dir))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(contents: &str) -> Vec<IgnoreRules> {
+        vec![IgnoreRules::from_str(Path::new("root"), contents)]
+    }
+
+    #[test]
+    fn unanchored_rules_match_at_any_depth() {
+        let stack = rules("*.log\n# comment\n\ntarget/");
+
+        assert!(is_ignored(&stack, Path::new("root/a/b/debug.log"), false));
+        assert!(is_ignored(&stack, Path::new("root/a/target"), true));
+        assert!(!is_ignored(&stack, Path::new("root/a/target"), false));
+        assert!(!is_ignored(&stack, Path::new("root/src/lib.rs"), false));
+    }
+
+    #[test]
+    fn anchored_rules_match_relative_to_the_ignore_file() {
+        let stack = rules("/build\ndocs/*.md");
+
+        assert!(is_ignored(&stack, Path::new("root/build"), true));
+        assert!(!is_ignored(&stack, Path::new("root/src/build"), true));
+        assert!(is_ignored(&stack, Path::new("root/docs/intro.md"), false));
+        assert!(!is_ignored(
+            &stack,
+            Path::new("root/docs/en/intro.md"),
+            false
+        ));
+    }
+
+    #[test]
+    fn later_negations_whitelist() {
+        let stack = rules("*.log\n!keep.log");
+
+        assert!(is_ignored(&stack, Path::new("root/debug.log"), false));
+        assert!(!is_ignored(&stack, Path::new("root/keep.log"), false));
+    }
+
+    #[test]
+    fn deeper_rules_take_precedence() {
+        let stack = vec![
+            IgnoreRules::from_str(Path::new("root"), "*.log"),
+            IgnoreRules::from_str(Path::new("root/logs"), "!*.log"),
+        ];
+
+        assert!(is_ignored(&stack, Path::new("root/debug.log"), false));
+        assert!(!is_ignored(&stack, Path::new("root/logs/debug.log"), false));
+    }
+}
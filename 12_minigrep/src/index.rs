@@ -0,0 +1,470 @@
+//! A persistent trigram index for repeated searches over the same tree.
+//!
+//! `minigrep index <dir>` records, for every file under `dir`, which
+//! three-byte sequences it contains. `minigrep query <pattern> [dir]` then
+//! only has to search the files containing every trigram of the pattern,
+//! plus any file that changed since the index was built.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+use crate::casefold;
+use crate::error::MinigrepError;
+use crate::walk::{self, WalkOptions};
+#[cfg(feature = "cli")]
+use crate::{input, run, Config, Summary};
+
+/// The index file written into the indexed directory.
+pub const INDEX_FILE: &str = ".minigrep-index";
+
+/// The first line of every index file, so an old format is never misread.
+const HEADER: &str = "minigrep-index 2";
+
+type Trigram = [u8; 3];
+
+/// What the index remembers about each file, to tell when it changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Stamp {
+    /// Relative to the indexed directory.
+    path: String,
+    modified: (u64, u32),
+    size: u64,
+}
+
+impl Stamp {
+    fn read(dir: &Path, path: &str) -> io::Result<Stamp> {
+        let metadata = fs::metadata(dir.join(path))?;
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Ok(Stamp {
+            path: path.to_string(),
+            modified: (modified.as_secs(), modified.subsec_nanos()),
+            size: metadata.len(),
+        })
+    }
+}
+
+/// The files under a directory and the trigrams each contains.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Index {
+    files: Vec<Stamp>,
+    /// For each trigram, the positions in `files` of the files containing it.
+    postings: BTreeMap<Trigram, Vec<u32>>,
+}
+
+impl Index {
+    /// Reads every file under `dir` that a search would, skipping hidden and
+    /// ignored ones. Files that can't be read are reported and left out, so
+    /// a later query treats them as new.
+    pub fn build(dir: &Path) -> Result<Index, MinigrepError> {
+        let mut index = Index::default();
+        for path in walk::walk(dir, &WalkOptions::default()) {
+            let path = match path {
+                Ok(path) => path,
+                Err(e) => {
+                    eprintln!("minigrep: {e}");
+                    continue;
+                }
+            };
+            let relative = path.strip_prefix(dir).unwrap_or(&path);
+            let Some(relative) = relative.to_str().filter(|path| !path.contains('\n')) else {
+                continue;
+            };
+            let read = Stamp::read(dir, relative).and_then(|stamp| Ok((stamp, fs::read(&path)?)));
+            let (stamp, contents) = match read {
+                Ok(read) => read,
+                Err(e) => {
+                    eprintln!("minigrep: {}", MinigrepError::io(&path, e));
+                    continue;
+                }
+            };
+            let id = index.files.len() as u32;
+            for trigram in trigrams(&contents) {
+                index.postings.entry(trigram).or_default().push(id);
+            }
+            index.files.push(stamp);
+        }
+        Ok(index)
+    }
+
+    /// Writes the index to [`INDEX_FILE`] in `dir`.
+    pub fn save(&self, dir: &Path) -> Result<(), MinigrepError> {
+        let path = dir.join(INDEX_FILE);
+        let file = File::create(&path).map_err(|e| MinigrepError::io(&path, e))?;
+        self.write(BufWriter::new(file))
+            .map_err(|e| MinigrepError::io(&path, e))
+    }
+
+    /// Reads the index that `save` wrote into `dir`.
+    pub fn load(dir: &Path) -> Result<Index, MinigrepError> {
+        let path = dir.join(INDEX_FILE);
+        let file = File::open(&path).map_err(|e| MinigrepError::io(&path, e))?;
+        Index::read(BufReader::new(file)).map_err(|e| MinigrepError::io(&path, e))
+    }
+
+    /// The format is line based: the header, then `F <secs> <nanos> <size>
+    /// <path>` per file, then `T <trigram as hex> <file positions...>` per
+    /// trigram.
+    fn write(&self, mut out: impl Write) -> io::Result<()> {
+        writeln!(out, "{HEADER}")?;
+        for file in &self.files {
+            let (secs, nanos) = file.modified;
+            writeln!(out, "F {secs} {nanos} {} {}", file.size, file.path)?;
+        }
+        for (trigram, ids) in &self.postings {
+            let ids: Vec<_> = ids.iter().map(u32::to_string).collect();
+            let [a, b, c] = trigram;
+            writeln!(out, "T {a:02x}{b:02x}{c:02x} {}", ids.join(" "))?;
+        }
+        out.flush()
+    }
+
+    fn read(reader: impl BufRead) -> io::Result<Index> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "corrupt index, rebuild it");
+        let mut lines = reader.lines();
+        if lines.next().transpose()?.as_deref() != Some(HEADER) {
+            return Err(invalid());
+        }
+        let mut index = Index::default();
+        for line in lines {
+            let line = line?;
+            if let Some(rest) = line.strip_prefix("F ") {
+                let mut fields = rest.splitn(4, ' ');
+                let mut number = || fields.next().and_then(|field| field.parse::<u64>().ok());
+                let (Some(secs), Some(nanos), Some(size)) = (number(), number(), number()) else {
+                    return Err(invalid());
+                };
+                let path = fields.next().ok_or_else(invalid)?;
+                index.files.push(Stamp {
+                    path: path.to_string(),
+                    modified: (secs, nanos as u32),
+                    size,
+                });
+            } else if let Some(rest) = line.strip_prefix("T ") {
+                let mut fields = rest.split(' ');
+                let hex = fields.next().ok_or_else(invalid)?;
+                let trigram = parse_trigram(hex).ok_or_else(invalid)?;
+                let ids = fields
+                    .map(|id| id.parse().map_err(|_| invalid()))
+                    .collect::<io::Result<Vec<u32>>>()?;
+                index.postings.insert(trigram, ids);
+            } else {
+                return Err(invalid());
+            }
+        }
+        Ok(index)
+    }
+
+    /// The indexed files that might contain `literal`: those containing all
+    /// of its trigrams.
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    fn candidates(&self, literal: &[u8]) -> BTreeSet<u32> {
+        let all = || (0..self.files.len() as u32).collect();
+        let mut found: Option<BTreeSet<u32>> = None;
+        for trigram in trigrams(literal) {
+            let ids: BTreeSet<u32> = match self.postings.get(&trigram) {
+                Some(ids) => ids.iter().copied().collect(),
+                None => return BTreeSet::new(),
+            };
+            found = Some(match found {
+                Some(found) => &found & &ids,
+                None => ids,
+            });
+        }
+        // Patterns shorter than a trigram can't narrow anything down.
+        found.unwrap_or_else(all)
+    }
+}
+
+/// The distinct trigrams of `bytes` once case folded, so the index also
+/// serves case-insensitive searches, where `\u{212A}elvin` matches `kelvin`.
+/// Folding goes character by character, so text containing a pattern still
+/// contains it after both are folded.
+fn trigrams(bytes: &[u8]) -> BTreeSet<Trigram> {
+    let folded = casefold::fold_str(&String::from_utf8_lossy(bytes));
+    folded
+        .as_bytes()
+        .windows(3)
+        .map(|window| [window[0], window[1], window[2]])
+        .filter(|trigram| !trigram.contains(&b'\n'))
+        .collect()
+}
+
+fn parse_trigram(hex: &str) -> Option<Trigram> {
+    if hex.len() != 6 {
+        return None;
+    }
+    let byte = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some([byte(0)?, byte(2)?, byte(4)?])
+}
+
+/// Builds the index for `dir` and saves it there. Returns how many files
+/// were indexed.
+pub fn create(dir: &Path) -> Result<usize, MinigrepError> {
+    let index = Index::build(dir)?;
+    index.save(dir)?;
+    Ok(index.files.len())
+}
+
+/// Searches the indexed directory named by `config` (the current one if
+/// none is), reading only the files that can match: the index's candidates,
+/// files changed since indexing, and files added since. The directory is
+/// walked with the search's own options, so `--type`, `--include`,
+/// `--hidden` and the rest pick files just as they would without an index.
+#[cfg(feature = "cli")]
+pub fn query(config: Config) -> Result<Summary, MinigrepError> {
+    match narrow(config)? {
+        Some(config) => run(config),
+        None => Ok(Summary::default()),
+    }
+}
+
+/// Points `config` at the files `query` has to search, or returns `None` if
+/// there are none.
+#[cfg(feature = "cli")]
+fn narrow(mut config: Config) -> Result<Option<Config>, MinigrepError> {
+    let dir = match config.filenames.as_slice() {
+        [] => PathBuf::from("."),
+        [dir] if dir == input::STDIN => PathBuf::from("."),
+        [dir] => PathBuf::from(dir),
+        _ => return Err("query searches a single indexed directory".into()),
+    };
+    let index = Index::load(&dir)?;
+
+    let filter = can_filter(&config);
+    let mut wanted = BTreeSet::new();
+    if filter {
+        for pattern in &config.patterns {
+            wanted.extend(index.candidates(pattern.as_bytes()));
+        }
+    }
+    let indexed: BTreeMap<&str, (u32, &Stamp)> = index
+        .files
+        .iter()
+        .enumerate()
+        .map(|(id, stamp)| (stamp.path.as_str(), (id as u32, stamp)))
+        .collect();
+    let mut files = Vec::new();
+    for path in walk::walk(&dir, &config.walk).into_iter().flatten() {
+        let relative = path.strip_prefix(&dir).unwrap_or(&path);
+        let Some(relative) = relative.to_str() else {
+            continue;
+        };
+        if relative == INDEX_FILE {
+            continue;
+        }
+        let search = match indexed.get(relative) {
+            Some(&(id, stamp)) => {
+                let changed = Stamp::read(&dir, relative).map_or(true, |now| now != *stamp);
+                !filter || changed || wanted.contains(&id)
+            }
+            None => true,
+        };
+        if search {
+            files.push(display(&dir, path));
+        }
+    }
+
+    if files.is_empty() {
+        return Ok(None);
+    }
+    // Every match comes from somewhere in the directory, so say where even
+    // when only one file is left to search.
+    config.filenames = files;
+    config.with_filename = true;
+    Ok(Some(config))
+}
+
+/// Whether the index can rule files out for this search. It can't for
+/// regexes, inverted matches or input that is decoded before searching.
+#[cfg(feature = "cli")]
+fn can_filter(config: &Config) -> bool {
+    !(config.regex || config.invert_match || config.read.transforms())
+}
+
+/// Keeps paths under `.` relative, the way a walk prints them.
+#[cfg(feature = "cli")]
+fn display(dir: &Path, path: PathBuf) -> String {
+    if dir == Path::new(".") {
+        path.strip_prefix(".")
+            .unwrap_or(&path)
+            .display()
+            .to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::tree;
+
+    fn paths(index: &Index, ids: BTreeSet<u32>) -> Vec<&str> {
+        ids.into_iter()
+            .map(|id| index.files[id as usize].path.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn finds_candidate_files() {
+        let root = tree(
+            "candidates",
+            &[
+                ("poem.txt", "I'm Nobody! Who are you?\n"),
+                ("src/lib.rs", "fn body() {}\n"),
+            ],
+        );
+        let index = Index::build(&root).unwrap();
+
+        assert_eq!(vec!["poem.txt"], paths(&index, index.candidates(b"nobody")));
+        assert_eq!(
+            vec!["poem.txt", "src/lib.rs"],
+            paths(&index, index.candidates(b"body"))
+        );
+        assert!(index.candidates(b"zebra").is_empty());
+        assert_eq!(2, index.candidates(b"fn").len());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn folds_case_beyond_ascii() {
+        let root = tree(
+            "folding",
+            &[
+                ("kelvin.txt", "0 \u{212A}elvin is cold\n"),
+                ("sun.txt", "the \u{17F}un\n"),
+                ("other.txt", "nothing\n"),
+            ],
+        );
+        let index = Index::build(&root).unwrap();
+
+        assert_eq!(
+            vec!["kelvin.txt"],
+            paths(&index, index.candidates(b"kelvin"))
+        );
+        assert_eq!(
+            vec!["kelvin.txt"],
+            paths(&index, index.candidates("\u{212A}ELVIN".as_bytes()))
+        );
+        assert_eq!(vec!["sun.txt"], paths(&index, index.candidates(b"SUN")));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn round_trips_through_its_file() {
+        let root = tree(
+            "format",
+            &[("a b.txt", "alpha\nbeta\n"), ("c.txt", "gamma")],
+        );
+        let index = Index::build(&root).unwrap();
+        index.save(&root).unwrap();
+
+        assert_eq!(index, Index::load(&root).unwrap());
+        assert!(Index::read("not an index\n".as_bytes()).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn query_checks_changed_and_new_files() {
+        let root = tree(
+            "query",
+            &[("old.txt", "nothing here\n"), ("hit.txt", "needle\n")],
+        );
+        create(&root).unwrap();
+        // Changed and added after indexing, so the index doesn't know they
+        // match.
+        fs::write(root.join("old.txt"), "a needle now\n").unwrap();
+        fs::write(root.join("new.txt"), "another needle\n").unwrap();
+
+        let config = Config::builder()
+            .query("needle")
+            .path(root.display().to_string())
+            .quiet(true)
+            .build()
+            .unwrap();
+        assert_eq!(3, query(config).unwrap().files_matched);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn query_names_a_single_matching_file() {
+        let root = tree(
+            "query-single",
+            &[("hit.txt", "needle\n"), ("miss.txt", "hay\n")],
+        );
+        create(&root).unwrap();
+
+        let config = Config::builder()
+            .query("needle")
+            .path(root.display().to_string())
+            .build()
+            .unwrap();
+        let config = narrow(config).unwrap().unwrap();
+        assert_eq!(
+            vec![root.join("hit.txt").display().to_string()],
+            config.filenames
+        );
+        let (search, _) = crate::Search::new(&config).unwrap();
+        assert!(search.show_filename);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn query_walks_with_the_search_options() {
+        let root = tree(
+            "query-options",
+            &[
+                ("a.rs", "needle\n"),
+                ("b.txt", "needle\n"),
+                ("deep/c.rs", "needle\n"),
+                (".hidden.rs", "needle\n"),
+            ],
+        );
+        create(&root).unwrap();
+
+        let config = Config::builder()
+            .query("needle")
+            .path(root.display().to_string())
+            .file_type("rust")
+            .max_depth(1)
+            .build()
+            .unwrap();
+        let config = narrow(config).unwrap().unwrap();
+        assert_eq!(
+            vec![root.join("a.rs").display().to_string()],
+            config.filenames
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn query_ignores_case_like_a_search() {
+        let root = tree(
+            "query-fold",
+            &[("a.txt", "\u{212A}elvin\n"), ("b.txt", "celsius\n")],
+        );
+        create(&root).unwrap();
+
+        let config = Config::builder()
+            .query("kelvin")
+            .path(root.display().to_string())
+            .case_insensitive(true)
+            .quiet(true)
+            .build()
+            .unwrap();
+        assert_eq!(1, query(config).unwrap().files_matched);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,286 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::decode::{Compression, TextEncoding};
+#[cfg(feature = "cli")]
+use crate::preprocess::Preprocessor;
+
+/// The filename that stands for standard input.
+pub const STDIN: &str = "-";
+
+/// Files at least this big are memory-mapped when the choice is `Auto`.
+pub const MMAP_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+/// Whether to memory-map files instead of reading them through a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MmapChoice {
+    /// Map files of at least [`MMAP_THRESHOLD`] bytes.
+    #[default]
+    Auto,
+    /// Map every file (`--mmap`).
+    Always,
+    /// Never map files (`--no-mmap`).
+    Never,
+}
+
+impl MmapChoice {
+    fn wants(self, len: u64) -> bool {
+        match self {
+            MmapChoice::Auto => len >= MMAP_THRESHOLD,
+            MmapChoice::Always => true,
+            MmapChoice::Never => false,
+        }
+    }
+}
+
+/// How raw input bytes are turned into the lines that get searched.
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+    pub mmap: MmapChoice,
+    /// Decompress files in a known compression format (`-z`).
+    pub search_zip: bool,
+    /// Convert input from this encoding to UTF-8 (`--encoding`). Without it,
+    /// input must already be UTF-8.
+    pub encoding: Option<TextEncoding>,
+    /// Search what this command prints for each file instead (`--pre`).
+    #[cfg(feature = "cli")]
+    pub pre: Option<Preprocessor>,
+}
+
+impl ReadOptions {
+    /// Whether the bytes searched can differ from the bytes in the file, by
+    /// being decompressed, decoded or preprocessed.
+    pub(crate) fn transforms(&self) -> bool {
+        #[cfg(feature = "cli")]
+        if self.pre.is_some() {
+            return true;
+        }
+        self.search_zip || self.encoding.is_some()
+    }
+}
+
+/// Opens a named input for reading line by line, treating `-` as standard
+/// input.
+///
+/// The raw bytes pass through a chain of decoding layers: with `--pre` the
+/// file is replaced by a command's output, with `-z` compressed files are
+/// decompressed, and with `--encoding` the result is then converted to UTF-8. Files that need neither are memory-mapped if
+/// `mmap` asks for it, falling back to buffered reads if mapping fails.
+pub fn reader(filename: &str, options: &ReadOptions) -> io::Result<Box<dyn BufRead>> {
+    let compression = options
+        .search_zip
+        .then(|| Compression::detect(Path::new(filename)))
+        .flatten();
+
+    let source: Box<dyn Read> = if filename == STDIN {
+        Box::new(io::stdin())
+    } else if let Some(output) = preprocessed(filename, options)? {
+        output
+    } else {
+        let file = File::open(filename)?;
+        if compression.is_none() && options.encoding.is_none() {
+            if options.mmap.wants(file.metadata()?.len()) {
+                if let Ok(reader) = MmapReader::new(&file) {
+                    return Ok(Box::new(reader));
+                }
+            }
+            return Ok(Box::new(BufReader::new(file)));
+        }
+        Box::new(file)
+    };
+
+    let decompressed = match compression {
+        Some(compression) => compression.decoder(source),
+        None => source,
+    };
+    let decoded = match options.encoding {
+        Some(encoding) => encoding.decoder(decompressed),
+        None => decompressed,
+    };
+    Ok(Box::new(BufReader::new(decoded)))
+}
+
+/// Reads straight out of a memory-mapped file.
+struct MmapReader {
+    map: Mmap,
+    pos: usize,
+}
+
+impl MmapReader {
+    fn new(file: &File) -> io::Result<Self> {
+        // SAFETY: the map is only ever read, and minigrep accepts that a file
+        // truncated by another process while mapped may fault, as ripgrep does.
+        let map = unsafe { Mmap::map(file)? };
+        Ok(MmapReader { map, pos: 0 })
+    }
+}
+
+impl Read for MmapReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = (&self.map[self.pos..]).read(buf)?;
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl BufRead for MmapReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(&self.map[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.map.len());
+    }
+}
+
+/// What the `--pre` command printed for `filename`, if it applies to it.
+#[cfg(feature = "cli")]
+fn preprocessed(filename: &str, options: &ReadOptions) -> io::Result<Option<Box<dyn Read>>> {
+    match &options.pre {
+        Some(pre) if pre.applies(Path::new(filename)) => {
+            let output = pre.run(Path::new(filename))?;
+            Ok(Some(Box::new(output)))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Without the command line there's no `--pre`.
+#[cfg(not(feature = "cli"))]
+fn preprocessed(_filename: &str, _options: &ReadOptions) -> io::Result<Option<Box<dyn Read>>> {
+    Ok(None)
+}
+
+/// How much of the start of an input [`looks_binary`] checks.
+const BINARY_CHECK_LEN: usize = 8 * 1024;
+
+/// Whether the first block of `reader` looks like binary data rather than
+/// text: it has a NUL byte, or, when `utf8` says the input should already
+/// be UTF-8, bytes that aren't. Nothing is consumed, so the search still
+/// starts from the beginning.
+pub fn looks_binary(reader: &mut dyn BufRead, utf8: bool) -> io::Result<bool> {
+    let buf = reader.fill_buf()?;
+    let block = &buf[..buf.len().min(BINARY_CHECK_LEN)];
+    if block.contains(&0) {
+        return Ok(true);
+    }
+    // A character cut off at the end of the block is fine; it goes on in the
+    // next one.
+    Ok(utf8 && std::str::from_utf8(block).is_err_and(|e| e.error_len().is_some()))
+}
+
+/// The name to print for an input in output and error messages.
+pub fn display_name(filename: &str) -> &str {
+    if filename == STDIN {
+        "(standard input)"
+    } else {
+        filename
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn names_stdin() {
+        assert_eq!("(standard input)", display_name("-"));
+        assert_eq!("poem.txt", display_name("poem.txt"));
+    }
+
+    #[test]
+    fn spots_binary_data() {
+        let looks_binary =
+            |bytes: &[u8], utf8| looks_binary(&mut Cursor::new(bytes), utf8).unwrap();
+        assert!(!looks_binary(b"plain text\n", true));
+        assert!(!looks_binary("caf\u{e9}".as_bytes(), true));
+        assert!(looks_binary(b"ELF\0\x01", true));
+        assert!(looks_binary(b"\xff\xfe text", true));
+        assert!(!looks_binary(b"\xff\xfe text", false));
+        // Cut off in the middle of a character by the block's end.
+        let mut cut = vec![b'a'; BINARY_CHECK_LEN - 1];
+        cut.extend("\u{e9}".as_bytes());
+        assert!(!looks_binary(&cut, true));
+    }
+
+    #[test]
+    fn auto_maps_only_large_files() {
+        assert!(!MmapChoice::Auto.wants(1024));
+        assert!(MmapChoice::Auto.wants(MMAP_THRESHOLD));
+        assert!(MmapChoice::Always.wants(0));
+        assert!(!MmapChoice::Never.wants(u64::MAX));
+    }
+
+    #[test]
+    fn mapped_and_buffered_reads_agree() {
+        let read = |mmap| {
+            let mut lines = Vec::new();
+            let options = ReadOptions {
+                mmap,
+                ..ReadOptions::default()
+            };
+            for line in reader("poem.txt", &options).unwrap().lines() {
+                lines.push(line.unwrap());
+            }
+            lines
+        };
+
+        assert_eq!(read(MmapChoice::Never), read(MmapChoice::Always));
+        assert_eq!(9, read(MmapChoice::Always).len());
+    }
+
+    #[test]
+    fn search_zip_decompresses() {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!("minigrep-{}.txt.gz", std::process::id()));
+        let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Default::default());
+        encoder.write_all(b"Rust:\nPick three.\n").unwrap();
+        encoder.finish().unwrap();
+        let filename = path.to_str().unwrap();
+
+        let options = ReadOptions {
+            search_zip: true,
+            ..ReadOptions::default()
+        };
+        let lines: Vec<_> = reader(filename, &options).unwrap().lines().collect();
+        assert_eq!("Pick three.", lines[1].as_ref().unwrap());
+
+        let raw = reader(filename, &ReadOptions::default()).unwrap();
+        assert!(raw.lines().next().unwrap().is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn decoding_layers_chain() {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!("minigrep-{}.utf16.gz", std::process::id()));
+        let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Default::default());
+        let utf16: Vec<u8> = "Rust:\nTrust me.\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        encoder.write_all(&utf16).unwrap();
+        encoder.finish().unwrap();
+
+        let options = ReadOptions {
+            search_zip: true,
+            encoding: Some(TextEncoding::Utf16Le),
+            ..ReadOptions::default()
+        };
+        let lines: Vec<_> = reader(path.to_str().unwrap(), &options)
+            .unwrap()
+            .lines()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(vec!["Rust:", "Trust me."], lines);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,66 +1,801 @@
-pub struct Config {
-    pub query: String,
-    pub filename: String,
-    pub case_sensitive: bool,
+#[cfg(feature = "async")]
+pub mod asynk;
+pub mod bench;
+mod casefold;
+mod color;
+mod config;
+mod decode;
+mod error;
+mod filter;
+mod ignore;
+pub mod index;
+mod input;
+mod limit;
+pub mod log;
+pub mod matcher;
+mod output;
+mod paths;
+mod pool;
+#[cfg(feature = "cli")]
+mod preprocess;
+pub mod printer;
+#[cfg(feature = "cli")]
+mod progress;
+mod replace;
+#[cfg(feature = "cli")]
+pub mod serve;
+mod sort;
+#[cfg(test)]
+mod test_support;
+mod types;
+#[cfg(feature = "cli")]
+mod user_config;
+#[cfg(feature = "cli")]
+mod vcs;
+mod walk;
+#[cfg(feature = "cli")]
+mod watch;
+
+pub use color::{Color, ColorChoice, ColorSpec, Style, Theme};
+pub use config::{Config, ConfigBuilder, OutputMode};
+pub use decode::TextEncoding;
+pub use error::MinigrepError;
+pub use filter::PathGlobs;
+pub use input::{MmapChoice, ReadOptions};
+use limit::{Budget, FileLimit, Interruptible};
+use matcher::{CaseInsensitive, Literal, Matcher};
+pub use output::Filenames;
+#[cfg(feature = "cli")]
+pub use preprocess::Preprocessor;
+#[cfg(feature = "cli")]
+use printer::{CsvPrinter, Format, JsonPrinter, NdjsonPrinter, Printer, TextPrinter};
+#[cfg(feature = "cli")]
+use progress::Progress;
+pub use sort::SortBy;
+pub use types::FileTypes;
+pub use walk::WalkOptions;
+
+#[cfg(feature = "cli")]
+use std::sync::{Mutex, PoisonError};
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, BufRead},
+    ops::Range,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+pub type Result<T, E = MinigrepError> = std::result::Result<T, E>;
+
+/// A matching line that owns its text, so it can outlive the search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMatch {
+    pub line_no: usize,
+    /// The byte offset of the start of the line within its input.
+    pub offset: u64,
+    pub line: String,
+    pub spans: Vec<Range<usize>>,
+    /// Whether the line ended in `\r\n`, which isn't part of `line`.
+    pub crlf: bool,
+}
+
+impl LineMatch {
+    /// The 1-based byte column at which the first match on the line starts.
+    pub fn column(&self) -> usize {
+        self.spans.first().map_or(1, |span| span.start + 1)
+    }
+}
+
+impl From<Match<'_>> for LineMatch {
+    fn from(m: Match) -> Self {
+        LineMatch {
+            line_no: m.line_no,
+            offset: m.offset,
+            line: m.line.to_string(),
+            spans: m.spans,
+            crlf: m.crlf,
+        }
+    }
+}
+
+/// Everything found in one searched file.
+#[derive(Debug)]
+pub struct FileMatches {
+    /// The file's name as it should be shown, e.g. `(standard input)`.
+    pub path: String,
+    /// The matching lines, or every line with `--passthru`. Left empty when
+    /// the output mode only needs counts or file names.
+    pub matches: Vec<LineMatch>,
+    /// How many lines went straight to a sink instead of into `matches`.
+    pub streamed: usize,
+    /// How many lines matched, or were rewritten with `--write`.
+    pub count: usize,
+    /// How many bytes were read before the search finished.
+    pub bytes: u64,
+    /// Why the file couldn't be searched, if it couldn't.
+    pub error: Option<MinigrepError>,
+    /// Whether the file looked binary, so it was skipped instead.
+    pub binary: bool,
+}
+
+impl FileMatches {
+    fn new(path: &str) -> Self {
+        FileMatches {
+            path: path.to_string(),
+            matches: Vec::new(),
+            streamed: 0,
+            count: 0,
+            bytes: 0,
+            error: None,
+            binary: false,
+        }
+    }
+
+    /// Whether any lines were found to print, kept or streamed.
+    pub fn has_lines(&self) -> bool {
+        !self.matches.is_empty() || self.streamed > 0
+    }
+
+    fn failed(error: MinigrepError) -> Self {
+        let path = error.path().map(|path| path.display().to_string());
+        FileMatches {
+            error: Some(error),
+            ..FileMatches::new(&path.unwrap_or_default())
+        }
+    }
+}
+
+/// Searches everything `config` asks for and returns the results per file,
+/// in the order the files finished. Files that couldn't be searched are
+/// included with their `error` set.
+pub fn search_file(config: &Config) -> Result<Vec<FileMatches>> {
+    let (search, inputs) = Search::new(config)?;
+    let mut results = Vec::new();
+    search.each(inputs, None, |file| results.push(file));
+    Ok(results)
+}
+
+/// What a [`run`] found, so the binary can pick its exit status.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// How many lines matched (or were rewritten) across all files.
+    pub matches: usize,
+    /// How many files couldn't be searched.
+    pub errors: usize,
+    /// How many files were searched.
+    pub files: usize,
+    /// How many of those had at least one match.
+    pub files_matched: usize,
+    /// How many files were left out by `--max-filesize` or `--max-files`, or
+    /// for looking binary.
+    pub skipped: usize,
+    /// Whether `--timeout` cut the run short.
+    pub timed_out: bool,
+    /// How many bytes were read across all files.
+    pub bytes: u64,
+    /// How long the whole run took.
+    pub elapsed: Duration,
+}
+
+impl Summary {
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    fn add(&mut self, file: &FileMatches) {
+        if file.error.is_some() {
+            self.errors += 1;
+            return;
+        }
+        if file.binary {
+            self.skipped += 1;
+            return;
+        }
+        self.files += 1;
+        self.matches += file.count;
+        self.bytes += file.bytes;
+        if file.count > 0 {
+            self.files_matched += 1;
+        }
+    }
+
+    /// The grep-compatible exit status: 2 if anything went wrong, otherwise
+    /// 0 if something matched and 1 if nothing did.
+    pub fn exit_code(&self) -> i32 {
+        if self.errors > 0 {
+            2
+        } else if self.matches > 0 {
+            0
+        } else {
+            1
+        }
+    }
+}
+
+/// Searches everything `config` asks for, printing results as each file
+/// finishes and reporting files that couldn't be searched on stderr.
+#[cfg(feature = "cli")]
+pub fn run(mut config: Config) -> Result<Summary> {
+    let start = Instant::now();
+    if let Some(level) = config.verbose {
+        log::set_logger(Box::new(log::StderrLogger::new(level)));
+    }
+    if config.debug {
+        log::debug(|| format!("config: {config:#?}"));
+    }
+    // --passthru prints every line, too many to hold until a file is done, so
+    // they go to the printer as they're read. Searching one file at a time
+    // keeps each file's lines together.
+    let passthru = config.passthru
+        && config.output == OutputMode::Lines
+        && config.format != Format::Ndjson
+        && config.sort.is_none();
+    if passthru {
+        config.jobs = 1;
+    }
+    let (search, inputs) = Search::new(&config)?;
+    let colored = config.color.enabled();
+    let filenames = Filenames::choose(&config, search.show_filename);
+    let mut summary = Summary {
+        skipped: search.skipped,
+        ..Summary::default()
+    };
+    if config.watch {
+        let mut files = Vec::new();
+        for input in inputs {
+            match input {
+                Ok(filename) => files.push(filename),
+                Err(e) => eprintln!("minigrep: {e}"),
+            }
+        }
+        let mut printer = printer(&config, &*search.matcher, filenames, colored);
+        summary.matches = watch::follow(&config, &search.matcher, files, &mut *printer)?;
+        return Ok(summary);
+    }
+    let progress = (search.recursive
+        && !config.quiet
+        && !passthru
+        && config.verbose.is_none()
+        && Progress::wanted())
+    .then(Progress::default);
+    let printer = printer(&config, &*search.matcher, filenames, colored);
+    // The printer along with the first failure to write, after which nothing
+    // more is printed.
+    let printed = Mutex::new((printer, Ok(())));
+    // With --format ndjson the workers print each match as soon as they find
+    // it, unless --sort needs everything first.
+    let streamed = (config.format == Format::Ndjson && config.sort.is_none()).then(|| {
+        let printer = NdjsonPrinter::new(io::stdout()).path_separator(config.path_separator);
+        Mutex::new((printer, Ok(())))
+    });
+    let stream = |file: &FileMatches, m: &LineMatch| {
+        if let Some(streamed) = &streamed {
+            let mut streamed = streamed.lock().unwrap_or_else(PoisonError::into_inner);
+            let (printer, written) = &mut *streamed;
+            if !config.quiet && written.is_ok() {
+                *written = printer.match_found(file, m);
+            }
+        } else if passthru {
+            let mut printed = printed.lock().unwrap_or_else(PoisonError::into_inner);
+            let (printer, written) = &mut *printed;
+            if config.quiet || written.is_err() {
+                return;
+            }
+            if file.streamed == 1 {
+                *written = printer.file_begin(file);
+            }
+            if written.is_ok() {
+                *written = printer.match_found(file, m);
+            }
+        }
+    };
+    let sink: Option<&Sink> = (streamed.is_some() || passthru).then_some(&stream);
+    // The counts to chart with --histogram once everything is searched.
+    let mut counts = Vec::new();
+    let searching = || {
+        // Results arrive here one file at a time, so lines from different
+        // files never interleave.
+        summary.timed_out = search.each(inputs, sink, |file| {
+            summary.add(&file);
+            if config.output == OutputMode::Histogram && file.count > 0 {
+                let path = output::display_path(&file.path, config.path_separator);
+                counts.push((path.into_owned(), file.count));
+            }
+            let print = || {
+                let mut printed = printed.lock().unwrap_or_else(PoisonError::into_inner);
+                let (printer, written) = &mut *printed;
+                match &file.error {
+                    Some(e) => eprintln!("minigrep: {e}"),
+                    None if config.quiet || file.binary || written.is_err() => {}
+                    // Passthru lines were printed as they were read.
+                    None if passthru && file.streamed > 0 => {
+                        *written = printer.file_end(&file);
+                    }
+                    None => *written = printer::print_file(&mut **printer, &file),
+                }
+            };
+            match &progress {
+                Some(progress) => {
+                    progress.record(&file);
+                    progress.clear_then(print);
+                }
+                None => print(),
+            }
+        })
+    };
+    match &progress {
+        Some(progress) => progress.show_while(searching),
+        None => searching(),
+    }
+    let (printer, mut written) = printed.into_inner().unwrap_or_else(PoisonError::into_inner);
+    drop(printer);
+    if let Some(streamed) = streamed {
+        let (_, streamed) = streamed
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        written = written.and(streamed);
+    }
+    written.map_err(|e| MinigrepError::io("(standard output)", e))?;
+    summary.elapsed = start.elapsed();
+
+    if config.output == OutputMode::Histogram && !config.quiet {
+        print!("{}", output::format_histogram(counts));
+    }
+    if config.stats && !config.quiet {
+        print!("{}", output::format_stats(&summary));
+    }
+    if summary.timed_out {
+        let timeout = config.timeout.unwrap_or_default();
+        eprintln!("minigrep: stopped after --timeout {timeout:?}; results are incomplete");
+    }
+    Ok(summary)
+}
+
+/// The printer `--format` asks for, writing to stdout.
+#[cfg(feature = "cli")]
+fn printer<'a>(
+    config: &'a Config,
+    matcher: &'a dyn Matcher,
+    filenames: Filenames,
+    colored: bool,
+) -> Box<dyn Printer + Send + 'a> {
+    let stdout = io::stdout();
+    match config.format {
+        Format::Text => Box::new(TextPrinter::new(
+            stdout, config, matcher, filenames, colored,
+        )),
+        Format::Json => Box::new(JsonPrinter::new(stdout).path_separator(config.path_separator)),
+        Format::Ndjson => {
+            Box::new(NdjsonPrinter::new(stdout).path_separator(config.path_separator))
+        }
+        Format::Csv => Box::new(
+            CsvPrinter::new(stdout)
+                .delimiter(config.field_separator.as_deref().unwrap_or(","))
+                .path_separator(config.path_separator),
+        ),
+    }
 }
 
-impl Config {
-    pub fn new(mut args: std::env::Args) -> Result<Self, &'static str> {
-        args.next();
+/// A search that is ready to go: the matcher is built and we know how the
+/// file arguments expanded.
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
+struct Search<'a> {
+    config: &'a Config,
+    matcher: Box<dyn Matcher>,
+    show_filename: bool,
+    /// Whether any argument is a directory to search recursively.
+    recursive: bool,
+    /// How many inputs `limit` dropped.
+    skipped: usize,
+    /// When `--timeout` runs out.
+    deadline: Option<Instant>,
+}
 
-        let Some(query) = args.next() else {
-            return Err("Didn't get a query string");
+impl<'a> Search<'a> {
+    /// Also returns the expanded inputs, which are consumed by `each`.
+    fn new(config: &'a Config) -> Result<(Self, Vec<Result<String>>)> {
+        let deadline = config.timeout.map(|timeout| Instant::now() + timeout);
+        let matcher = config.matcher()?;
+        #[cfg(feature = "cli")]
+        let changed = match config.changed {
+            true => Some(vcs::changed_files().map_err(|e| MinigrepError::io("git", e))?),
+            false => None,
         };
-        let Some(filename) = args.next() else {
-            return Err("Didn't get a file name");
+        // Only the command line asks git what changed.
+        #[cfg(not(feature = "cli"))]
+        let changed = None;
+        let walk = WalkOptions {
+            deadline,
+            ..config.walk.clone()
         };
+        let mut limits = Limits::new(config, changed.as_ref());
+        paths::expand(&config.filenames, &walk, &mut |input| limits.offer(input));
+        if let Some(list) = &config.files_from {
+            for filename in paths::from_list(list, &config.walk)? {
+                limits.offer(Ok(filename));
+            }
+        }
+        let recursive = config
+            .filenames
+            .iter()
+            .any(|filename| Path::new(filename).is_dir());
+        let show_filename =
+            config.with_filename || limits.offered > 1 || recursive || config.files_from.is_some();
+        let search = Search {
+            config,
+            matcher,
+            show_filename,
+            recursive,
+            skipped: limits.skipped,
+            deadline,
+        };
+        Ok((search, limits.kept))
+    }
 
-        let case_sensitive = env::var("CASE_SENSITIVE").is_err();
+    /// Searches the files on the worker pool, handing each file's results to
+    /// `emit` on the calling thread. Returns whether `--timeout` ran out
+    /// first, leaving the results incomplete.
+    ///
+    /// Given a `sink`, matching lines go to it from the workers as they're
+    /// found instead of being kept in the results.
+    fn each(
+        &self,
+        inputs: Vec<Result<String>>,
+        sink: Option<&Sink>,
+        mut emit: impl FnMut(FileMatches),
+    ) -> bool {
+        let mut files = Vec::new();
+        for input in inputs {
+            match input {
+                Ok(filename) => files.push(filename),
+                Err(e) => emit(FileMatches::failed(e)),
+            }
+        }
 
-        Ok(Config {
-            query,
-            filename,
-            case_sensitive,
-        })
+        let budget = Budget::new(self.config.max_total).deadline(self.deadline);
+        let (config, matcher) = (self.config, &self.matcher);
+        let search = |filename: &String| search_logged(config, matcher, filename, &budget, sink);
+        let Some(sort) = config.sort else {
+            pool::run(config.jobs, &files, &budget, search, emit);
+            return budget.timed_out();
+        };
+        // Sorting needs every result before the first can be printed.
+        let mut results = Vec::with_capacity(files.len());
+        pool::run(config.jobs, &files, &budget, search, |file| {
+            results.push(file)
+        });
+        sort.sort(&mut results);
+        results.into_iter().for_each(emit);
+        budget.timed_out()
     }
 }
 
-use std::{env, error::Error, fs};
+/// Drops the inputs that `--changed`, `--max-filesize` and `--max-files` rule
+/// out as they're found, so a walk can stop as soon as `--max-files` has all
+/// it wants.
+struct Limits<'a> {
+    config: &'a Config,
+    /// The set of changed files with `--changed`.
+    changed: Option<&'a HashSet<PathBuf>>,
+    kept: Vec<Result<String>>,
+    /// How many inputs were offered, kept or not.
+    offered: usize,
+    /// How many files were kept.
+    files: usize,
+    /// How many files were dropped. Those a walk never reached aren't
+    /// counted.
+    skipped: usize,
+}
+
+impl<'a> Limits<'a> {
+    fn new(config: &'a Config, changed: Option<&'a HashSet<PathBuf>>) -> Self {
+        Limits {
+            config,
+            changed,
+            kept: Vec::new(),
+            offered: 0,
+            files: 0,
+            skipped: 0,
+        }
+    }
 
-pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
-    let contents = fs::read_to_string(config.filename)?;
+    /// Keeps or drops `input`, returning whether more are wanted.
+    fn offer(&mut self, input: Result<String>) -> bool {
+        self.offered += 1;
+        if let Ok(filename) = &input {
+            let too_big = self.config.max_filesize.is_some_and(|max| {
+                filename != input::STDIN
+                    && fs::metadata(filename).is_ok_and(|metadata| metadata.len() > max)
+            });
+            let unchanged = self.changed.is_some_and(|changed| {
+                filename != input::STDIN
+                    && !fs::canonicalize(filename).is_ok_and(|path| changed.contains(&path))
+            });
+            let reason = if unchanged {
+                "unchanged (--changed)"
+            } else if too_big {
+                "bigger than --max-filesize"
+            } else if !self.wants_more() {
+                "past --max-files"
+            } else {
+                ""
+            };
+            if !reason.is_empty() {
+                log::info(|| format!("skipping {filename}: {reason}"));
+                self.skipped += 1;
+                return self.wants_more();
+            }
+            self.files += 1;
+        }
+        self.kept.push(input);
+        self.wants_more()
+    }
 
-    let results = if config.case_sensitive {
-        search(&config.query, &contents)
+    fn wants_more(&self) -> bool {
+        self.config.max_files.is_none_or(|max| self.files < max)
+    }
+}
+
+/// Receives matching lines from the workers as they're found.
+type Sink<'a> = dyn Fn(&FileMatches, &LineMatch) + Sync + 'a;
+
+/// Searches one file like [`search_one`], turning a failure into results
+/// that carry the error, and logs how long it took.
+fn search_logged(
+    config: &Config,
+    matcher: &impl Matcher,
+    filename: &str,
+    budget: &Budget,
+    sink: Option<&Sink>,
+) -> FileMatches {
+    let name = input::display_name(filename);
+    let start = Instant::now();
+    let file =
+        search_one(config, matcher, filename, budget, sink).unwrap_or_else(|e| FileMatches {
+            error: Some(e),
+            ..FileMatches::new(name)
+        });
+    log::debug(|| format!("searched {name} in {:?}", start.elapsed()));
+    file
+}
+
+/// Searches one file, sending its matching lines to `sink` if there is one.
+fn search_one(
+    config: &Config,
+    matcher: &impl Matcher,
+    filename: &str,
+    budget: &Budget,
+    sink: Option<&Sink>,
+) -> Result<FileMatches> {
+    let name = input::display_name(filename);
+    let mut file = FileMatches::new(name);
+    if config.write {
+        if filename == input::STDIN {
+            let message = format!("{name}: can't rewrite standard input");
+            return Err(MinigrepError::BadArgs(message));
+        }
+        let replacement = config.replace.as_deref().unwrap_or_default();
+        file.count = replace::write_in_place(
+            Path::new(filename),
+            matcher,
+            replacement,
+            config.backup,
+            config.max_count,
+        )
+        .map_err(|e| MinigrepError::io(name, e))?;
+        return Ok(file);
+    }
+
+    // A file we can't read shouldn't stop us from searching the rest.
+    let mut reader =
+        input::reader(filename, &config.read).map_err(|e| MinigrepError::io(name, e))?;
+    let utf8 = config.read.encoding.is_none();
+    if input::looks_binary(&mut *reader, utf8).map_err(|e| MinigrepError::io(name, e))? {
+        log::info(|| format!("skipping {name}: binary"));
+        file.binary = true;
+        return Ok(file);
+    }
+    let reader = Interruptible::new(reader, budget);
+
+    let mut limit = FileLimit::new(config.max_count, budget);
+    // Every match on the counted lines, for --count-matches.
+    let mut each_match = 0;
+    let bytes = match config.output {
+        // Every line is kept, with spans only on the ones that match.
+        OutputMode::Lines if config.passthru => {
+            read_lines(reader, |line_no, offset, line, crlf| {
+                let spans = match matcher.find(line) {
+                    Some(spans) if limit.admit() => spans,
+                    _ => Vec::new(),
+                };
+                let m = LineMatch {
+                    line_no,
+                    offset,
+                    line: line.to_string(),
+                    spans,
+                    crlf,
+                };
+                match sink {
+                    Some(sink) => {
+                        file.streamed += 1;
+                        sink(&file, &m);
+                    }
+                    None => file.matches.push(m),
+                }
+                true
+            })
+        }
+        OutputMode::Lines => search_reader(matcher, reader, |m| {
+            if !limit.admit() {
+                return false;
+            }
+            match sink {
+                Some(sink) => {
+                    file.streamed += 1;
+                    sink(&file, &m.into());
+                }
+                None => file.matches.push(m.into()),
+            }
+            !limit.is_full()
+        }),
+        OutputMode::Count | OutputMode::Histogram => search_reader(matcher, reader, |m| {
+            if !limit.admit() {
+                return false;
+            }
+            // Inverted matches have no spans but still count once.
+            each_match += m.spans.len().max(1);
+            !limit.is_full()
+        }),
+        // Stop reading at the first hit.
+        OutputMode::FilesWithMatches => search_reader(matcher, reader, |_| {
+            limit.admit();
+            false
+        }),
+    };
+    file.bytes = bytes.map_err(|e| MinigrepError::io(name, e))?;
+    file.count = if config.count_matches {
+        each_match
     } else {
-        search_case_insensitive(&config.query, &contents)
+        limit.count()
     };
+    Ok(file)
+}
+
+/// Searches `reader` one line at a time, calling `on_match` for every
+/// matching line until it returns `false`. Only the current line is held in
+/// memory, so this works on inputs of any size.
+///
+/// Returns how many bytes were read.
+pub fn search_reader(
+    matcher: &impl Matcher,
+    reader: impl BufRead,
+    mut on_match: impl FnMut(Match) -> bool,
+) -> io::Result<u64> {
+    read_lines(reader, |line_no, offset, line, crlf| {
+        match matcher.find(line) {
+            Some(spans) => on_match(Match {
+                line_no,
+                offset,
+                line,
+                spans,
+                crlf,
+            }),
+            None => true,
+        }
+    })
+}
+
+/// Calls `on_line` with the number, offset, text and whether it ended in
+/// `\r\n` of every line in `reader`, without its line ending, until it
+/// returns `false`. Returns how many bytes were read.
+fn read_lines(
+    mut reader: impl BufRead,
+    mut on_line: impl FnMut(usize, u64, &str, bool) -> bool,
+) -> io::Result<u64> {
+    let mut buf = String::new();
+    let mut line_no = 0;
+    let mut bytes = 0;
+    loop {
+        buf.clear();
+        let read = reader.read_line(&mut buf)?;
+        if read == 0 {
+            return Ok(bytes);
+        }
+        line_no += 1;
+        let (skipped, line, crlf) = trim_line(&buf, line_no == 1);
+        if !on_line(line_no, bytes + skipped as u64, line, crlf) {
+            return Ok(bytes + read as u64);
+        }
+        bytes += read as u64;
+    }
+}
 
-    for line in results {
-        println!("{}", line);
+/// Splits a line read along with its ending into how many bytes lead up to
+/// the text to search, that text, and whether the line ended in `\r\n`. A
+/// UTF-8 byte order mark is dropped from the `first` line so `^` still
+/// matches there.
+pub(crate) fn trim_line(buf: &str, first: bool) -> (usize, &str, bool) {
+    let line = buf.strip_suffix('\n').unwrap_or(buf);
+    let (line, crlf) = match line.strip_suffix('\r') {
+        Some(line) if line.len() + 1 < buf.len() => (line, true),
+        _ => (line, false),
+    };
+    match line.strip_prefix('\u{feff}').filter(|_| first) {
+        Some(text) => ('\u{feff}'.len_utf8(), text, crlf),
+        None => (0, line, crlf),
     }
+}
 
-    Ok(())
+/// Counts the matching lines in `contents`.
+pub fn count_matches(matcher: &impl Matcher, contents: &str) -> usize {
+    matches(matcher, contents).count()
 }
 
-pub fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
-    contents
-        .lines()
-        .filter(|line| line.contains(query))
-        .collect()
+/// Whether any line of `contents` matches, stopping at the first hit.
+pub fn has_match(matcher: &impl Matcher, contents: &str) -> bool {
+    matches(matcher, contents).next().is_some()
 }
 
-pub fn search_case_insensitive<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
-    let query = query.to_lowercase();
+/// A single matching line, with its 1-based line number, the byte offset at
+/// which it starts and the byte range of every match within the line.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Match<'a> {
+    pub line_no: usize,
+    pub offset: u64,
+    pub line: &'a str,
+    pub spans: Vec<Range<usize>>,
+    /// Whether the line ended in `\r\n`, which isn't part of `line`.
+    pub crlf: bool,
+}
+
+impl Match<'_> {
+    /// The 1-based byte column at which the first match on the line starts.
+    pub fn column(&self) -> usize {
+        self.spans.first().map_or(1, |span| span.start + 1)
+    }
+}
+
+pub fn search<'a>(query: &str, contents: &'a str) -> Vec<Match<'a>> {
+    search_with(&Literal::new(query), contents)
+}
+
+pub fn search_case_insensitive<'a>(query: &str, contents: &'a str) -> Vec<Match<'a>> {
+    search_with(&CaseInsensitive::new(query), contents)
+}
+
+/// Searches `contents` line by line with any [`Matcher`].
+pub fn search_with<'a>(matcher: &impl Matcher, contents: &'a str) -> Vec<Match<'a>> {
+    matches(matcher, contents).collect()
+}
+
+/// Lazily searches `contents` line by line, so callers can stop early or
+/// stream matches without collecting them all first.
+pub fn matches<'a: 'm, 'm, M: Matcher + ?Sized>(
+    matcher: &'m M,
+    contents: &'a str,
+) -> impl Iterator<Item = Match<'a>> + 'm {
     contents
-        .lines()
-        .filter(|line| line.to_lowercase().contains(&query))
-        .collect()
+        .split_inclusive('\n')
+        .enumerate()
+        .filter_map(|(i, buf)| {
+            let (_, line, crlf) = trim_line(buf, i == 0);
+            matcher.find(line).map(|spans| Match {
+                line_no: i + 1,
+                // `line` is a slice of `contents`, so this is where it starts.
+                offset: (line.as_ptr() as usize - contents.as_ptr() as usize) as u64,
+                line,
+                spans,
+                crlf,
+            })
+        })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Write;
+
+    fn lines<'a>(matches: Vec<Match<'a>>) -> Vec<&'a str> {
+        matches.into_iter().map(|m| m.line).collect()
+    }
 
     #[test]
     fn one_result() {
@@ -70,7 +805,10 @@ Rust:
 safe, fast, productive.
 Pick three.";
 
-        assert_eq!(vec!["safe, fast, productive."], search(query, contents));
+        assert_eq!(
+            vec!["safe, fast, productive."],
+            lines(search(query, contents))
+        );
     }
 
     #[test]
@@ -82,7 +820,10 @@ safe, fast, productive.
 Pick three.
 Duct tape.";
 
-        assert_eq!(vec!["safe, fast, productive."], search(query, contents));
+        assert_eq!(
+            vec!["safe, fast, productive."],
+            lines(search(query, contents))
+        );
     }
 
     #[test]
@@ -96,7 +837,324 @@ Trust me.";
 
         assert_eq!(
             vec!["Rust:", "Trust me."],
-            search_case_insensitive(query, contents)
+            lines(search_case_insensitive(query, contents))
         )
     }
+
+    #[test]
+    fn match_reports_line_and_column() {
+        let contents = "\
+Rust:
+safe, fast, productive.";
+
+        assert_eq!(
+            vec![Match {
+                line_no: 2,
+                offset: 6,
+                line: "safe, fast, productive.",
+                spans: vec![Range { start: 15, end: 19 }],
+                crlf: false,
+            }],
+            search("duct", contents)
+        );
+        assert_eq!(16, search("duct", contents)[0].column());
+    }
+
+    #[test]
+    fn case_insensitive_column_maps_to_original_line() {
+        let contents = "İ rust";
+
+        assert_eq!(4, search_case_insensitive("RUST", contents)[0].column());
+    }
+
+    #[test]
+    fn case_insensitive_spans_cover_original_text() {
+        let m = &search_case_insensitive("rust", "İ Rust, rUST")[0];
+
+        assert_eq!(vec![3..7, 9..13], m.spans);
+        assert_eq!("Rust", &m.line[m.spans[0].clone()]);
+    }
+
+    #[test]
+    fn counts_and_detects_matches() {
+        let contents = "\
+Rust:
+safe, fast, productive.
+Trust me.";
+        let matcher = CaseInsensitive::new("rust");
+
+        assert_eq!(2, count_matches(&matcher, contents));
+        assert!(has_match(&matcher, contents));
+        assert!(!has_match(&Literal::new("duct tape"), contents));
+    }
+
+    #[test]
+    fn search_reader_streams_large_inputs() {
+        let path = std::env::temp_dir().join(format!("minigrep-large-{}.txt", std::process::id()));
+        {
+            let mut file = io::BufWriter::new(std::fs::File::create(&path).unwrap());
+            for i in 0..500_000 {
+                if i % 1000 == 0 {
+                    writeln!(file, "line {i}: needle in a haystack").unwrap();
+                } else {
+                    writeln!(file, "line {i}: just hay, nothing to see here").unwrap();
+                }
+            }
+        }
+
+        let reader = io::BufReader::new(std::fs::File::open(&path).unwrap());
+        let mut line_nos = Vec::new();
+        search_reader(&Literal::new("needle"), reader, |m| {
+            line_nos.push(m.line_no);
+            true
+        })
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(500, line_nos.len());
+        assert_eq!(Some(&499_001), line_nos.last());
+    }
+
+    #[test]
+    fn search_reader_stops_when_asked() {
+        let mut seen = 0;
+        let bytes = search_reader(&Literal::new("a"), "a\r\nb\na\n".as_bytes(), |m| {
+            assert_eq!("a", m.line);
+            seen += 1;
+            false
+        })
+        .unwrap();
+        assert_eq!(1, seen);
+        assert_eq!(3, bytes);
+    }
+
+    #[test]
+    fn search_file_returns_structured_results() {
+        let config = Config::builder()
+            .query("nobody")
+            .path("poem.txt")
+            .path("no-such-file.txt")
+            .threads(1)
+            .build()
+            .unwrap();
+
+        let results = search_file(&config).unwrap();
+
+        assert_eq!(2, results.len());
+        let poem = results.iter().find(|file| file.path == "poem.txt").unwrap();
+        assert_eq!(2, poem.count);
+        assert_eq!(
+            vec![1, 2],
+            poem.matches.iter().map(|m| m.line_no).collect::<Vec<_>>()
+        );
+        let missing = results
+            .iter()
+            .find(|file| file.path == "no-such-file.txt")
+            .unwrap();
+        assert!(matches!(missing.error, Some(MinigrepError::Io { .. })));
+    }
+
+    #[test]
+    fn matches_are_lazy() {
+        let contents = "a1\nb\na2\na3";
+        let matcher = Literal::new("a");
+
+        let mut found = matches(&matcher, contents);
+        assert_eq!(Some("a1"), found.next().map(|m| m.line));
+        assert_eq!(
+            vec![3],
+            found.take(1).map(|m| m.line_no).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn summary_exit_codes() {
+        let found = Summary {
+            matches: 3,
+            ..Summary::default()
+        };
+        let none = Summary::default();
+        let failed = Summary {
+            matches: 3,
+            errors: 1,
+            ..Summary::default()
+        };
+
+        assert_eq!(0, found.exit_code());
+        assert_eq!(1, none.exit_code());
+        assert_eq!(2, failed.exit_code());
+    }
+
+    #[test]
+    fn tracks_byte_offsets() {
+        let contents = "a\r\nb\nab\n";
+        let offsets: Vec<_> = matches(&Literal::new("b"), contents)
+            .map(|m| m.offset)
+            .collect();
+        assert_eq!(vec![3, 5], offsets);
+
+        let mut streamed = Vec::new();
+        search_reader(&Literal::new("b"), contents.as_bytes(), |m| {
+            streamed.push(m.offset);
+            true
+        })
+        .unwrap();
+        assert_eq!(offsets, streamed);
+    }
+
+    #[test]
+    fn passthru_keeps_every_line() {
+        let config = Config::builder()
+            .query("nobody")
+            .path("poem.txt")
+            .passthru(true)
+            .build()
+            .unwrap();
+
+        let results = search_file(&config).unwrap();
+
+        assert_eq!(2, results[0].count);
+        assert_eq!(9, results[0].matches.len());
+        assert!(results[0].matches[2].spans.is_empty());
+    }
+
+    #[test]
+    fn passthru_streams_lines_to_a_sink() {
+        let config = Config::builder()
+            .query("nobody")
+            .path("poem.txt")
+            .passthru(true)
+            .build()
+            .unwrap();
+        let (search, inputs) = Search::new(&config).unwrap();
+        let seen = std::sync::Mutex::new(Vec::new());
+        let sink = |file: &FileMatches, m: &LineMatch| {
+            seen.lock().unwrap().push((file.streamed, m.line_no));
+        };
+
+        let mut results = Vec::new();
+        search.each(inputs, Some(&sink), |file| results.push(file));
+
+        assert!(results[0].matches.is_empty());
+        assert_eq!(9, results[0].streamed);
+        assert!(results[0].has_lines());
+        let seen = seen.into_inner().unwrap();
+        assert_eq!((1, 1), seen[0]);
+        assert_eq!((9, 9), seen[8]);
+    }
+
+    #[test]
+    fn skips_files_over_the_limits() {
+        // poem.txt is a few hundred bytes; Cargo.toml is bigger.
+        let config = Config::builder()
+            .query("nobody")
+            .path("Cargo.toml")
+            .path("poem.txt")
+            .max_filesize(300)
+            .build()
+            .unwrap();
+        let (search, inputs) = Search::new(&config).unwrap();
+        assert_eq!(1, search.skipped);
+        assert_eq!("poem.txt", inputs[0].as_ref().unwrap());
+
+        let config = Config::builder()
+            .query("nobody")
+            .path("poem.txt")
+            .path("Cargo.toml")
+            .path("no-such-file.txt")
+            .max_files(1)
+            .build()
+            .unwrap();
+        let (search, inputs) = Search::new(&config).unwrap();
+        assert_eq!(2, search.skipped);
+        assert_eq!("poem.txt", inputs[0].as_ref().unwrap());
+    }
+
+    #[test]
+    fn stops_walking_at_max_files() {
+        let root = test_support::tree("max-files", &[("a.txt", "x\n"), ("b.txt", "x\n")]);
+        std::fs::write(root.join("a-big.txt"), "x\n".repeat(100)).unwrap();
+        let config = Config::builder()
+            .query("x")
+            .path(root.display().to_string())
+            .max_files(1)
+            .max_filesize(10)
+            .build()
+            .unwrap();
+
+        let (search, inputs) = Search::new(&config).unwrap();
+        // b.txt was never reached, so only a-big.txt counts as skipped.
+        assert_eq!(1, inputs.len());
+        assert!(inputs[0].as_ref().unwrap().ends_with("/a.txt"));
+        assert_eq!(1, search.skipped);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn walks_past_binary_files() {
+        let root = test_support::tree("binary", &[("notes.txt", "hello there\n")]);
+        std::fs::write(root.join("app.bin"), b"hello\0\x7fELF").unwrap();
+        std::fs::write(root.join("latin1.txt"), b"hello caf\xe9\n").unwrap();
+
+        let config = Config::builder()
+            .query("hello")
+            .path(root.to_str().unwrap())
+            .build()
+            .unwrap();
+        let (search, inputs) = Search::new(&config).unwrap();
+        let mut summary = Summary::default();
+        let mut matched = Vec::new();
+        search.each(inputs, None, |file| {
+            summary.add(&file);
+            if file.count > 0 {
+                matched.push(file.path);
+            }
+        });
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(0, summary.errors);
+        assert_eq!(2, summary.skipped);
+        assert_eq!(1, summary.files);
+        assert_eq!(0, summary.exit_code());
+        assert!(matched[0].ends_with("notes.txt"), "{matched:?}");
+    }
+
+    #[test]
+    fn stops_at_the_timeout() {
+        let config = Config::builder()
+            .query("nobody")
+            .path("src")
+            .path("poem.txt")
+            .timeout(Duration::ZERO)
+            .build()
+            .unwrap();
+        let (search, inputs) = Search::new(&config).unwrap();
+        let mut searched = Vec::new();
+        assert!(search.each(inputs, None, |file| searched.push(file)));
+        assert!(searched.iter().all(|file| file.count == 0));
+    }
+
+    #[test]
+    fn tolerates_crlf_and_byte_order_marks() {
+        let contents = "\u{feff}Rust:\r\nsafe\r\nRust:";
+        let matcher = matcher::Regex::new("^Rust:$", false).unwrap();
+
+        let eager: Vec<_> = matches(&matcher, contents).collect();
+        let mut streamed = Vec::new();
+        search_reader(&matcher, contents.as_bytes(), |m| {
+            streamed.push((m.line_no, m.offset, m.crlf));
+            true
+        })
+        .unwrap();
+
+        assert_eq!(vec![(1, 3, true), (3, 16, false)], streamed);
+        assert_eq!(
+            streamed,
+            eager
+                .iter()
+                .map(|m| (m.line_no, m.offset, m.crlf))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["Rust:", "Rust:"], lines(eager));
+    }
 }
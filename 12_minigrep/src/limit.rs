@@ -0,0 +1,206 @@
+use std::io::{self, BufRead, Read};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// A run-wide cap on how many matches may be reported (`--max-total`) and
+/// how long the run may take (`--timeout`), shared between the worker
+/// threads.
+///
+/// Once the cap is used up or the deadline passes the budget is marked as
+/// cancelled, which tells every worker to stop starting on new files.
+#[derive(Debug, Default)]
+pub struct Budget {
+    remaining: Option<AtomicUsize>,
+    cancelled: AtomicBool,
+    deadline: Option<Instant>,
+    timed_out: AtomicBool,
+}
+
+impl Budget {
+    pub fn new(max_total: Option<usize>) -> Self {
+        Budget {
+            remaining: max_total.map(AtomicUsize::new),
+            cancelled: AtomicBool::new(false),
+            deadline: None,
+            timed_out: AtomicBool::new(false),
+        }
+    }
+
+    pub fn deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Whether the deadline has passed, cancelling the run the first time
+    /// it's noticed.
+    pub fn timed_out(&self) -> bool {
+        if self.timed_out.load(Ordering::SeqCst) {
+            return true;
+        }
+        if self
+            .deadline
+            .is_none_or(|deadline| Instant::now() < deadline)
+        {
+            return false;
+        }
+        self.timed_out.store(true, Ordering::SeqCst);
+        self.cancel();
+        true
+    }
+
+    /// Claims one match from the budget, or returns `false` (and cancels the
+    /// run) if none are left.
+    pub fn take(&self) -> bool {
+        let Some(remaining) = &self.remaining else {
+            return true;
+        };
+        let taken = remaining
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok();
+        if !taken {
+            self.cancel();
+        }
+        taken
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.timed_out() || self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// A reader that runs dry once its budget times out, so a file being
+/// searched when the deadline passes ends early with what was found so far.
+pub struct Interruptible<'a, R> {
+    inner: R,
+    budget: &'a Budget,
+}
+
+impl<'a, R: BufRead> Interruptible<'a, R> {
+    pub fn new(inner: R, budget: &'a Budget) -> Self {
+        Interruptible { inner, budget }
+    }
+}
+
+impl<R: BufRead> Read for Interruptible<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.budget.timed_out() {
+            return Ok(0);
+        }
+        self.inner.read(buf)
+    }
+}
+
+impl<R: BufRead> BufRead for Interruptible<'_, R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.budget.timed_out() {
+            return Ok(&[]);
+        }
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount);
+    }
+}
+
+/// Applies the per-file (`-m`) and run-wide limits to one file's matches.
+pub struct FileLimit<'a> {
+    max_count: Option<usize>,
+    count: usize,
+    budget: &'a Budget,
+}
+
+impl<'a> FileLimit<'a> {
+    pub fn new(max_count: Option<usize>, budget: &'a Budget) -> Self {
+        FileLimit {
+            max_count,
+            count: 0,
+            budget,
+        }
+    }
+
+    /// Whether another match may be reported. Callers should stop searching
+    /// the file as soon as this returns `false`.
+    pub fn admit(&mut self) -> bool {
+        if self.is_full() || !self.budget.take() {
+            return false;
+        }
+        self.count += 1;
+        true
+    }
+
+    /// Whether the file has reached its `-m` limit, so reading further is
+    /// pointless.
+    pub fn is_full(&self) -> bool {
+        self.max_count.is_some_and(|max| self.count >= max)
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn unlimited_budget_always_admits() {
+        let budget = Budget::new(None);
+        assert!((0..1000).all(|_| budget.take()));
+        assert!(!budget.is_cancelled());
+    }
+
+    #[test]
+    fn budget_is_shared_across_threads() {
+        let budget = Budget::new(Some(100));
+        let taken: usize = thread::scope(|s| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| s.spawn(|| (0..50).filter(|_| budget.take()).count()))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).sum()
+        });
+
+        assert_eq!(100, taken);
+        assert!(budget.is_cancelled());
+    }
+
+    #[test]
+    fn deadline_cancels_the_run() {
+        let budget = Budget::new(None).deadline(Some(Instant::now()));
+        assert!(budget.timed_out());
+        assert!(budget.is_cancelled());
+
+        let mut text = String::new();
+        Interruptible::new("some text".as_bytes(), &budget)
+            .read_line(&mut text)
+            .unwrap();
+        assert_eq!("", text);
+
+        let budget = Budget::new(None).deadline(Some(Instant::now() + Duration::from_secs(60)));
+        assert!(!budget.is_cancelled());
+    }
+
+    #[test]
+    fn file_limit_stops_at_max_count() {
+        let budget = Budget::new(Some(10));
+        let mut limit = FileLimit::new(Some(2), &budget);
+
+        assert!(limit.admit());
+        assert!(limit.admit());
+        assert!(limit.is_full());
+        assert!(!limit.admit());
+        assert_eq!(2, limit.count());
+        assert!(!budget.is_cancelled());
+
+        let mut none = FileLimit::new(Some(0), &budget);
+        assert!(none.is_full());
+        assert!(!none.admit());
+    }
+}
@@ -0,0 +1,110 @@
+//! A small logging facade for explaining what a search did (`--verbose`).
+//! Programs that embed minigrep can capture the messages with
+//! [`set_logger`]; nothing is logged until a logger is set.
+
+use std::sync::{PoisonError, RwLock};
+
+/// How much detail a message carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Decisions about the whole search, like which files were skipped and
+    /// which matcher was picked (`--verbose`).
+    Info,
+    /// Details about each file, like how long it took to search (`-vv`).
+    Debug,
+}
+
+/// Receives log messages.
+pub trait Logger: Send + Sync {
+    /// Whether messages at `level` are wanted, checked before they're built.
+    fn enabled(&self, level: Level) -> bool;
+
+    fn log(&self, level: Level, message: &str);
+}
+
+/// Prints messages up to some level on stderr.
+#[derive(Debug, Clone, Copy)]
+pub struct StderrLogger {
+    level: Level,
+}
+
+impl StderrLogger {
+    pub fn new(level: Level) -> Self {
+        StderrLogger { level }
+    }
+}
+
+impl Logger for StderrLogger {
+    fn enabled(&self, level: Level) -> bool {
+        level <= self.level
+    }
+
+    fn log(&self, _level: Level, message: &str) {
+        eprintln!("minigrep: {message}");
+    }
+}
+
+static LOGGER: RwLock<Option<Box<dyn Logger>>> = RwLock::new(None);
+
+/// Sends every later message to `logger`, replacing any logger set before.
+pub fn set_logger(logger: Box<dyn Logger>) {
+    *LOGGER.write().unwrap_or_else(PoisonError::into_inner) = Some(logger);
+}
+
+pub(crate) fn info(message: impl FnOnce() -> String) {
+    log(Level::Info, message);
+}
+
+pub(crate) fn debug(message: impl FnOnce() -> String) {
+    log(Level::Debug, message);
+}
+
+fn log(level: Level, message: impl FnOnce() -> String) {
+    let logger = LOGGER.read().unwrap_or_else(PoisonError::into_inner);
+    if let Some(logger) = logger.as_deref() {
+        if logger.enabled(level) {
+            logger.log(level, &message());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    struct Capture(Arc<Mutex<Vec<String>>>);
+
+    impl Logger for Capture {
+        fn enabled(&self, level: Level) -> bool {
+            level == Level::Info
+        }
+
+        fn log(&self, _level: Level, message: &str) {
+            self.0.lock().unwrap().push(message.to_string());
+        }
+    }
+
+    #[test]
+    fn sends_enabled_messages_to_the_logger() {
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        set_logger(Box::new(Capture(Arc::clone(&messages))));
+
+        // Other tests may log at the same time, so only look for our own.
+        info(|| String::from("log-test: kept"));
+        debug(|| panic!("built a message nobody wants"));
+        let messages = messages.lock().unwrap();
+        let ours: Vec<_> = messages
+            .iter()
+            .filter(|message| message.starts_with("log-test"))
+            .collect();
+        assert_eq!(vec!["log-test: kept"], ours);
+    }
+
+    #[test]
+    fn stderr_logger_filters_by_level() {
+        assert!(StderrLogger::new(Level::Info).enabled(Level::Info));
+        assert!(!StderrLogger::new(Level::Info).enabled(Level::Debug));
+        assert!(StderrLogger::new(Level::Debug).enabled(Level::Info));
+    }
+}
@@ -1,18 +1,63 @@
 use std::env;
+use std::path::Path;
 use std::process;
 
-use minigrep::Config;
+use minigrep::serve::{self, ServeOptions};
+use minigrep::{index, Config, MinigrepError, Summary};
 
 fn main() {
     // std::env::args returns an iterator over the CLI arguments passed into this binary.
-    let config = Config::new(env::args()).unwrap_or_else(|err| {
-        eprintln!("Problem parsing arguments: {err}");
-        process::exit(1);
-    });
+    let mut args: Vec<String> = env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("index") => {
+            let Some(dir) = args.get(2) else {
+                eprintln!("Problem parsing arguments: index needs a directory");
+                process::exit(2);
+            };
+            match index::create(Path::new(dir)) {
+                Ok(files) => eprintln!("Indexed {files} files into {dir}/{}", index::INDEX_FILE),
+                Err(e) => {
+                    eprintln!("Application error: {e}");
+                    process::exit(2);
+                }
+            }
+        }
+        Some("serve") => {
+            let options = ServeOptions::parse(args.drain(2..)).unwrap_or_else(|err| {
+                eprintln!("Problem parsing arguments: {err}");
+                process::exit(2);
+            });
+            if let Err(e) = serve::serve(&options) {
+                eprintln!("Application error: {e}");
+                process::exit(2);
+            }
+        }
+        Some("query") => {
+            args.remove(1);
+            let config = parse(args);
+            exit_with(index::query(config));
+        }
+        _ => {
+            let config = parse(args);
+            exit_with(minigrep::run(config));
+        }
+    }
+}
 
-    if let Err(e) = minigrep::run(config) {
-        eprintln!("Application error: {e}");
+fn parse(args: Vec<String>) -> Config {
+    Config::new(args.into_iter()).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {err}");
+        process::exit(2);
+    })
+}
 
-        process::exit(1);
+fn exit_with(result: Result<Summary, MinigrepError>) -> ! {
+    // Like grep: 0 if something matched, 1 if nothing did, 2 on errors.
+    match result {
+        Ok(summary) => process::exit(summary.exit_code()),
+        Err(e) => {
+            eprintln!("Application error: {e}");
+            process::exit(2);
+        }
     }
 }
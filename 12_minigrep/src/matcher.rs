@@ -0,0 +1,446 @@
+use std::ops::Range;
+
+use crate::casefold::{self, Folded};
+
+/// Decides whether a line matches, and where.
+pub trait Matcher: Send + Sync {
+    /// Returns the byte ranges of the matches in `line`, or `None` if the
+    /// line doesn't match at all. A matching line may have no spans, e.g.
+    /// when the match is inverted.
+    fn find(&self, line: &str) -> Option<Vec<Range<usize>>>;
+
+    /// Substitutes `replacement` for every match in `line`.
+    fn replace(&self, line: &str, replacement: &str) -> String {
+        let Some(spans) = self.find(line) else {
+            return line.to_string();
+        };
+        let mut out = String::with_capacity(line.len());
+        let mut last = 0;
+        for span in spans {
+            out.push_str(&line[last..span.start]);
+            out.push_str(replacement);
+            last = span.end;
+        }
+        out.push_str(&line[last..]);
+        out
+    }
+
+    /// What `replacement` becomes in place of the match at `span` of `line`,
+    /// for printing each match on its own with `-o`.
+    fn replace_at(&self, _line: &str, _span: &Range<usize>, replacement: &str) -> String {
+        replacement.to_string()
+    }
+}
+
+/// Matches a fixed string exactly.
+pub struct Literal {
+    query: String,
+}
+
+impl Literal {
+    pub fn new(query: &str) -> Self {
+        Literal {
+            query: query.to_string(),
+        }
+    }
+}
+
+impl Matcher for Literal {
+    fn find(&self, line: &str) -> Option<Vec<Range<usize>>> {
+        let spans: Vec<_> = line
+            .match_indices(self.query.as_str())
+            .map(|(start, m)| start..start + m.len())
+            .collect();
+        (!spans.is_empty()).then_some(spans)
+    }
+}
+
+/// Matches a fixed string regardless of case, using Unicode simple case
+/// folding.
+pub struct CaseInsensitive {
+    query: String,
+}
+
+impl CaseInsensitive {
+    pub fn new(query: &str) -> Self {
+        CaseInsensitive {
+            query: casefold::fold_str(query),
+        }
+    }
+}
+
+impl Matcher for CaseInsensitive {
+    fn find(&self, line: &str) -> Option<Vec<Range<usize>>> {
+        let folded = Folded::new(line);
+        let spans: Vec<_> = folded
+            .text
+            .match_indices(self.query.as_str())
+            .map(|(start, m)| folded.origin(start)..folded.origin(start + m.len()))
+            .collect();
+        (!spans.is_empty()).then_some(spans)
+    }
+}
+
+/// Matches a regular expression, e.g. `fn \w+\(`.
+pub struct Regex {
+    engine: Engine,
+    /// With several patterns, each one compiled on its own, as joining them
+    /// renumbers their capture groups. Replacements expand the captures of
+    /// whichever pattern matched.
+    alternatives: Vec<Engine>,
+}
+
+/// The fast engine handles everything except lookaround and backreferences,
+/// which need the backtracking one.
+enum Engine {
+    Fast(regex::Regex),
+    #[cfg(feature = "fancy")]
+    Backtracking(fancy_regex::Regex),
+}
+
+impl Regex {
+    pub fn new(pattern: &str, ignore_case: bool) -> Result<Self, regex::Error> {
+        #[cfg(feature = "fancy")]
+        if needs_backtracking(pattern) {
+            let regex = fancy_regex::RegexBuilder::new(pattern)
+                .case_insensitive(ignore_case)
+                .build()
+                .map_err(|e| regex::Error::Syntax(e.to_string()))?;
+            return Ok(Regex {
+                engine: Engine::Backtracking(regex),
+                alternatives: Vec::new(),
+            });
+        }
+        let regex = regex::RegexBuilder::new(pattern)
+            .case_insensitive(ignore_case)
+            .build()?;
+        Ok(Regex {
+            engine: Engine::Fast(regex),
+            alternatives: Vec::new(),
+        })
+    }
+
+    /// Whether the backtracking engine was picked for this pattern.
+    #[cfg(feature = "fancy")]
+    pub fn is_backtracking(&self) -> bool {
+        matches!(self.engine, Engine::Backtracking(_))
+    }
+
+    /// Combines several patterns into one regex that matches wherever any of
+    /// them does, so a line is scanned once however many patterns there are.
+    /// Unless `regex` is set the patterns are taken literally.
+    pub fn any(patterns: &[String], regex: bool, ignore_case: bool) -> Result<Self, regex::Error> {
+        let alternation: Vec<_> = patterns
+            .iter()
+            .map(|pattern| {
+                if regex {
+                    format!("(?:{pattern})")
+                } else {
+                    regex::escape(pattern)
+                }
+            })
+            .collect();
+        let mut combined = Regex::new(&alternation.join("|"), ignore_case)?;
+        if regex && patterns.len() > 1 {
+            combined.alternatives = patterns
+                .iter()
+                .map(|pattern| Ok(Regex::new(pattern, ignore_case)?.engine))
+                .collect::<Result<_, regex::Error>>()?;
+        }
+        Ok(combined)
+    }
+}
+
+impl Engine {
+    /// If this regex matches exactly `span` of `line`, appends `replacement`
+    /// with that match's captures expanded to `out`.
+    fn expand_at(
+        &self,
+        line: &str,
+        span: &Range<usize>,
+        replacement: &str,
+        out: &mut String,
+    ) -> bool {
+        match self {
+            Engine::Fast(regex) => match regex.captures_at(line, span.start) {
+                Some(captures) if captures.get(0).is_some_and(|m| m.range() == *span) => {
+                    captures.expand(replacement, out);
+                    true
+                }
+                _ => false,
+            },
+            #[cfg(feature = "fancy")]
+            Engine::Backtracking(regex) => match regex.captures_from_pos(line, span.start) {
+                Ok(Some(captures)) if captures.get(0).is_some_and(|m| m.range() == *span) => {
+                    captures.expand(replacement, out);
+                    true
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Matcher for Regex {
+    fn find(&self, line: &str) -> Option<Vec<Range<usize>>> {
+        let spans: Vec<_> = match &self.engine {
+            Engine::Fast(regex) => regex.find_iter(line).map(|m| m.range()).collect(),
+            // A match that backtracks too far is treated as no match.
+            #[cfg(feature = "fancy")]
+            Engine::Backtracking(regex) => regex
+                .find_iter(line)
+                .map_while(Result::ok)
+                .map(|m| m.range())
+                .collect(),
+        };
+        (!spans.is_empty()).then_some(spans)
+    }
+
+    /// Expands `$1`, `${name}` and friends from each match's captures.
+    fn replace(&self, line: &str, replacement: &str) -> String {
+        if !self.alternatives.is_empty() {
+            let Some(spans) = self.find(line) else {
+                return line.to_string();
+            };
+            let mut out = String::with_capacity(line.len());
+            let mut last = 0;
+            for span in spans {
+                out.push_str(&line[last..span.start]);
+                let expanded = self
+                    .alternatives
+                    .iter()
+                    .any(|alternative| alternative.expand_at(line, &span, replacement, &mut out));
+                if !expanded {
+                    out.push_str(&line[span.clone()]);
+                }
+                last = span.end;
+            }
+            out.push_str(&line[last..]);
+            return out;
+        }
+        match &self.engine {
+            Engine::Fast(regex) => regex.replace_all(line, replacement).into_owned(),
+            #[cfg(feature = "fancy")]
+            Engine::Backtracking(regex) => regex.replace_all(line, replacement).into_owned(),
+        }
+    }
+
+    fn replace_at(&self, line: &str, span: &Range<usize>, replacement: &str) -> String {
+        let engines = match self.alternatives.is_empty() {
+            true => std::slice::from_ref(&self.engine),
+            false => &self.alternatives,
+        };
+        let mut out = String::new();
+        if !engines
+            .iter()
+            .any(|engine| engine.expand_at(line, span, replacement, &mut out))
+        {
+            out.push_str(replacement);
+        }
+        out
+    }
+}
+
+/// Whether `pattern` uses lookaround (`(?=`, `(?!`, `(?<=`, `(?<!`) or
+/// backreferences (`\1`, `\k<name>`), which only the backtracking engine
+/// supports.
+#[cfg(feature = "fancy")]
+fn needs_backtracking(pattern: &str) -> bool {
+    let mut chars = pattern.chars().peekable();
+    let mut in_class = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('1'..='9' | 'k') if !in_class => return true,
+                _ => {}
+            },
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '(' if !in_class && chars.peek() == Some(&'?') => {
+                let rest: String = chars.clone().skip(1).take(3).collect();
+                if ["=", "!", "<=", "<!"]
+                    .iter()
+                    .any(|look| rest.starts_with(look))
+                {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Matches exactly the lines the inner matcher doesn't.
+pub struct Invert<M> {
+    inner: M,
+}
+
+impl<M: Matcher> Invert<M> {
+    pub fn new(inner: M) -> Self {
+        Invert { inner }
+    }
+}
+
+impl<M: Matcher> Matcher for Invert<M> {
+    fn find(&self, line: &str) -> Option<Vec<Range<usize>>> {
+        match self.inner.find(line) {
+            Some(_) => None,
+            None => Some(Vec::new()),
+        }
+    }
+}
+
+impl<M: Matcher + ?Sized> Matcher for Box<M> {
+    fn find(&self, line: &str) -> Option<Vec<Range<usize>>> {
+        (**self).find(line)
+    }
+
+    fn replace(&self, line: &str, replacement: &str) -> String {
+        (**self).replace(line, replacement)
+    }
+
+    fn replace_at(&self, line: &str, span: &Range<usize>, replacement: &str) -> String {
+        (**self).replace_at(line, span, replacement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invert_flips_literal() {
+        let matcher = Invert::new(Literal::new("duct"));
+
+        assert_eq!(None, matcher.find("safe, fast, productive."));
+        assert_eq!(Some(Vec::new()), matcher.find("Pick three."));
+    }
+
+    #[test]
+    fn invert_composes_with_case_insensitive() {
+        let matcher = Invert::new(CaseInsensitive::new("rust"));
+
+        assert_eq!(None, matcher.find("Trust me."));
+        assert_eq!(Some(Vec::new()), matcher.find("Pick three."));
+    }
+
+    #[test]
+    fn regex_finds_every_match() {
+        let matcher = Regex::new(r"\b[fp]\w+", false).unwrap();
+
+        assert_eq!(
+            Some(vec![6..10, 12..22]),
+            matcher.find("safe, fast, productive.")
+        );
+        assert_eq!(None, Regex::new("RUST", false).unwrap().find("Rust:"));
+        assert!(Regex::new("RUST", true).unwrap().find("Rust:").is_some());
+    }
+
+    #[test]
+    fn literal_replace() {
+        let matcher = Literal::new("duct");
+
+        assert_eq!(
+            "proTAPEive, TAPE",
+            matcher.replace("productive, duct", "TAPE")
+        );
+        assert_eq!("Pick three.", matcher.replace("Pick three.", "TAPE"));
+    }
+
+    #[test]
+    fn regex_replace_expands_captures() {
+        let matcher = Regex::new(r"(\w+), (\w+)", false).unwrap();
+
+        assert_eq!("fast, safe.", matcher.replace("safe, fast.", "$2, $1"));
+    }
+
+    #[test]
+    fn any_matches_every_pattern() {
+        let patterns = vec![String::from("fast"), String::from("(three)")];
+        let matcher = Regex::any(&patterns, false, false).unwrap();
+
+        assert!(matcher.find("safe, fast, productive.").is_some());
+        assert_eq!(None, matcher.find("Pick three."));
+        assert!(matcher.find("Pick (three).").is_some());
+
+        let matcher = Regex::any(&patterns, true, true).unwrap();
+        assert_eq!(Some(vec![5..10, 12..16]), matcher.find("Pick THREE, fast"));
+    }
+
+    #[test]
+    fn any_replaces_with_the_matching_patterns_captures() {
+        let patterns = vec![String::from("(a)x"), String::from("(b)y")];
+        let matcher = Regex::any(&patterns, true, false).unwrap();
+
+        assert_eq!("[a] [b]", matcher.replace("ax by", "[$1]"));
+    }
+
+    #[test]
+    fn replaces_a_single_match() {
+        let matcher = Regex::new(r"(\w)x", false).unwrap();
+        assert_eq!("[b]", matcher.replace_at("ax bx", &(3..5), "[$1]"));
+
+        let patterns = vec![String::from("(a)x"), String::from("(b)y")];
+        let matcher = Regex::any(&patterns, true, false).unwrap();
+        assert_eq!("[b]", matcher.replace_at("ax by", &(3..5), "[$1]"));
+
+        assert_eq!(
+            "[$1]",
+            Literal::new("by").replace_at("ax by", &(3..5), "[$1]")
+        );
+    }
+
+    #[cfg(feature = "fancy")]
+    #[test]
+    fn picks_engine_from_pattern() {
+        assert!(needs_backtracking(r"foo(?=bar)"));
+        assert!(needs_backtracking(r"(?<!x)y"));
+        assert!(needs_backtracking(r"(\w+) \1"));
+        assert!(!needs_backtracking(r"(?i)foo"));
+        assert!(!needs_backtracking(r"(?P<word>\w+)"));
+        assert!(!needs_backtracking(r"[\1(?=]"));
+        assert!(!needs_backtracking(r"\\1"));
+
+        assert!(!Regex::new("fo+", false).unwrap().is_backtracking());
+        assert!(Regex::new("fo(?=o)", false).unwrap().is_backtracking());
+    }
+
+    #[cfg(feature = "fancy")]
+    #[test]
+    fn lookaround_and_backreferences() {
+        let lookahead = Regex::new(r"foo(?=bar)", false).unwrap();
+        assert_eq!(
+            Some(vec![Range { start: 7, end: 10 }]),
+            lookahead.find("foobaz foobar")
+        );
+
+        let repeated = Regex::new(r"\b(\w+) \1\b", false).unwrap();
+        assert_eq!(
+            Some(vec![Range { start: 3, end: 8 }]),
+            repeated.find("it is is fine")
+        );
+        assert_eq!("it X fine", repeated.replace("it is is fine", "X"));
+    }
+
+    #[test]
+    fn case_insensitive_uses_unicode_folding() {
+        // Final and medial sigma fold together.
+        let sigma = CaseInsensitive::new("ΟΔΟΣ");
+        assert_eq!(Some(vec![Range { start: 0, end: 8 }]), sigma.find("οδος"));
+        assert!(sigma.find("ΟΔΟσ").is_some());
+
+        // 'ẞ' and 'ß' fold together, but simple folding doesn't expand 'ß'
+        // to "ss".
+        let eszett = CaseInsensitive::new("STRAẞE");
+        assert!(eszett.find("Straße").is_some());
+        assert!(eszett.find("Strasse").is_none());
+
+        // 'İ' has no simple folding, so it only matches itself.
+        let dotted = CaseInsensitive::new("İ");
+        assert!(dotted.find("İstanbul").is_some());
+        assert!(dotted.find("istanbul").is_none());
+
+        assert!(CaseInsensitive::new("ſun").find("SUN").is_some());
+    }
+}
@@ -0,0 +1,701 @@
+use std::borrow::Cow;
+use std::fmt;
+#[cfg(feature = "cli")]
+use std::io::{self, IsTerminal};
+use std::ops::Range;
+use std::path::MAIN_SEPARATOR;
+
+use crate::color::{Style, Theme};
+use crate::matcher::Matcher;
+use crate::{Config, FileMatches, LineMatch, OutputMode, Summary};
+
+/// How file names appear alongside matching lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filenames {
+    Hidden,
+    /// Before every line, as `file:line`.
+    Prefixed,
+    /// Once above each file's lines (`--heading`).
+    Heading,
+}
+
+impl Filenames {
+    /// Picks the style for a search that should `show` file names. Headings
+    /// are used on a terminal unless `--heading` or `--no-heading` says
+    /// otherwise, and only when whole lines are printed. Library callers
+    /// pick a variant themselves instead.
+    #[cfg(feature = "cli")]
+    pub fn choose(config: &Config, show: bool) -> Self {
+        if !show {
+            return Filenames::Hidden;
+        }
+        let heading = config.heading.unwrap_or_else(|| io::stdout().is_terminal());
+        if heading && config.output == OutputMode::Lines {
+            Filenames::Heading
+        } else {
+            Filenames::Prefixed
+        }
+    }
+}
+
+/// Renders everything that should be printed for one searched file, the way
+/// [`TextPrinter`](crate::printer::TextPrinter) prints it.
+#[cfg(test)]
+pub fn format_file(
+    config: &Config,
+    matcher: &dyn Matcher,
+    file: &FileMatches,
+    filenames: Filenames,
+    colored: bool,
+) -> String {
+    let mut out = format_begin(config, file, filenames, colored);
+    for m in &file.matches {
+        out.push_str(&format_line(config, matcher, file, m, filenames, colored));
+    }
+    out.push_str(&format_end(config, file, filenames, colored));
+    out
+}
+
+/// What comes before a file's lines: its name, when it's a heading.
+pub(crate) fn format_begin(
+    config: &Config,
+    file: &FileMatches,
+    filenames: Filenames,
+    colored: bool,
+) -> String {
+    if config.write
+        || config.output != OutputMode::Lines
+        || filenames != Filenames::Heading
+        || !file.has_lines()
+    {
+        return String::new();
+    }
+    let terminator = if config.null { '\0' } else { '\n' };
+    format!("{}{terminator}", painted_path(config, file, colored))
+}
+
+/// One matching line, or each of its matches with `-o`.
+pub(crate) fn format_line(
+    config: &Config,
+    matcher: &dyn Matcher,
+    file: &FileMatches,
+    m: &LineMatch,
+    filenames: Filenames,
+    colored: bool,
+) -> String {
+    let mut out = String::new();
+    if config.write || config.output != OutputMode::Lines {
+        return out;
+    }
+    let theme = colored.then_some(&config.theme);
+    let path = painted_path(config, file, colored);
+    let filename = (filenames == Filenames::Prefixed).then_some(path.as_str());
+    write_match(&mut out, config, matcher, filename, m, theme);
+    out
+}
+
+/// What comes after a file's lines: its count with `-c`, or its name with
+/// `-l` if it matched.
+pub(crate) fn format_end(
+    config: &Config,
+    file: &FileMatches,
+    filenames: Filenames,
+    colored: bool,
+) -> String {
+    if config.write {
+        return String::new();
+    }
+    let path = painted_path(config, file, colored);
+    match config.output {
+        OutputMode::Lines => String::new(),
+        OutputMode::Count if filenames != Filenames::Hidden => {
+            let separator = filename_separator(config);
+            format!("{path}{separator}{}\n", file.count)
+        }
+        OutputMode::Count => format!("{}\n", file.count),
+        OutputMode::FilesWithMatches if file.count > 0 => {
+            let terminator = if config.null { '\0' } else { '\n' };
+            format!("{path}{terminator}")
+        }
+        OutputMode::FilesWithMatches | OutputMode::Histogram => String::new(),
+    }
+}
+
+/// How many characters the longest `--histogram` bar takes up.
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// Renders the `--histogram` chart: files with the most matches first, each
+/// with a bar scaled against the biggest count.
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
+pub fn format_histogram(mut counts: Vec<(String, usize)>) -> String {
+    counts.sort_by(|(a_path, a), (b_path, b)| b.cmp(a).then_with(|| a_path.cmp(b_path)));
+    let Some(&(_, most)) = counts.first() else {
+        return String::new();
+    };
+    let digits = most.to_string().len();
+    let mut out = String::new();
+    for (path, count) in &counts {
+        let bar = (count * HISTOGRAM_WIDTH).div_ceil(most);
+        out.push_str(&format!(
+            "{count:>digits$} {:<HISTOGRAM_WIDTH$} {path}\n",
+            "#".repeat(bar)
+        ));
+    }
+    out
+}
+
+fn painted_path(config: &Config, file: &FileMatches, colored: bool) -> String {
+    let theme = colored.then_some(&config.theme);
+    let path = display_path(&file.path, config.path_separator);
+    paint(theme.map(|theme| &theme.path), &path)
+}
+
+/// `path` with its directory separators swapped for `separator`, if given
+/// (`--path-separator`).
+pub(crate) fn display_path(path: &str, separator: Option<char>) -> Cow<'_, str> {
+    match separator {
+        Some(separator) => Cow::Owned(path.replace(['/', MAIN_SEPARATOR], &separator.to_string())),
+        None => Cow::Borrowed(path),
+    }
+}
+
+/// Renders the `--stats` summary printed after the results.
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
+pub fn format_stats(summary: &Summary) -> String {
+    let mut out = format!(
+        "\n{}\n{} contained matches\n{} searched\n",
+        counted(summary.matches, "match", "matches"),
+        counted(summary.files_matched, "file", "files"),
+        counted(summary.files, "file", "files"),
+    );
+    if summary.skipped > 0 {
+        let skipped = counted(summary.skipped, "file", "files");
+        out.push_str(&format!("{skipped} skipped\n"));
+    }
+    out.push_str(&format!(
+        "{} searched\n{:.6} seconds\n",
+        counted(summary.bytes, "byte", "bytes"),
+        summary.elapsed.as_secs_f64(),
+    ));
+    out
+}
+
+/// `count` followed by `one` or `many`, whichever fits, like "1 match".
+fn counted<T: fmt::Display + PartialEq + From<u8>>(count: T, one: &str, many: &str) -> String {
+    let noun = if count == T::from(1) { one } else { many };
+    format!("{count} {noun}")
+}
+
+/// What follows a file name: a NUL with `-0`, since that can't appear in
+/// file names.
+fn filename_separator(config: &Config) -> &str {
+    if config.null {
+        "\0"
+    } else {
+        field_separator(config)
+    }
+}
+
+/// What follows the line number, offset and column (`--field-separator`).
+fn field_separator(config: &Config) -> &str {
+    config.field_separator.as_deref().unwrap_or(":")
+}
+
+/// Paints `text` with `style` when color is on.
+fn paint(style: Option<&Style>, text: &str) -> String {
+    match style {
+        Some(style) => style.paint(text),
+        None => text.to_string(),
+    }
+}
+
+fn write_match(
+    out: &mut String,
+    config: &Config,
+    matcher: &dyn Matcher,
+    filename: Option<&str>,
+    m: &LineMatch,
+    theme: Option<&Theme>,
+) {
+    if config.only_matching {
+        for span in &m.spans {
+            write_prefix(out, config, filename, m, span.start, theme);
+            let text = match &config.replace {
+                Some(replacement) => Cow::Owned(matcher.replace_at(&m.line, span, replacement)),
+                None => Cow::Borrowed(&m.line[span.clone()]),
+            };
+            out.push_str(&paint(theme.map(|theme| &theme.matched), &text));
+            out.push('\n');
+        }
+        return;
+    }
+
+    write_prefix(out, config, filename, m, m.column() - 1, theme);
+    let max = config.max_columns.unwrap_or(usize::MAX);
+    if let Some(replacement) = &config.replace {
+        let replaced = matcher.replace(&m.line, replacement);
+        out.push_str(&truncate(&replaced, &[], max).0);
+        if config.crlf && m.crlf {
+            out.push('\r');
+        }
+    } else {
+        let (line, spans) = truncate(&m.line, &m.spans, max);
+        match theme {
+            Some(theme) => out.push_str(&theme.highlight(&line, &spans)),
+            None => out.push_str(&line),
+        }
+    }
+    out.push('\n');
+}
+
+/// What stands in for the part of a line cut off by `--max-columns`.
+const ELLIPSIS: &str = "…";
+
+/// Cuts `line` down to `max` characters around its first span, marking
+/// what was cut off with an ellipsis, and moves the `spans` to match. Spans
+/// outside what's left are dropped and those straddling its edges clipped.
+fn truncate<'a>(
+    line: &'a str,
+    spans: &[Range<usize>],
+    max: usize,
+) -> (Cow<'a, str>, Vec<Range<usize>>) {
+    let chars = line.chars().count();
+    if chars <= max {
+        return (Cow::Borrowed(line), spans.to_vec());
+    }
+    // Byte offsets of each character, and of the end.
+    let bounds: Vec<usize> = line
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([line.len()])
+        .collect();
+    let column = |byte: usize| bounds.partition_point(|&bound| bound < byte);
+    // Center the first match in the window, or as much of it as fits.
+    let first = spans
+        .first()
+        .map_or(0..0, |span| column(span.start)..column(span.end));
+    let context = max.saturating_sub(first.len()) / 2;
+    let end = (first.start.saturating_sub(context) + max).min(chars);
+    let start = end - max;
+    let (from, to) = (bounds[start], bounds[end]);
+
+    let lead = if from > 0 { ELLIPSIS } else { "" };
+    let trail = if to < line.len() { ELLIPSIS } else { "" };
+    let text = format!("{lead}{}{trail}", &line[from..to]);
+    let spans = spans
+        .iter()
+        .filter(|span| span.start < to && span.end > from)
+        .map(|span| {
+            let clip = |byte: usize| byte.clamp(from, to) - from + lead.len();
+            clip(span.start)..clip(span.end)
+        })
+        .collect();
+    (Cow::Owned(text), spans)
+}
+
+/// Writes the `file:line:offset:column:` prefix for whatever starts at byte
+/// `start` of the line.
+fn write_prefix(
+    out: &mut String,
+    config: &Config,
+    filename: Option<&str>,
+    m: &LineMatch,
+    start: usize,
+    theme: Option<&Theme>,
+) {
+    if let Some(filename) = filename {
+        out.push_str(&format!("{filename}{}", filename_separator(config)));
+    }
+    let separator = field_separator(config);
+    if config.line_number {
+        let line_no = m.line_no.to_string();
+        out.push_str(&format!(
+            "{}{separator}",
+            paint(theme.map(|theme| &theme.line), &line_no)
+        ));
+    }
+    if config.byte_offset {
+        // The line's offset, or the match's own with -o.
+        let offset = if config.only_matching {
+            m.offset + start as u64
+        } else {
+            m.offset
+        };
+        out.push_str(&format!("{offset}{separator}"));
+    }
+    if config.column {
+        let column = (start + 1).to_string();
+        out.push_str(&format!(
+            "{}{separator}",
+            paint(theme.map(|theme| &theme.column), &column)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::ColorSpec;
+    use crate::matcher::Literal;
+
+    fn file() -> FileMatches {
+        FileMatches {
+            path: String::from("poem.txt"),
+            matches: vec![LineMatch {
+                line_no: 2,
+                offset: 25,
+                line: String::from("Are you nobody, too?"),
+                spans: vec![8..14, 16..17],
+                crlf: false,
+            }],
+            streamed: 0,
+            count: 1,
+            bytes: 45,
+            error: None,
+            binary: false,
+        }
+    }
+
+    #[test]
+    fn formats_lines_with_prefixes() {
+        let config = Config::builder()
+            .query("nobody")
+            .line_number(true)
+            .column(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            "poem.txt:2:9:Are you nobody, too?\n",
+            format_file(
+                &config,
+                &Literal::new("nobody"),
+                &file(),
+                Filenames::Prefixed,
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn formats_byte_offsets() {
+        let matcher = Literal::new("o");
+        let lines = Config::builder()
+            .query("o")
+            .byte_offset(true)
+            .build()
+            .unwrap();
+        let only = Config::builder()
+            .query("o")
+            .byte_offset(true)
+            .only_matching(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            "25:Are you nobody, too?\n",
+            format_file(&lines, &matcher, &file(), Filenames::Hidden, false)
+        );
+        assert_eq!(
+            "33:nobody\n41:t\n",
+            format_file(&only, &matcher, &file(), Filenames::Hidden, false)
+        );
+    }
+
+    #[test]
+    fn formats_counts_and_names() {
+        let matcher = Literal::new("nobody");
+        let count = Config::builder()
+            .query("nobody")
+            .output(OutputMode::Count)
+            .build()
+            .unwrap();
+        let names = Config::builder()
+            .query("nobody")
+            .output(OutputMode::FilesWithMatches)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            "1\n",
+            format_file(&count, &matcher, &file(), Filenames::Hidden, false)
+        );
+        assert_eq!(
+            "poem.txt\n",
+            format_file(&names, &matcher, &file(), Filenames::Hidden, false)
+        );
+    }
+
+    #[test]
+    fn formats_nul_separators() {
+        let matcher = Literal::new("nobody");
+        let lines = Config::builder()
+            .query("nobody")
+            .null(true)
+            .build()
+            .unwrap();
+        let names = Config::builder()
+            .query("nobody")
+            .output(OutputMode::FilesWithMatches)
+            .null(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            "poem.txt\0Are you nobody, too?\n",
+            format_file(&lines, &matcher, &file(), Filenames::Prefixed, false)
+        );
+        assert_eq!(
+            "poem.txt\0",
+            format_file(&names, &matcher, &file(), Filenames::Prefixed, false)
+        );
+    }
+
+    #[test]
+    fn formats_with_theme() {
+        let config = Config::builder()
+            .query("nobody")
+            .line_number(true)
+            .colors(ColorSpec::parse("path:fg:magenta").unwrap())
+            .colors(ColorSpec::parse("line:fg:green").unwrap())
+            .build()
+            .unwrap();
+        let file = FileMatches {
+            matches: vec![LineMatch {
+                spans: vec![8..14, 16..18],
+                ..file().matches[0].clone()
+            }],
+            ..file()
+        };
+
+        assert_eq!(
+            "\x1b[35mpoem.txt\x1b[0m:\x1b[32m2\x1b[0m:Are you \x1b[1;31mnobody\x1b[0m, \
+             \x1b[1;31mto\x1b[0mo?\n",
+            format_file(
+                &config,
+                &Literal::new("nobody"),
+                &file,
+                Filenames::Prefixed,
+                true
+            )
+        );
+    }
+
+    #[test]
+    fn formats_headings() {
+        let config = Config::builder()
+            .query("nobody")
+            .line_number(true)
+            .build()
+            .unwrap();
+        let matcher = Literal::new("nobody");
+
+        assert_eq!(
+            "poem.txt\n2:Are you nobody, too?\n",
+            format_file(&config, &matcher, &file(), Filenames::Heading, false)
+        );
+        let empty = FileMatches {
+            matches: Vec::new(),
+            ..file()
+        };
+        assert_eq!(
+            "",
+            format_file(&config, &matcher, &empty, Filenames::Heading, false)
+        );
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn chooses_filename_style() {
+        let heading = Config::builder().query("a").heading(true).build().unwrap();
+        let flat = Config::builder().query("a").heading(false).build().unwrap();
+        let count = Config::builder()
+            .query("a")
+            .heading(true)
+            .output(OutputMode::Count)
+            .build()
+            .unwrap();
+
+        assert_eq!(Filenames::Heading, Filenames::choose(&heading, true));
+        assert_eq!(Filenames::Hidden, Filenames::choose(&heading, false));
+        assert_eq!(Filenames::Prefixed, Filenames::choose(&flat, true));
+        assert_eq!(Filenames::Prefixed, Filenames::choose(&count, true));
+    }
+
+    #[test]
+    fn formats_replacements() {
+        let config = Config::builder()
+            .query("nobody")
+            .replace("NOBODY")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            "Are you NOBODY, too?\n",
+            format_file(
+                &config,
+                &Literal::new("nobody"),
+                &file(),
+                Filenames::Hidden,
+                false
+            )
+        );
+
+        let crlf = FileMatches {
+            matches: vec![LineMatch {
+                crlf: true,
+                ..file().matches[0].clone()
+            }],
+            ..file()
+        };
+        let restored = Config::builder()
+            .query("nobody")
+            .replace("NOBODY")
+            .crlf(true)
+            .build()
+            .unwrap();
+        let matcher = Literal::new("nobody");
+        assert_eq!(
+            "Are you NOBODY, too?\n",
+            format_file(&config, &matcher, &crlf, Filenames::Hidden, false)
+        );
+        assert_eq!(
+            "Are you NOBODY, too?\r\n",
+            format_file(&restored, &matcher, &crlf, Filenames::Hidden, false)
+        );
+
+        let only = Config::builder()
+            .query("nobody")
+            .replace("NOBODY")
+            .only_matching(true)
+            .build()
+            .unwrap();
+        assert_eq!(
+            "NOBODY\nNOBODY\n",
+            format_file(&only, &matcher, &file(), Filenames::Hidden, false)
+        );
+    }
+
+    #[test]
+    fn formats_custom_separators() {
+        let config = Config::builder()
+            .query("nobody")
+            .line_number(true)
+            .column(true)
+            .field_separator("\t")
+            .path_separator('\\')
+            .build()
+            .unwrap();
+        let nested = FileMatches {
+            path: String::from("docs/poem.txt"),
+            ..file()
+        };
+
+        assert_eq!(
+            "docs\\poem.txt\t2\t9\tAre you nobody, too?\n",
+            format_file(
+                &config,
+                &Literal::new("nobody"),
+                &nested,
+                Filenames::Prefixed,
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn truncates_long_lines_around_the_match() {
+        let line = "a".repeat(20) + "needle" + &"b".repeat(20) + "needle";
+        let (text, spans) = truncate(&line, &[20..26, 46..52], 10);
+        assert_eq!("…aaneedlebb…", text);
+        assert_eq!(vec![5..11], spans);
+        assert_eq!("needle", &text[spans[0].clone()]);
+
+        // Near the start there's nothing to cut before the match.
+        let (text, spans) = truncate("needle and needle", &[0..6, 11..17], 8);
+        assert_eq!("needle a…", text);
+        assert_eq!(vec![0..6], spans);
+
+        // A match wider than the limit keeps its start.
+        let (text, spans) = truncate("xx needle yy", &[3..9, 10..12], 4);
+        assert_eq!("…need…", text);
+        assert_eq!(vec![3..7], spans);
+
+        assert_eq!("short", truncate("short", &[], 10).0);
+        assert_eq!("ééé…", truncate("éééééé", &[], 3).0);
+
+        let config = Config::builder()
+            .query("nobody")
+            .max_columns(8)
+            .build()
+            .unwrap();
+        assert_eq!(
+            "… nobody,…\n",
+            format_file(
+                &config,
+                &Literal::new("nobody"),
+                &file(),
+                Filenames::Hidden,
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn formats_histograms() {
+        let counts = vec![
+            (String::from("b.rs"), 1),
+            (String::from("a.rs"), 20),
+            (String::from("c.rs"), 1),
+        ];
+
+        assert_eq!(
+            format!(
+                "20 {} a.rs\n 1 {:<40} b.rs\n 1 {:<40} c.rs\n",
+                "#".repeat(40),
+                "##",
+                "##"
+            ),
+            format_histogram(counts)
+        );
+        assert_eq!("", format_histogram(Vec::new()));
+    }
+
+    #[test]
+    fn formats_stats() {
+        let summary = Summary {
+            matches: 2,
+            files: 3,
+            files_matched: 1,
+            bytes: 120,
+            ..Summary::default()
+        };
+
+        assert_eq!(
+            "\n2 matches\n1 file contained matches\n3 files searched\n\
+             120 bytes searched\n0.000000 seconds\n",
+            format_stats(&summary)
+        );
+
+        let summary = Summary {
+            skipped: 4,
+            ..summary
+        };
+        assert!(format_stats(&summary).contains("\n3 files searched\n4 files skipped\n"));
+
+        let one = Summary {
+            matches: 1,
+            files: 1,
+            files_matched: 1,
+            skipped: 1,
+            bytes: 1,
+            ..Summary::default()
+        };
+        assert_eq!(
+            "\n1 match\n1 file contained matches\n1 file searched\n1 file skipped\n\
+             1 byte searched\n0.000000 seconds\n",
+            format_stats(&one)
+        );
+    }
+}
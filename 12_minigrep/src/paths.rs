@@ -0,0 +1,170 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use crate::error::MinigrepError;
+use crate::filter;
+use crate::input;
+use crate::walk::{self, WalkOptions};
+
+/// Whether `arg` should be expanded as a glob rather than opened as-is. A
+/// path that exists is taken literally, so `br[1].txt` names that file.
+fn is_glob(arg: &str) -> bool {
+    arg.contains(['*', '?', '[']) && !Path::new(arg).exists()
+}
+
+/// Turns the file arguments into the inputs to search, expanding any glob
+/// patterns ourselves so `src/**/*.rs` works without shell support, and
+/// walking directories recursively. Each input goes to `found` as soon as
+/// it's known.
+///
+/// Each input is either one to open or an error explaining why an argument
+/// produced nothing. Once `found` returns false no more directories are
+/// walked or globs expanded, though plain arguments are still handed over.
+pub fn expand(
+    args: &[String],
+    options: &WalkOptions,
+    found: &mut dyn FnMut(Result<String, MinigrepError>) -> bool,
+) {
+    let mut wanted = true;
+    for arg in args {
+        if arg != input::STDIN && Path::new(arg).is_dir() {
+            if wanted {
+                walk::walk_each(Path::new(arg), options, &mut |file| {
+                    wanted = found(file.map(|path| path.display().to_string()));
+                    wanted
+                });
+            }
+            continue;
+        }
+        if arg == input::STDIN || !is_glob(arg) {
+            wanted = found(Ok(arg.clone()));
+            continue;
+        }
+        if !wanted {
+            continue;
+        }
+        let paths = match glob::glob(arg) {
+            Ok(paths) => paths,
+            Err(e) => {
+                let message = format!("{arg}: invalid glob: {e}");
+                wanted = found(Err(MinigrepError::BadArgs(message)));
+                continue;
+            }
+        };
+        let mut matched = false;
+        for path in paths {
+            wanted = match path {
+                Ok(path) if path.is_file() => found(Ok(path.display().to_string())),
+                Ok(_) => continue,
+                Err(e) => {
+                    let path = e.path().to_path_buf();
+                    found(Err(MinigrepError::io(path, e.into())))
+                }
+            };
+            matched = true;
+            if !wanted {
+                break;
+            }
+        }
+        if !matched {
+            let source = io::Error::new(io::ErrorKind::NotFound, "no files match");
+            wanted = found(Err(MinigrepError::io(arg, source)));
+        }
+    }
+}
+
+/// Reads a newline-separated list of files to search from `list`, or from
+/// standard input if it's `-`, keeping those that pass the type and path
+/// filters. Nothing is walked: directories in the list are not descended.
+pub fn from_list(list: &str, options: &WalkOptions) -> Result<Vec<String>, MinigrepError> {
+    let reader: Box<dyn BufRead> = if list == input::STDIN {
+        Box::new(io::stdin().lock())
+    } else {
+        let file = File::open(list).map_err(|e| MinigrepError::io(list, e))?;
+        Box::new(BufReader::new(file))
+    };
+    read_list(reader, options).map_err(|e| MinigrepError::io(input::display_name(list), e))
+}
+
+fn read_list(reader: impl BufRead, options: &WalkOptions) -> io::Result<Vec<String>> {
+    let mut files = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let path = line.trim_end_matches('\r');
+        if !path.is_empty() && filter::allows_listed(options, Path::new(path)) {
+            files.push(path.to_string());
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expanded(args: &[String]) -> Vec<Result<String, MinigrepError>> {
+        let mut inputs = Vec::new();
+        expand(args, &WalkOptions::default(), &mut |input| {
+            inputs.push(input);
+            true
+        });
+        inputs
+    }
+
+    #[test]
+    fn plain_arguments_pass_through() {
+        let args = vec![String::from("poem.txt"), String::from("-")];
+        let inputs: Vec<_> = expanded(&args).into_iter().map(Result::unwrap).collect();
+        assert_eq!(vec!["poem.txt", "-"], inputs);
+    }
+
+    #[test]
+    fn expands_recursive_globs() {
+        let inputs: Vec<_> = expanded(&[String::from("src/**/*.rs")])
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert!(inputs.contains(&String::from("src/lib.rs")));
+        assert!(inputs.contains(&String::from("src/main.rs")));
+    }
+
+    #[test]
+    fn existing_paths_are_not_globs() {
+        let root = crate::test_support::tree("literal-glob", &[("br[1].txt", "x\n")]);
+        let path = root.join("br[1].txt").display().to_string();
+
+        let inputs = expanded(std::slice::from_ref(&path));
+        assert_eq!(path, *inputs[0].as_ref().unwrap());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn reports_globs_without_matches() {
+        let inputs = expanded(&[String::from("src/*.nothing")]);
+        assert_eq!(1, inputs.len());
+        let e = inputs[0].as_ref().unwrap_err();
+        assert_eq!("src/*.nothing: no files match", e.to_string());
+    }
+
+    #[test]
+    fn walks_directories() {
+        let inputs = expanded(&[String::from("src")]);
+        assert!(inputs
+            .iter()
+            .any(|input| input.as_deref().ok() == Some("src/lib.rs")));
+    }
+
+    #[test]
+    fn reads_file_lists() {
+        let mut options = WalkOptions::default();
+        options.globs.include("*.rs");
+        options.globs.compile().unwrap();
+
+        let list = "src/lib.rs\r\n\npoem.txt\nsrc/main.rs\n";
+        assert_eq!(
+            vec!["src/lib.rs", "src/main.rs"],
+            read_list(list.as_bytes(), &options).unwrap()
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use crate::limit::Budget;
+
+/// Runs `work` over every item on `jobs` worker threads, handing each result
+/// to `emit` on the calling thread as soon as it is ready. Workers stop
+/// picking up new items once `budget` is cancelled.
+///
+/// Results arrive in completion order. Because only the calling thread ever
+/// sees them, `emit` can print without output from different items getting
+/// interleaved.
+pub fn run<T, R>(
+    jobs: usize,
+    items: &[T],
+    budget: &Budget,
+    work: impl Fn(&T) -> R + Sync,
+    mut emit: impl FnMut(R),
+) where
+    T: Sync,
+    R: Send,
+{
+    if jobs <= 1 {
+        items
+            .iter()
+            .take_while(|_| !budget.is_cancelled())
+            .map(&work)
+            .for_each(emit);
+        return;
+    }
+
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|s| {
+        for _ in 0..jobs.min(items.len()) {
+            let tx = tx.clone();
+            let (next, work) = (&next, &work);
+            s.spawn(move || loop {
+                if budget.is_cancelled() {
+                    break;
+                }
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else {
+                    break;
+                };
+                if tx.send(work(item)).is_err() {
+                    break;
+                }
+            });
+        }
+        // Once the workers' senders are gone the receiver loop ends.
+        drop(tx);
+        rx.into_iter().for_each(&mut emit);
+    });
+}
+
+/// The default number of worker threads.
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_item_is_processed_once() {
+        let items: Vec<u32> = (1..=100).collect();
+        let mut results = Vec::new();
+
+        run(
+            4,
+            &items,
+            &Budget::default(),
+            |n| n * 2,
+            |n| results.push(n),
+        );
+
+        results.sort();
+        assert_eq!(items.iter().map(|n| n * 2).collect::<Vec<_>>(), results);
+    }
+
+    #[test]
+    fn single_job_keeps_order() {
+        let items = ["a", "b", "c"];
+        let mut results = Vec::new();
+
+        run(
+            1,
+            &items,
+            &Budget::default(),
+            |s| s.to_uppercase(),
+            |s| results.push(s),
+        );
+
+        assert_eq!(vec!["A", "B", "C"], results);
+    }
+
+    #[test]
+    fn cancelling_stops_new_items() {
+        let items = ["a", "b", "c"];
+        let budget = Budget::new(Some(1));
+        let mut results = Vec::new();
+
+        run(
+            1,
+            &items,
+            &budget,
+            |s| budget.take().then_some(*s),
+            |s| results.push(s),
+        );
+
+        assert_eq!(vec![Some("a"), None], results);
+    }
+}
@@ -0,0 +1,327 @@
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::filter::PathGlobs;
+
+/// How long a preprocessor may run on one file unless `--pre-timeout` says
+/// otherwise.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often a running preprocessor is checked on.
+const POLL: Duration = Duration::from_millis(10);
+
+/// An external command that turns a file into searchable text, like
+/// `pdftotext` (`--pre`). It gets the file's path as its only argument and
+/// its standard output is searched instead of the file.
+#[derive(Debug, Clone)]
+pub struct Preprocessor {
+    pub command: String,
+    /// Which files to run it on (`--pre-glob`); all of them if empty.
+    pub globs: PathGlobs,
+    pub timeout: Duration,
+}
+
+impl Preprocessor {
+    pub fn new(command: impl Into<String>) -> Self {
+        Preprocessor {
+            command: command.into(),
+            globs: PathGlobs::default(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    pub fn applies(&self, path: &Path) -> bool {
+        self.globs.includes(path)
+    }
+
+    /// Starts the command on `path`, returning its output to read as it's
+    /// printed. Failing to start is an error straight away; running past
+    /// the timeout or exiting unsuccessfully is an error from reading,
+    /// carrying whatever the command wrote to stderr.
+    pub fn run(&self, path: &Path) -> io::Result<Output> {
+        let mut command = Command::new(&self.command);
+        command
+            .arg(path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // In a group of its own, anything it starts can be killed along with
+        // it; otherwise a leftover child holding the pipes open would keep us
+        // reading them long after the timeout.
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let mut child = command
+            .spawn()
+            .map_err(|e| io::Error::new(e.kind(), format!("can't run {}: {e}", self.command)))?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+
+        // Both pipes are drained on threads of their own, so a chatty command
+        // can't block on a full pipe and a stuck one can't block us past the
+        // deadline.
+        let (chunk_tx, chunks) = mpsc::sync_channel(CHUNKS);
+        thread::spawn(move || pump(stdout, chunk_tx));
+        let (stderr_tx, errors) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buf = String::new();
+            let _ = stderr.read_to_string(&mut buf);
+            let _ = stderr_tx.send(buf);
+        });
+
+        Ok(Output {
+            command: self.command.clone(),
+            timeout: self.timeout,
+            deadline: Instant::now() + self.timeout,
+            child: Some(child),
+            chunks,
+            errors,
+            chunk: Vec::new(),
+            pos: 0,
+        })
+    }
+}
+
+/// How many chunks of output may wait to be read before the command is made
+/// to wait for us.
+const CHUNKS: usize = 4;
+
+/// How much output is read from the command at a time.
+const CHUNK_LEN: usize = 64 * 1024;
+
+/// Sends what `stdout` yields down `chunks` until it ends or nobody is
+/// listening any more.
+fn pump(mut stdout: impl Read, chunks: SyncSender<io::Result<Vec<u8>>>) {
+    let mut buf = vec![0; CHUNK_LEN];
+    loop {
+        let chunk = match stdout.read(&mut buf) {
+            Ok(0) => return,
+            Ok(n) => Ok(buf[..n].to_vec()),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => Err(e),
+        };
+        let failed = chunk.is_err();
+        if chunks.send(chunk).is_err() || failed {
+            return;
+        }
+    }
+}
+
+/// The output of a running [`Preprocessor`]. Reading to the end checks how
+/// the command exited; if the whole run takes longer than its timeout, the
+/// command and everything it started are killed and reading fails.
+pub struct Output {
+    command: String,
+    timeout: Duration,
+    deadline: Instant,
+    /// Until it has been waited for.
+    child: Option<Child>,
+    chunks: Receiver<io::Result<Vec<u8>>>,
+    errors: Receiver<String>,
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl Output {
+    fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    fn timed_out(&self) -> io::Error {
+        let message = format!("{} timed out after {:?}", self.command, self.timeout);
+        io::Error::new(io::ErrorKind::TimedOut, message)
+    }
+
+    /// Waits for the command to exit once its output has ended.
+    fn finish(&mut self) -> io::Result<()> {
+        let Some(mut child) = self.child.take() else {
+            return Ok(());
+        };
+        let Some(status) = wait_timeout(&mut child, self.remaining())? else {
+            return Err(self.timed_out());
+        };
+        if !status.success() {
+            let err = self
+                .errors
+                .recv_timeout(self.remaining())
+                .unwrap_or_default();
+            let message = format!("{} failed ({status}): {}", self.command, err.trim());
+            return Err(io::Error::other(message));
+        }
+        Ok(())
+    }
+}
+
+impl Read for Output {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.chunk.len() {
+            if self.child.is_none() {
+                return Ok(0);
+            }
+            match self.chunks.recv_timeout(self.remaining()) {
+                Ok(chunk) => {
+                    self.chunk = chunk?;
+                    self.pos = 0;
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Some(mut child) = self.child.take() {
+                        kill_group(&mut child)?;
+                        child.wait()?;
+                    }
+                    return Err(self.timed_out());
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.finish()?;
+                    return Ok(0);
+                }
+            }
+        }
+        let n = (&self.chunk[self.pos..]).read(buf)?;
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl Drop for Output {
+    /// A search that stops early doesn't leave the command running.
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = kill_group(&mut child);
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Waits for `child` to exit, killing it and everything it started if it
+/// takes longer than `timeout`. Returns `None` if it had to be killed.
+fn wait_timeout(child: &mut Child, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            kill_group(child)?;
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(POLL);
+    }
+}
+
+/// Kills `child` and the rest of the process group `run` put it in.
+#[cfg(unix)]
+fn kill_group(child: &mut Child) -> io::Result<()> {
+    let group = libc::pid_t::try_from(child.id()).map_err(io::Error::other)?;
+    // SAFETY: killpg only sends a signal, to a group that is ours as the
+    // child hasn't been waited for yet.
+    if unsafe { libc::killpg(group, libc::SIGKILL) } == 0 {
+        return Ok(());
+    }
+    child.kill()
+}
+
+#[cfg(not(unix))]
+fn kill_group(child: &mut Child) -> io::Result<()> {
+    child.kill()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+
+    fn script(name: &str, body: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("minigrep-pre-{name}-{}", std::process::id()));
+        fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    /// Runs `pre` on poem.txt and reads everything it prints.
+    fn output(pre: &Preprocessor) -> io::Result<String> {
+        let mut out = String::new();
+        pre.run(Path::new("poem.txt"))?.read_to_string(&mut out)?;
+        Ok(out)
+    }
+
+    #[test]
+    fn searches_command_output() {
+        let upper = script("upper", "tr a-z A-Z < \"$1\"");
+        let pre = Preprocessor::new(upper.display().to_string());
+
+        assert!(output(&pre).unwrap().starts_with("I'M NOBODY!"));
+        fs::remove_file(upper).unwrap();
+    }
+
+    #[test]
+    fn reports_failures_and_timeouts() {
+        let failing = script("failing", "echo 'no such page' >&2; exit 3");
+        let e = output(&Preprocessor::new(failing.display().to_string())).unwrap_err();
+        assert!(e.to_string().ends_with("no such page"), "{e}");
+
+        let slow = script("slow", "sleep 5");
+        let mut pre = Preprocessor::new(slow.display().to_string());
+        pre.timeout = Duration::from_millis(50);
+        let e = output(&pre).unwrap_err();
+        assert_eq!(io::ErrorKind::TimedOut, e.kind());
+
+        // A background child still holding the pipes dies with it.
+        let forks = script("forks", "sh -c 'sleep 10 & sleep 10'");
+        let mut pre = Preprocessor::new(forks.display().to_string());
+        pre.timeout = Duration::from_millis(50);
+        let start = Instant::now();
+        let e = output(&pre).unwrap_err();
+        assert_eq!(io::ErrorKind::TimedOut, e.kind());
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "{:?}",
+            start.elapsed()
+        );
+
+        let missing = Preprocessor::new("minigrep-no-such-command");
+        assert!(missing.run(Path::new("poem.txt")).is_err());
+        fs::remove_file(failing).unwrap();
+        fs::remove_file(slow).unwrap();
+        fs::remove_file(forks).unwrap();
+    }
+
+    #[test]
+    fn stops_waiting_for_a_child_left_holding_stdout() {
+        // The command itself is done at once, but what it left in the
+        // background would keep its output open for ten seconds.
+        let leaves = script("leaves", "sleep 10 & echo started");
+        let mut pre = Preprocessor::new(leaves.display().to_string());
+        pre.timeout = Duration::from_millis(200);
+        let mut output = pre.run(Path::new("poem.txt")).unwrap();
+
+        let start = Instant::now();
+        let mut line = [0; 8];
+        output.read_exact(&mut line).unwrap();
+        assert_eq!(b"started\n", &line);
+        let e = output.read(&mut line).unwrap_err();
+        assert_eq!(io::ErrorKind::TimedOut, e.kind());
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "{:?}",
+            start.elapsed()
+        );
+        fs::remove_file(leaves).unwrap();
+    }
+
+    #[test]
+    fn applies_to_matching_files() {
+        let mut pre = Preprocessor::new("pdftotext");
+        assert!(pre.applies(Path::new("poem.txt")));
+        pre.globs.include("*.pdf");
+        pre.globs.compile().unwrap();
+        assert!(pre.applies(Path::new("docs/manual.pdf")));
+        assert!(!pre.applies(Path::new("poem.txt")));
+    }
+}
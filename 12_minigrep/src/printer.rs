@@ -0,0 +1,415 @@
+//! Renders search results as they're found, so the search can be embedded
+//! and its results shown however the embedding program likes.
+
+use std::io::{self, Write};
+use std::mem;
+
+use crate::matcher::Matcher;
+use crate::output::{self, display_path, Filenames};
+use crate::{Config, FileMatches, LineMatch};
+
+/// Receives each searched file's results in order: `file_begin`, then
+/// `match_found` for every line, then `file_end`.
+pub trait Printer {
+    fn file_begin(&mut self, _file: &FileMatches) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn match_found(&mut self, file: &FileMatches, m: &LineMatch) -> io::Result<()>;
+
+    fn file_end(&mut self, _file: &FileMatches) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Hands all of `file`'s results to `printer`.
+pub fn print_file<P: Printer + ?Sized>(printer: &mut P, file: &FileMatches) -> io::Result<()> {
+    printer.file_begin(file)?;
+    for m in &file.matches {
+        printer.match_found(file, m)?;
+    }
+    printer.file_end(file)
+}
+
+/// Which of the built-in printers to use (`--format`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// grep-style lines, as shaped by the other output flags.
+    #[default]
+    Text,
+    /// One JSON object per line for each file's beginning, every match, and
+    /// each file's end.
+    Json,
+    /// One JSON object per match, printed and flushed as soon as it's found.
+    Ndjson,
+    /// One CSV row per matching line, after a header row.
+    Csv,
+}
+
+impl Format {
+    pub fn parse(value: &str) -> Result<Self, &'static str> {
+        match value {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "ndjson" => Ok(Format::Ndjson),
+            "csv" => Ok(Format::Csv),
+            _ => Err("--format must be one of text, json, ndjson or csv"),
+        }
+    }
+}
+
+/// Prints lines the way grep does, honouring `-n`, `-c`, `-l`, `--heading`
+/// and the rest of `config`.
+pub struct TextPrinter<'a, W> {
+    out: W,
+    config: &'a Config,
+    matcher: &'a dyn Matcher,
+    filenames: Filenames,
+    colored: bool,
+    /// Whether a group of lines under a heading has been printed yet.
+    grouped: bool,
+}
+
+impl<'a, W: Write> TextPrinter<'a, W> {
+    pub fn new(
+        out: W,
+        config: &'a Config,
+        matcher: &'a dyn Matcher,
+        filenames: Filenames,
+        colored: bool,
+    ) -> Self {
+        TextPrinter {
+            out,
+            config,
+            matcher,
+            filenames,
+            colored,
+            grouped: false,
+        }
+    }
+}
+
+impl<W: Write> Printer for TextPrinter<'_, W> {
+    fn file_begin(&mut self, file: &FileMatches) -> io::Result<()> {
+        let heading = output::format_begin(self.config, file, self.filenames, self.colored);
+        if heading.is_empty() {
+            return Ok(());
+        }
+        // Headed groups are separated by a blank line.
+        if mem::replace(&mut self.grouped, true) {
+            writeln!(self.out)?;
+        }
+        self.out.write_all(heading.as_bytes())
+    }
+
+    fn match_found(&mut self, file: &FileMatches, m: &LineMatch) -> io::Result<()> {
+        let line = output::format_line(
+            self.config,
+            self.matcher,
+            file,
+            m,
+            self.filenames,
+            self.colored,
+        );
+        self.out.write_all(line.as_bytes())
+    }
+
+    fn file_end(&mut self, file: &FileMatches) -> io::Result<()> {
+        let end = output::format_end(self.config, file, self.filenames, self.colored);
+        self.out.write_all(end.as_bytes())
+    }
+}
+
+/// Prints JSON Lines: a `begin` and an `end` object around the `match`
+/// objects of each file with matches.
+pub struct JsonPrinter<W> {
+    out: W,
+    path_separator: Option<char>,
+}
+
+impl<W: Write> JsonPrinter<W> {
+    pub fn new(out: W) -> Self {
+        JsonPrinter {
+            out,
+            path_separator: None,
+        }
+    }
+
+    /// Prints paths with `separator` between directories.
+    pub fn path_separator(mut self, separator: Option<char>) -> Self {
+        self.path_separator = separator;
+        self
+    }
+
+    fn path(&self, file: &FileMatches) -> String {
+        json_string(&display_path(&file.path, self.path_separator))
+    }
+}
+
+impl<W: Write> Printer for JsonPrinter<W> {
+    fn file_begin(&mut self, file: &FileMatches) -> io::Result<()> {
+        if !file.has_lines() {
+            return Ok(());
+        }
+        writeln!(self.out, r#"{{"type":"begin","path":{}}}"#, self.path(file))
+    }
+
+    fn match_found(&mut self, file: &FileMatches, m: &LineMatch) -> io::Result<()> {
+        writeln!(self.out, "{}", match_object(&self.path(file), m))
+    }
+
+    fn file_end(&mut self, file: &FileMatches) -> io::Result<()> {
+        if !file.has_lines() {
+            return Ok(());
+        }
+        writeln!(
+            self.out,
+            r#"{{"type":"end","path":{},"matches":{},"bytes":{}}}"#,
+            self.path(file),
+            file.count,
+            file.bytes,
+        )
+    }
+}
+
+/// Prints a self-contained JSON object for every match, flushing after each
+/// one so a reader like an editor can show results while the search runs.
+pub struct NdjsonPrinter<W> {
+    json: JsonPrinter<W>,
+}
+
+impl<W: Write> NdjsonPrinter<W> {
+    pub fn new(out: W) -> Self {
+        NdjsonPrinter {
+            json: JsonPrinter::new(out),
+        }
+    }
+
+    /// Prints paths with `separator` between directories.
+    pub fn path_separator(mut self, separator: Option<char>) -> Self {
+        self.json = self.json.path_separator(separator);
+        self
+    }
+}
+
+impl<W: Write> Printer for NdjsonPrinter<W> {
+    fn match_found(&mut self, file: &FileMatches, m: &LineMatch) -> io::Result<()> {
+        self.json.match_found(file, m)?;
+        self.json.out.flush()
+    }
+}
+
+/// The `match` object for `m`, whose file's `path` is already quoted.
+fn match_object(path: &str, m: &LineMatch) -> String {
+    let submatches: Vec<_> = m
+        .spans
+        .iter()
+        .map(|span| format!(r#"{{"start":{},"end":{}}}"#, span.start, span.end))
+        .collect();
+    format!(
+        r#"{{"type":"match","path":{path},"line_number":{},"offset":{},"line":{},"submatches":[{}]}}"#,
+        m.line_no,
+        m.offset,
+        json_string(&m.line),
+        submatches.join(","),
+    )
+}
+
+/// Quotes `text` as a JSON string.
+pub(crate) fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Prints a `path,line_number,byte_offset,column,line` row per matching
+/// line, with the header before the first row.
+pub struct CsvPrinter<W> {
+    out: W,
+    header: bool,
+    delimiter: String,
+    path_separator: Option<char>,
+}
+
+impl<W: Write> CsvPrinter<W> {
+    pub fn new(out: W) -> Self {
+        CsvPrinter {
+            out,
+            header: false,
+            delimiter: String::from(","),
+            path_separator: None,
+        }
+    }
+
+    /// Separates fields with `delimiter` instead of a comma, e.g. a tab.
+    pub fn delimiter(mut self, delimiter: impl Into<String>) -> Self {
+        self.delimiter = delimiter.into();
+        self
+    }
+
+    /// Prints paths with `separator` between directories.
+    pub fn path_separator(mut self, separator: Option<char>) -> Self {
+        self.path_separator = separator;
+        self
+    }
+
+    /// Quotes `text` as a field if it needs it.
+    fn field(&self, text: &str) -> String {
+        if text.contains(self.delimiter.as_str()) || text.contains(['"', '\n', '\r']) {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+impl<W: Write> Printer for CsvPrinter<W> {
+    fn match_found(&mut self, file: &FileMatches, m: &LineMatch) -> io::Result<()> {
+        let d = &self.delimiter;
+        if !mem::replace(&mut self.header, true) {
+            writeln!(self.out, "path{d}line_number{d}byte_offset{d}column{d}line")?;
+        }
+        let path = self.field(&display_path(&file.path, self.path_separator));
+        let line = self.field(&m.line);
+        let d = &self.delimiter;
+        writeln!(
+            self.out,
+            "{path}{d}{}{d}{}{d}{}{d}{line}",
+            m.line_no,
+            m.offset,
+            m.column(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::Literal;
+
+    fn file() -> FileMatches {
+        FileMatches {
+            path: String::from("poem.txt"),
+            matches: vec![LineMatch {
+                line_no: 2,
+                offset: 25,
+                line: String::from("Are you \"nobody\", too?"),
+                spans: vec![9..15, 18..19],
+                crlf: false,
+            }],
+            streamed: 0,
+            count: 1,
+            bytes: 45,
+            error: None,
+            binary: false,
+        }
+    }
+
+    fn printed(printer: &mut dyn Printer, files: &[FileMatches]) {
+        for file in files {
+            print_file(printer, file).unwrap();
+        }
+    }
+
+    #[test]
+    fn text_printer_matches_format_file() {
+        let config = Config::builder()
+            .query("nobody")
+            .line_number(true)
+            .heading(true)
+            .build()
+            .unwrap();
+        let matcher = Literal::new("nobody");
+        let mut out = Vec::new();
+        let mut printer = TextPrinter::new(&mut out, &config, &matcher, Filenames::Heading, false);
+        printed(&mut printer, &[file(), file()]);
+
+        let one = output::format_file(&config, &matcher, &file(), Filenames::Heading, false);
+        assert_eq!(format!("{one}\n{one}"), String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn json_printer_writes_lines_of_objects() {
+        let mut out = Vec::new();
+        let empty = FileMatches::new("empty.txt");
+        printed(&mut JsonPrinter::new(&mut out), &[file(), empty]);
+
+        assert_eq!(
+            concat!(
+                r#"{"type":"begin","path":"poem.txt"}"#,
+                "\n",
+                r#"{"type":"match","path":"poem.txt","line_number":2,"offset":25,"#,
+                r#""line":"Are you \"nobody\", too?","#,
+                r#""submatches":[{"start":9,"end":15},{"start":18,"end":19}]}"#,
+                "\n",
+                r#"{"type":"end","path":"poem.txt","matches":1,"bytes":45}"#,
+                "\n",
+            ),
+            String::from_utf8(out).unwrap()
+        );
+        assert_eq!(r#""tab\there\u0001""#, json_string("tab\there\u{1}"));
+    }
+
+    #[test]
+    fn ndjson_printer_writes_only_matches() {
+        let mut out = Vec::new();
+        printed(&mut NdjsonPrinter::new(&mut out), &[file()]);
+
+        let mut json = Vec::new();
+        JsonPrinter::new(&mut json)
+            .match_found(&file(), &file().matches[0])
+            .unwrap();
+        assert_eq!(json, out);
+    }
+
+    #[test]
+    fn csv_printer_quotes_fields() {
+        let mut out = Vec::new();
+        printed(&mut CsvPrinter::new(&mut out), &[file(), file()]);
+
+        let row = "poem.txt,2,25,10,\"Are you \"\"nobody\"\", too?\"\n";
+        assert_eq!(
+            format!("path,line_number,byte_offset,column,line\n{row}{row}"),
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn printers_take_separators() {
+        let nested = FileMatches {
+            path: String::from("docs/poem.txt"),
+            ..file()
+        };
+        let mut out = Vec::new();
+        let printer = CsvPrinter::new(&mut out)
+            .delimiter("\t")
+            .path_separator(Some('\\'));
+        printed(&mut { printer }, &[nested]);
+
+        assert_eq!(
+            "path\tline_number\tbyte_offset\tcolumn\tline\n\
+             docs\\poem.txt\t2\t25\t10\t\"Are you \"\"nobody\"\", too?\"\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!(Ok(Format::Json), Format::parse("json"));
+        assert_eq!(Ok(Format::Ndjson), Format::parse("ndjson"));
+        assert_eq!(Ok(Format::Csv), Format::parse("csv"));
+        assert!(Format::parse("xml").is_err());
+    }
+}
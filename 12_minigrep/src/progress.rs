@@ -0,0 +1,100 @@
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::FileMatches;
+
+/// How often the progress line is redrawn.
+const TICK: Duration = Duration::from_millis(100);
+
+/// A `files scanned / matches found` line on stderr, redrawn by a ticker
+/// thread while a long search runs.
+#[derive(Default)]
+pub struct Progress {
+    files: AtomicUsize,
+    matches: AtomicUsize,
+    /// Whether the line is currently on screen. Held while drawing or
+    /// printing results so the two never mix.
+    shown: Mutex<bool>,
+}
+
+impl Progress {
+    /// Only worth showing to a person watching stderr.
+    pub fn wanted() -> bool {
+        io::stderr().is_terminal()
+    }
+
+    pub fn record(&self, file: &FileMatches) {
+        self.files.fetch_add(1, Ordering::Relaxed);
+        self.matches.fetch_add(file.count, Ordering::Relaxed);
+    }
+
+    fn draw(&self) {
+        let mut shown = self.shown.lock().unwrap();
+        let files = self.files.load(Ordering::Relaxed);
+        let matches = self.matches.load(Ordering::Relaxed);
+        eprint!("\r\x1b[K{files} files scanned, {matches} matches found");
+        let _ = io::stderr().flush();
+        *shown = true;
+    }
+
+    /// Clears the progress line, then runs `print` before it can be redrawn.
+    pub fn clear_then(&self, print: impl FnOnce()) {
+        let mut shown = self.shown.lock().unwrap();
+        if *shown {
+            eprint!("\r\x1b[K");
+            *shown = false;
+        }
+        print();
+        let _ = io::stdout().flush();
+    }
+
+    /// Runs `body` while a ticker thread keeps the progress line up to date,
+    /// clearing it once `body` is done.
+    pub fn show_while<R>(&self, body: impl FnOnce() -> R) -> R {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let result = thread::scope(|s| {
+            s.spawn(move || {
+                // Ends once `stop` is dropped.
+                while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(TICK) {
+                    self.draw();
+                }
+            });
+            let result = body();
+            drop(stop);
+            result
+        });
+        self.clear_then(|| {});
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_files_and_matches() {
+        let progress = Progress::default();
+        let file = FileMatches {
+            path: String::from("poem.txt"),
+            matches: Vec::new(),
+            streamed: 0,
+            count: 2,
+            bytes: 0,
+            error: None,
+            binary: false,
+        };
+
+        progress.show_while(|| {
+            progress.record(&file);
+            progress.record(&file);
+        });
+
+        assert_eq!(2, progress.files.load(Ordering::Relaxed));
+        assert_eq!(4, progress.matches.load(Ordering::Relaxed));
+        assert!(!*progress.shown.lock().unwrap());
+    }
+}
@@ -0,0 +1,254 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+
+use crate::matcher::Matcher;
+use crate::trim_line;
+
+/// Rewrites the file at `path`, substituting `replacement` for every match
+/// on the first `max_count` matching lines (all of them if `None`), and
+/// returns how many lines changed.
+///
+/// A symbolic link is followed, so the file it points to is rewritten and
+/// the link stays a link.
+///
+/// The new contents are written to a temporary file next to the original,
+/// which then replaces it with a rename, so a failure part way through never
+/// leaves a half-written file behind. With `backup`, the original is also
+/// linked or copied to `<path>.bak` before that rename, replacing any older
+/// backup, so the original stays at `path` until the new contents take its
+/// place. Line endings and a leading byte order mark are left exactly as
+/// they were.
+pub fn write_in_place(
+    path: &Path,
+    matcher: &impl Matcher,
+    replacement: &str,
+    backup: bool,
+    max_count: Option<usize>,
+) -> io::Result<usize> {
+    let path = &fs::canonicalize(path)?;
+    let temp = temp_path(path);
+    let changed = match rewrite(path, &temp, matcher, replacement, max_count) {
+        Ok(changed) => changed,
+        Err(e) => {
+            let _ = fs::remove_file(&temp);
+            return Err(e);
+        }
+    };
+    if changed == 0 {
+        fs::remove_file(&temp)?;
+        return Ok(0);
+    }
+    let replaced = fs::metadata(path)
+        .and_then(|metadata| fs::set_permissions(&temp, metadata.permissions()))
+        .and_then(|()| if backup { keep_backup(path) } else { Ok(()) })
+        .and_then(|()| fs::rename(&temp, path));
+    if let Err(e) = replaced {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+    Ok(changed)
+}
+
+/// Keeps the file at `path` as `<path>.bak` while leaving it where it is.
+/// A hard link is tried first since it copies nothing; a copy is the
+/// fallback where links aren't supported.
+fn keep_backup(path: &Path) -> io::Result<()> {
+    let backup = backup_path(path);
+    match fs::remove_file(&backup) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    if fs::hard_link(path, &backup).is_err() {
+        fs::copy(path, &backup)?;
+    }
+    Ok(())
+}
+
+fn rewrite(
+    path: &Path,
+    temp: &Path,
+    matcher: &impl Matcher,
+    replacement: &str,
+    max_count: Option<usize>,
+) -> io::Result<usize> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut writer = BufWriter::new(File::create(temp)?);
+    let mut changed = 0;
+    let mut left = max_count.unwrap_or(usize::MAX);
+    let mut buf = String::new();
+    let mut first = true;
+    while reader.read_line(&mut buf)? > 0 {
+        let content_len = buf.trim_end_matches(['\r', '\n']).len();
+        let (line, ending) = buf.split_at(content_len);
+        let (skipped, line, _) = trim_line(line, mem::take(&mut first));
+        writer.write_all(&buf.as_bytes()[..skipped])?;
+        if left > 0 && matcher.find(line).is_some() {
+            left -= 1;
+            let replaced = matcher.replace(line, replacement);
+            if replaced != line {
+                changed += 1;
+            }
+            writer.write_all(replaced.as_bytes())?;
+        } else {
+            writer.write_all(line.as_bytes())?;
+        }
+        writer.write_all(ending.as_bytes())?;
+        buf.clear();
+    }
+    writer.flush()?;
+    Ok(changed)
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{name}.minigrep-tmp"))
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::{Literal, Regex};
+    use std::env;
+
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("minigrep-replace-{name}-{}", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn rewrites_matching_lines_only() {
+        let path = temp_file("rewrite", "Rust:\r\nsafe, fast, productive.\r\nPick three.");
+
+        let changed = write_in_place(&path, &Literal::new("fast"), "quick", false, None).unwrap();
+
+        assert_eq!(1, changed);
+        assert_eq!(
+            "Rust:\r\nsafe, quick, productive.\r\nPick three.",
+            fs::read_to_string(&path).unwrap()
+        );
+        assert!(!temp_path(&path).exists());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn keeps_a_byte_order_mark() {
+        let path = temp_file("bom", "\u{feff}Rust:\r\n");
+
+        let changed = write_in_place(
+            &path,
+            &Regex::new("^Rust:$", false).unwrap(),
+            "Go:",
+            false,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(1, changed);
+        assert_eq!("\u{feff}Go:\r\n", fs::read_to_string(&path).unwrap());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn keeps_a_backup() {
+        let path = temp_file("backup", "safe, fast\n");
+
+        write_in_place(
+            &path,
+            &Regex::new(r"(\w+), (\w+)", false).unwrap(),
+            "$2, $1",
+            true,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!("fast, safe\n", fs::read_to_string(&path).unwrap());
+        assert_eq!(
+            "safe, fast\n",
+            fs::read_to_string(backup_path(&path)).unwrap()
+        );
+        fs::remove_file(backup_path(&path)).unwrap();
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn replaces_an_older_backup() {
+        let path = temp_file(
+            "old-backup",
+            "safe, fast
+",
+        );
+        fs::write(
+            backup_path(&path),
+            "long gone
+",
+        )
+        .unwrap();
+
+        write_in_place(&path, &Literal::new("fast"), "quick", true, None).unwrap();
+
+        assert_eq!(
+            "safe, quick
+",
+            fs::read_to_string(&path).unwrap()
+        );
+        assert_eq!(
+            "safe, fast
+",
+            fs::read_to_string(backup_path(&path)).unwrap()
+        );
+        assert!(!temp_path(&path).exists());
+        fs::remove_file(backup_path(&path)).unwrap();
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn leaves_unmatched_files_alone() {
+        let path = temp_file("untouched", "Pick three.\n");
+
+        assert_eq!(
+            0,
+            write_in_place(&path, &Literal::new("duct"), "x", true, None).unwrap()
+        );
+        assert!(!backup_path(&path).exists());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn stops_after_max_count_lines() {
+        let path = temp_file("max-count", "fast\nfast\nfast\n");
+
+        let changed =
+            write_in_place(&path, &Literal::new("fast"), "quick", false, Some(2)).unwrap();
+
+        assert_eq!(2, changed);
+        assert_eq!("quick\nquick\nfast\n", fs::read_to_string(&path).unwrap());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rewrites_through_a_symlink() {
+        let target = temp_file("link-target", "safe, fast\n");
+        let link = target.with_file_name(format!("minigrep-replace-link-{}", std::process::id()));
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write_in_place(&link, &Literal::new("fast"), "quick", false, None).unwrap();
+
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!("safe, quick\n", fs::read_to_string(&target).unwrap());
+        fs::remove_file(link).unwrap();
+        fs::remove_file(target).unwrap();
+    }
+}
@@ -0,0 +1,534 @@
+//! `minigrep serve`: a small HTTP server answering `/search?q=...` with JSON,
+//! so a team can share one code-search service over a checkout.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::printer::json_string;
+use crate::{search_file, Config, MinigrepError};
+
+/// How many matches a page holds unless `per_page` says otherwise.
+pub const DEFAULT_PER_PAGE: usize = 20;
+
+/// The most matches a page can hold.
+pub const MAX_PER_PAGE: usize = 100;
+
+/// How many connections are answered at once. The rest wait their turn.
+const WORKERS: usize = 8;
+
+/// How long a client may take to send its request or read the answer.
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a client may take to send the request line and headers in all,
+/// however slowly it trickles them in.
+const HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The most a request line and its headers may take up together.
+const MAX_HEAD_BYTES: u64 = 8 * 1024;
+
+/// Where to serve from (`--root`, `--host`, `--port`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServeOptions {
+    pub root: PathBuf,
+    /// Only local connections are accepted unless this is changed, e.g. to
+    /// `0.0.0.0`.
+    pub host: String,
+    pub port: u16,
+}
+
+impl Default for ServeOptions {
+    fn default() -> Self {
+        ServeOptions {
+            root: PathBuf::from("."),
+            host: String::from("127.0.0.1"),
+            port: 8080,
+        }
+    }
+}
+
+impl ServeOptions {
+    /// Parses the arguments after `serve`.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, &'static str> {
+        let mut options = ServeOptions::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--root" => options.root = args.next().ok_or("--root needs a directory")?.into(),
+                "--host" => options.host = args.next().ok_or("--host needs an address")?,
+                "--port" => {
+                    let port = args.next().and_then(|port| port.parse().ok());
+                    options.port = port.ok_or("--port needs a port number")?;
+                }
+                _ => return Err("serve takes only --root, --host and --port"),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Serves searches of `options.root` until the process is stopped.
+pub fn serve(options: &ServeOptions) -> Result<(), MinigrepError> {
+    let address = format!("{}:{}", options.host, options.port);
+    let listener = TcpListener::bind(&address).map_err(|e| MinigrepError::io(&address, e))?;
+    eprintln!(
+        "Serving {} on http://{address}/search?q=...",
+        options.root.display()
+    );
+    accept(listener, &options.root);
+    Ok(())
+}
+
+/// Answers connections on [`WORKERS`] threads, each taking the next one
+/// as soon as it's done with the last.
+fn accept(listener: TcpListener, root: &Path) {
+    thread::scope(|s| {
+        for _ in 0..WORKERS {
+            s.spawn(|| {
+                for stream in listener.incoming().flatten() {
+                    if let Err(e) = handle(stream, root) {
+                        eprintln!("minigrep: {e}");
+                    }
+                }
+            });
+        }
+    });
+}
+
+fn handle(stream: TcpStream, root: &Path) -> io::Result<()> {
+    // A client that stops sending or reading mustn't hold a worker forever.
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let head = Deadline {
+        stream: &stream,
+        deadline: Instant::now() + HEAD_TIMEOUT,
+    };
+    let response = match read_head(head) {
+        Ok(Some(request_line)) => respond(&request_line, root),
+        Ok(None) => Response::error(
+            "431 Request Header Fields Too Large",
+            "the request line and headers must fit in 8 KiB",
+        ),
+        // A timed out socket read is `WouldBlock` on some platforms.
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+            ) =>
+        {
+            Response::error("408 Request Timeout", "the request took too long to arrive")
+        }
+        Err(e) => return Err(e),
+    };
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        response.status,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+/// Reads from a connection until `deadline`, after which reads fail with
+/// `TimedOut`. The socket's own timeout only bounds each read, which a
+/// client sending a byte at a time never runs into.
+struct Deadline<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(left.min(IO_TIMEOUT)))?;
+        let mut stream = self.stream;
+        stream.read(buf)
+    }
+}
+
+/// Reads the request line and the headers after it, returning the request
+/// line, or `None` if the two are longer than [`MAX_HEAD_BYTES`].
+fn read_head(stream: impl Read) -> io::Result<Option<String>> {
+    let mut reader = BufReader::new(stream).take(MAX_HEAD_BYTES);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // The headers don't change the answer, but have to be read past.
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if reader.read_line(&mut header)? == 0 {
+            break;
+        }
+        if header.trim_end().is_empty() {
+            return Ok(Some(request_line));
+        }
+    }
+    // Running out of room looks just like the client hanging up.
+    Ok((reader.limit() > 0).then_some(request_line))
+}
+
+/// What to send back for one request.
+#[derive(Debug, PartialEq, Eq)]
+struct Response {
+    status: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Self {
+        Response {
+            status: "200 OK",
+            body,
+        }
+    }
+
+    fn error(status: &'static str, message: &str) -> Self {
+        Response {
+            status,
+            body: format!(r#"{{"error":{}}}"#, json_string(message)),
+        }
+    }
+}
+
+/// Routes a request line like `GET /search?q=duct HTTP/1.1`.
+fn respond(request_line: &str, root: &Path) -> Response {
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Response::error("400 Bad Request", "malformed request");
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    match (method, path) {
+        ("GET", "/search") => match SearchRequest::parse(query) {
+            Ok(request) => request.run(root),
+            Err(message) => Response::error("400 Bad Request", message),
+        },
+        (_, "/search") => Response::error("405 Method Not Allowed", "only GET is supported"),
+        _ => Response::error("404 Not Found", "try /search?q=..."),
+    }
+}
+
+/// The parameters of `/search`.
+#[derive(Debug, PartialEq, Eq)]
+struct SearchRequest {
+    query: String,
+    regex: bool,
+    ignore_case: bool,
+    /// 1-based.
+    page: usize,
+    per_page: usize,
+}
+
+impl SearchRequest {
+    fn parse(query: &str) -> Result<Self, &'static str> {
+        let mut request = SearchRequest {
+            query: String::new(),
+            regex: false,
+            ignore_case: false,
+            page: 1,
+            per_page: DEFAULT_PER_PAGE,
+        };
+        for (key, value) in query_pairs(query) {
+            match key.as_str() {
+                "q" => request.query = value,
+                "regex" => request.regex = flag(&value)?,
+                "i" => request.ignore_case = flag(&value)?,
+                "page" => match value.parse() {
+                    Ok(page) if page > 0 => request.page = page,
+                    _ => return Err("page must be a positive number"),
+                },
+                "per_page" => match value.parse() {
+                    Ok(n) if (1..=MAX_PER_PAGE).contains(&n) => request.per_page = n,
+                    _ => return Err("per_page must be between 1 and 100"),
+                },
+                _ => return Err("unknown parameter; use q, regex, i, page or per_page"),
+            }
+        }
+        if request.query.is_empty() {
+            return Err("q is required");
+        }
+        Ok(request)
+    }
+
+    /// Searches `root` and renders the requested page of matches.
+    ///
+    /// The search stops one match past the end of the page, which is enough
+    /// to say whether there's another. Files are searched one at a time, in
+    /// the order the walk finds them, so every request sees the matches in
+    /// the same order and the pages line up.
+    fn run(&self, root: &Path) -> Response {
+        let start = (self.page - 1).saturating_mul(self.per_page);
+        let end = start.saturating_add(self.per_page);
+        let config = Config::builder()
+            .query(self.query.as_str())
+            .path(root.display().to_string())
+            .regex(self.regex)
+            .case_insensitive(self.ignore_case)
+            .threads(1)
+            .max_total(end.saturating_add(1))
+            .build();
+        let results = match config.and_then(|config| search_file(&config)) {
+            Ok(results) => results,
+            Err(e) => return Response::error("400 Bad Request", &e.to_string()),
+        };
+        let all: Vec<_> = results
+            .iter()
+            .flat_map(|file| file.matches.iter().map(move |m| (file, m)))
+            .collect();
+        let page: Vec<_> = all
+            .iter()
+            .skip(start)
+            .take(self.per_page)
+            .map(|(file, m)| {
+                let path = Path::new(&file.path);
+                let path = path.strip_prefix(root).unwrap_or(path);
+                let submatches: Vec<_> = m
+                    .spans
+                    .iter()
+                    .map(|span| format!(r#"{{"start":{},"end":{}}}"#, span.start, span.end))
+                    .collect();
+                format!(
+                    r#"{{"path":{},"line_number":{},"line":{},"submatches":[{}]}}"#,
+                    json_string(&path.display().to_string()),
+                    m.line_no,
+                    json_string(&m.line),
+                    submatches.join(","),
+                )
+            })
+            .collect();
+        Response::ok(format!(
+            r#"{{"query":{},"page":{},"per_page":{},"more":{},"matches":[{}]}}"#,
+            json_string(&self.query),
+            self.page,
+            self.per_page,
+            all.len() > end,
+            page.join(","),
+        ))
+    }
+}
+
+fn flag(value: &str) -> Result<bool, &'static str> {
+    match value {
+        "" | "1" | "true" => Ok(true),
+        "0" | "false" => Ok(false),
+        _ => Err("regex and i must be true or false"),
+    }
+}
+
+/// Splits a query string into decoded `key=value` pairs.
+fn query_pairs(query: &str) -> impl Iterator<Item = (String, String)> + '_ {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+}
+
+/// Decodes `%XX` escapes and `+` for spaces. Malformed escapes are kept
+/// as they are.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                // `from_str_radix` would also take a sign, as in `%+F`.
+                let hex = bytes
+                    .get(i + 1..i + 3)
+                    .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+                let hex = hex.and_then(|hex| std::str::from_utf8(hex).ok());
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Read;
+
+    fn root(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("minigrep-serve-{name}-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::copy("poem.txt", root.join("poem.txt")).unwrap();
+        root
+    }
+
+    #[test]
+    fn parses_options() {
+        let args = ["--root", "src", "--port", "9000"].map(String::from);
+        let options = ServeOptions::parse(args).unwrap();
+        assert_eq!(PathBuf::from("src"), options.root);
+        assert_eq!(9000, options.port);
+        assert_eq!("127.0.0.1", options.host);
+        assert!(ServeOptions::parse(["--port", "http"].map(String::from)).is_err());
+    }
+
+    #[test]
+    fn decodes_query_strings() {
+        assert_eq!("a b/c%", percent_decode("a+b%2Fc%"));
+        assert_eq!("héllo", percent_decode("h%C3%A9llo"));
+        assert_eq!("% F%-1", percent_decode("%+F%-1"));
+        let pairs: Vec<_> = query_pairs("q=duct+tape&i").collect();
+        assert_eq!(
+            vec![
+                (String::from("q"), String::from("duct tape")),
+                (String::from("i"), String::new())
+            ],
+            pairs
+        );
+    }
+
+    #[test]
+    fn validates_search_parameters() {
+        let request = SearchRequest::parse("q=nobody&page=2&per_page=5&i").unwrap();
+        assert_eq!(2, request.page);
+        assert_eq!(5, request.per_page);
+        assert!(request.ignore_case);
+        assert!(SearchRequest::parse("page=2").is_err());
+        assert!(SearchRequest::parse("q=a&per_page=1000").is_err());
+        assert!(SearchRequest::parse("q=a&page=0").is_err());
+        assert!(SearchRequest::parse("q=a&color=red").is_err());
+    }
+
+    #[test]
+    fn routes_requests() {
+        let dir = root("routes");
+        let root = dir.as_path();
+        let found = respond("GET /search?q=nobody&per_page=1 HTTP/1.1\r\n", root);
+        assert_eq!("200 OK", found.status);
+        assert!(found.body.contains(r#""per_page":1,"more":true,"#));
+        assert!(found.body.contains(r#""path":"poem.txt","line_number":1,"#));
+
+        let second = respond("GET /search?q=nobody&per_page=1&page=2 HTTP/1.1", root);
+        assert!(second.body.contains(r#""more":false,"#));
+        assert!(second.body.contains(r#""line_number":2,"#));
+        let past_the_end = respond("GET /search?q=nobody&page=9 HTTP/1.1", root);
+        assert!(past_the_end.body.ends_with(r#""matches":[]}"#));
+
+        assert_eq!("404 Not Found", respond("GET / HTTP/1.1", root).status);
+        let post = respond("POST /search?q=a HTTP/1.1", root);
+        assert_eq!("405 Method Not Allowed", post.status);
+        let bad = respond("GET /search?q=(&regex HTTP/1.1", root);
+        assert_eq!("400 Bad Request", bad.status);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn serves_over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let dir = root("tcp");
+        let served = dir.clone();
+        thread::spawn(move || accept(listener, &served));
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream
+            .write_all(b"GET /search?q=nobody HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(r#""more":false"#));
+
+        // Connections past the workers wait rather than being turned away.
+        let streams: Vec<_> = (0..WORKERS * 2)
+            .map(|_| TcpStream::connect(address).unwrap())
+            .collect();
+        for mut stream in streams {
+            stream
+                .write_all(b"GET /search?q=duct HTTP/1.1\r\n\r\n")
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn caps_the_request_head() {
+        let request = "GET /search?q=a HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        let head = read_head(request.as_bytes()).unwrap();
+        assert_eq!(Some("GET /search?q=a HTTP/1.1\r\n"), head.as_deref());
+
+        let huge = format!(
+            "GET /search?q=a HTTP/1.1\r\nCookie: {}\r\n\r\n",
+            "x".repeat(9000)
+        );
+        assert_eq!(None, read_head(huge.as_bytes()).unwrap());
+        let long_target = format!("GET /search?q={} HTTP/1.1\r\n\r\n", "a".repeat(9000));
+        assert_eq!(None, read_head(long_target.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn gives_up_on_slow_request_heads() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(address).unwrap();
+            // Never quite finishes, but never goes quiet for long either.
+            for _ in 0..20 {
+                if stream.write_all(b"x").is_err() {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(20));
+            }
+        });
+        let (stream, _) = listener.accept().unwrap();
+        let head = Deadline {
+            stream: &stream,
+            deadline: Instant::now() + Duration::from_millis(100),
+        };
+        let start = Instant::now();
+        let e = read_head(head).unwrap_err();
+        assert_eq!(io::ErrorKind::TimedOut, e.kind());
+        assert!(start.elapsed() < Duration::from_millis(300));
+        drop(stream);
+        client.join().unwrap();
+    }
+
+    #[test]
+    fn stops_searching_past_the_page() {
+        let dir = root("early");
+        let lines: String = (0..500).map(|i| format!("needle {i}\n")).collect();
+        fs::write(dir.join("many.txt"), lines).unwrap();
+        let request = SearchRequest::parse("q=needle&per_page=3&page=2").unwrap();
+        let response = request.run(&dir);
+        assert!(
+            response.body.contains(r#""more":true"#),
+            "{}",
+            response.body
+        );
+        assert!(
+            response.body.contains(r#""line":"needle 3""#),
+            "{}",
+            response.body
+        );
+        assert!(!response.body.contains(r#""line":"needle 6""#));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
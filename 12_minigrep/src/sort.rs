@@ -0,0 +1,101 @@
+use std::fs;
+use std::time::SystemTime;
+
+use crate::FileMatches;
+
+/// How to order results before printing them (`--sort`), instead of the
+/// order the workers happened to finish in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    Path,
+    /// Oldest first.
+    Modified,
+    /// Smallest first.
+    Size,
+}
+
+impl SortBy {
+    pub fn parse(value: &str) -> Result<Self, &'static str> {
+        match value {
+            "path" => Ok(SortBy::Path),
+            "modified" => Ok(SortBy::Modified),
+            "size" => Ok(SortBy::Size),
+            _ => Err("--sort must be one of path, modified or size"),
+        }
+    }
+
+    /// Sorts `files` in place. Files whose metadata can't be read, like
+    /// standard input, come first; ties are broken by path.
+    pub fn sort(self, files: &mut [FileMatches]) {
+        match self {
+            SortBy::Path => files.sort_by(|a, b| a.path.cmp(&b.path)),
+            SortBy::Modified => {
+                files.sort_by_cached_key(|file| (modified(file), file.path.clone()))
+            }
+            SortBy::Size => files.sort_by_cached_key(|file| (size(file), file.path.clone())),
+        }
+    }
+}
+
+fn modified(file: &FileMatches) -> Option<SystemTime> {
+    fs::metadata(&file.path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn size(file: &FileMatches) -> Option<u64> {
+    fs::metadata(&file.path).map(|metadata| metadata.len()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(paths: &[&str]) -> Vec<FileMatches> {
+        paths
+            .iter()
+            .map(|path| FileMatches {
+                path: path.to_string(),
+                matches: Vec::new(),
+                streamed: 0,
+                count: 0,
+                bytes: 0,
+                error: None,
+                binary: false,
+            })
+            .collect()
+    }
+
+    fn paths(files: &[FileMatches]) -> Vec<&str> {
+        files.iter().map(|file| file.path.as_str()).collect()
+    }
+
+    #[test]
+    fn sorts_by_path_and_size() {
+        let mut found = files(&["src/main.rs", "poem.txt", "Cargo.toml", "(standard input)"]);
+
+        SortBy::Path.sort(&mut found);
+        assert_eq!(
+            vec!["(standard input)", "Cargo.toml", "poem.txt", "src/main.rs"],
+            paths(&found)
+        );
+
+        let mut sizes: Vec<_> = ["src/main.rs", "poem.txt", "Cargo.toml"]
+            .into_iter()
+            .map(|path| (fs::metadata(path).unwrap().len(), path))
+            .collect();
+        sizes.sort();
+        SortBy::Size.sort(&mut found);
+        assert_eq!("(standard input)", found[0].path);
+        assert_eq!(
+            sizes.iter().map(|(_, path)| *path).collect::<Vec<_>>(),
+            paths(&found[1..])
+        );
+    }
+
+    #[test]
+    fn parses_sort_keys() {
+        assert_eq!(Ok(SortBy::Modified), SortBy::parse("modified"));
+        assert!(SortBy::parse("name").is_err());
+    }
+}
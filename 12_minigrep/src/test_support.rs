@@ -0,0 +1,20 @@
+//! Helpers shared by the unit tests of more than one module.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Makes a fresh directory under the temp dir holding `files`, given as
+/// paths relative to it and their contents. `name` keeps tests running at
+/// the same time out of each other's way, so each test needs its own.
+pub fn tree(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let root = env::temp_dir().join(format!("minigrep-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    for (path, contents) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    root
+}
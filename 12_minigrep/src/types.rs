@@ -0,0 +1,147 @@
+use std::path::Path;
+
+use glob::Pattern;
+
+use crate::error::MinigrepError;
+
+/// The file types every search knows about, as `name: globs`.
+const BUILT_IN: &[(&str, &[&str])] = &[
+    ("c", &["*.c", "*.h"]),
+    ("cpp", &["*.cpp", "*.cc", "*.cxx", "*.hpp", "*.hh"]),
+    ("css", &["*.css"]),
+    ("go", &["*.go"]),
+    ("html", &["*.html", "*.htm"]),
+    ("java", &["*.java"]),
+    ("js", &["*.js", "*.mjs", "*.cjs"]),
+    ("json", &["*.json"]),
+    ("md", &["*.md", "*.markdown"]),
+    ("py", &["*.py"]),
+    ("rust", &["*.rs"]),
+    ("sh", &["*.sh", "*.bash"]),
+    ("toml", &["*.toml"]),
+    ("ts", &["*.ts", "*.tsx"]),
+    ("txt", &["*.txt"]),
+    ("yaml", &["*.yaml", "*.yml"]),
+];
+
+/// Restricts a recursive search to files of some types (`--type`), or away
+/// from them (`--type-not`), matched on the file name.
+#[derive(Debug, Clone, Default)]
+pub struct FileTypes {
+    /// Extra definitions from `--type-add name:glob`.
+    added: Vec<String>,
+    selected: Vec<String>,
+    negated: Vec<String>,
+    /// The globs of the selected and negated types, filled in by `compile`
+    /// once every definition is known.
+    select: Vec<Pattern>,
+    negate: Vec<Pattern>,
+}
+
+impl FileTypes {
+    pub(crate) fn add(&mut self, definition: impl Into<String>) {
+        self.added.push(definition.into());
+    }
+
+    pub(crate) fn select(&mut self, name: impl Into<String>) {
+        self.selected.push(name.into());
+    }
+
+    pub(crate) fn negate(&mut self, name: impl Into<String>) {
+        self.negated.push(name.into());
+    }
+
+    /// Resolves the selected type names to their globs, so that `--type-add`
+    /// can come before or after the `--type` that uses it.
+    pub(crate) fn compile(&mut self) -> Result<(), MinigrepError> {
+        let mut defs: Vec<(String, String)> = BUILT_IN
+            .iter()
+            .flat_map(|(name, globs)| {
+                globs
+                    .iter()
+                    .map(|glob| (name.to_string(), glob.to_string()))
+            })
+            .collect();
+        for definition in &self.added {
+            let Some((name, glob)) = definition.split_once(':') else {
+                let message = format!("--type-add needs name:glob, got {definition}");
+                return Err(MinigrepError::BadArgs(message));
+            };
+            defs.push((name.to_string(), glob.to_string()));
+        }
+
+        self.select = resolve(&defs, &self.selected)?;
+        self.negate = resolve(&defs, &self.negated)?;
+        Ok(())
+    }
+
+    /// Whether a file at `path` should be searched.
+    pub fn allows(&self, path: &Path) -> bool {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            return self.select.is_empty();
+        };
+        let matches = |patterns: &[Pattern]| patterns.iter().any(|p| p.matches(name));
+        (self.select.is_empty() || matches(&self.select)) && !matches(&self.negate)
+    }
+}
+
+fn resolve(defs: &[(String, String)], names: &[String]) -> Result<Vec<Pattern>, MinigrepError> {
+    let mut patterns = Vec::new();
+    for name in names {
+        let globs: Vec<_> = defs.iter().filter(|(def, _)| def == name).collect();
+        if globs.is_empty() {
+            return Err(MinigrepError::BadArgs(format!("unknown file type: {name}")));
+        }
+        for (_, glob) in globs {
+            let pattern = Pattern::new(glob)
+                .map_err(|e| MinigrepError::BadArgs(format!("{name}: invalid glob {glob}: {e}")))?;
+            patterns.push(pattern);
+        }
+    }
+    Ok(patterns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_and_negates_types() {
+        let mut types = FileTypes::default();
+        assert!(types.allows(Path::new("notes.md")));
+
+        types.select("rust");
+        types.select("toml");
+        types.compile().unwrap();
+        assert!(types.allows(Path::new("src/lib.rs")));
+        assert!(types.allows(Path::new("Cargo.toml")));
+        assert!(!types.allows(Path::new("README.md")));
+
+        let mut types = FileTypes::default();
+        types.negate("md");
+        types.compile().unwrap();
+        assert!(types.allows(Path::new("src/lib.rs")));
+        assert!(!types.allows(Path::new("README.markdown")));
+    }
+
+    #[test]
+    fn custom_types() {
+        let mut types = FileTypes::default();
+        types.select("poems");
+        types.add("poems:*.poem");
+        types.compile().unwrap();
+        assert!(types.allows(Path::new("frog.poem")));
+        assert!(!types.allows(Path::new("poem.txt")));
+    }
+
+    #[test]
+    fn rejects_bad_definitions() {
+        let mut types = FileTypes::default();
+        types.select("nope");
+        assert!(types.compile().is_err());
+
+        let mut types = FileTypes::default();
+        types.add("poems");
+        assert!(types.compile().is_err());
+    }
+}
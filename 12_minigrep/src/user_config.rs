@@ -0,0 +1,127 @@
+use std::path::PathBuf;
+use std::{env, fs, io};
+
+use toml::{Table, Value};
+
+use crate::config::LONG_OPTIONS;
+use crate::error::MinigrepError;
+
+/// Where the user's default flags live: `$XDG_CONFIG_HOME/minigrep/config.toml`,
+/// falling back to `~/.config/minigrep/config.toml`.
+pub fn path() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("minigrep").join("config.toml"))
+}
+
+/// Reads the user's config file as a list of flags, to be parsed before the
+/// ones on the command line so those win. A missing file means no defaults,
+/// and a key that isn't a long option is an error naming the file.
+pub fn load() -> Result<Vec<String>, MinigrepError> {
+    let Some(path) = path() else {
+        return Ok(Vec::new());
+    };
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(MinigrepError::io(path, e)),
+    };
+    to_args(&text).map_err(|e| MinigrepError::BadArgs(format!("{}: {e}", path.display())))
+}
+
+/// Turns each `key = value` into the matching long flag: `true` into
+/// `--key`, strings and numbers into `--key value`, and arrays into one
+/// flag per element.
+fn to_args(text: &str) -> Result<Vec<String>, String> {
+    let table: Table = text.parse().map_err(|e: toml::de::Error| e.to_string())?;
+    let mut args = Vec::new();
+    for (key, value) in &table {
+        match value {
+            Value::Array(values) => {
+                for value in values {
+                    push_flag(&mut args, key, value)?;
+                }
+            }
+            value => push_flag(&mut args, key, value)?,
+        }
+    }
+    Ok(args)
+}
+
+fn push_flag(args: &mut Vec<String>, key: &str, value: &Value) -> Result<(), String> {
+    let Some(&(_, takes_value)) = LONG_OPTIONS.iter().find(|(name, _)| *name == key) else {
+        return Err(format!("unknown config key `{key}`"));
+    };
+    let value = match value {
+        Value::Boolean(true) if !takes_value => {
+            args.push(format!("--{key}"));
+            return Ok(());
+        }
+        Value::Boolean(false) if !takes_value => return Ok(()),
+        _ if !takes_value => return Err(format!("`{key}` must be true or false")),
+        Value::String(value) => value.clone(),
+        Value::Integer(value) => value.to_string(),
+        _ => return Err(format!("unsupported value for {key}")),
+    };
+    args.push(format!("--{key}"));
+    args.push(value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_keys_to_flags() {
+        let text = r#"
+            color = "never"
+            hidden = true
+            no-ignore = false
+            threads = 2
+            type-add = ["poems:*.poem", "notes:*.note"]
+        "#;
+
+        assert_eq!(
+            vec![
+                "--color",
+                "never",
+                "--hidden",
+                "--threads",
+                "2",
+                "--type-add",
+                "poems:*.poem",
+                "--type-add",
+                "notes:*.note",
+            ],
+            to_args(text).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_bad_files() {
+        assert!(to_args("color = ").is_err());
+        assert!(to_args("threads = 1.5").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert_eq!(
+            Err(String::from("unknown config key `colour`")),
+            to_args(r#"colour = "never""#)
+        );
+        assert!(to_args("no-config = true").is_err());
+    }
+
+    #[test]
+    fn checks_values_fit_the_flag() {
+        assert_eq!(
+            Err(String::from("`hidden` must be true or false")),
+            to_args(r#"hidden = "yes""#)
+        );
+        assert!(to_args("threads = true").is_err());
+        assert_eq!(vec!["--count"], to_args("count = true").unwrap());
+    }
+}
@@ -0,0 +1,73 @@
+//! Asks git which files have changed, so `--changed` can search just those,
+//! e.g. from a pre-commit hook.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// The files `git status` reports as modified, staged, renamed or untracked
+/// in the repository around the current directory, canonicalized so they
+/// can be compared with the paths being searched. Deleted files are left
+/// out since there's nothing to search.
+pub fn changed_files() -> io::Result<HashSet<PathBuf>> {
+    let root = git(&["rev-parse", "--show-toplevel"])?;
+    let root = PathBuf::from(String::from_utf8_lossy(&root).trim_end());
+    let status = git(&["status", "--porcelain", "-z", "--untracked-files=all"])?;
+    Ok(parse_status(&status)
+        .into_iter()
+        .filter_map(|path| fs::canonicalize(root.join(path)).ok())
+        .collect())
+}
+
+/// Runs git with `args` and returns what it printed, or what it complained
+/// about if it failed.
+fn git(args: &[&str]) -> io::Result<Vec<u8>> {
+    let output = Command::new("git")
+        .args(args)
+        .stdin(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(stderr.trim().to_string()));
+    }
+    Ok(output.stdout)
+}
+
+/// The paths in `git status --porcelain -z` output, relative to the
+/// repository's root. Each entry is two status letters, a space and a path;
+/// renames and copies are followed by an extra entry with the old path,
+/// which is skipped.
+fn parse_status(status: &[u8]) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut entries = status.split(|&b| b == 0).filter(|entry| !entry.is_empty());
+    while let Some(entry) = entries.next() {
+        if entry.len() < 4 {
+            continue;
+        }
+        let (x, y) = (entry[0], entry[1]);
+        if x == b'R' || x == b'C' {
+            entries.next();
+        }
+        if x == b'D' || y == b'D' {
+            continue;
+        }
+        paths.push(String::from_utf8_lossy(&entry[3..]).into_owned());
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_porcelain_status() {
+        let status = b" M src/lib.rs\0?? notes.txt\0R  new.rs\0old.rs\0 D gone.rs\0A  added.rs\0";
+        assert_eq!(
+            vec!["src/lib.rs", "notes.txt", "new.rs", "added.rs"],
+            parse_status(status)
+        );
+    }
+}
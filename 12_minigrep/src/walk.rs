@@ -0,0 +1,319 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::error::MinigrepError;
+use crate::filter::{FilterChain, PathGlobs};
+use crate::log;
+use crate::types::FileTypes;
+
+/// Controls which entries a recursive search visits.
+#[derive(Debug, Clone, Default)]
+pub struct WalkOptions {
+    /// Search hidden files and directories (`--hidden`).
+    pub hidden: bool,
+    /// Don't read `.gitignore` and `.ignore` files (`--no-ignore`).
+    pub no_ignore: bool,
+    /// How many directories deep to descend, where 1 only searches the
+    /// directory's own files (`--max-depth`).
+    pub max_depth: Option<usize>,
+    /// Follow symbolic links rather than skipping them (`--follow`).
+    pub follow: bool,
+    /// Which file types to search (`--type`, `--type-not`).
+    pub types: FileTypes,
+    /// Which paths to search (`--include`, `--exclude`).
+    pub globs: PathGlobs,
+    /// Stop descending into directories once this passes (`--timeout`).
+    pub deadline: Option<Instant>,
+}
+
+/// Recursively collects the files under `root`, in sorted order, skipping
+/// symbolic links and whatever the [`FilterChain`] rejects unless `options`
+/// say otherwise.
+pub fn walk(root: &Path, options: &WalkOptions) -> Vec<Result<PathBuf, MinigrepError>> {
+    let mut files = Vec::new();
+    walk_each(root, options, &mut |file| {
+        files.push(file);
+        true
+    });
+    files
+}
+
+/// Walks like [`walk`], handing each file to `found` as it's reached and
+/// stopping as soon as `found` returns false.
+pub fn walk_each(
+    root: &Path,
+    options: &WalkOptions,
+    found: &mut dyn FnMut(Result<PathBuf, MinigrepError>) -> bool,
+) {
+    let real_root = match fs::canonicalize(root) {
+        Ok(real_root) => real_root,
+        Err(e) => {
+            found(Err(MinigrepError::io(root, e)));
+            return;
+        }
+    };
+    let mut walker = Walker {
+        options,
+        filters: FilterChain::new(options, root, &real_root),
+        ancestors: Vec::new(),
+        found,
+        done: false,
+    };
+    walker.visit(root, &real_root, 0);
+}
+
+/// Identifies a directory independently of the path it was reached by, so
+/// symlink loops can be spotted.
+#[cfg(unix)]
+type DirId = (u64, u64);
+#[cfg(not(unix))]
+type DirId = PathBuf;
+
+#[cfg(unix)]
+fn dir_id(path: &Path) -> io::Result<DirId> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = fs::metadata(path)?;
+    Ok((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn dir_id(path: &Path) -> io::Result<DirId> {
+    fs::canonicalize(path)
+}
+
+/// The state of one walk.
+struct Walker<'a> {
+    options: &'a WalkOptions,
+    filters: FilterChain<'a>,
+    /// The directories currently being visited, from the root down.
+    ancestors: Vec<DirId>,
+    found: &'a mut dyn FnMut(Result<PathBuf, MinigrepError>) -> bool,
+    /// Whether `found` has had all it wants.
+    done: bool,
+}
+
+impl Walker<'_> {
+    fn report(&mut self, file: Result<PathBuf, MinigrepError>) {
+        self.done = !(self.found)(file);
+    }
+
+    /// Visits `dir`, which is the path as it should be printed, while `real`
+    /// is its canonical form that ignore rules are matched against. `depth`
+    /// counts the directories between `dir` and the root.
+    fn visit(&mut self, dir: &Path, real: &Path, depth: usize) {
+        if self.done || self.options.max_depth.is_some_and(|max| depth >= max) {
+            return;
+        }
+        if self
+            .options
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            log::info(|| format!("skipping {}: past --timeout", dir.display()));
+            return;
+        }
+        let id = match dir_id(dir) {
+            Ok(id) => id,
+            Err(e) => {
+                self.report(Err(MinigrepError::io(dir, e)));
+                return;
+            }
+        };
+        // Only followed symlinks can lead back to a directory we're inside.
+        if self.ancestors.contains(&id) {
+            let source = io::Error::other("symlink loop, skipping directory");
+            self.report(Err(MinigrepError::io(dir, source)));
+            return;
+        }
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.report(Err(MinigrepError::io(dir, e)));
+                return;
+            }
+        };
+        let mut entries: Vec<_> = entries.filter_map(Result::ok).collect();
+        entries.sort_by_key(|entry| entry.file_name());
+
+        let pushed = self.filters.enter(real);
+        self.ancestors.push(id);
+
+        for entry in entries {
+            let name = entry.file_name();
+            // Keep paths under `.` relative, the way they'd be typed.
+            let path = if dir == Path::new(".") {
+                PathBuf::from(&name)
+            } else {
+                dir.join(&name)
+            };
+            let real_path = real.join(&name);
+            let Ok(mut file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_symlink() {
+                if !self.options.follow {
+                    log::info(|| format!("skipping {}: symbolic link", path.display()));
+                    continue;
+                }
+                // A dangling link has nothing to search.
+                let Ok(metadata) = fs::metadata(&path) else {
+                    continue;
+                };
+                file_type = metadata.file_type();
+            }
+            let is_dir = file_type.is_dir();
+            if !self.filters.allows(&path, &real_path, is_dir) {
+                continue;
+            }
+            if is_dir {
+                self.visit(&path, &real_path, depth + 1);
+            } else if file_type.is_file() {
+                self.report(Ok(path));
+            }
+            if self.done {
+                break;
+            }
+        }
+
+        self.ancestors.pop();
+        self.filters.leave(pushed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::tree;
+
+    fn names(root: &Path, options: &WalkOptions) -> Vec<String> {
+        walk(root, options)
+            .into_iter()
+            .map(|path| {
+                let path = path.unwrap();
+                path.strip_prefix(root).unwrap().display().to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn skips_hidden_and_ignored_files() {
+        let root = tree(
+            "skip",
+            &[
+                (".gitignore", "*.log\n"),
+                (".hidden/secret.txt", ""),
+                ("debug.log", ""),
+                ("src/lib.rs", ""),
+                ("src/.ignore", "generated.rs\n"),
+                ("src/generated.rs", ""),
+            ],
+        );
+
+        assert_eq!(vec!["src/lib.rs"], names(&root, &WalkOptions::default()));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn hidden_and_no_ignore_flags() {
+        let root = tree("flags", &[(".gitignore", "*.log\n"), ("debug.log", "")]);
+
+        let hidden = WalkOptions {
+            hidden: true,
+            ..WalkOptions::default()
+        };
+        assert_eq!(vec![".gitignore"], names(&root, &hidden));
+
+        let everything = WalkOptions {
+            hidden: true,
+            no_ignore: true,
+            ..WalkOptions::default()
+        };
+        assert_eq!(vec![".gitignore", "debug.log"], names(&root, &everything));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn limits_depth() {
+        let root = tree(
+            "depth",
+            &[("top.txt", ""), ("a/mid.txt", ""), ("a/b/deep.txt", "")],
+        );
+
+        let options = WalkOptions {
+            max_depth: Some(2),
+            ..WalkOptions::default()
+        };
+        assert_eq!(vec!["a/mid.txt", "top.txt"], names(&root, &options));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn stops_when_nothing_more_is_wanted() {
+        let root = tree(
+            "stop",
+            &[("a/one.txt", ""), ("a/two.txt", ""), ("b/three.txt", "")],
+        );
+
+        let mut found = Vec::new();
+        walk_each(&root, &WalkOptions::default(), &mut |file| {
+            found.push(file.unwrap());
+            found.len() < 2
+        });
+        assert_eq!(vec![root.join("a/one.txt"), root.join("a/two.txt")], found);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follows_symlinks_without_looping() {
+        let root = tree("follow", &[("dir/file.txt", "")]);
+        std::os::unix::fs::symlink(&root, root.join("dir/loop")).unwrap();
+
+        assert_eq!(vec!["dir/file.txt"], names(&root, &WalkOptions::default()));
+
+        let follow = WalkOptions {
+            follow: true,
+            ..WalkOptions::default()
+        };
+        let results = walk(&root, &follow);
+        let files: Vec<_> = results
+            .iter()
+            .filter_map(|path| path.as_ref().ok())
+            .collect();
+        assert_eq!(vec![&root.join("dir/file.txt")], files);
+        assert_eq!(1, results.iter().filter(|path| path.is_err()).count());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn filters_by_type() {
+        let root = tree("types", &[("README.md", ""), ("src/lib.rs", "")]);
+
+        let mut options = WalkOptions::default();
+        options.types.select("rust");
+        options.types.compile().unwrap();
+        assert_eq!(vec!["src/lib.rs"], names(&root, &options));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn filters_by_path_globs() {
+        let root = tree(
+            "globs",
+            &[
+                ("Cargo.toml", ""),
+                ("src/lib.rs", ""),
+                ("target/out.toml", ""),
+            ],
+        );
+
+        let mut options = WalkOptions::default();
+        options.globs.include("*.toml");
+        options.globs.exclude("target");
+        options.globs.compile().unwrap();
+        assert_eq!(vec!["Cargo.toml"], names(&root, &options));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,256 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::mpsc;
+
+use notify::{RecursiveMode, Watcher};
+
+use crate::error::MinigrepError;
+use crate::matcher::Matcher;
+use crate::printer::{self, Printer};
+use crate::{input, trim_line, Config, FileMatches, LineMatch, OutputMode};
+
+/// A file being followed, and how far into it we've searched.
+struct Tail {
+    path: String,
+    /// The byte offset just past the last complete line searched.
+    offset: u64,
+    line_no: usize,
+    /// How many bytes the last scan searched.
+    read: u64,
+    /// How many lines have matched so far, or matches with
+    /// `--count-matches`.
+    count: usize,
+}
+
+impl Tail {
+    fn new(path: String) -> Self {
+        Tail {
+            path,
+            offset: 0,
+            line_no: 0,
+            read: 0,
+            count: 0,
+        }
+    }
+
+    /// Searches the complete lines added since the last scan. A file that
+    /// shrank was truncated, so it's searched again from the start. Lines
+    /// that aren't valid UTF-8 are searched with the bad bytes replaced.
+    fn scan(&mut self, matcher: &dyn Matcher) -> io::Result<Vec<LineMatch>> {
+        self.read = 0;
+        let mut file = File::open(&self.path)?;
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            self.offset = 0;
+            self.line_no = 0;
+            self.count = 0;
+        }
+        let mut matches = Vec::new();
+        if len == self.offset {
+            return Ok(matches);
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+
+        let mut reader = BufReader::new(file);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            let read = reader.read_until(b'\n', &mut buf)?;
+            // Leave a line that's still being written for the next scan.
+            if read == 0 || !buf.ends_with(b"\n") {
+                return Ok(matches);
+            }
+            self.line_no += 1;
+            let text = String::from_utf8_lossy(&buf);
+            let (skipped, line, crlf) = trim_line(&text, self.offset == 0);
+            if let Some(spans) = matcher.find(line) {
+                matches.push(LineMatch {
+                    line_no: self.line_no,
+                    offset: self.offset + skipped as u64,
+                    line: line.to_string(),
+                    spans,
+                    crlf,
+                });
+            }
+            self.offset += read as u64;
+            self.read += read as u64;
+        }
+    }
+}
+
+/// Searches `files`, then keeps running and prints matches in lines
+/// appended to them as filesystem notifications come in, like
+/// `tail -f | grep`. `-c` reprints a file's count as it grows and `-l`
+/// names a file once it first matches.
+///
+/// Only returns if watching fails, or with `-q` once something matches,
+/// with how many lines matched.
+pub fn follow(
+    config: &Config,
+    matcher: &dyn Matcher,
+    files: Vec<String>,
+    printer: &mut dyn Printer,
+) -> Result<usize, MinigrepError> {
+    if files.iter().any(|file| file == input::STDIN) {
+        return Err("--watch needs files to watch, not standard input".into());
+    }
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| MinigrepError::io("--watch", io::Error::other(e)))?;
+    let mut tails: Vec<Tail> = files.into_iter().map(Tail::new).collect();
+    for tail in &tails {
+        watcher
+            .watch(Path::new(&tail.path), RecursiveMode::NonRecursive)
+            .map_err(|e| MinigrepError::io(&tail.path, io::Error::other(e)))?;
+    }
+
+    let written =
+        |result: io::Result<usize>| result.map_err(|e| MinigrepError::io("(standard output)", e));
+    let mut found = written(scan_all(config, matcher, &mut tails, printer))?;
+    // Events only say that something changed; checking every file's length
+    // is cheap, and catches changes to files the event doesn't name.
+    while !(config.quiet && found > 0) {
+        let Ok(event) = rx.recv() else {
+            break;
+        };
+        if let Err(e) = event {
+            eprintln!("minigrep: {e}");
+        }
+        found += written(scan_all(config, matcher, &mut tails, printer))?;
+    }
+    Ok(found)
+}
+
+/// Scans every file for appended lines and prints what they turned up,
+/// returning how many lines matched.
+fn scan_all(
+    config: &Config,
+    matcher: &dyn Matcher,
+    tails: &mut [Tail],
+    printer: &mut dyn Printer,
+) -> io::Result<usize> {
+    let mut found = 0;
+    for tail in tails {
+        let matches = match tail.scan(matcher) {
+            Ok(matches) if matches.is_empty() => continue,
+            Ok(matches) => matches,
+            Err(e) => {
+                eprintln!("minigrep: {}", MinigrepError::io(&tail.path, e));
+                continue;
+            }
+        };
+        found += matches.len();
+        let listed = tail.count > 0;
+        tail.count += match config.count_matches {
+            true => matches.iter().map(|m| m.spans.len().max(1)).sum(),
+            false => matches.len(),
+        };
+        if config.quiet || (config.output == OutputMode::FilesWithMatches && listed) {
+            continue;
+        }
+        let file = FileMatches {
+            path: tail.path.clone(),
+            matches,
+            streamed: 0,
+            count: tail.count,
+            bytes: tail.read,
+            error: None,
+            binary: false,
+        };
+        printer::print_file(printer, &file)?;
+    }
+    io::stdout().flush()?;
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::Literal;
+    use crate::printer::TextPrinter;
+    use crate::Filenames;
+    use std::env;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    #[test]
+    fn scans_only_appended_lines() {
+        let path = env::temp_dir().join(format!("minigrep-watch-{}.log", std::process::id()));
+        fs::write(&path, "error: one\nfine\n").unwrap();
+        let matcher = Literal::new("error");
+        let mut tail = Tail::new(path.display().to_string());
+
+        let lines = |matches: Vec<LineMatch>| -> Vec<(usize, u64)> {
+            matches.iter().map(|m| (m.line_no, m.offset)).collect()
+        };
+        assert_eq!(vec![(1, 0)], lines(tail.scan(&matcher).unwrap()));
+        assert!(tail.scan(&matcher).unwrap().is_empty());
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "error: two").unwrap();
+        assert!(tail.scan(&matcher).unwrap().is_empty());
+        writeln!(file, " finished").unwrap();
+        assert_eq!(vec![(3, 16)], lines(tail.scan(&matcher).unwrap()));
+
+        fs::write(&path, "error: again\n").unwrap();
+        assert_eq!(vec![(1, 0)], lines(tail.scan(&matcher).unwrap()));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn gets_past_lines_that_are_not_utf8() {
+        let path = env::temp_dir().join(format!("minigrep-watch-bad-{}.log", std::process::id()));
+        fs::write(&path, b"error: caf\xe9\nerror: fine\n").unwrap();
+        let matcher = Literal::new("error");
+        let mut tail = Tail::new(path.display().to_string());
+
+        let matches = tail.scan(&matcher).unwrap();
+        let lines: Vec<_> = matches.iter().map(|m| m.line.as_str()).collect();
+        assert_eq!(vec!["error: caf\u{fffd}", "error: fine"], lines);
+        assert!(tail.scan(&matcher).unwrap().is_empty());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn prints_counts_and_names_as_they_change() {
+        let path = env::temp_dir().join(format!("minigrep-watch-modes-{}.log", std::process::id()));
+        let name = path.display().to_string();
+        fs::write(&path, "error: one\n").unwrap();
+        let matcher = Literal::new("error");
+        let count = Config::builder()
+            .query("error")
+            .output(OutputMode::Count)
+            .build()
+            .unwrap();
+        let list = Config::builder()
+            .query("error")
+            .output(OutputMode::FilesWithMatches)
+            .build()
+            .unwrap();
+        let mut counted = vec![Tail::new(name.clone())];
+        let mut listed = vec![Tail::new(name.clone())];
+        let (mut counts, mut names) = (Vec::new(), Vec::new());
+
+        for _ in 0..2 {
+            let mut printer =
+                TextPrinter::new(&mut counts, &count, &matcher, Filenames::Hidden, false);
+            assert_eq!(
+                1,
+                scan_all(&count, &matcher, &mut counted, &mut printer).unwrap()
+            );
+            let mut printer =
+                TextPrinter::new(&mut names, &list, &matcher, Filenames::Prefixed, false);
+            assert_eq!(
+                1,
+                scan_all(&list, &matcher, &mut listed, &mut printer).unwrap()
+            );
+            let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+            writeln!(file, "error: again").unwrap();
+        }
+
+        assert_eq!("1\n2\n", String::from_utf8(counts).unwrap());
+        assert_eq!(format!("{name}\n"), String::from_utf8(names).unwrap());
+        fs::remove_file(path).unwrap();
+    }
+}